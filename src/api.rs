@@ -0,0 +1,246 @@
+/// Library entry point for embedding athenadef in other tools
+///
+/// The CLI commands are a layer over this API that adds prompts, progress, and
+/// formatted output.
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::apply_state::ApplyStateOptions;
+use crate::aws::region::RegionalExecutors;
+use crate::commands::apply::{TableOutcome, apply_changes, apply_result};
+use crate::differ::calculate_regional_diff;
+use crate::remote_cache::RemoteCache;
+use crate::sql_source::SqlSource;
+use crate::target_filter::{parse_target_filter_with_excludes, resolve_targets};
+use crate::types::apply_result::ApplyResult;
+use crate::types::config::{Concurrency, Config};
+use crate::types::diff_result::{DiffOperation, DiffResult};
+use crate::warnings::warn;
+
+/// Plans and applies the SQL files under a base path against Athena
+///
+/// Nothing is printed to stdout; results are returned as data.
+///
+/// # Example
+///
+/// ```
+/// use athenadef::Athenadef;
+/// use athenadef::types::config::{Concurrency, Config};
+///
+/// let athenadef = Athenadef::new(Config::default(), "sql")
+///     .with_excludes(vec!["salesdb.tmp_*".to_string()])
+///     .with_concurrency(Some(Concurrency::Fixed(4)));
+/// assert_eq!(athenadef.base_path(), std::path::Path::new("sql"));
+/// assert_eq!(athenadef.config().workgroup, "primary");
+///
+/// // Inside an async context:
+/// // let plan = athenadef.plan(&["salesdb.*".to_string()]).await?;
+/// // let result = athenadef.apply(&plan).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Athenadef {
+    config: Config,
+    base_path: PathBuf,
+    sql_source: SqlSource,
+    excludes: Vec<String>,
+    concurrency: Option<Concurrency>,
+    include_unchanged: bool,
+    refresh: bool,
+    continue_on_error: bool,
+}
+
+impl Athenadef {
+    /// Create an instance for the SQL files under `base_path`
+    ///
+    /// # Arguments
+    /// * `config` - Loaded configuration, such as from `Config::load_from_path`
+    /// * `base_path` - Directory containing `database/table.sql` files, such as
+    ///   the one `Config::base_path` resolves
+    pub fn new(config: Config, base_path: impl Into<PathBuf>) -> Self {
+        let sql_source = SqlSource::default().with_layout(config.sql_layout());
+        Self {
+            config,
+            base_path: base_path.into(),
+            sql_source,
+            excludes: Vec::new(),
+            concurrency: None,
+            include_unchanged: false,
+            refresh: false,
+            continue_on_error: false,
+        }
+    }
+
+    /// Read SQL files from this source, such as a git ref, instead of the working tree
+    pub fn with_sql_source(mut self, sql_source: SqlSource) -> Self {
+        self.sql_source = sql_source;
+        self
+    }
+
+    /// Leave out tables matching these `database.table` patterns
+    pub fn with_excludes(mut self, excludes: Vec<String>) -> Self {
+        self.excludes = excludes;
+        self
+    }
+
+    /// Run this many queries at a time instead of `max_concurrent_queries`
+    pub fn with_concurrency(mut self, concurrency: Option<Concurrency>) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Also include NoChange entries for unchanged tables in plans
+    pub fn with_unchanged(mut self, include_unchanged: bool) -> Self {
+        self.include_unchanged = include_unchanged;
+        self
+    }
+
+    /// Query every remote definition again instead of using `cache_ttl_seconds` entries
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Keep applying the other tables after one fails
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// The configuration in use
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Directory the SQL files are read from
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
+    }
+
+    /// Compare the local SQL files with the remote tables
+    ///
+    /// # Arguments
+    /// * `targets` - `database.table` patterns to include; `databases` from the
+    ///   config, or every table, when empty
+    pub async fn plan(&self, targets: &[String]) -> Result<DiffResult> {
+        let executors = RegionalExecutors::from_config(&self.config).await?;
+        self.plan_with(&executors, targets).await
+    }
+
+    /// Apply a plan, such as one returned by `plan`
+    ///
+    /// A table that fails to apply is reported in the result with its error
+    /// instead of failing the call; tables after it are skipped unless
+    /// `with_continue_on_error` is set.
+    pub async fn apply(&self, plan: &DiffResult) -> Result<ApplyResult> {
+        if plan.no_change {
+            return Ok(ApplyResult::new(false, Vec::new()));
+        }
+
+        let executors = RegionalExecutors::from_config(&self.config).await?;
+        let outcomes = self.apply_with(&executors, plan, None, false).await?;
+        Ok(apply_result(&outcomes))
+    }
+
+    /// Compare the local SQL files with the remote tables using these executors
+    pub(crate) async fn plan_with(
+        &self,
+        executors: &RegionalExecutors,
+        targets: &[String],
+    ) -> Result<DiffResult> {
+        let targets = resolve_targets(targets, self.config.databases.as_ref());
+        let target_filter = parse_target_filter_with_excludes(
+            &targets,
+            &self.excludes,
+            self.config.table_name_case.unwrap_or_default(),
+        )?;
+
+        calculate_regional_diff(
+            executors,
+            &self.config,
+            self.config.concurrency(self.concurrency),
+            &self.sql_source,
+            &self.base_path,
+            self.include_unchanged,
+            self.remote_cache().as_ref(),
+            |db: &str, table: &str| target_filter(db, table),
+        )
+        .await
+    }
+
+    /// Apply a plan using these executors
+    ///
+    /// Cached definitions of the changed tables are removed first, since they
+    /// are outdated from then on.
+    ///
+    /// # Arguments
+    /// * `state` - Where applied tables are recorded, and whether to resume from it
+    /// * `print_progress` - Print a line per table as it completes
+    ///
+    /// # Returns
+    /// One outcome per table applied in this run
+    pub(crate) async fn apply_with(
+        &self,
+        executors: &RegionalExecutors,
+        plan: &DiffResult,
+        state: Option<&ApplyStateOptions>,
+        print_progress: bool,
+    ) -> Result<Vec<TableOutcome>> {
+        if let Some(cache) = self.remote_cache() {
+            for table_diff in &plan.table_diffs {
+                if table_diff.operation == DiffOperation::NoChange {
+                    continue;
+                }
+                let region = executors.for_database(&table_diff.database_name).region();
+                if let Err(e) =
+                    cache.invalidate(region, &table_diff.database_name, &table_diff.table_name)
+                {
+                    warn(format!("{:#}", e));
+                }
+            }
+        }
+
+        apply_changes(
+            plan,
+            executors,
+            &self.base_path,
+            &self.sql_source,
+            self.config.update_strategy.unwrap_or_default(),
+            self.config.drop_behavior.unwrap_or_default(),
+            state,
+            self.config
+                .concurrency(self.concurrency)
+                .resolve(plan.table_diffs.len()),
+            self.continue_on_error,
+            print_progress,
+        )
+        .await
+    }
+
+    fn remote_cache(&self) -> Option<RemoteCache> {
+        RemoteCache::from_config(&self.config, &self.base_path, self.refresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_apply_without_changes() {
+        let athenadef = Athenadef::new(Config::default(), ".");
+        let result = athenadef.apply(&DiffResult::new()).await.unwrap();
+        assert!(!result.dry_run);
+        assert!(result.tables.is_empty());
+    }
+
+    #[test]
+    fn test_new_uses_config_layout() {
+        let config = Config {
+            default_database: Some("salesdb".to_string()),
+            ..Config::default()
+        };
+        let athenadef = Athenadef::new(config.clone(), "sql");
+        assert_eq!(athenadef.sql_source.layout(), &config.sql_layout());
+        assert!(athenadef.sql_source.git_ref().is_none());
+    }
+}
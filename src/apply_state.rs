@@ -0,0 +1,105 @@
+/// Progress of an apply, persisted so an interrupted run can be resumed
+///
+/// Each successfully applied table is recorded with a hash of the DDL that was
+/// run for it. A resumed apply skips a table only if the DDL it would run now has
+/// the same hash, so a changed definition is always applied again.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where apply records its progress, and whether recorded tables are skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplyStateOptions {
+    pub path: PathBuf,
+    pub resume: bool,
+}
+
+/// Tables applied so far, keyed by "database.table"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApplyState {
+    /// SHA-256 of the applied DDL for each table
+    pub applied: BTreeMap<String, String>,
+}
+
+impl ApplyState {
+    /// Load a state file, or an empty state if it does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read apply state file {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse apply state file {}\n\nDelete it to apply every table again.",
+                path.display()
+            )
+        })
+    }
+
+    /// Write the state file, replacing any previous content
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write apply state file {}", path.display()))
+    }
+
+    /// Check whether a table was applied with the same DDL
+    pub fn is_applied(&self, table: &str, ddl_hash: &str) -> bool {
+        self.applied.get(table).is_some_and(|hash| hash == ddl_hash)
+    }
+
+    /// Record a table as applied with the given DDL hash
+    pub fn record(&mut self, table: String, ddl_hash: String) {
+        self.applied.insert(table, ddl_hash);
+    }
+}
+
+/// Hash the DDL applied to a table
+///
+/// # Arguments
+/// * `ddl` - Statement (or local definition) applied to the table
+///
+/// # Returns
+/// Lowercase hex SHA-256 digest
+pub fn ddl_hash(ddl: &str) -> String {
+    format!("{:x}", Sha256::digest(ddl.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_state_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("apply-state.json");
+
+        assert_eq!(ApplyState::load(&path).unwrap(), ApplyState::default());
+
+        let mut state = ApplyState::default();
+        state.record("salesdb.customers".to_string(), ddl_hash("CREATE TABLE a"));
+        state.save(&path).unwrap();
+
+        let loaded = ApplyState::load(&path).unwrap();
+        assert!(loaded.is_applied("salesdb.customers", &ddl_hash("CREATE TABLE a")));
+        assert!(!loaded.is_applied("salesdb.customers", &ddl_hash("CREATE TABLE b")));
+        assert!(!loaded.is_applied("salesdb.orders", &ddl_hash("CREATE TABLE a")));
+    }
+
+    #[test]
+    fn test_apply_state_load_invalid() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("apply-state.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let err = ApplyState::load(&path).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Delete it to apply every table again")
+        );
+    }
+}
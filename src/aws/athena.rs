@@ -1,15 +1,57 @@
 use anyhow::{Context, Result};
 use aws_sdk_athena::{
     Client as AthenaClient,
-    types::{QueryExecutionState, ResultConfiguration},
+    error::SdkError,
+    operation::get_query_execution::{GetQueryExecutionError, GetQueryExecutionOutput},
+    operation::start_query_execution::builders::StartQueryExecutionFluentBuilder,
+    types::{
+        QueryExecution, QueryExecutionContext, QueryExecutionState, ResultConfiguration,
+        ResultReuseByAgeConfiguration, ResultReuseConfiguration, WorkGroupState,
+    },
 };
-use std::sync::Arc;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 use tokio::sync::Semaphore;
-use tracing::error;
+use tracing::{debug, error, info};
 
+use crate::aws::glue::GlueCatalogClient;
+use crate::aws::retry::{RetryPolicy, is_transient, with_retries};
+use crate::aws::s3::S3Manager;
+use crate::progress::ProgressCounter;
 use crate::types::query_execution::{QueryExecutionStatus, QueryResult, QueryRow};
 
+/// Id of this athenadef run, shared by the request tokens of all its queries
+static RUN_ID: OnceLock<String> = OnceLock::new();
+
+/// Number of queries started in this run, for unique request tokens
+static QUERY_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Set the id of this run, used in the client request token of every query
+///
+/// Called once by main before a command runs; later calls are ignored.
+pub fn set_run_id(run_id: &str) {
+    let _ = RUN_ID.set(run_id.to_string());
+}
+
+/// Get the id of this run, generating one if none was set
+pub fn run_id() -> &'static str {
+    RUN_ID.get_or_init(new_run_id)
+}
+
+/// Generate a new run id
+pub fn new_run_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Client request token of the `sequence`-th query of a run
+///
+/// Shows up in CloudTrail, so the queries of one run can be found by its id.
+pub fn client_request_token(run_id: &str, sequence: u64) -> String {
+    format!("athenadef-{}-{}", run_id, sequence)
+}
+
 /// Client for executing queries on AWS Athena
 #[derive(Clone)]
 pub struct QueryExecutor {
@@ -17,6 +59,215 @@ pub struct QueryExecutor {
     workgroup: String,
     output_location: Option<String>,
     timeout_seconds: u64,
+    result_reuse_max_age_minutes: Option<u64>,
+    retry_policy: RetryPolicy,
+    catalog: Option<String>,
+    result_cleanup: Option<S3Manager>,
+    in_flight: InFlightQueries,
+}
+
+/// Execution IDs of queries that were started and not yet seen finishing
+///
+/// Shared by every clone of a `QueryExecutor`, so an interrupt handler can stop
+/// the queries started by parallel tasks. Queries are removed once they reach a
+/// final state, so finished work is never cancelled. Once closed, no new query
+/// may start, so tasks still running cannot add work the handler would miss.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightQueries {
+    state: Arc<Mutex<InFlightState>>,
+}
+
+#[derive(Debug, Default)]
+struct InFlightState {
+    execution_ids: BTreeSet<String>,
+    closed: bool,
+}
+
+impl InFlightQueries {
+    /// Record a started query
+    ///
+    /// # Returns
+    /// false if the registry was closed, in which case the caller must stop the
+    /// query itself; it was started after the in-flight queries were collected
+    pub fn start(&self, execution_id: &str) -> bool {
+        let mut state = self.lock();
+        if state.closed {
+            return false;
+        }
+        state.execution_ids.insert(execution_id.to_string());
+        true
+    }
+
+    /// Forget a query that reached a final state
+    pub fn finish(&self, execution_id: &str) {
+        self.lock().execution_ids.remove(execution_id);
+    }
+
+    /// Get the execution IDs of queries still in flight, sorted
+    pub fn execution_ids(&self) -> Vec<String> {
+        self.lock().execution_ids.iter().cloned().collect()
+    }
+
+    /// Refuse new queries from now on
+    ///
+    /// # Returns
+    /// The execution IDs of queries still in flight, sorted
+    pub fn close(&self) -> Vec<String> {
+        let mut state = self.lock();
+        state.closed = true;
+        state.execution_ids.iter().cloned().collect()
+    }
+
+    /// Whether `close` was called
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InFlightState> {
+        // A panic while holding the lock cannot leave the set inconsistent
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Execution of a single query, abstracted so command logic can be tested
+/// without a real Athena workgroup
+#[cfg_attr(test, mockall::automock)]
+pub trait QueryRunner {
+    /// Execute a query and wait for completion
+    fn execute_query(
+        &self,
+        query: &str,
+    ) -> impl std::future::Future<Output = Result<QueryResult>> + Send;
+}
+
+impl QueryRunner for QueryExecutor {
+    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+        QueryExecutor::execute_query(self, query).await
+    }
+}
+
+impl<R: QueryRunner + Send + Sync> QueryRunner for Arc<R> {
+    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+        self.as_ref().execute_query(query).await
+    }
+}
+
+/// Access to the workgroup's configuration, abstracted for the same reason as `QueryRunner`
+#[cfg_attr(test, mockall::automock)]
+pub trait WorkgroupInspector {
+    /// Get the state of a workgroup via GetWorkGroup
+    fn workgroup_state(
+        &self,
+        workgroup: &str,
+    ) -> impl std::future::Future<Output = Result<Option<WorkGroupState>>> + Send;
+}
+
+impl WorkgroupInspector for QueryExecutor {
+    async fn workgroup_state(&self, workgroup: &str) -> Result<Option<WorkGroupState>> {
+        let response = self
+            .athena_client
+            .get_work_group()
+            .work_group(workgroup)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get workgroup '{}': {}", workgroup, e))?;
+
+        Ok(response
+            .work_group()
+            .and_then(|work_group| work_group.state())
+            .cloned())
+    }
+}
+
+/// Fail fast if the workgroup is disabled, since every query would fail otherwise
+///
+/// If the state cannot be read (e.g. no `athena:GetWorkGroup` permission), the
+/// check is skipped and queries report any problem themselves.
+///
+/// # Arguments
+/// * `inspector` - Workgroup API for the region being checked
+/// * `workgroup` - Workgroup name
+pub async fn ensure_workgroup_enabled<W: WorkgroupInspector>(
+    inspector: &W,
+    workgroup: &str,
+) -> Result<()> {
+    match inspector.workgroup_state(workgroup).await {
+        Ok(Some(WorkGroupState::Disabled)) => Err(anyhow::anyhow!(
+            "Workgroup '{}' is disabled\n\nEnable it in the Athena console or with:\n  aws athena update-work-group --work-group {} --state ENABLED\nor choose another workgroup with `workgroup` in athenadef.yaml or ATHENADEF_WORKGROUP.",
+            workgroup,
+            workgroup
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            info!("Skipping workgroup state check: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Names listed by a SHOW DATABASES, SHOW TABLES, or SHOW VIEWS query
+///
+/// These return one name per row in the first column. Rows are collected from
+/// every result page by `get_query_results`; blank rows, such as those of an
+/// empty database, are skipped and surrounding whitespace is removed.
+fn single_column_values(result: &QueryResult) -> Vec<String> {
+    result
+        .rows
+        .iter()
+        .filter_map(|row| row.get_column(0))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get list of all databases using SHOW DATABASES
+///
+/// # Arguments
+/// * `runner` - Query runner to execute SHOW DATABASES with
+///
+/// # Returns
+/// Vector of database names
+pub async fn list_databases<R: QueryRunner>(runner: &R) -> Result<Vec<String>> {
+    let result = runner.execute_query("SHOW DATABASES").await?;
+
+    Ok(single_column_values(&result))
+}
+
+/// Get list of tables in a specific database using SHOW TABLES
+///
+/// # Arguments
+/// * `runner` - Query runner to execute SHOW TABLES with
+/// * `database` - Database name
+///
+/// # Returns
+/// Vector of table names
+pub async fn list_tables<R: QueryRunner>(runner: &R, database: &str) -> Result<Vec<String>> {
+    let query = format!("SHOW TABLES IN `{}`", database);
+
+    let result = runner.execute_query(&query).await?;
+
+    Ok(single_column_values(&result))
+}
+
+/// Get list of views in a specific database using SHOW VIEWS
+///
+/// SHOW TABLES lists views as well, so this tells which of them need SHOW CREATE VIEW.
+///
+/// # Arguments
+/// * `runner` - Query runner to execute SHOW VIEWS with
+/// * `database` - Database name
+///
+/// # Returns
+/// Vector of view names
+pub async fn list_views<R: QueryRunner>(runner: &R, database: &str) -> Result<Vec<String>> {
+    let query = format!("SHOW VIEWS IN `{}`", database);
+
+    let result = runner.execute_query(&query).await?;
+
+    Ok(single_column_values(&result))
 }
 
 impl QueryExecutor {
@@ -38,11 +289,90 @@ impl QueryExecutor {
             workgroup,
             output_location,
             timeout_seconds,
+            result_reuse_max_age_minutes: None,
+            retry_policy: RetryPolicy::default(),
+            catalog: None,
+            result_cleanup: None,
+            in_flight: InFlightQueries::default(),
         }
     }
 
+    /// Retry throttled and failed API calls according to this policy
+    ///
+    /// StartQueryExecution, GetQueryExecution, and GetQueryResults are retried on
+    /// throttling and server errors; other errors fail immediately.
+    ///
+    /// # Arguments
+    /// * `retry_policy` - Number of retries and backoff
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Let Athena return cached results of identical queries up to this age
+    ///
+    /// # Arguments
+    /// * `max_age_minutes` - Maximum age of reused results, or None to always run queries
+    pub fn with_result_reuse(mut self, max_age_minutes: Option<u64>) -> Self {
+        self.result_reuse_max_age_minutes = max_age_minutes;
+        self
+    }
+
+    /// Run queries and metadata lookups against another Athena data catalog
+    ///
+    /// # Arguments
+    /// * `catalog` - Data catalog name, or None for the account's own Glue catalog
+    pub fn with_catalog(mut self, catalog: Option<String>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Delete the result files of each query from S3 once its results are read
+    ///
+    /// # Arguments
+    /// * `s3_manager` - Client deleting the files, or None to keep them
+    pub fn with_result_cleanup(mut self, s3_manager: Option<S3Manager>) -> Self {
+        self.result_cleanup = s3_manager;
+        self
+    }
+
+    /// Result reuse settings attached to every query, if reuse is enabled
+    fn result_reuse_configuration(&self) -> Option<ResultReuseConfiguration> {
+        let max_age = self.result_reuse_max_age_minutes?;
+        Some(
+            ResultReuseConfiguration::builder()
+                .result_reuse_by_age_configuration(
+                    ResultReuseByAgeConfiguration::builder()
+                        .enabled(true)
+                        .max_age_in_minutes(i32::try_from(max_age).unwrap_or(i32::MAX))
+                        .build(),
+                )
+                .build(),
+        )
+    }
+
+    /// Client for reading table metadata from the Glue Data Catalog in this region
+    pub fn catalog_client(&self) -> GlueCatalogClient {
+        let client = GlueCatalogClient::new(self.athena_client.clone(), self.retry_policy);
+        match self.catalog {
+            Some(ref catalog) => client.with_catalog_name(catalog),
+            None => client,
+        }
+    }
+
+    /// Get the region this executor's client is configured for
+    pub fn region(&self) -> Option<&str> {
+        self.athena_client
+            .config()
+            .region()
+            .map(|region| region.as_ref())
+    }
+
     /// Execute a query and wait for completion
     ///
+    /// With result cleanup enabled, the result files are deleted from S3 after
+    /// the results are read; failing to delete them does not fail the query.
+    ///
     /// # Arguments
     /// * `query` - SQL query string to execute
     ///
@@ -51,7 +381,20 @@ impl QueryExecutor {
     pub async fn execute_query(&self, query: &str) -> Result<QueryResult> {
         let execution_id = self.start_query_execution(query).await?;
         self.wait_for_completion(&execution_id, Some(query)).await?;
-        self.get_query_results(&execution_id).await
+        let result = self.get_query_results(&execution_id).await?;
+
+        if let (Some(s3_manager), Some(output_location)) =
+            (&self.result_cleanup, result.output_location.as_deref())
+        {
+            if let Err(e) = s3_manager.cleanup_query_result(output_location).await {
+                debug!(
+                    execution_id,
+                    output_location, "Failed to clean up query result: {:#}", e
+                );
+            }
+        }
+
+        Ok(result)
     }
 
     /// Start a query execution without waiting for completion
@@ -62,6 +405,45 @@ impl QueryExecutor {
     /// # Returns
     /// Query execution ID
     pub async fn start_query_execution(&self, query: &str) -> Result<String> {
+        if self.in_flight.is_closed() {
+            return Err(anyhow::anyhow!("Query not started: interrupted"));
+        }
+        let request = self.start_query_request(query);
+
+        let response = with_retries(
+            &self.retry_policy,
+            "StartQueryExecution",
+            is_transient,
+            || request.clone().send(),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to start query execution. Query: {}", query);
+            anyhow::anyhow!("Failed to start query execution: {}", e)
+        })?;
+
+        let execution_id = response
+            .query_execution_id()
+            .ok_or_else(|| anyhow::anyhow!("No query execution ID returned"))?;
+        debug!(
+            execution_id,
+            query,
+            client_request_token = request.get_client_request_token().as_deref(),
+            "Started query execution"
+        );
+        if !self.in_flight.start(execution_id) {
+            // Interrupted while the query was being started
+            if let Err(e) = self.stop_query_execution(execution_id).await {
+                error!("{}", e);
+            }
+            return Err(anyhow::anyhow!("Query stopped: interrupted"));
+        }
+
+        Ok(execution_id.to_string())
+    }
+
+    /// Build the StartQueryExecution request for a query
+    fn start_query_request(&self, query: &str) -> StartQueryExecutionFluentBuilder {
         let mut request = self
             .athena_client
             .start_query_execution()
@@ -78,15 +460,19 @@ impl QueryExecutor {
             );
         }
 
-        let response = request.send().await.map_err(|e| {
-            error!("Failed to start query execution. Query: {}", query);
-            anyhow::anyhow!("Failed to start query execution: {}", e)
-        })?;
+        // Unqualified database names resolve in the configured data catalog
+        if let Some(ref catalog) = self.catalog {
+            request = request
+                .query_execution_context(QueryExecutionContext::builder().catalog(catalog).build());
+        }
 
-        response
-            .query_execution_id()
-            .ok_or_else(|| anyhow::anyhow!("No query execution ID returned"))
-            .map(|s| s.to_string())
+        if let Some(reuse) = self.result_reuse_configuration() {
+            request = request.result_reuse_configuration(reuse);
+        }
+
+        // One token for every attempt, so a retry never starts the query twice
+        let sequence = QUERY_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        request.client_request_token(client_request_token(run_id(), sequence))
     }
 
     /// Wait for a query execution to complete
@@ -114,10 +500,7 @@ impl QueryExecutor {
             }
 
             let response = self
-                .athena_client
-                .get_query_execution()
-                .query_execution_id(execution_id)
-                .send()
+                .get_query_execution(execution_id)
                 .await
                 .context("Failed to get query execution status")?;
 
@@ -126,6 +509,21 @@ impl QueryExecutor {
                 .and_then(|qe| qe.status())
                 .and_then(|s| s.state());
 
+            if let Some(
+                state @ (QueryExecutionState::Succeeded
+                | QueryExecutionState::Failed
+                | QueryExecutionState::Cancelled),
+            ) = state
+            {
+                debug!(
+                    execution_id,
+                    state = state.as_str(),
+                    duration_ms = start_time.elapsed().as_millis() as u64,
+                    "Query execution finished"
+                );
+                self.in_flight.finish(execution_id);
+            }
+
             match state {
                 Some(QueryExecutionState::Succeeded) => {
                     return Ok(());
@@ -164,6 +562,69 @@ impl QueryExecutor {
         }
     }
 
+    /// Stop a running query execution
+    ///
+    /// Stopping a query that already finished has no effect on it.
+    ///
+    /// # Arguments
+    /// * `execution_id` - Query execution ID
+    pub async fn stop_query_execution(&self, execution_id: &str) -> Result<()> {
+        let request = self
+            .athena_client
+            .stop_query_execution()
+            .query_execution_id(execution_id);
+        with_retries(
+            &self.retry_policy,
+            "StopQueryExecution",
+            is_transient,
+            || request.clone().send(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to stop query execution {}: {}", execution_id, e))?;
+
+        debug!(execution_id, "Stopped query execution");
+        self.in_flight.finish(execution_id);
+        Ok(())
+    }
+
+    /// Get the queries this executor (or any clone of it) started that are still running
+    pub fn in_flight_queries(&self) -> &InFlightQueries {
+        &self.in_flight
+    }
+
+    /// Stop every query still in flight, and refuse to start new ones
+    ///
+    /// # Returns
+    /// The execution IDs that were stopped; failures are logged and skipped
+    pub async fn stop_in_flight_queries(&self) -> Vec<String> {
+        let mut stopped = Vec::new();
+        for execution_id in self.in_flight.close() {
+            match self.stop_query_execution(&execution_id).await {
+                Ok(()) => stopped.push(execution_id),
+                Err(e) => error!("{}", e),
+            }
+        }
+        stopped
+    }
+
+    /// Call GetQueryExecution, retrying transient errors
+    async fn get_query_execution(
+        &self,
+        execution_id: &str,
+    ) -> Result<GetQueryExecutionOutput, SdkError<GetQueryExecutionError>> {
+        let request = self
+            .athena_client
+            .get_query_execution()
+            .query_execution_id(execution_id);
+        with_retries(
+            &self.retry_policy,
+            "GetQueryExecution",
+            is_transient,
+            || request.clone().send(),
+        )
+        .await
+    }
+
     /// Get query execution status
     ///
     /// # Arguments
@@ -173,10 +634,7 @@ impl QueryExecutor {
     /// QueryExecutionStatus
     pub async fn get_query_status(&self, execution_id: &str) -> Result<QueryExecutionStatus> {
         let response = self
-            .athena_client
-            .get_query_execution()
-            .query_execution_id(execution_id)
-            .send()
+            .get_query_execution(execution_id)
             .await
             .context("Failed to get query execution status")?;
 
@@ -186,14 +644,7 @@ impl QueryExecutor {
             .and_then(|s| s.state())
             .ok_or_else(|| anyhow::anyhow!("Query execution state not available"))?;
 
-        Ok(match state {
-            QueryExecutionState::Queued => QueryExecutionStatus::Queued,
-            QueryExecutionState::Running => QueryExecutionStatus::Running,
-            QueryExecutionState::Succeeded => QueryExecutionStatus::Succeeded,
-            QueryExecutionState::Failed => QueryExecutionStatus::Failed,
-            QueryExecutionState::Cancelled => QueryExecutionStatus::Cancelled,
-            _ => QueryExecutionStatus::Failed,
-        })
+        Ok(to_status(state))
     }
 
     /// Get list of all databases using SHOW DATABASES
@@ -201,16 +652,7 @@ impl QueryExecutor {
     /// # Returns
     /// Vector of database names
     pub async fn get_databases(&self) -> Result<Vec<String>> {
-        let result = self.execute_query("SHOW DATABASES").await?;
-
-        let databases: Vec<String> = result
-            .rows
-            .iter()
-            .filter_map(|row| row.get_column(0))
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(databases)
+        list_databases(self).await
     }
 
     /// Get list of tables in a specific database using SHOW TABLES
@@ -221,18 +663,7 @@ impl QueryExecutor {
     /// # Returns
     /// Vector of table names
     pub async fn get_tables(&self, database: &str) -> Result<Vec<String>> {
-        let query = format!("SHOW TABLES IN `{}`", database);
-
-        let result = self.execute_query(&query).await?;
-
-        let tables: Vec<String> = result
-            .rows
-            .iter()
-            .filter_map(|row| row.get_column(0))
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(tables)
+        list_tables(self, database).await
     }
 
     /// Get query results
@@ -243,21 +674,27 @@ impl QueryExecutor {
     /// # Returns
     /// QueryResult with rows and status
     pub async fn get_query_results(&self, execution_id: &str) -> Result<QueryResult> {
-        let status = self.get_query_status(execution_id).await?;
+        let response = self
+            .get_query_execution(execution_id)
+            .await
+            .context("Failed to get query execution status")?;
+        let query_execution = response.query_execution();
+        let state = query_execution
+            .and_then(|qe| qe.status())
+            .and_then(|s| s.state())
+            .ok_or_else(|| anyhow::anyhow!("Query execution state not available"))?;
+        let status = to_status(state);
+
+        let mut result = QueryResult::new(execution_id.to_string(), status);
+        let statistics = query_execution.and_then(|qe| qe.statistics());
+        result.data_scanned_bytes = statistics.and_then(|s| s.data_scanned_in_bytes());
+        result.engine_execution_time_ms =
+            statistics.and_then(|s| s.engine_execution_time_in_millis());
+        result.output_location = output_location(query_execution).map(str::to_string);
 
         if status != QueryExecutionStatus::Succeeded {
-            let mut result = QueryResult::new(execution_id.to_string(), status);
             if status == QueryExecutionStatus::Failed {
-                let response = self
-                    .athena_client
-                    .get_query_execution()
-                    .query_execution_id(execution_id)
-                    .send()
-                    .await
-                    .context("Failed to get query execution details")?;
-
-                result.error_message = response
-                    .query_execution()
+                result.error_message = query_execution
                     .and_then(|qe| qe.status())
                     .and_then(|s| s.state_change_reason())
                     .map(|s| s.to_string());
@@ -265,7 +702,6 @@ impl QueryExecutor {
             return Ok(result);
         }
 
-        let mut result = QueryResult::new(execution_id.to_string(), status);
         let mut next_token: Option<String> = None;
 
         loop {
@@ -278,8 +714,10 @@ impl QueryExecutor {
                 request = request.next_token(token);
             }
 
-            let response = request
-                .send()
+            let response =
+                with_retries(&self.retry_policy, "GetQueryResults", is_transient, || {
+                    request.clone().send()
+                })
                 .await
                 .context("Failed to get query results")?;
 
@@ -309,23 +747,102 @@ impl QueryExecutor {
     }
 }
 
+/// S3 URL of the result file of a query execution, if Athena reported one
+fn output_location(query_execution: Option<&QueryExecution>) -> Option<&str> {
+    query_execution
+        .and_then(|qe| qe.result_configuration())
+        .and_then(|config| config.output_location())
+        .filter(|location| !location.is_empty())
+}
+
+/// Map an Athena query state to the status reported in query results
+fn to_status(state: &QueryExecutionState) -> QueryExecutionStatus {
+    match state {
+        QueryExecutionState::Queued => QueryExecutionStatus::Queued,
+        QueryExecutionState::Running => QueryExecutionStatus::Running,
+        QueryExecutionState::Succeeded => QueryExecutionStatus::Succeeded,
+        QueryExecutionState::Failed => QueryExecutionStatus::Failed,
+        QueryExecutionState::Cancelled => QueryExecutionStatus::Cancelled,
+        _ => QueryExecutionStatus::Failed,
+    }
+}
+
 /// Executor for running multiple queries in parallel with concurrency control
-pub struct ParallelQueryExecutor {
-    executor: QueryExecutor,
+pub struct ParallelQueryExecutor<R = QueryExecutor> {
+    executor: R,
     semaphore: Arc<Semaphore>,
+    progress: Option<Arc<ProgressCounter>>,
 }
 
-impl ParallelQueryExecutor {
+impl<R> ParallelQueryExecutor<R> {
     /// Create a new ParallelQueryExecutor
     ///
     /// # Arguments
     /// * `executor` - QueryExecutor instance
     /// * `max_concurrent` - Maximum number of concurrent queries
-    pub fn new(executor: QueryExecutor, max_concurrent: usize) -> Self {
+    pub fn new(executor: R, max_concurrent: usize) -> Self {
         Self {
             executor,
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            progress: None,
+        }
+    }
+
+    /// Report each completed query of `execute_queries` to a progress counter
+    pub fn with_progress(mut self, progress: Arc<ProgressCounter>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+impl<R: QueryRunner + Clone + Send + Sync + 'static> ParallelQueryExecutor<R> {
+    /// Execute queries in parallel, handing over each result as soon as it completes
+    ///
+    /// Unlike `execute_queries`, a failed query does not fail the others; its
+    /// error is passed to `on_result` like any other result. `on_result` runs on
+    /// the calling task, one result at a time, so its output never interleaves.
+    ///
+    /// # Arguments
+    /// * `queries` - SQL queries, each with a key identifying it to `on_result`
+    /// * `on_result` - Called with each key and result in completion order; an
+    ///   error stops the remaining queries and is returned
+    pub async fn for_each_completed<K, F>(
+        &self,
+        queries: Vec<(K, String)>,
+        mut on_result: F,
+    ) -> Result<()>
+    where
+        K: Send + 'static,
+        F: FnMut(K, Result<QueryResult>) -> Result<()>,
+    {
+        let mut tasks = tokio::task::JoinSet::new();
+        for (key, query) in queries {
+            let executor = self.executor.clone();
+            let semaphore = self.semaphore.clone();
+            let progress = self.progress.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let result = executor.execute_query(&query).await;
+                if let Some(progress) = progress {
+                    progress.inc();
+                }
+                Ok::<_, anyhow::Error>((key, result))
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            let (key, result) = joined.context("Task join failed")??;
+            on_result(key, result)?;
         }
+
+        Ok(())
+    }
+}
+
+impl ParallelQueryExecutor {
+    /// Get the execution IDs of started queries that have not finished yet
+    pub fn started_execution_ids(&self) -> Vec<String> {
+        self.executor.in_flight_queries().execution_ids()
     }
 
     /// Execute multiple queries in parallel
@@ -342,10 +859,15 @@ impl ParallelQueryExecutor {
             .map(|query| {
                 let executor = self.executor.clone();
                 let semaphore = self.semaphore.clone();
+                let progress = self.progress.clone();
 
                 tokio::spawn(async move {
                     let _permit = semaphore.acquire().await.unwrap();
-                    executor.execute_query(&query).await
+                    let result = executor.execute_query(&query).await;
+                    if let Some(progress) = progress {
+                        progress.inc();
+                    }
+                    result
                 })
             })
             .collect();
@@ -417,6 +939,58 @@ impl ParallelQueryExecutor {
 mod tests {
     use super::*;
 
+    fn query_result(rows: &[&[&str]]) -> QueryResult {
+        let mut result = QueryResult::new("query-1".to_string(), QueryExecutionStatus::Succeeded);
+        result.rows = rows
+            .iter()
+            .map(|row| QueryRow::new(row.iter().map(|value| value.to_string()).collect()))
+            .collect();
+        result
+    }
+
+    #[test]
+    fn test_single_column_values() {
+        let result = query_result(&[&["salesdb"], &["marketingdb\t"], &[""], &[], &["logs", "x"]]);
+        assert_eq!(
+            single_column_values(&result),
+            vec!["salesdb", "marketingdb", "logs"]
+        );
+
+        assert!(single_column_values(&query_result(&[])).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_tables_query() {
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW TABLES IN `salesdb`")
+            .times(1)
+            .returning(|_| {
+                let result = query_result(&[&["customers"], &["orders"]]);
+                Box::pin(async move { Ok(result) })
+            });
+
+        assert_eq!(
+            list_tables(&runner, "salesdb").await.unwrap(),
+            vec!["customers", "orders"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_databases_empty() {
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW DATABASES")
+            .returning(|_| {
+                let result = query_result(&[]);
+                Box::pin(async move { Ok(result) })
+            });
+
+        assert!(list_databases(&runner).await.unwrap().is_empty());
+    }
+
     #[test]
     fn test_query_executor_new() {
         // Create a mock config for testing
@@ -456,6 +1030,174 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_start_query_request_catalog() {
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let executor = QueryExecutor::new(
+            AthenaClient::new(&aws_config),
+            "primary".to_string(),
+            None,
+            300,
+        );
+
+        let request = executor.start_query_request("SHOW DATABASES");
+        assert!(request.get_query_execution_context().is_none());
+
+        let executor = executor.with_catalog(Some("shared_lake".to_string()));
+        let request = executor.start_query_request("SHOW DATABASES");
+        assert_eq!(
+            request
+                .get_query_execution_context()
+                .as_ref()
+                .and_then(|context| context.catalog()),
+            Some("shared_lake")
+        );
+        assert_eq!(request.get_work_group().as_deref(), Some("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_start_query_request_data_catalog_ddl() {
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let executor = QueryExecutor::new(
+            AthenaClient::new(&aws_config),
+            "primary".to_string(),
+            None,
+            300,
+        );
+        let query = "SHOW CREATE TABLE `salesdb`.`orders`";
+
+        // Athena DDL cannot name a catalog, so the query text stays the same and
+        // the catalog is given in the execution context
+        let request = executor.start_query_request(query);
+        assert_eq!(request.get_query_string().as_deref(), Some(query));
+        assert!(request.get_query_execution_context().is_none());
+
+        let executor = executor.with_catalog(Some("dynamo_connector".to_string()));
+        let request = executor.start_query_request(query);
+        assert_eq!(request.get_query_string().as_deref(), Some(query));
+        assert_eq!(
+            request
+                .get_query_execution_context()
+                .as_ref()
+                .and_then(|context| context.catalog()),
+            Some("dynamo_connector")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_query_request_client_request_token() {
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let executor = QueryExecutor::new(
+            AthenaClient::new(&aws_config),
+            "primary".to_string(),
+            None,
+            300,
+        );
+
+        // Every query gets its own token, tagged with the run id
+        let first = executor.start_query_request("SHOW DATABASES");
+        let second = executor.start_query_request("SHOW DATABASES");
+        let first = first.get_client_request_token().clone().unwrap();
+        let second = second.get_client_request_token().clone().unwrap();
+        assert_ne!(first, second);
+        let prefix = format!("athenadef-{}-", run_id());
+        assert!(first.starts_with(&prefix));
+        assert!(second.starts_with(&prefix));
+    }
+
+    #[test]
+    fn test_output_location() {
+        assert_eq!(output_location(None), None);
+
+        let query_execution = QueryExecution::builder().build();
+        assert_eq!(output_location(Some(&query_execution)), None);
+
+        let query_execution = QueryExecution::builder()
+            .result_configuration(
+                ResultConfiguration::builder()
+                    .output_location("s3://results/athena/abc-123.txt")
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            output_location(Some(&query_execution)),
+            Some("s3://results/athena/abc-123.txt")
+        );
+    }
+
+    #[test]
+    fn test_client_request_token() {
+        let run_id = new_run_id();
+        assert_eq!(run_id.len(), 32);
+
+        let token = client_request_token(&run_id, 0);
+        assert_eq!(token, format!("athenadef-{}-0", run_id));
+        // Athena accepts tokens of 32 to 128 characters
+        assert!((32..=128).contains(&token.len()));
+        assert!(client_request_token(&run_id, u64::MAX).len() <= 128);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_workgroup_enabled_rejects_disabled() {
+        let mut inspector = MockWorkgroupInspector::new();
+        inspector
+            .expect_workgroup_state()
+            .withf(|workgroup| workgroup == "analytics")
+            .returning(|_| Box::pin(async { Ok(Some(WorkGroupState::Disabled)) }));
+
+        let err = ensure_workgroup_enabled(&inspector, "analytics")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Workgroup 'analytics' is disabled")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ensure_workgroup_enabled_proceeds() {
+        let mut inspector = MockWorkgroupInspector::new();
+        inspector
+            .expect_workgroup_state()
+            .returning(|_| Box::pin(async { Ok(Some(WorkGroupState::Enabled)) }));
+        assert!(
+            ensure_workgroup_enabled(&inspector, "primary")
+                .await
+                .is_ok()
+        );
+
+        // Without permission to read the workgroup, queries are still attempted
+        let mut inspector = MockWorkgroupInspector::new();
+        inspector
+            .expect_workgroup_state()
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("AccessDenied")) }));
+        assert!(
+            ensure_workgroup_enabled(&inspector, "primary")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_query_executor_result_reuse() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+            let client = AthenaClient::new(&aws_config);
+
+            let executor = QueryExecutor::new(client, "primary".to_string(), None, 300);
+            assert!(executor.result_reuse_configuration().is_none());
+
+            let executor = executor.with_result_reuse(Some(60));
+            let reuse = executor
+                .result_reuse_configuration()
+                .and_then(|reuse| reuse.result_reuse_by_age_configuration)
+                .unwrap();
+            assert!(reuse.enabled());
+            assert_eq!(reuse.max_age_in_minutes(), Some(60));
+        });
+    }
+
     #[test]
     fn test_parallel_query_executor_new() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -494,4 +1236,138 @@ mod tests {
             assert_eq!(parallel_executor_1.semaphore.available_permits(), 1);
         });
     }
+
+    #[test]
+    fn test_in_flight_queries_shared_by_clones() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+            let client = AthenaClient::new(&aws_config);
+
+            let executor = QueryExecutor::new(client, "primary".to_string(), None, 300);
+            let parallel_executor = ParallelQueryExecutor::new(executor.clone(), 5);
+
+            // Queries started through a clone are visible to the parallel executor
+            let in_flight = executor.clone().in_flight_queries().clone();
+            in_flight.start("exec-2");
+            in_flight.start("exec-1");
+            assert_eq!(
+                parallel_executor.started_execution_ids(),
+                vec!["exec-1".to_string(), "exec-2".to_string()]
+            );
+
+            // Finished queries are no longer candidates for stopping
+            executor.in_flight_queries().finish("exec-1");
+            assert_eq!(
+                parallel_executor.started_execution_ids(),
+                vec!["exec-2".to_string()]
+            );
+        });
+    }
+
+    #[test]
+    fn test_in_flight_queries_close() {
+        let in_flight = InFlightQueries::default();
+        assert!(in_flight.start("exec-1"));
+        assert!(!in_flight.is_closed());
+
+        assert_eq!(in_flight.clone().close(), vec!["exec-1".to_string()]);
+        assert!(in_flight.is_closed());
+
+        // A query started after the snapshot is refused, to be stopped by its caller
+        assert!(!in_flight.start("exec-2"));
+        assert_eq!(in_flight.execution_ids(), vec!["exec-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_start_query_execution_refused_after_close() {
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let executor = QueryExecutor::new(
+            AthenaClient::new(&aws_config),
+            "primary".to_string(),
+            None,
+            300,
+        );
+        executor.in_flight_queries().close();
+
+        let error = executor
+            .start_query_execution("DROP TABLE `salesdb`.`orders`")
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Query not started: interrupted");
+    }
+
+    /// Runner recording how many queries run at once
+    #[derive(Clone, Default)]
+    struct CountingRunner {
+        running: Arc<std::sync::atomic::AtomicUsize>,
+        max_running: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl QueryRunner for CountingRunner {
+        async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+            use std::sync::atomic::Ordering;
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            if query == "fail" {
+                Err(anyhow::anyhow!("access denied"))
+            } else {
+                Ok(QueryResult::new(
+                    query.to_string(),
+                    QueryExecutionStatus::Succeeded,
+                ))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_for_each_completed_reports_every_result() {
+        let runner = CountingRunner::default();
+        let parallel_executor = ParallelQueryExecutor::new(runner.clone(), 3);
+        let queries: Vec<(usize, String)> = (0..10)
+            .map(|i| {
+                (
+                    i,
+                    if i == 4 {
+                        "fail".to_string()
+                    } else {
+                        format!("q{}", i)
+                    },
+                )
+            })
+            .collect();
+
+        let mut seen = Vec::new();
+        let mut failures = 0;
+        parallel_executor
+            .for_each_completed(queries, |key, result| {
+                match result {
+                    Ok(result) => assert_eq!(result.execution_id, format!("q{}", key)),
+                    Err(_) => failures += 1,
+                }
+                seen.push(key);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // A failed query does not stop the others
+        seen.sort();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        assert_eq!(failures, 1);
+        let max_running = runner.max_running.load(std::sync::atomic::Ordering::SeqCst);
+        assert!((2..=3).contains(&max_running), "{}", max_running);
+
+        // An error from the callback is returned
+        let err = parallel_executor
+            .for_each_completed(vec![((), "q".to_string())], |_, _| {
+                Err(anyhow::anyhow!("disk full"))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
 }
@@ -0,0 +1,596 @@
+/// Table metadata from the Glue Data Catalog
+///
+/// Tables are read with Athena's ListTableMetadata and GetTableMetadata APIs against the Glue-backed
+/// `AwsDataCatalog` (or the data catalog registered for a shared Glue catalog
+/// with `catalog_id`), so the regional Athena client (and its credentials and
+/// endpoint settings) is reused instead of configuring a separate Glue client.
+use anyhow::Result;
+use aws_sdk_athena::Client as AthenaClient;
+use aws_sdk_athena::error::ProvideErrorMetadata;
+use aws_sdk_athena::operation::get_table_metadata::GetTableMetadataError;
+use aws_sdk_athena::operation::get_table_metadata::builders::GetTableMetadataFluentBuilder;
+use aws_sdk_athena::operation::list_table_metadata::builders::ListTableMetadataFluentBuilder;
+use aws_sdk_athena::types::{Column, DataCatalog, DataCatalogType, TableMetadata};
+
+use crate::aws::retry::{RetryPolicy, is_transient, with_retries};
+use crate::types::table_definition::{
+    ColumnDefinition, PartitionDefinition, StorageDescriptor, TableDefinition,
+};
+
+/// Name of the Glue Data Catalog in Athena
+pub const GLUE_CATALOG_NAME: &str = "AwsDataCatalog";
+
+/// Parameter of a GLUE-type Athena data catalog holding the Glue catalog ID
+const CATALOG_ID_PARAMETER: &str = "catalog-id";
+
+/// Error codes of requests denied by IAM
+const ACCESS_DENIED_CODES: &[&str] = &["AccessDeniedException", "AccessDenied"];
+
+/// Error codes of lookups of a table or database that does not exist
+const NOT_FOUND_CODES: &[&str] = &["EntityNotFoundException", "ResourceNotFoundException"];
+
+/// Glue error Athena names in the message of a `MetadataException` for a missing table
+const ENTITY_NOT_FOUND_MESSAGE: &str = "EntityNotFoundException";
+
+/// Most tables a ListTableMetadata call returns
+const LIST_TABLE_METADATA_PAGE_SIZE: i32 = 50;
+
+/// Prefix of table parameters holding SerDe properties
+const SERDE_PARAMETER_PREFIX: &str = "serde.param.";
+
+/// Table parameters Glue and Athena maintain themselves, never set in DDL
+const BOOKKEEPING_PARAMETERS: &[&str] = &[
+    "EXTERNAL",
+    "transient_lastDdlTime",
+    "last_modified_by",
+    "last_modified_time",
+    "metadata_location",
+    "previous_metadata_location",
+    "numFiles",
+    "numRows",
+    "rawDataSize",
+    "totalSize",
+    "COLUMN_STATS_ACCURATE",
+];
+
+/// Lookup of tables' catalog metadata, abstracted so diff logic can be tested
+/// without AWS
+#[cfg_attr(test, mockall::automock)]
+pub trait TableCatalog {
+    /// Get the definition of a table, or None if it does not exist
+    fn get_table(
+        &self,
+        database: &str,
+        table: &str,
+    ) -> impl std::future::Future<Output = Result<Option<TableDefinition>>> + Send;
+
+    /// Get the definitions of every table in a database, many per request
+    fn list_tables(
+        &self,
+        database: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<TableDefinition>>> + Send;
+}
+
+/// Client reading table definitions from the Glue Data Catalog
+#[derive(Clone)]
+pub struct GlueCatalogClient {
+    athena_client: AthenaClient,
+    retry_policy: RetryPolicy,
+    catalog_name: String,
+}
+
+impl GlueCatalogClient {
+    /// Create a new GlueCatalogClient for the account's own catalog
+    ///
+    /// # Arguments
+    /// * `athena_client` - AWS Athena client for the catalog's region
+    /// * `retry_policy` - Retries of throttled and failed GetTableMetadata calls
+    pub fn new(athena_client: AthenaClient, retry_policy: RetryPolicy) -> Self {
+        Self {
+            athena_client,
+            retry_policy,
+            catalog_name: GLUE_CATALOG_NAME.to_string(),
+        }
+    }
+
+    /// Read tables through another Athena data catalog, such as one registered
+    /// for a Glue catalog shared from another account
+    ///
+    /// # Arguments
+    /// * `catalog_name` - Athena data catalog name (see `resolve_catalog_name`)
+    pub fn with_catalog_name(mut self, catalog_name: impl Into<String>) -> Self {
+        self.catalog_name = catalog_name.into();
+        self
+    }
+
+    /// Get the definition of a table via GetTableMetadata
+    ///
+    /// # Arguments
+    /// * `database` - Database name
+    /// * `table` - Table name
+    ///
+    /// # Returns
+    /// The definition, or None if the table does not exist, such as when it was
+    /// dropped after being listed
+    pub async fn get_table(&self, database: &str, table: &str) -> Result<Option<TableDefinition>> {
+        let request = self.table_metadata_request(database, table);
+        let result = with_retries(&self.retry_policy, "GetTableMetadata", is_transient, || {
+            request.clone().send()
+        })
+        .await;
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                return match e.as_service_error().map(classify_table_metadata_error) {
+                    Some(MetadataErrorKind::NotFound) => Ok(None),
+                    Some(MetadataErrorKind::AccessDenied) => Err(anyhow::anyhow!(
+                        "Access denied reading table metadata for {}.{}: {}\n\nMake sure the credentials allow athena:GetTableMetadata, and glue:GetTable on the table, its database, and the catalog.",
+                        database,
+                        table,
+                        e
+                    )),
+                    _ => Err(anyhow::anyhow!(
+                        "Failed to get table metadata for {}.{}: {}",
+                        database,
+                        table,
+                        e
+                    )),
+                };
+            }
+        };
+
+        let metadata = response.table_metadata().ok_or_else(|| {
+            anyhow::anyhow!("No table metadata returned for {}.{}", database, table)
+        })?;
+        Ok(Some(table_definition_from_metadata(database, metadata)))
+    }
+
+    /// Get the definitions of every table in a database via ListTableMetadata
+    ///
+    /// Each call returns up to 50 tables, so a database costs far fewer round
+    /// trips than one GetTableMetadata per table.
+    ///
+    /// # Arguments
+    /// * `database` - Database name
+    pub async fn list_tables(&self, database: &str) -> Result<Vec<TableDefinition>> {
+        let mut definitions = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let request = self
+                .table_metadata_list_request(database)
+                .set_next_token(next_token.clone());
+            let response = with_retries(
+                &self.retry_policy,
+                "ListTableMetadata",
+                is_transient,
+                || request.clone().send(),
+            )
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to list table metadata for database {}: {}",
+                    database,
+                    e
+                )
+            })?;
+
+            definitions.extend(
+                response
+                    .table_metadata_list()
+                    .iter()
+                    .map(|metadata| table_definition_from_metadata(database, metadata)),
+            );
+
+            match response.next_token() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+        Ok(definitions)
+    }
+
+    /// Build the GetTableMetadata request for a table in this client's catalog
+    fn table_metadata_request(&self, database: &str, table: &str) -> GetTableMetadataFluentBuilder {
+        self.athena_client
+            .get_table_metadata()
+            .catalog_name(&self.catalog_name)
+            .database_name(database)
+            .table_name(table)
+    }
+
+    /// Build the first ListTableMetadata request for a database in this client's catalog
+    fn table_metadata_list_request(&self, database: &str) -> ListTableMetadataFluentBuilder {
+        self.athena_client
+            .list_table_metadata()
+            .catalog_name(&self.catalog_name)
+            .database_name(database)
+            .max_results(LIST_TABLE_METADATA_PAGE_SIZE)
+    }
+}
+
+impl TableCatalog for GlueCatalogClient {
+    async fn get_table(&self, database: &str, table: &str) -> Result<Option<TableDefinition>> {
+        GlueCatalogClient::get_table(self, database, table).await
+    }
+
+    async fn list_tables(&self, database: &str) -> Result<Vec<TableDefinition>> {
+        GlueCatalogClient::list_tables(self, database).await
+    }
+}
+
+/// Why a GetTableMetadata call failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetadataErrorKind {
+    /// The table or its database does not exist
+    NotFound,
+    /// The credentials lack a permission
+    AccessDenied,
+    /// Anything else, such as an invalid request or a service error
+    Other,
+}
+
+/// Classify a GetTableMetadata error by its type and error code
+///
+/// The error code is checked first. As a last resort, a `MetadataException`
+/// whose message names the Glue `EntityNotFoundException` is a missing table:
+/// Athena reports it that way with the `MetadataException` code, so there is
+/// no structured field to check. Other variants are never matched by message.
+fn classify_table_metadata_error(error: &GetTableMetadataError) -> MetadataErrorKind {
+    match error.code() {
+        Some(code) if ACCESS_DENIED_CODES.contains(&code) => {
+            return MetadataErrorKind::AccessDenied;
+        }
+        Some(code) if NOT_FOUND_CODES.contains(&code) => return MetadataErrorKind::NotFound,
+        _ => {}
+    }
+    match error {
+        GetTableMetadataError::MetadataException(exception)
+            if exception
+                .message()
+                .is_some_and(|message| message.contains(ENTITY_NOT_FOUND_MESSAGE)) =>
+        {
+            MetadataErrorKind::NotFound
+        }
+        _ => MetadataErrorKind::Other,
+    }
+}
+
+/// Find the Athena data catalog registered for a Glue Data Catalog ID
+///
+/// Athena reaches a Glue catalog shared from another account through a data
+/// catalog of type GLUE whose `catalog-id` parameter is that account's ID, so
+/// queries and metadata lookups use the name of that data catalog.
+///
+/// # Arguments
+/// * `athena_client` - AWS Athena client for the catalog's region
+/// * `retry_policy` - Retries of throttled and failed API calls
+/// * `catalog_id` - Glue Data Catalog ID, usually the owning account's ID
+pub async fn resolve_catalog_name(
+    athena_client: &AthenaClient,
+    retry_policy: &RetryPolicy,
+    catalog_id: &str,
+) -> Result<String> {
+    let mut next_token: Option<String> = None;
+    loop {
+        let request = athena_client
+            .list_data_catalogs()
+            .set_next_token(next_token.clone());
+        let response = with_retries(retry_policy, "ListDataCatalogs", is_transient, || {
+            request.clone().send()
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list Athena data catalogs: {}", e))?;
+
+        for summary in response.data_catalogs_summary() {
+            let Some(name) = summary.catalog_name() else {
+                continue;
+            };
+            if summary.r#type() != Some(&DataCatalogType::Glue) || name == GLUE_CATALOG_NAME {
+                continue;
+            }
+            let request = athena_client.get_data_catalog().name(name);
+            let response = with_retries(retry_policy, "GetDataCatalog", is_transient, || {
+                request.clone().send()
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get Athena data catalog {}: {}", name, e))?;
+            if response
+                .data_catalog()
+                .and_then(glue_catalog_id)
+                .is_some_and(|id| id == catalog_id)
+            {
+                return Ok(name.to_string());
+            }
+        }
+
+        match response.next_token() {
+            Some(token) => next_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No Athena data catalog is registered for Glue catalog '{}'.\n\nRegister one with:\n  aws athena create-data-catalog --name <name> --type GLUE --parameters catalog-id={}",
+        catalog_id,
+        catalog_id
+    ))
+}
+
+/// Glue catalog ID a GLUE-type Athena data catalog points to
+pub fn glue_catalog_id(catalog: &DataCatalog) -> Option<&str> {
+    if catalog.r#type() != &DataCatalogType::Glue {
+        return None;
+    }
+    catalog
+        .parameters()
+        .and_then(|parameters| parameters.get(CATALOG_ID_PARAMETER))
+        .map(String::as_str)
+}
+
+/// Convert Athena table metadata into a table definition
+///
+/// Storage settings arrive as table parameters (`location`, `inputformat`,
+/// `outputformat`, `serde.serialization.lib`, and `serde.param.*`) and are moved
+/// into the storage descriptor. Bookkeeping parameters are dropped; the rest are
+/// the table's TBLPROPERTIES.
+///
+/// # Arguments
+/// * `database` - Database the table belongs to
+/// * `metadata` - Metadata returned by GetTableMetadata
+pub fn table_definition_from_metadata(database: &str, metadata: &TableMetadata) -> TableDefinition {
+    let mut definition = TableDefinition::new(database.to_string(), metadata.name().to_string());
+    definition.columns = metadata
+        .columns()
+        .iter()
+        .map(|column| ColumnDefinition {
+            name: column.name().to_string(),
+            data_type: column_type(column),
+            comment: column.comment().map(str::to_string),
+        })
+        .collect();
+    definition.partitions = metadata
+        .partition_keys()
+        .iter()
+        .map(|column| PartitionDefinition {
+            name: column.name().to_string(),
+            data_type: column_type(column),
+            comment: column.comment().map(str::to_string),
+        })
+        .collect();
+
+    let mut storage = StorageDescriptor::default();
+    for (key, value) in metadata.parameters().into_iter().flatten() {
+        match key.as_str() {
+            "location" => storage.location = Some(value.clone()),
+            "inputformat" => storage.input_format = Some(value.clone()),
+            "outputformat" => storage.output_format = Some(value.clone()),
+            "serde.serialization.lib" => storage.serialization_library = Some(value.clone()),
+            "comment" => definition.comment = Some(value.clone()),
+            key if BOOKKEEPING_PARAMETERS.contains(&key) => {}
+            key => match key.strip_prefix(SERDE_PARAMETER_PREFIX) {
+                Some(property) => {
+                    storage
+                        .parameters
+                        .insert(property.to_string(), value.clone());
+                }
+                None => {
+                    definition
+                        .table_properties
+                        .insert(key.to_string(), value.clone());
+                }
+            },
+        }
+    }
+    definition.storage_descriptor = storage;
+
+    definition
+}
+
+fn column_type(column: &Column) -> String {
+    column.r#type().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_definition_from_metadata() {
+        let metadata = TableMetadata::builder()
+            .name("orders")
+            .columns(
+                Column::builder()
+                    .name("id")
+                    .r#type("bigint")
+                    .build()
+                    .unwrap(),
+            )
+            .columns(
+                Column::builder()
+                    .name("total")
+                    .r#type("decimal(10,2)")
+                    .comment("with tax")
+                    .build()
+                    .unwrap(),
+            )
+            .partition_keys(
+                Column::builder()
+                    .name("dt")
+                    .r#type("string")
+                    .build()
+                    .unwrap(),
+            )
+            .parameters("EXTERNAL", "TRUE")
+            .parameters("transient_lastDdlTime", "1700000000")
+            .parameters("location", "s3://bucket/orders/")
+            .parameters(
+                "inputformat",
+                "org.apache.hadoop.hive.ql.io.parquet.MapredParquetInputFormat",
+            )
+            .parameters(
+                "outputformat",
+                "org.apache.hadoop.hive.ql.io.parquet.MapredParquetOutputFormat",
+            )
+            .parameters(
+                "serde.serialization.lib",
+                "org.apache.hadoop.hive.ql.io.parquet.serde.ParquetHiveSerDe",
+            )
+            .parameters("serde.param.serialization.format", "1")
+            .parameters("parquet.compression", "SNAPPY")
+            .build()
+            .unwrap();
+
+        let definition = table_definition_from_metadata("salesdb", &metadata);
+        assert_eq!(definition.qualified_name(), "salesdb.orders");
+        assert_eq!(definition.columns.len(), 2);
+        assert_eq!(definition.columns[1].data_type, "decimal(10,2)");
+        assert_eq!(definition.columns[1].comment.as_deref(), Some("with tax"));
+        assert_eq!(definition.partitions[0].name, "dt");
+
+        let storage = &definition.storage_descriptor;
+        assert_eq!(storage.location.as_deref(), Some("s3://bucket/orders/"));
+        assert!(
+            storage
+                .serialization_library
+                .as_deref()
+                .unwrap()
+                .ends_with("ParquetHiveSerDe")
+        );
+        assert_eq!(storage.parameters["serialization.format"], "1");
+
+        // Bookkeeping parameters are not table properties
+        assert_eq!(definition.table_properties.len(), 1);
+        assert_eq!(definition.table_properties["parquet.compression"], "SNAPPY");
+    }
+
+    fn test_client() -> GlueCatalogClient {
+        let config = aws_sdk_athena::Config::builder()
+            .behavior_version(aws_sdk_athena::config::BehaviorVersion::latest())
+            .region(aws_sdk_athena::config::Region::new("us-east-1"))
+            .build();
+        GlueCatalogClient::new(AthenaClient::from_conf(config), RetryPolicy::default())
+    }
+
+    #[test]
+    fn test_table_metadata_request_catalog() {
+        let request = test_client().table_metadata_request("salesdb", "orders");
+        assert_eq!(
+            request.get_catalog_name().as_deref(),
+            Some(GLUE_CATALOG_NAME)
+        );
+        assert_eq!(request.get_database_name().as_deref(), Some("salesdb"));
+
+        let request = test_client()
+            .with_catalog_name("shared_lake")
+            .table_metadata_request("salesdb", "orders");
+        assert_eq!(request.get_catalog_name().as_deref(), Some("shared_lake"));
+    }
+
+    #[test]
+    fn test_table_metadata_list_request() {
+        let request = test_client()
+            .with_catalog_name("shared_lake")
+            .table_metadata_list_request("salesdb");
+        assert_eq!(request.get_catalog_name().as_deref(), Some("shared_lake"));
+        assert_eq!(request.get_database_name().as_deref(), Some("salesdb"));
+        assert_eq!(
+            request.get_max_results(),
+            &Some(LIST_TABLE_METADATA_PAGE_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_classify_table_metadata_error() {
+        use aws_sdk_athena::error::ErrorMetadata;
+        use aws_sdk_athena::types::error::{InvalidRequestException, MetadataException};
+
+        let not_found = GetTableMetadataError::MetadataException(
+            MetadataException::builder()
+                .message("EntityNotFoundException: Table orders not found")
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&not_found),
+            MetadataErrorKind::NotFound
+        );
+
+        let access_denied = GetTableMetadataError::generic(
+            ErrorMetadata::builder()
+                .code("AccessDeniedException")
+                .message("User is not authorized to perform: glue:GetTable")
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&access_denied),
+            MetadataErrorKind::AccessDenied
+        );
+
+        // The error code decides, whatever the message says
+        let not_found_by_code = GetTableMetadataError::generic(
+            ErrorMetadata::builder()
+                .code("EntityNotFoundException")
+                .message("Table orders does not exist in database salesdb")
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&not_found_by_code),
+            MetadataErrorKind::NotFound
+        );
+
+        let metadata_not_found_by_code = GetTableMetadataError::MetadataException(
+            MetadataException::builder()
+                .message("Could not find table orders")
+                .meta(
+                    ErrorMetadata::builder()
+                        .code("ResourceNotFoundException")
+                        .build(),
+                )
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&metadata_not_found_by_code),
+            MetadataErrorKind::NotFound
+        );
+
+        // Other metastore failures are not mistaken for a missing table
+        let lambda_failure = GetTableMetadataError::MetadataException(
+            MetadataException::builder()
+                .message("Lambda function returned 403")
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&lambda_failure),
+            MetadataErrorKind::Other
+        );
+
+        let invalid = GetTableMetadataError::InvalidRequestException(
+            InvalidRequestException::builder()
+                .message("EntityNotFoundException")
+                .build(),
+        );
+        assert_eq!(
+            classify_table_metadata_error(&invalid),
+            MetadataErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_glue_catalog_id() {
+        let catalog = DataCatalog::builder()
+            .name("shared_lake")
+            .r#type(DataCatalogType::Glue)
+            .parameters("catalog-id", "210987654321")
+            .build()
+            .unwrap();
+        assert_eq!(glue_catalog_id(&catalog), Some("210987654321"));
+
+        let catalog = DataCatalog::builder()
+            .name("federated")
+            .r#type(DataCatalogType::Lambda)
+            .parameters("catalog-id", "210987654321")
+            .build()
+            .unwrap();
+        assert_eq!(glue_catalog_id(&catalog), None);
+    }
+}
@@ -1,2 +1,6 @@
 pub mod athena;
+pub mod glue;
+pub mod region;
+pub mod retry;
 pub mod s3;
+pub mod sdk_config;
@@ -0,0 +1,293 @@
+use anyhow::Result;
+use aws_sdk_athena::Client as AthenaClient;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::aws::athena::{QueryExecutor, ensure_workgroup_enabled};
+use crate::aws::glue::resolve_catalog_name;
+use crate::aws::retry::{DEFAULT_MAX_RETRIES, DEFAULT_RETRY_BASE_DELAY_MS, RetryPolicy};
+use crate::aws::s3::{S3Manager, check_output_location_region};
+use crate::aws::sdk_config::{
+    build_aws_config, load_sdk_config, profile_override, region_override, resolve_region,
+};
+use crate::types::config::Config;
+
+/// Maps databases to the region whose catalog holds them
+///
+/// A region of None means the default region (from `--region`, then `region` in
+/// the config, or the environment when both are unset).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RegionRoutes {
+    default_region: Option<String>,
+    overrides: BTreeMap<String, String>,
+}
+
+impl RegionRoutes {
+    /// Build routes from the configuration's `region` and `region_overrides`
+    ///
+    /// `--region` replaces `region` as the default region. Overrides pointing at
+    /// the default region are dropped, so those databases share the default group.
+    /// Database names match case-insensitively, as Athena stores them in lower case.
+    pub fn from_config(config: &Config) -> Self {
+        Self::with_region_override(config, region_override())
+    }
+
+    /// Build routes with the default region taken from `cli_region` when given
+    ///
+    /// # Arguments
+    /// * `config` - Loaded athenadef configuration
+    /// * `cli_region` - Region given with `--region`
+    pub fn with_region_override(config: &Config, cli_region: Option<&str>) -> Self {
+        let default_region = resolve_region(cli_region, config).map(str::to_string);
+        let overrides = config
+            .region_overrides
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, region)| Some(region) != default_region.as_ref())
+            .map(|(database_name, region)| (database_name.to_lowercase(), region))
+            .collect();
+
+        Self {
+            default_region,
+            overrides,
+        }
+    }
+
+    /// Get the region for a database, or None for the default region
+    pub fn region_for(&self, database_name: &str) -> Option<&str> {
+        self.overrides
+            .get(&database_name.to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Get every effective region, the default region (None) first
+    pub fn regions(&self) -> Vec<Option<&str>> {
+        let overridden: BTreeSet<&str> = self.overrides.values().map(String::as_str).collect();
+        std::iter::once(None)
+            .chain(overridden.into_iter().map(Some))
+            .collect()
+    }
+
+    /// Get the configured default region, if any
+    pub fn default_region(&self) -> Option<&str> {
+        self.default_region.as_deref()
+    }
+}
+
+/// Query executors for each effective region, routed by database
+pub struct RegionalExecutors {
+    routes: RegionRoutes,
+    executors: HashMap<Option<String>, QueryExecutor>,
+}
+
+impl RegionalExecutors {
+    /// Create a query executor for the default region and each overridden region
+    ///
+    /// With `--catalog` or `data_catalog`, each executor uses that Athena data
+    /// catalog. Otherwise, with `catalog_id`, it uses the data catalog registered
+    /// for that Glue catalog in its region. Fails if no such data catalog exists,
+    /// if the workgroup is disabled in any of the regions, or if the
+    /// `output_location` bucket is in another region and
+    /// `output_location_region_check` is `error`.
+    ///
+    /// # Arguments
+    /// * `config` - Loaded athenadef configuration
+    pub async fn from_config(config: &Config) -> Result<Self> {
+        let routes = RegionRoutes::from_config(config);
+        let mut executors = HashMap::new();
+
+        for region in routes.regions() {
+            let aws_config = match region {
+                Some(region) => build_aws_config(config, Some(region), profile_override()).await?,
+                None => load_sdk_config(config).await?,
+            };
+            let athena_client = AthenaClient::new(&aws_config);
+            let retry_policy = RetryPolicy::new(
+                config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+                config
+                    .retry_base_delay_ms
+                    .unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS),
+            );
+            let catalog = match (config.data_catalog.clone(), config.catalog_id.as_deref()) {
+                (Some(data_catalog), _) => Some(data_catalog),
+                (None, Some(catalog_id)) => {
+                    Some(resolve_catalog_name(&athena_client, &retry_policy, catalog_id).await?)
+                }
+                (None, None) => None,
+            };
+            let executor = QueryExecutor::new(
+                athena_client,
+                config.workgroup.clone(),
+                config.output_location.clone(),
+                config.query_timeout_seconds.unwrap_or(300),
+            )
+            .with_result_reuse(config.result_reuse_max_age_minutes)
+            .with_retry_policy(retry_policy)
+            .with_catalog(catalog)
+            .with_result_cleanup(config.cleanup_query_results.unwrap_or(false).then(|| {
+                S3Manager::from_sdk_config(&aws_config, config.s3_force_path_style.unwrap_or(false))
+            }));
+            ensure_workgroup_enabled(&executor, &config.workgroup).await?;
+
+            if let (Some(output_location), Some(athena_region)) =
+                (config.output_location.as_deref(), aws_config.region())
+            {
+                let s3_manager = S3Manager::from_sdk_config(
+                    &aws_config,
+                    config.s3_force_path_style.unwrap_or(false),
+                );
+                check_output_location_region(
+                    &s3_manager,
+                    output_location,
+                    athena_region.as_ref(),
+                    config.output_location_region_check.unwrap_or_default(),
+                )
+                .await?;
+            }
+            executors.insert(region.map(str::to_string), executor);
+        }
+
+        Ok(Self { routes, executors })
+    }
+
+    /// Get the routes used by these executors
+    pub fn routes(&self) -> &RegionRoutes {
+        &self.routes
+    }
+
+    /// Get the executor for the region a database lives in
+    pub fn for_database(&self, database_name: &str) -> &QueryExecutor {
+        self.for_region(self.routes.region_for(database_name))
+    }
+
+    /// Stop the queries still in flight in every region
+    ///
+    /// # Returns
+    /// The number of queries stopped
+    pub async fn stop_in_flight_queries(&self) -> usize {
+        let mut stopped = 0;
+        for executor in self.executors.values() {
+            stopped += executor.stop_in_flight_queries().await.len();
+        }
+        stopped
+    }
+
+    /// Get the executor for a region (None for the default region)
+    pub fn for_region(&self, region: Option<&str>) -> &QueryExecutor {
+        self.executors
+            .get(&region.map(str::to_string))
+            .expect("an executor is created for every routed region")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_overrides(region: Option<&str>, overrides: &[(&str, &str)]) -> Config {
+        Config {
+            region: region.map(str::to_string),
+            region_overrides: Some(
+                overrides
+                    .iter()
+                    .map(|(db, region)| (db.to_string(), region.to_string()))
+                    .collect(),
+            ),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_region_for_overridden_database() {
+        let routes = RegionRoutes::from_config(&config_with_overrides(
+            Some("us-east-1"),
+            &[("eudb", "eu-west-1"), ("apdb", "ap-northeast-1")],
+        ));
+
+        assert_eq!(routes.region_for("eudb"), Some("eu-west-1"));
+        assert_eq!(routes.region_for("apdb"), Some("ap-northeast-1"));
+        assert_eq!(routes.region_for("salesdb"), None);
+        assert_eq!(routes.default_region(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn test_region_for_ignores_database_name_case() {
+        let routes = RegionRoutes::from_config(&config_with_overrides(
+            Some("us-east-1"),
+            &[("EuDB", "eu-west-1")],
+        ));
+
+        assert_eq!(routes.region_for("eudb"), Some("eu-west-1"));
+        assert_eq!(routes.region_for("EUDB"), Some("eu-west-1"));
+        assert_eq!(routes.region_for("salesdb"), None);
+    }
+
+    #[test]
+    fn test_region_override_replaces_default_region() {
+        let config = config_with_overrides(
+            Some("us-east-1"),
+            &[("eudb", "eu-west-1"), ("apdb", "ap-northeast-1")],
+        );
+        let routes = RegionRoutes::with_region_override(&config, Some("eu-west-1"));
+
+        assert_eq!(routes.default_region(), Some("eu-west-1"));
+        // Databases overridden to the new default region join the default group
+        assert_eq!(routes.region_for("eudb"), None);
+        assert_eq!(routes.region_for("apdb"), Some("ap-northeast-1"));
+        assert_eq!(routes.regions(), vec![None, Some("ap-northeast-1")]);
+
+        let routes = RegionRoutes::with_region_override(&config, None);
+        assert_eq!(routes.default_region(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn test_regions_default_first_and_deduplicated() {
+        let routes = RegionRoutes::from_config(&config_with_overrides(
+            None,
+            &[
+                ("eudb1", "eu-west-1"),
+                ("eudb2", "eu-west-1"),
+                ("apdb", "ap-northeast-1"),
+            ],
+        ));
+
+        assert_eq!(
+            routes.regions(),
+            vec![None, Some("ap-northeast-1"), Some("eu-west-1")]
+        );
+    }
+
+    #[test]
+    fn test_override_to_default_region_uses_default_group() {
+        let routes = RegionRoutes::from_config(&config_with_overrides(
+            Some("us-east-1"),
+            &[("salesdb", "us-east-1")],
+        ));
+
+        assert_eq!(routes.region_for("salesdb"), None);
+        assert_eq!(routes.regions(), vec![None]);
+    }
+
+    #[test]
+    fn test_no_overrides() {
+        let routes = RegionRoutes::from_config(&Config::default());
+        assert_eq!(routes.region_for("salesdb"), None);
+        assert_eq!(routes.regions(), vec![None]);
+    }
+
+    #[tokio::test]
+    async fn test_regional_executors_route_by_database() {
+        let executors = RegionalExecutors::from_config(&config_with_overrides(
+            Some("us-east-1"),
+            &[("eudb", "eu-west-1")],
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(executors.for_database("eudb").region(), Some("eu-west-1"));
+        assert_eq!(
+            executors.for_database("salesdb").region(),
+            Some("us-east-1")
+        );
+    }
+}
@@ -0,0 +1,241 @@
+/// Retries of transient AWS API errors
+///
+/// Throttling (`ThrottlingException`, `TooManyRequestsException`, HTTP 429),
+/// server errors (HTTP 5xx), timeouts, and connection failures are retried with
+/// exponential backoff and jitter. Any other error, such as access denied or an
+/// invalid request, is returned immediately.
+use aws_sdk_athena::config::http::HttpResponse;
+use aws_sdk_athena::error::{ProvideErrorMetadata, SdkError};
+use std::future::Future;
+use std::time::Duration;
+use tracing::debug;
+
+/// Default number of retries after the first attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Default delay before the first retry, in milliseconds
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 200;
+
+/// Upper bound for a single backoff delay, before jitter
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(20);
+
+/// Error codes AWS services use for throttling
+const THROTTLING_CODES: &[&str] = &[
+    "ThrottlingException",
+    "Throttling",
+    "TooManyRequestsException",
+    "RequestLimitExceeded",
+];
+
+/// How often and how long to wait when retrying transient errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each later one
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy from the `max_retries` and `retry_base_delay_ms` settings
+    pub fn new(max_retries: u32, base_delay_ms: u64) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(base_delay_ms),
+        }
+    }
+
+    /// Backoff before a retry, without jitter
+    ///
+    /// # Arguments
+    /// * `retry` - Zero-based index of the retry
+    ///
+    /// # Returns
+    /// `base_delay * 2^retry`, capped at 20 seconds
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+    }
+
+    /// Backoff before a retry with jitter applied
+    ///
+    /// Half of the backoff is kept and the other half is scaled by `jitter`, so
+    /// concurrent queries throttled together do not retry in lockstep.
+    ///
+    /// # Arguments
+    /// * `retry` - Zero-based index of the retry
+    /// * `jitter` - Random fraction in `0.0..=1.0`
+    pub fn delay(&self, retry: u32, jitter: f64) -> Duration {
+        let backoff = self.backoff(retry);
+        let half = backoff / 2;
+        half + half.mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// Whether an AWS SDK error is worth retrying
+pub fn is_transient<E: ProvideErrorMetadata>(error: &SdkError<E, HttpResponse>) -> bool {
+    match error {
+        SdkError::TimeoutError(_) | SdkError::ResponseError(_) => true,
+        SdkError::DispatchFailure(failure) => failure.is_io() || failure.is_timeout(),
+        SdkError::ServiceError(service_error) => {
+            let status = service_error.raw().status();
+            status.is_server_error()
+                || status.as_u16() == 429
+                || service_error
+                    .err()
+                    .code()
+                    .is_some_and(|code| THROTTLING_CODES.contains(&code))
+        }
+        _ => false,
+    }
+}
+
+/// Run an operation, retrying transient errors according to a policy
+///
+/// # Arguments
+/// * `policy` - Number of retries and backoff
+/// * `operation` - API operation name, for logging
+/// * `is_retryable` - Whether an error is transient
+/// * `attempt` - Starts one attempt of the operation
+pub async fn with_retries<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    operation: &str,
+    is_retryable: impl Fn(&E) -> bool,
+    mut attempt: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retry = 0;
+    loop {
+        match attempt().await {
+            Err(error) if retry < policy.max_retries && is_retryable(&error) => {
+                let delay = policy.delay(retry, random_fraction());
+                debug!(
+                    operation,
+                    retry = retry + 1,
+                    max_retries = policy.max_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    "Retrying after transient error"
+                );
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Random fraction in `0.0..1.0` for jitter, taken from a v4 UUID's random bits
+fn random_fraction() -> f64 {
+    // The low 53 bits are random (version and variant bits sit higher up)
+    let bits = uuid::Uuid::new_v4().as_u128() as u64 & ((1 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_schedule_doubles_until_cap() {
+        let policy = RetryPolicy::default();
+        let schedule: Vec<u64> = (0..10)
+            .map(|retry| policy.backoff(retry).as_millis() as u64)
+            .collect();
+        assert_eq!(
+            schedule,
+            vec![200, 400, 800, 1600, 3200, 6400, 12800, 20000, 20000, 20000]
+        );
+
+        // Very large retry counts saturate instead of overflowing
+        assert_eq!(policy.backoff(64), MAX_RETRY_DELAY);
+        assert_eq!(RetryPolicy::new(3, 0).backoff(2), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_delay_jitter_stays_within_backoff() {
+        let policy = RetryPolicy::new(5, 1000);
+        assert_eq!(policy.delay(1, 0.0), Duration::from_millis(1000));
+        assert_eq!(policy.delay(1, 1.0), Duration::from_millis(2000));
+        assert_eq!(policy.delay(1, 0.5), Duration::from_millis(1500));
+        // Out-of-range jitter is clamped
+        assert_eq!(policy.delay(0, 7.0), Duration::from_millis(1000));
+
+        for _ in 0..100 {
+            let fraction = random_fraction();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_retries_transient_errors() {
+        let policy = RetryPolicy::new(3, 1);
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<u32, &str> = with_retries(
+            &policy,
+            "Test",
+            |error| *error == "throttled",
+            || async {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                if attempt < 2 {
+                    Err("throttled")
+                } else {
+                    Ok(attempt)
+                }
+            },
+        )
+        .await;
+        assert_eq!(result, Ok(2));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_gives_up() {
+        let policy = RetryPolicy::new(2, 1);
+
+        // Transient errors are retried max_retries times, then returned
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), &str> = with_retries(
+            &policy,
+            "Test",
+            |error| *error == "throttled",
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err("throttled")
+            },
+        )
+        .await;
+        assert_eq!(result, Err("throttled"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // Other errors fail on the first attempt
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), &str> = with_retries(
+            &policy,
+            "Test",
+            |error| *error == "throttled",
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err("access denied")
+            },
+        )
+        .await;
+        assert_eq!(result, Err("access denied"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,5 +1,101 @@
 use anyhow::{Context, Result};
+use aws_config::SdkConfig;
 use aws_sdk_s3::Client as S3Client;
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use std::time::SystemTime;
+use tracing::info;
+
+use crate::types::config::RegionCheck;
+use crate::warnings::warn;
+
+/// Maximum number of keys accepted by a single S3 DeleteObjects request
+pub const MAX_DELETE_BATCH_SIZE: usize = 1000;
+
+/// An object listed from S3
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Object {
+    pub key: String,
+    pub size: i64,
+    pub last_modified: Option<SystemTime>,
+}
+
+/// Object listing and batch deletion, abstracted so maintenance logic can be tested
+/// without a real bucket
+#[cfg_attr(test, mockall::automock)]
+pub trait ObjectStore {
+    /// List all objects under an S3 prefix (e.g., "s3://bucket-name/path/")
+    fn list_objects(
+        &self,
+        s3_prefix: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<S3Object>>> + Send;
+
+    /// Delete a single batch of keys (at most `MAX_DELETE_BATCH_SIZE`) from a bucket
+    fn delete_objects(
+        &self,
+        bucket: &str,
+        keys: &[String],
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+}
+
+/// Bucket region lookup, abstracted so configuration checks can be tested without AWS
+#[cfg_attr(test, mockall::automock)]
+pub trait BucketLocator {
+    /// Get the region a bucket lives in (e.g., "eu-west-1")
+    fn bucket_region(
+        &self,
+        bucket: &str,
+    ) -> impl std::future::Future<Output = Result<String>> + Send;
+}
+
+/// Compare the region of the `output_location` bucket with the Athena region
+///
+/// Depending on `check`, a mismatch is ignored, reported as a warning, or returned
+/// as an error. If the bucket region cannot be looked up (e.g., missing
+/// s3:GetBucketLocation permission), the check is skipped.
+///
+/// # Arguments
+/// * `locator` - Bucket region lookup
+/// * `output_location` - Configured query result location (e.g., "s3://bucket/prefix/")
+/// * `athena_region` - Region queries run in
+/// * `check` - What to do on a mismatch
+pub async fn check_output_location_region<L: BucketLocator>(
+    locator: &L,
+    output_location: &str,
+    athena_region: &str,
+    check: RegionCheck,
+) -> Result<()> {
+    if check == RegionCheck::Off {
+        return Ok(());
+    }
+
+    let (bucket, _) = S3Manager::parse_s3_prefix(output_location)?;
+    let bucket_region = match locator.bucket_region(&bucket).await {
+        Ok(region) => region,
+        Err(e) => {
+            info!(
+                "Could not look up the region of bucket {}, skipping the output_location region check: {:#}",
+                bucket, e
+            );
+            return Ok(());
+        }
+    };
+
+    if bucket_region == athena_region {
+        return Ok(());
+    }
+
+    let message = format!(
+        "output_location {} is in region {}, but Athena runs in {}. Athena cannot write query results across regions; use a bucket in {} or change region.",
+        output_location, bucket_region, athena_region, athena_region
+    );
+    match check {
+        RegionCheck::Error => Err(anyhow::anyhow!(message)),
+        _ => {
+            warn(message);
+            Ok(())
+        }
+    }
+}
 
 /// Client for S3 operations related to Athena query results
 #[derive(Clone)]
@@ -16,6 +112,19 @@ impl S3Manager {
         Self { s3_client }
     }
 
+    /// Create an S3Manager from a loaded SDK configuration
+    ///
+    /// # Arguments
+    /// * `aws_config` - SDK configuration, including any `endpoint_url`
+    /// * `force_path_style` - Address buckets as `endpoint/bucket` instead of
+    ///   `bucket.endpoint`, as S3 mocks such as LocalStack expect
+    pub fn from_sdk_config(aws_config: &SdkConfig, force_path_style: bool) -> Self {
+        let s3_config = aws_sdk_s3::config::Builder::from(aws_config)
+            .force_path_style(force_path_style)
+            .build();
+        Self::new(S3Client::from_conf(s3_config))
+    }
+
     /// Retrieve query result from S3
     ///
     /// # Arguments
@@ -97,14 +206,15 @@ impl S3Manager {
     /// # Returns
     /// Ok if both deletions succeeded or if files don't exist
     pub async fn cleanup_query_result(&self, s3_url: &str) -> Result<()> {
+        let (result_url, metadata_url) = Self::query_result_urls(s3_url)?;
+
         // Delete the result file
-        if let Err(e) = self.delete_query_result(s3_url).await {
+        if let Err(e) = self.delete_query_result(&result_url).await {
             // Log but don't fail if file doesn't exist
-            tracing::debug!("Failed to delete result file {}: {}", s3_url, e);
+            tracing::debug!("Failed to delete result file {}: {}", result_url, e);
         }
 
         // Delete the metadata file
-        let metadata_url = format!("{}.metadata", s3_url);
         if let Err(e) = self.delete_query_result(&metadata_url).await {
             // Log but don't fail if metadata file doesn't exist
             tracing::debug!("Failed to delete metadata file {}: {}", metadata_url, e);
@@ -154,6 +264,44 @@ impl S3Manager {
             .is_ok()
     }
 
+    /// Get the URLs of the result file and metadata file of a query
+    ///
+    /// # Arguments
+    /// * `output_location` - Result location Athena reported for the query
+    ///   (e.g., "s3://bucket-name/results/query-id.csv")
+    ///
+    /// # Returns
+    /// Tuple of (result_url, metadata_url)
+    pub fn query_result_urls(output_location: &str) -> Result<(String, String)> {
+        let (bucket, key) = Self::parse_s3_url(output_location)?;
+        let result_url = format!("s3://{}/{}", bucket, key);
+        let metadata_url = format!("{}.metadata", result_url);
+        Ok((result_url, metadata_url))
+    }
+
+    /// Parse an S3 prefix URL into bucket and (possibly empty) prefix components
+    ///
+    /// # Arguments
+    /// * `s3_url` - S3 URL (e.g., "s3://bucket-name/path/" or "s3://bucket-name")
+    ///
+    /// # Returns
+    /// Tuple of (bucket_name, key_prefix)
+    pub fn parse_s3_prefix(s3_url: &str) -> Result<(String, String)> {
+        let url_without_prefix = s3_url
+            .strip_prefix("s3://")
+            .ok_or_else(|| anyhow::anyhow!("Invalid S3 URL: must start with s3://"))?;
+
+        let (bucket, prefix) = url_without_prefix
+            .split_once('/')
+            .unwrap_or((url_without_prefix, ""));
+
+        if bucket.is_empty() {
+            return Err(anyhow::anyhow!("Invalid S3 URL: bucket must not be empty"));
+        }
+
+        Ok((bucket.to_string(), prefix.to_string()))
+    }
+
     /// Parse S3 URL into bucket and key components
     ///
     /// # Arguments
@@ -188,6 +336,120 @@ impl S3Manager {
     }
 }
 
+impl ObjectStore for S3Manager {
+    async fn list_objects(&self, s3_prefix: &str) -> Result<Vec<S3Object>> {
+        let (bucket, prefix) = Self::parse_s3_prefix(s3_prefix)?;
+
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut request = self
+                .s3_client
+                .list_objects_v2()
+                .bucket(&bucket)
+                .prefix(&prefix);
+
+            if let Some(token) = continuation_token {
+                request = request.continuation_token(token);
+            }
+
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Failed to list objects under {}", s3_prefix))?;
+
+            for object in response.contents() {
+                let Some(key) = object.key() else {
+                    continue;
+                };
+                objects.push(S3Object {
+                    key: key.to_string(),
+                    size: object.size().unwrap_or(0),
+                    last_modified: object
+                        .last_modified()
+                        .and_then(|dt| SystemTime::try_from(*dt).ok()),
+                });
+            }
+
+            continuation_token = response.next_continuation_token().map(|s| s.to_string());
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn delete_objects(&self, bucket: &str, keys: &[String]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        if keys.len() > MAX_DELETE_BATCH_SIZE {
+            return Err(anyhow::anyhow!(
+                "Cannot delete {} objects in one request (maximum is {})",
+                keys.len(),
+                MAX_DELETE_BATCH_SIZE
+            ));
+        }
+
+        let objects = keys
+            .iter()
+            .map(|key| ObjectIdentifier::builder().key(key).build())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to build object identifiers")?;
+
+        let delete = Delete::builder()
+            .set_objects(Some(objects))
+            .quiet(true)
+            .build()
+            .context("Failed to build delete request")?;
+
+        let response = self
+            .s3_client
+            .delete_objects()
+            .bucket(bucket)
+            .delete(delete)
+            .send()
+            .await
+            .context("Failed to delete objects from S3")?;
+
+        if let Some(first_error) = response.errors().first() {
+            return Err(anyhow::anyhow!(
+                "Failed to delete {} object(s) from s3://{}, e.g. {}: {}",
+                response.errors().len(),
+                bucket,
+                first_error.key().unwrap_or("unknown key"),
+                first_error.message().unwrap_or("unknown error")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl BucketLocator for S3Manager {
+    async fn bucket_region(&self, bucket: &str) -> Result<String> {
+        let response = self
+            .s3_client
+            .get_bucket_location()
+            .bucket(bucket)
+            .send()
+            .await
+            .with_context(|| format!("Failed to get the location of bucket {}", bucket))?;
+
+        // Buckets in us-east-1 have no location constraint, and "EU" is the legacy
+        // name for eu-west-1
+        let region = match response.location_constraint().map(|c| c.as_str()) {
+            None | Some("") => "us-east-1",
+            Some("EU") => "eu-west-1",
+            Some(region) => region,
+        };
+
+        Ok(region.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +531,50 @@ mod tests {
         assert_eq!(key, "path/file.csv?version=1");
     }
 
+    #[test]
+    fn test_parse_s3_prefix_with_path() {
+        let (bucket, prefix) =
+            S3Manager::parse_s3_prefix("s3://my-bucket/athena/results/").unwrap();
+        assert_eq!(bucket, "my-bucket");
+        assert_eq!(prefix, "athena/results/");
+    }
+
+    #[test]
+    fn test_parse_s3_prefix_bucket_only() {
+        let (bucket, prefix) = S3Manager::parse_s3_prefix("s3://my-bucket").unwrap();
+        assert_eq!(bucket, "my-bucket");
+        assert_eq!(prefix, "");
+
+        let (bucket, prefix) = S3Manager::parse_s3_prefix("s3://my-bucket/").unwrap();
+        assert_eq!(bucket, "my-bucket");
+        assert_eq!(prefix, "");
+    }
+
+    #[test]
+    fn test_parse_s3_prefix_invalid() {
+        assert!(S3Manager::parse_s3_prefix("my-bucket/path/").is_err());
+        assert!(S3Manager::parse_s3_prefix("s3:///path/").is_err());
+    }
+
+    #[test]
+    fn test_query_result_urls() {
+        let (result_url, metadata_url) =
+            S3Manager::query_result_urls("s3://my-bucket/athena/results/abc-123.csv").unwrap();
+        assert_eq!(result_url, "s3://my-bucket/athena/results/abc-123.csv");
+        assert_eq!(
+            metadata_url,
+            "s3://my-bucket/athena/results/abc-123.csv.metadata"
+        );
+
+        // DDL queries write a .txt result
+        let (_, metadata_url) = S3Manager::query_result_urls("s3://my-bucket/abc-123.txt").unwrap();
+        assert_eq!(metadata_url, "s3://my-bucket/abc-123.txt.metadata");
+
+        // A bucket alone is never deleted
+        assert!(S3Manager::query_result_urls("s3://my-bucket/").is_err());
+        assert!(S3Manager::query_result_urls("my-bucket/abc-123.csv").is_err());
+    }
+
     #[tokio::test]
     async fn test_s3_manager_new() {
         let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
@@ -278,4 +584,81 @@ mod tests {
         // Just verify we can create the manager
         assert!(std::mem::size_of_val(&manager) > 0);
     }
+
+    fn locator_returning(region: &'static str) -> MockBucketLocator {
+        let mut locator = MockBucketLocator::new();
+        locator
+            .expect_bucket_region()
+            .withf(|bucket| bucket == "results-bucket")
+            .returning(move |_| Box::pin(async move { Ok(region.to_string()) }));
+        locator
+    }
+
+    #[tokio::test]
+    async fn test_check_output_location_region_mismatch() {
+        let locator = locator_returning("eu-west-1");
+
+        let err = check_output_location_region(
+            &locator,
+            "s3://results-bucket/athena/",
+            "us-east-1",
+            RegionCheck::Error,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("is in region eu-west-1"));
+        assert!(err.to_string().contains("Athena runs in us-east-1"));
+
+        // Warn only reports the mismatch
+        check_output_location_region(
+            &locator,
+            "s3://results-bucket/athena/",
+            "us-east-1",
+            RegionCheck::Warn,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_output_location_region_match() {
+        let locator = locator_returning("us-east-1");
+
+        check_output_location_region(
+            &locator,
+            "s3://results-bucket/athena/",
+            "us-east-1",
+            RegionCheck::Error,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_output_location_region_off_or_lookup_failure() {
+        let mut locator = MockBucketLocator::new();
+        locator.expect_bucket_region().times(0);
+        check_output_location_region(
+            &locator,
+            "s3://results-bucket/",
+            "us-east-1",
+            RegionCheck::Off,
+        )
+        .await
+        .unwrap();
+
+        let mut locator = MockBucketLocator::new();
+        locator
+            .expect_bucket_region()
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("AccessDenied")) }));
+        check_output_location_region(
+            &locator,
+            "s3://results-bucket/",
+            "us-east-1",
+            RegionCheck::Error,
+        )
+        .await
+        .unwrap();
+    }
 }
@@ -0,0 +1,465 @@
+use anyhow::Result;
+use aws_config::SdkConfig;
+use aws_config::profile::ProfileFileCredentialsProvider;
+use aws_config::sts::AssumeRoleProvider;
+use aws_credential_types::provider::{self, future};
+use aws_runtime::env_config::file::EnvConfigFiles;
+use aws_sdk_athena::config::{Credentials, ProvideCredentials, Region, SharedCredentialsProvider};
+use aws_types::os_shim_internal::{Env, Fs};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tracing::info;
+
+use crate::types::config::Config;
+
+/// Session name used when assuming a role without `session_name`
+pub const DEFAULT_ASSUME_ROLE_SESSION_NAME: &str = "athenadef";
+
+/// How long before expiry assumed-role credentials are refreshed
+const REFRESH_BEFORE_EXPIRY: Duration = Duration::from_secs(300);
+
+/// Assumed-role sessions shared by every client created in this process
+static ASSUMED_ROLES: Mutex<Vec<(CredentialsSource, SharedCredentialsProvider)>> =
+    Mutex::new(Vec::new());
+
+/// Profile given with the global `--profile` flag
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Region given with the global `--region` flag
+static REGION_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Use a named profile for every AWS client, overriding `profile` in athenadef.yaml
+///
+/// Called once by the CLI before a command runs; later calls are ignored.
+pub fn set_profile_override(profile: &str) {
+    let _ = PROFILE_OVERRIDE.set(profile.to_string());
+}
+
+/// Use a default region for every AWS client, overriding `region` in athenadef.yaml
+///
+/// Called once by the CLI before a command runs; later calls are ignored.
+/// Databases in `region_overrides` keep their own region.
+pub fn set_region_override(region: &str) {
+    let _ = REGION_OVERRIDE.set(region.to_string());
+}
+
+/// Profile given with `--profile`, if any
+pub fn profile_override() -> Option<&'static str> {
+    PROFILE_OVERRIDE.get().map(String::as_str)
+}
+
+/// Region given with `--region`, if any
+pub fn region_override() -> Option<&'static str> {
+    REGION_OVERRIDE.get().map(String::as_str)
+}
+
+/// Pick the default region to use, the `--region` flag taking precedence
+///
+/// Empty names count as unset. None means the region is taken from the
+/// environment (or the named profile).
+///
+/// # Arguments
+/// * `cli_region` - Region given with `--region`
+/// * `config` - Loaded athenadef configuration
+pub fn resolve_region<'a>(cli_region: Option<&'a str>, config: &'a Config) -> Option<&'a str> {
+    cli_region
+        .filter(|region| !region.is_empty())
+        .or(config.region.as_deref())
+        .filter(|region| !region.is_empty())
+}
+
+/// Pick the named profile to use, the `--profile` flag taking precedence
+///
+/// Empty names count as unset.
+///
+/// # Arguments
+/// * `cli_profile` - Profile given with `--profile`
+/// * `config` - Loaded athenadef configuration
+pub fn resolve_profile<'a>(cli_profile: Option<&'a str>, config: &'a Config) -> Option<&'a str> {
+    cli_profile
+        .or(config.profile.as_deref())
+        .filter(|profile| !profile.is_empty())
+}
+
+/// Where base credentials come from before any configured role is assumed
+///
+/// Precedence, highest first:
+/// 1. `assume_role` (or the `assume_role_arn` shorthand) in athenadef.yaml, assumed
+///    using the credentials below
+/// 2. The named profile from `--profile` or `profile` in athenadef.yaml, read from
+///    the shared config and credentials files; environment credentials are ignored
+/// 3. The AWS default provider chain: environment access keys, `AWS_PROFILE` /
+///    shared config, web identity (`AWS_WEB_IDENTITY_TOKEN_FILE` + `AWS_ROLE_ARN`,
+///    as set up by EKS IRSA), ECS container credentials, then EC2 instance metadata
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialsSource {
+    /// Use the default provider chain as-is
+    DefaultChain,
+    /// Assume a role on top of the default provider chain
+    AssumeRole {
+        role_arn: String,
+        external_id: Option<String>,
+        session_name: String,
+    },
+}
+
+impl CredentialsSource {
+    /// Determine the credentials source for a configuration
+    ///
+    /// The `assume_role` section is used when present, then `assume_role_arn`.
+    pub fn from_config(config: &Config) -> Self {
+        if let Some(ref assume_role) = config.assume_role {
+            return CredentialsSource::AssumeRole {
+                role_arn: assume_role.role_arn.clone(),
+                external_id: assume_role.external_id.clone(),
+                session_name: assume_role
+                    .session_name
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_ASSUME_ROLE_SESSION_NAME.to_string()),
+            };
+        }
+
+        match config.assume_role_arn.as_deref() {
+            Some(role_arn) if !role_arn.is_empty() => CredentialsSource::AssumeRole {
+                role_arn: role_arn.to_string(),
+                external_id: None,
+                session_name: DEFAULT_ASSUME_ROLE_SESSION_NAME.to_string(),
+            },
+            _ => CredentialsSource::DefaultChain,
+        }
+    }
+}
+
+/// Credentials of one assumed-role session, refreshed shortly before they expire
+#[derive(Debug)]
+struct SessionCredentials {
+    provider: AssumeRoleProvider,
+    cached: tokio::sync::Mutex<Option<Credentials>>,
+}
+
+impl SessionCredentials {
+    async fn credentials(&self) -> provider::Result {
+        let mut cached = self.cached.lock().await;
+        if let Some(credentials) = cached.as_ref() {
+            if is_fresh(credentials, SystemTime::now()) {
+                return Ok(credentials.clone());
+            }
+        }
+
+        let credentials = self.provider.provide_credentials().await?;
+        *cached = Some(credentials.clone());
+        Ok(credentials)
+    }
+}
+
+impl ProvideCredentials for SessionCredentials {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Whether credentials stay valid long enough to hand out again
+fn is_fresh(credentials: &Credentials, now: SystemTime) -> bool {
+    credentials
+        .expiry()
+        .is_none_or(|expiry| expiry > now + REFRESH_BEFORE_EXPIRY)
+}
+
+/// Get the credentials provider for an assumed role, assuming it on first use
+///
+/// The role is assumed once per process; every client (across regions and
+/// services) then shares that session until it nears expiry.
+async fn assumed_role_provider(
+    source: &CredentialsSource,
+    base_config: &SdkConfig,
+) -> Result<SharedCredentialsProvider> {
+    let CredentialsSource::AssumeRole {
+        role_arn,
+        external_id,
+        session_name,
+    } = source
+    else {
+        unreachable!("only called for roles to assume");
+    };
+
+    if let Some((_, provider)) = lock_assumed_roles()
+        .iter()
+        .find(|(assumed, _)| assumed == source)
+    {
+        return Ok(provider.clone());
+    }
+
+    info!("Assuming role: {}", role_arn);
+    let mut builder = AssumeRoleProvider::builder(role_arn.clone())
+        .session_name(session_name.clone())
+        .configure(base_config);
+    if let Some(external_id) = external_id {
+        builder = builder.external_id(external_id.clone());
+    }
+    let session = SessionCredentials {
+        provider: builder.build().await,
+        cached: tokio::sync::Mutex::new(None),
+    };
+
+    // Assume the role now, so a failure is reported before any query runs
+    session.credentials().await.map_err(|e| {
+        anyhow::Error::new(e).context(format!(
+            "Failed to assume role {}. Check that the current credentials are allowed to call sts:AssumeRole on it, and that its trust policy accepts them (and the external ID, if it requires one).",
+            role_arn
+        ))
+    })?;
+
+    let provider = SharedCredentialsProvider::new(session);
+    lock_assumed_roles().push((source.clone(), provider.clone()));
+    Ok(provider)
+}
+
+fn lock_assumed_roles()
+-> std::sync::MutexGuard<'static, Vec<(CredentialsSource, SharedCredentialsProvider)>> {
+    // A panic while holding the lock cannot leave the Vec inconsistent
+    ASSUMED_ROLES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Load the AWS SDK configuration for a command
+///
+/// Uses the `--region` and `--profile` flags as overrides; see
+/// `build_aws_config`.
+///
+/// # Arguments
+/// * `config` - Loaded athenadef configuration
+pub async fn load_sdk_config(config: &Config) -> Result<SdkConfig> {
+    build_aws_config(config, region_override(), profile_override()).await
+}
+
+/// Build the AWS SDK configuration from athenadef.yaml and command line overrides
+///
+/// The region is `region_override` when given, then `region` in athenadef.yaml,
+/// otherwise the environment's. Every service client sends requests to
+/// `endpoint_url` when it is set. Credentials follow the precedence documented
+/// on `CredentialsSource`, so web identity credentials are never replaced unless
+/// a role is configured, in which case they are used to assume it. With a named
+/// profile, the region falls back to the profile's region instead of the
+/// environment's.
+///
+/// Fails if the named profile is not defined, rather than silently using the
+/// default credentials, or if the configured role cannot be assumed.
+///
+/// # Arguments
+/// * `config` - Loaded athenadef configuration
+/// * `region_override` - Region taking precedence over `region` in the config
+/// * `profile_override` - Profile taking precedence over `profile` in the config
+pub async fn build_aws_config(
+    config: &Config,
+    region_override: Option<&str>,
+    profile_override: Option<&str>,
+) -> Result<SdkConfig> {
+    let mut loader = match resolve_region(region_override, config) {
+        Some(region) => aws_config::from_env().region(Region::new(region.to_string())),
+        None => aws_config::from_env(),
+    };
+    if let Some(ref endpoint_url) = config.endpoint_url {
+        info!("Using endpoint URL: {}", endpoint_url);
+        loader = loader.endpoint_url(endpoint_url);
+    }
+    if let Some(profile) = resolve_profile(profile_override, config) {
+        ensure_profile_exists(&Fs::real(), &Env::real(), profile).await?;
+        info!("Using AWS profile: {}", profile);
+        loader = loader.profile_name(profile).credentials_provider(
+            ProfileFileCredentialsProvider::builder()
+                .profile_name(profile)
+                .build(),
+        );
+    }
+    let base_config = loader.load().await;
+
+    Ok(match CredentialsSource::from_config(config) {
+        CredentialsSource::DefaultChain => base_config,
+        source @ CredentialsSource::AssumeRole { .. } => {
+            let provider = assumed_role_provider(&source, &base_config).await?;
+            base_config
+                .to_builder()
+                .credentials_provider(provider)
+                .build()
+        }
+    })
+}
+
+/// Check that a named profile is defined in the shared config or credentials files
+async fn ensure_profile_exists(fs: &Fs, env: &Env, profile: &str) -> Result<()> {
+    let profiles = aws_config::profile::load(fs, env, &EnvConfigFiles::default(), None)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load AWS profiles: {}", e))?;
+
+    if profiles.get_profile(profile).is_none() {
+        return Err(anyhow::anyhow!(
+            "AWS profile '{}' was not found in the shared config or credentials files (~/.aws/config, ~/.aws/credentials).\n\nCheck the profile name given with --profile or in the config file. If the files live elsewhere, set AWS_CONFIG_FILE or AWS_SHARED_CREDENTIALS_FILE.",
+            profile
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::config::AssumeRoleConfig;
+
+    #[test]
+    fn test_credentials_source_defaults_to_provider_chain() {
+        let config = Config::default();
+        assert_eq!(
+            CredentialsSource::from_config(&config),
+            CredentialsSource::DefaultChain
+        );
+
+        let config = Config {
+            assume_role_arn: Some(String::new()),
+            ..Config::default()
+        };
+        assert_eq!(
+            CredentialsSource::from_config(&config),
+            CredentialsSource::DefaultChain
+        );
+    }
+
+    #[test]
+    fn test_resolve_profile_prefers_cli() {
+        let config = Config {
+            profile: Some("staging".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(resolve_profile(Some("prod"), &config), Some("prod"));
+        assert_eq!(resolve_profile(None, &config), Some("staging"));
+        assert_eq!(resolve_profile(None, &Config::default()), None);
+        assert_eq!(resolve_profile(Some(""), &Config::default()), None);
+    }
+
+    #[test]
+    fn test_resolve_region_precedence() {
+        let config = Config {
+            region: Some("us-east-1".to_string()),
+            ..Config::default()
+        };
+        // --region beats the config file, which beats the environment (None)
+        assert_eq!(
+            resolve_region(Some("eu-west-1"), &config),
+            Some("eu-west-1")
+        );
+        assert_eq!(resolve_region(None, &config), Some("us-east-1"));
+        assert_eq!(
+            resolve_region(Some("eu-west-1"), &Config::default()),
+            Some("eu-west-1")
+        );
+        assert_eq!(resolve_region(None, &Config::default()), None);
+
+        // Empty values count as unset
+        assert_eq!(resolve_region(Some(""), &config), Some("us-east-1"));
+        let empty = Config {
+            region: Some(String::new()),
+            ..Config::default()
+        };
+        assert_eq!(resolve_region(None, &empty), None);
+    }
+
+    #[tokio::test]
+    async fn test_build_aws_config_region_precedence() {
+        let config = Config {
+            region: Some("us-east-1".to_string()),
+            ..Config::default()
+        };
+        let region = |sdk_config: SdkConfig| sdk_config.region().map(|r| r.to_string());
+
+        let sdk_config = build_aws_config(&config, Some("ap-northeast-1"), None)
+            .await
+            .unwrap();
+        assert_eq!(region(sdk_config), Some("ap-northeast-1".to_string()));
+
+        let sdk_config = build_aws_config(&config, None, None).await.unwrap();
+        assert_eq!(region(sdk_config), Some("us-east-1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_profile_exists() {
+        let fs = Fs::from_slice(&[
+            (
+                "/home/.aws/config",
+                "[profile staging]\nregion = us-west-2\n",
+            ),
+            (
+                "/home/.aws/credentials",
+                "[prod]\naws_access_key_id = AKID\naws_secret_access_key = SECRET\n",
+            ),
+        ]);
+        let env = Env::from_slice(&[("HOME", "/home")]);
+
+        assert!(ensure_profile_exists(&fs, &env, "staging").await.is_ok());
+        assert!(ensure_profile_exists(&fs, &env, "prod").await.is_ok());
+
+        let err = ensure_profile_exists(&fs, &env, "missing")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("AWS profile 'missing' was not found")
+        );
+    }
+
+    #[test]
+    fn test_credentials_source_assume_role() {
+        let config = Config {
+            assume_role_arn: Some("arn:aws:iam::123456789012:role/athenadef".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            CredentialsSource::from_config(&config),
+            CredentialsSource::AssumeRole {
+                role_arn: "arn:aws:iam::123456789012:role/athenadef".to_string(),
+                external_id: None,
+                session_name: "athenadef".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_credentials_source_assume_role_section() {
+        let config = Config {
+            assume_role: Some(AssumeRoleConfig {
+                role_arn: "arn:aws:iam::210987654321:role/athena-tables".to_string(),
+                external_id: Some("shared-secret".to_string()),
+                session_name: Some("ci-deploy".to_string()),
+            }),
+            ..Config::default()
+        };
+        assert_eq!(
+            CredentialsSource::from_config(&config),
+            CredentialsSource::AssumeRole {
+                role_arn: "arn:aws:iam::210987654321:role/athena-tables".to_string(),
+                external_id: Some("shared-secret".to_string()),
+                session_name: "ci-deploy".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let now = SystemTime::now();
+        let expiring_at = |expiry| Credentials::new("AKID", "SECRET", None, expiry, "test");
+
+        assert!(is_fresh(&expiring_at(None), now));
+        assert!(is_fresh(
+            &expiring_at(Some(now + Duration::from_secs(3600))),
+            now
+        ));
+        // Credentials about to expire are refreshed rather than handed out
+        assert!(!is_fresh(
+            &expiring_at(Some(now + Duration::from_secs(60))),
+            now
+        ));
+    }
+}
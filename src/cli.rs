@@ -1,7 +1,19 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
-use crate::commands::{apply, export, init, plan};
+use crate::apply_state::ApplyStateOptions;
+use crate::aws::sdk_config::{set_profile_override, set_region_override};
+use crate::commands::export::ExportOptions;
+use crate::commands::plan::PlanFormat;
+use crate::commands::{
+    apply, config, destroy, diff, dump_remote, export, fmt, gc, init, inventory, plan, schema,
+    validate, version,
+};
+use crate::output::{DisplayOptions, format_warning};
+use crate::target_filter::merge_target_file;
+use crate::types::config::{Concurrency, ConfigOverrides, validate_data_catalog_name};
+use crate::warnings::recorded_warnings;
 
 #[derive(Parser, Debug)]
 #[command(name = "athenadef")]
@@ -9,6 +21,79 @@ use crate::commands::{apply, export, init, plan};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Exit with an error if any warning was reported
+    ///
+    /// Warnings such as unparseable SQL files, DDL that could not be extracted, or
+    /// targets that matched nothing are summarized after the command completes.
+    #[arg(long, global = true)]
+    pub fail_on_warnings: bool,
+
+    /// Number of concurrent queries, or `auto` to size it from the table count
+    ///
+    /// Overrides `max_concurrent_queries`. `auto` runs one query per table up to
+    /// 25 at a time, to avoid Athena throttling.
+    #[arg(long, global = true, value_name = "N|auto")]
+    pub concurrency: Option<Concurrency>,
+
+    /// Write a detailed log to this file
+    ///
+    /// Captures debug-level events, including each query with its execution id,
+    /// final state, and duration, while the console keeps its normal level.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Named AWS profile to take credentials and the default region from
+    ///
+    /// Overrides `profile` in the config file. Fails if the profile is not defined
+    /// in the shared config or credentials files.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// AWS region for Athena operations
+    ///
+    /// Overrides `region` in the config file and the environment's region.
+    /// Databases listed in `region_overrides` keep their own region.
+    #[arg(long, global = true, value_name = "REGION")]
+    pub region: Option<String>,
+
+    /// Workspace of the config file to use
+    ///
+    /// Settings under `workspaces.<NAME>` replace the shared ones. Defaults to
+    /// the `default` workspace when the file defines one. Fails if the workspace
+    /// is not defined.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub workspace: Option<String>,
+
+    /// Directory of the table definition files
+    ///
+    /// Overrides `path` in the config file. Defaults to the directory of the
+    /// config file.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub path: Option<PathBuf>,
+
+    /// Athena data catalog to manage, such as a federated connector's
+    ///
+    /// Overrides `data_catalog` and `catalog_id` in the config file. Databases and
+    /// tables are listed, read, and changed in this catalog instead of
+    /// `AwsDataCatalog`.
+    #[arg(long, global = true, value_name = "NAME", value_parser = parse_data_catalog)]
+    pub catalog: Option<String>,
+
+    /// Disable colored output
+    ///
+    /// Colors are also disabled when `NO_COLOR` is set or when output is not a
+    /// terminal, unless `CLICOLOR_FORCE` is set.
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print only errors, warnings, results, and summaries
+    ///
+    /// Progress lines and per-table steps are left out; `--format json` and other
+    /// machine-readable output is unchanged. Console logging drops to warnings,
+    /// unless `--debug` is also given.
+    #[arg(long, global = true)]
+    pub quiet: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -45,7 +130,14 @@ pub enum Commands {
     /// Examples:
     ///   athenadef plan
     ///   athenadef plan --target salesdb.customers
-    ///   athenadef plan --json > changes.json
+    ///   athenadef plan --format json > changes.json
+    ///   athenadef plan --format sarif > athenadef.sarif
+    ///   athenadef plan --format markdown > plan.md
+    ///   athenadef plan --format yaml > changes.yaml
+    ///   athenadef plan --format github
+    ///   athenadef plan --no-remote
+    ///   athenadef plan --ref origin/main
+    ///   athenadef plan --out plan.json
     Plan {
         /// Config file path
         #[arg(short, long, default_value = "athenadef.yaml")]
@@ -62,6 +154,20 @@ pub enum Commands {
         #[arg(short, long)]
         target: Vec<String>,
 
+        /// Leave out tables in `<database>.<table>` format, even if a target matches them
+        ///
+        /// Can be used multiple times and supports the same wildcards as --target,
+        /// e.g. `--target salesdb.* --exclude salesdb.events`.
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Read more target patterns from a file, one per line
+        ///
+        /// Blank lines and lines starting with `#` are ignored. The patterns are added
+        /// to those given with --target.
+        #[arg(long, value_name = "PATH")]
+        target_file: Option<PathBuf>,
+
         /// Show tables with no changes
         ///
         /// By default, only tables with changes are displayed. Use this flag to also show
@@ -69,12 +175,77 @@ pub enum Commands {
         #[arg(long)]
         show_unchanged: bool,
 
-        /// Output in JSON format
+        /// Show the S3 locations affected by each change
         ///
-        /// Outputs the diff result as structured JSON instead of human-readable text.
-        /// Useful for programmatic processing or integration with other tools.
+        /// Prints the location of tables to be created or destroyed, and the old and
+        /// new location of tables whose location changes.
+        #[arg(long)]
+        show_locations: bool,
+
+        /// Show only the summary line and the counts of each database
+        ///
+        /// Table diffs are left out of the text output. Other formats stay complete.
+        #[arg(long)]
+        summary: bool,
+
+        /// Fetch every remote definition again instead of using the cache
+        ///
+        /// Only matters with `cache_ttl_seconds`; fresh results are still cached.
         #[arg(long)]
+        refresh: bool,
+
+        /// Output in JSON format (deprecated: use `--format json`)
+        ///
+        /// Outputs the diff result as structured JSON instead of human-readable text.
+        /// Kept as an alias of `--format json` for existing scripts.
+        #[arg(long, conflicts_with = "format")]
         json: bool,
+
+        /// Output format
+        ///
+        /// `sarif` reports destructive changes (deletes, removed columns, type and
+        /// location changes) for code scanning tools. `markdown` renders a summary
+        /// table and a `diff` block per updated table, without ANSI colors, for
+        /// pull request comments. `yaml` is the structured result of `json` as
+        /// YAML.
+        #[arg(long, value_enum, default_value_t = PlanFormat::Text)]
+        format: PlanFormat,
+
+        /// Only validate local files, without contacting AWS
+        ///
+        /// Discovers local SQL files, reports how many tables were found per database,
+        /// and fails if any file cannot be parsed. No remote state is fetched or diffed,
+        /// so only the `text`, `json`, and `yaml` formats are accepted.
+        #[arg(long, alias = "local-only")]
+        no_remote: bool,
+
+        /// Check partition projection settings in local files
+        ///
+        /// For tables with `projection.enabled` set to true, warns about partition
+        /// columns missing `projection.<column>.type` or the settings that type needs.
+        #[arg(long)]
+        check_projection: bool,
+
+        /// Read SQL files from a git revision instead of the working tree
+        ///
+        /// Accepts any branch, tag, or commit. Files are read with git directly,
+        /// so no checkout is needed (e.g. `--ref origin/main` in CI).
+        #[arg(long = "ref", value_name = "GIT_REF")]
+        git_ref: Option<String>,
+
+        /// Save the plan to this file for a later `athenadef apply <PLAN_FILE>`
+        ///
+        /// The file records a hash of the local SQL of each table the plan creates or
+        /// updates, so apply refuses the plan if those files change in between.
+        #[arg(long, value_name = "PATH", conflicts_with = "no_remote")]
+        out: Option<PathBuf>,
+
+        /// Signal pending changes through the exit code, like Terraform
+        ///
+        /// Exits with 0 when there are no changes, 2 when there are changes to
+        /// apply, and 1 on errors. The plan is printed in the chosen format first.
+        #[arg(long, conflicts_with = "no_remote")]
+        detailed_exitcode: bool,
     },
     /// Apply configuration changes
     ///
@@ -86,6 +257,7 @@ pub enum Commands {
     ///   athenadef apply
     ///   athenadef apply --auto-approve
     ///   athenadef apply --dry-run --target salesdb.*
+    ///   athenadef apply plan.json
     Apply {
         /// Config file path
         #[arg(short, long, default_value = "athenadef.yaml")]
@@ -102,6 +274,20 @@ pub enum Commands {
         #[arg(short, long)]
         target: Vec<String>,
 
+        /// Leave out tables in `<database>.<table>` format, even if a target matches them
+        ///
+        /// Can be used multiple times and supports the same wildcards as --target,
+        /// e.g. `--target salesdb.* --exclude salesdb.events`.
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Read more target patterns from a file, one per line
+        ///
+        /// Blank lines and lines starting with `#` are ignored. The patterns are added
+        /// to those given with --target.
+        #[arg(long, value_name = "PATH")]
+        target_file: Option<PathBuf>,
+
         /// Skip interactive approval
         ///
         /// Automatically approves and applies all changes without prompting for confirmation.
@@ -115,6 +301,106 @@ pub enum Commands {
         /// Similar to 'plan' but follows the apply workflow.
         #[arg(long)]
         dry_run: bool,
+
+        /// Skip displaying the plan before applying
+        ///
+        /// The plan is still calculated, but only a one-line notice is printed before
+        /// the apply progress. Intended for CI where the plan was reviewed upstream,
+        /// so it requires --auto-approve.
+        #[arg(long, requires = "auto_approve", conflicts_with = "dry_run")]
+        no_plan: bool,
+
+        /// Record each successfully applied table in this file
+        ///
+        /// Entries hold a hash of the DDL that was run. The file is removed once
+        /// every change has been applied.
+        #[arg(long, value_name = "PATH")]
+        state_file: Option<String>,
+
+        /// Skip tables the state file records as applied with the same DDL
+        ///
+        /// Use after an interrupted apply to continue where it stopped. Tables whose
+        /// definition changed since are applied again.
+        #[arg(long, requires = "state_file")]
+        resume: bool,
+
+        /// Read SQL files from a git revision instead of the working tree
+        ///
+        /// Accepts any branch, tag, or commit. Files are read with git directly,
+        /// so no checkout is needed (e.g. `--ref origin/main` in CI).
+        #[arg(long = "ref", value_name = "GIT_REF")]
+        git_ref: Option<String>,
+
+        /// Write the data scanned per table and the estimated cost to this JSON file
+        ///
+        /// Written once every change has been applied. The cost is only estimated
+        /// when `price_per_tb` is set in the config file.
+        #[arg(long, value_name = "PATH", conflicts_with = "dry_run")]
+        report: Option<PathBuf>,
+
+        /// Write the operation, status, error, and query execution id of each table as JSON
+        ///
+        /// Statuses are `succeeded`, `failed`, or `skipped` (not attempted after a
+        /// failure), followed by totals. Written also when the apply fails; with
+        /// `--dry-run`, every change is `planned`.
+        #[arg(long, value_name = "PATH")]
+        output_json: Option<PathBuf>,
+
+        /// Keep applying the remaining tables after a table fails
+        ///
+        /// Without it, tables not yet started are skipped once any table fails.
+        /// Either way, the run ends with a report of succeeded and failed tables
+        /// and a non-zero exit code if any failed.
+        #[arg(long, conflicts_with = "dry_run")]
+        continue_on_error: bool,
+
+        /// Show only the summary line and the counts of each database in the plan
+        ///
+        /// Table diffs are left out before asking for approval.
+        #[arg(long, conflicts_with = "no_plan")]
+        summary: bool,
+
+        /// Fetch every remote definition again instead of using the cache
+        ///
+        /// Only matters with `cache_ttl_seconds`; fresh results are still cached.
+        #[arg(long, conflicts_with = "plan_file")]
+        refresh: bool,
+
+        /// Apply a plan saved with `athenadef plan --out` instead of computing a new one
+        ///
+        /// Fails if the plan was written in another plan file format, or if the local
+        /// SQL it was made from changed since. Targets come from the saved plan.
+        #[arg(value_name = "PLAN_FILE", conflicts_with_all = ["target", "exclude", "target_file"])]
+        plan_file: Option<PathBuf>,
+    },
+    /// Drop every remote table matching the targets
+    ///
+    /// Lists the remote tables in scope, shows them as a plan where everything is
+    /// destroyed, and drops them after confirmation. Local SQL files are never read
+    /// or changed. Requires --target or `databases` in the config file.
+    ///
+    /// Examples:
+    ///   athenadef destroy --target scratchdb.*
+    ///   athenadef destroy --target scratchdb.* --auto-approve
+    Destroy {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Filter target tables in `<database>.<table>` format
+        ///
+        /// Can be used multiple times to specify multiple targets.
+        /// Supports wildcards: `salesdb.*` (all tables in database) or `*.customers` (table across databases).
+        #[arg(short, long)]
+        target: Vec<String>,
+
+        /// Skip interactive approval
+        #[arg(short, long)]
+        auto_approve: bool,
     },
     /// Export existing table definitions to local files
     ///
@@ -125,6 +411,9 @@ pub enum Commands {
     ///   athenadef export
     ///   athenadef export --overwrite
     ///   athenadef export --target salesdb.*
+    ///   athenadef export --dry-run
+    ///   athenadef export --dry-run --fetch-ddl --overwrite
+    ///   athenadef export --only-errors
     Export {
         /// Config file path
         #[arg(short, long, default_value = "athenadef.yaml")]
@@ -141,155 +430,905 @@ pub enum Commands {
         #[arg(short, long)]
         target: Vec<String>,
 
+        /// Leave out tables in `<database>.<table>` format, even if a target matches them
+        ///
+        /// Can be used multiple times and supports the same wildcards as --target,
+        /// e.g. `--target salesdb.* --exclude salesdb.events`.
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Read more target patterns from a file, one per line
+        ///
+        /// Blank lines and lines starting with `#` are ignored. The patterns are added
+        /// to those given with --target.
+        #[arg(long, value_name = "PATH")]
+        target_file: Option<PathBuf>,
+
         /// Overwrite existing files
         ///
         /// By default, existing files are skipped to prevent accidental overwrites.
         /// Use this flag to replace existing files with the remote definitions.
         #[arg(long)]
         overwrite: bool,
-    },
-}
 
-impl Cli {
-    pub async fn run(&self) -> Result<()> {
-        match &self.command {
-            Commands::Init {
-                config,
-                debug: _,
-                force,
-            } => init::execute(config, *force).await,
-            Commands::Plan {
-                config,
-                debug: _,
-                target,
-                show_unchanged,
-                json,
-            } => plan::execute(config, target, *show_unchanged, *json).await,
-            Commands::Apply {
-                config,
-                debug: _,
-                target,
-                auto_approve,
-                dry_run,
-            } => apply::execute(config, target, *auto_approve, *dry_run).await,
-            Commands::Export {
-                config,
-                debug: _,
-                target,
-                overwrite,
-            } => export::execute(config, target, *overwrite).await,
-        }
-    }
-}
+        /// List the files that would be created, overwritten, or skipped
+        ///
+        /// Only SHOW TABLES is run and no files are written. No SHOW CREATE TABLE
+        /// queries are issued unless --fetch-ddl is given.
+        #[arg(long)]
+        dry_run: bool,
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        /// With --dry-run, still run SHOW CREATE TABLE for the files that would be written
+        ///
+        /// Tables whose DDL cannot be fetched are reported as errors, so the preview
+        /// shows everything a real export would hit. No files are written.
+        #[arg(long, requires = "dry_run")]
+        fetch_ddl: bool,
 
-    #[test]
-    fn test_cli_default_config() {
-        let args = vec!["athenadef", "plan"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan { config, debug, .. } => {
-                assert_eq!(config, "athenadef.yaml");
-                assert!(!debug);
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Print only tables that failed to export, plus the final summary
+        ///
+        /// Exported and skipped tables are still counted in the summary.
+        #[arg(long)]
+        only_errors: bool,
 
-    #[test]
-    fn test_cli_custom_config() {
-        let args = vec!["athenadef", "plan", "--config", "custom.yaml"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan { config, .. } => {
-                assert_eq!(config, "custom.yaml");
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Write files in the layout of `athenadef fmt` instead of as Athena returns them
+        ///
+        /// Columns and properties are re-indented and keywords upper-cased, so a later
+        /// `athenadef fmt` leaves the exported files unchanged.
+        #[arg(long)]
+        normalize: bool,
+    },
+    /// Print remote table definitions exactly as Athena returns them
+    ///
+    /// Fetches SHOW CREATE TABLE output for the matching tables without diffing against
+    /// local files. Useful for diagnosing unexpected diffs.
+    ///
+    /// Examples:
+    ///   athenadef dump-remote --target salesdb.customers
+    ///   athenadef dump-remote --target salesdb.* --normalized
+    ///   athenadef dump-remote --target salesdb.customers --json
+    DumpRemote {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
 
-    #[test]
-    fn test_cli_debug_flag() {
-        let args = vec!["athenadef", "plan", "--debug"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan { debug, .. } => {
-                assert!(debug);
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
 
-    #[test]
-    fn test_cli_target_single() {
-        let args = vec!["athenadef", "plan", "--target", "salesdb.customers"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan { target, .. } => {
-                assert_eq!(target.len(), 1);
-                assert_eq!(target[0], "salesdb.customers");
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Filter target tables in `<database>.<table>` format
+        ///
+        /// Can be used multiple times to specify multiple targets.
+        /// Supports wildcards: `salesdb.*` (all tables in database) or `*.customers` (table across databases).
+        #[arg(short, long)]
+        target: Vec<String>,
 
-    #[test]
-    fn test_cli_target_multiple() {
-        let args = vec![
-            "athenadef",
-            "plan",
-            "--target",
-            "salesdb.*",
-            "--target",
-            "marketingdb.leads",
-        ];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan { target, .. } => {
-                assert_eq!(target.len(), 2);
-                assert_eq!(target[0], "salesdb.*");
-                assert_eq!(target[1], "marketingdb.leads");
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Also print the normalized DDL that plan compares against
+        #[arg(long)]
+        normalized: bool,
 
-    #[test]
-    fn test_cli_plan_command() {
-        let args = vec!["athenadef", "plan"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
-            Commands::Plan {
-                target,
-                show_unchanged,
-                json,
-                ..
-            } => {
-                assert_eq!(target.len(), 0);
-                assert!(!show_unchanged);
-                assert!(!json);
-            }
-            _ => panic!("Expected Plan command"),
-        }
-    }
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print every local and remote table in scope as JSON
+    ///
+    /// Like `plan --json`, but also lists unchanged tables and marks whether each
+    /// table has a local file and whether it exists in Athena. Useful for audits
+    /// and for reconciling what is tracked with what exists.
+    ///
+    /// Examples:
+    ///   athenadef inventory
+    ///   athenadef inventory --target salesdb.*
+    Inventory {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
 
-    #[test]
-    fn test_cli_plan_command_with_flags() {
-        let args = vec!["athenadef", "plan", "--show-unchanged", "--json"];
-        let cli = Cli::try_parse_from(args).unwrap();
-        match cli.command {
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Filter target tables in `<database>.<table>` format
+        ///
+        /// Can be used multiple times to specify multiple targets.
+        /// Supports wildcards: `salesdb.*` (all tables in database) or `*.customers` (table across databases).
+        #[arg(short, long)]
+        target: Vec<String>,
+    },
+    /// Rewrite local SQL files in a canonical layout
+    ///
+    /// Upper-cases clause keywords, puts each clause on its own line and each column
+    /// and property on its own indented line, and ends files with a single newline.
+    /// Names, types, and quoted text are kept as written. Files with SQL comments or
+    /// statements other than CREATE TABLE are skipped with a warning.
+    ///
+    /// Examples:
+    ///   athenadef fmt
+    ///   athenadef fmt --check
+    ///   athenadef fmt --target salesdb.*
+    Fmt {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Filter target tables in `<database>.<table>` format
+        ///
+        /// Can be used multiple times to specify multiple targets.
+        /// Supports wildcards: `salesdb.*` (all tables in database) or `*.customers` (table across databases).
+        #[arg(short, long)]
+        target: Vec<String>,
+
+        /// Do not write files; fail if any file would be reformatted
+        #[arg(long)]
+        check: bool,
+    },
+    /// Compare the SQL files of two directories without contacting AWS
+    ///
+    /// Prints the changes that turn OLD_DIR's definitions into NEW_DIR's, compared
+    /// the way plan compares local files with remote tables. OLD_DIR is shown as
+    /// the remote side.
+    ///
+    /// Examples:
+    ///   athenadef diff ../main/sql sql
+    ///   athenadef diff old new --json
+    Diff {
+        /// Directory with the definitions to compare against
+        old_dir: PathBuf,
+
+        /// Directory with the changed definitions
+        new_dir: PathBuf,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the config file and local SQL files without contacting AWS
+    ///
+    /// Reports files that cannot be parsed as `database/table.sql` (including invalid
+    /// database or table names), files that resolve to the same table, and empty files
+    /// as errors, and `.sql` files outside that layout as warnings. Exits with an error
+    /// if any error is found, which makes it suitable for pre-commit hooks.
+    ///
+    /// Examples:
+    ///   athenadef validate
+    ///   athenadef validate --json
+    Validate {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the effective configuration
+    ///
+    /// Loads the config file, applies environment overrides (ATHENADEF_WORKGROUP)
+    /// and defaults, validates the result, and prints it without running anything
+    /// against AWS. Exits with an error if the configuration is invalid.
+    ///
+    /// Examples:
+    ///   athenadef config
+    ///   athenadef config --json
+    Config {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Output in JSON format instead of YAML
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete stale query result files from the output location
+    ///
+    /// Lists objects under the configured output_location and deletes those older than
+    /// the given age. Useful for keeping the Athena results bucket from growing forever.
+    ///
+    /// Examples:
+    ///   athenadef gc --older-than 7d --dry-run
+    ///   athenadef gc --older-than 30d
+    Gc {
+        /// Config file path
+        #[arg(short, long, default_value = "athenadef.yaml")]
+        config: String,
+
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Delete result files older than this age
+        ///
+        /// A number followed by a unit: s (seconds), m (minutes), h (hours), d (days), w (weeks).
+        #[arg(long, value_name = "AGE")]
+        older_than: String,
+
+        /// Show what would be deleted without deleting
+        ///
+        /// Lists the expired objects and the total size that would be reclaimed.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the JSON Schema of the plan --json output
+    ///
+    /// The schema is generated from the types plan serializes, so consumers can
+    /// validate the JSON output against it.
+    ///
+    /// Examples:
+    ///   athenadef schema
+    ///   athenadef schema > plan.schema.json
+    Schema {
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+    },
+    /// Print the version and optionally check for a newer release
+    ///
+    /// With --check, asks the GitHub releases API for the latest athenadef release and
+    /// reports whether it is newer than the running version. A failed lookup is only
+    /// reported as a warning.
+    ///
+    /// Examples:
+    ///   athenadef version
+    ///   athenadef version --check
+    Version {
+        /// Enable debug logging
+        #[arg(long)]
+        debug: bool,
+
+        /// Check whether a newer release is available
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+impl Cli {
+    /// Run the command
+    ///
+    /// # Returns
+    /// The exit code; only `plan --detailed-exitcode` returns a non-zero code
+    /// without an error
+    pub async fn run(&self) -> Result<i32> {
+        if let Some(ref profile) = self.profile {
+            set_profile_override(profile);
+        }
+        if let Some(ref region) = self.region {
+            set_region_override(region);
+        }
+
+        let exit_code = self.run_command().await?;
+
+        if self.fail_on_warnings {
+            check_warnings(&recorded_warnings())?;
+        }
+
+        Ok(exit_code)
+    }
+
+    /// Settings of the global flags applied over every command's config file
+    fn config_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            path: self.path.clone(),
+            data_catalog: self.catalog.clone(),
+            workspace: self.workspace.clone(),
+        }
+    }
+
+    async fn run_command(&self) -> Result<i32> {
+        let overrides = self.config_overrides();
+        let result = match &self.command {
+            Commands::Init {
+                config,
+                debug: _,
+                force,
+            } => init::execute(config, *force).await,
+            Commands::Plan {
+                config,
+                debug: _,
+                target,
+                exclude,
+                target_file,
+                show_unchanged,
+                show_locations,
+                summary,
+                refresh,
+                json,
+                format,
+                no_remote,
+                check_projection,
+                git_ref,
+                out,
+                detailed_exitcode,
+            } => {
+                let format = if *json { PlanFormat::Json } else { *format };
+                let target = merge_target_file(target, target_file.as_deref())?;
+                return plan::execute(
+                    config,
+                    &overrides,
+                    &target,
+                    exclude,
+                    DisplayOptions {
+                        show_unchanged: *show_unchanged,
+                        show_locations: *show_locations,
+                        summary_only: *summary,
+                    },
+                    format,
+                    git_ref.as_deref(),
+                    *no_remote,
+                    *check_projection,
+                    self.concurrency,
+                    out.as_deref(),
+                    *refresh,
+                    *detailed_exitcode,
+                )
+                .await;
+            }
+            Commands::Apply {
+                config,
+                debug: _,
+                target,
+                exclude,
+                target_file,
+                auto_approve,
+                dry_run,
+                no_plan,
+                state_file,
+                resume,
+                git_ref,
+                report,
+                output_json,
+                continue_on_error,
+                summary,
+                refresh,
+                plan_file,
+            } => {
+                let state = state_file.as_ref().map(|path| ApplyStateOptions {
+                    path: PathBuf::from(path),
+                    resume: *resume,
+                });
+                let target = merge_target_file(target, target_file.as_deref())?;
+                apply::execute(
+                    config,
+                    &overrides,
+                    &target,
+                    exclude,
+                    *auto_approve,
+                    *dry_run,
+                    *no_plan,
+                    state,
+                    git_ref.as_deref(),
+                    self.concurrency,
+                    plan_file.as_deref(),
+                    report.as_deref(),
+                    *continue_on_error,
+                    *summary,
+                    *refresh,
+                    output_json.as_deref(),
+                )
+                .await
+            }
+            Commands::Destroy {
+                config,
+                debug: _,
+                target,
+                auto_approve,
+            } => destroy::execute(config, &overrides, target, *auto_approve).await,
+            Commands::Export {
+                config,
+                debug: _,
+                target,
+                exclude,
+                target_file,
+                overwrite,
+                dry_run,
+                fetch_ddl,
+                only_errors,
+                normalize,
+            } => {
+                let options = ExportOptions {
+                    overwrite: *overwrite,
+                    dry_run: *dry_run,
+                    fetch_ddl: *fetch_ddl,
+                    only_errors: *only_errors || self.quiet,
+                    normalize: *normalize,
+                };
+                let target = merge_target_file(target, target_file.as_deref())?;
+                export::execute(
+                    config,
+                    &overrides,
+                    &target,
+                    exclude,
+                    options,
+                    self.concurrency,
+                )
+                .await
+            }
+            Commands::DumpRemote {
+                config,
+                debug: _,
+                target,
+                normalized,
+                json,
+            } => {
+                dump_remote::execute(
+                    config,
+                    &overrides,
+                    target,
+                    *normalized,
+                    *json,
+                    self.concurrency,
+                )
+                .await
+            }
+            Commands::Inventory {
+                config,
+                debug: _,
+                target,
+            } => inventory::execute(config, &overrides, target, self.concurrency).await,
+            Commands::Fmt {
+                config,
+                debug: _,
+                target,
+                check,
+            } => fmt::execute(config, &overrides, target, *check).await,
+            Commands::Diff {
+                old_dir,
+                new_dir,
+                debug: _,
+                json,
+            } => diff::execute(old_dir, new_dir, *json).await,
+            Commands::Validate {
+                config,
+                debug: _,
+                json,
+            } => validate::execute(config, &overrides, *json).await,
+            Commands::Config {
+                config: config_path,
+                debug: _,
+                json,
+            } => config::execute(config_path, &overrides, *json).await,
+            Commands::Gc {
+                config,
+                debug: _,
+                older_than,
+                dry_run,
+            } => gc::execute(config, &overrides, older_than, *dry_run).await,
+            Commands::Schema { debug: _ } => schema::execute().await,
+            Commands::Version { debug: _, check } => version::execute(*check).await,
+        };
+        result.map(|()| 0)
+    }
+}
+
+/// Parse `--catalog`, rejecting names that are not simple identifiers
+fn parse_data_catalog(value: &str) -> Result<String> {
+    validate_data_catalog_name(value)?;
+    Ok(value.to_string())
+}
+
+/// Print a summary of recorded warnings and fail if there are any
+fn check_warnings(warnings: &[String]) -> Result<()> {
+    if warnings.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!(
+        "\n{}",
+        format_warning(&format!("{} warning(s) reported:", warnings.len()))
+    );
+    for warning in warnings {
+        eprintln!("  - {}", warning);
+    }
+
+    anyhow::bail!(
+        "{} warning(s) were reported and --fail-on-warnings is set",
+        warnings.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_fail_on_warnings_is_global() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--fail-on-warnings"]).unwrap();
+        assert!(cli.fail_on_warnings);
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert!(!cli.fail_on_warnings);
+    }
+
+    #[test]
+    fn test_cli_quiet_is_global() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "--quiet", "--debug"]).unwrap();
+        assert!(cli.quiet);
+        match cli.command {
+            Commands::Apply { debug, .. } => assert!(debug),
+            _ => panic!("Expected Apply command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "--quiet", "export"]).unwrap();
+        assert!(cli.quiet);
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert!(!cli.quiet);
+    }
+
+    #[test]
+    fn test_cli_profile_is_global() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "export", "--profile", "staging"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("staging"));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.profile, None);
+    }
+
+    #[test]
+    fn test_cli_region_is_global() {
+        for command in ["plan", "apply", "export"] {
+            let cli =
+                Cli::try_parse_from(vec!["athenadef", command, "--region", "eu-west-1"]).unwrap();
+            assert_eq!(cli.region.as_deref(), Some("eu-west-1"));
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.region, None);
+    }
+
+    #[test]
+    fn test_cli_workspace_is_global() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--workspace", "prod"]).unwrap();
+        assert_eq!(cli.workspace.as_deref(), Some("prod"));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply"]).unwrap();
+        assert_eq!(cli.workspace, None);
+    }
+
+    #[test]
+    fn test_cli_catalog() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--catalog", "dynamo_connector"])
+            .unwrap();
+        assert_eq!(cli.catalog.as_deref(), Some("dynamo_connector"));
+
+        assert!(Cli::try_parse_from(vec!["athenadef", "plan", "--catalog", "a.b"]).is_err());
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.catalog, None);
+    }
+
+    #[test]
+    fn test_cli_path_is_global() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "validate", "--path", "sql"]).unwrap();
+        assert_eq!(cli.path, Some(PathBuf::from("sql")));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "--path", "sql", "plan"]).unwrap();
+        assert_eq!(cli.path, Some(PathBuf::from("sql")));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.path, None);
+    }
+
+    #[test]
+    fn test_cli_concurrency() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--concurrency", "auto"]).unwrap();
+        assert_eq!(cli.concurrency, Some(Concurrency::Auto));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "--concurrency", "8"]).unwrap();
+        assert_eq!(cli.concurrency, Some(Concurrency::Fixed(8)));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.concurrency, None);
+
+        assert!(Cli::try_parse_from(vec!["athenadef", "plan", "--concurrency", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_log_file() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "--log-file", "run.log"]).unwrap();
+        assert_eq!(cli.log_file, Some(PathBuf::from("run.log")));
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan"]).unwrap();
+        assert_eq!(cli.log_file, None);
+    }
+
+    #[test]
+    fn test_check_warnings() {
+        assert!(check_warnings(&[]).is_ok());
+
+        let err = check_warnings(&["Failed to parse a.sql".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("1 warning(s)"));
+    }
+
+    #[test]
+    fn test_cli_default_config() {
+        let args = vec!["athenadef", "plan"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { config, debug, .. } => {
+                assert_eq!(config, "athenadef.yaml");
+                assert!(!debug);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_custom_config() {
+        let args = vec!["athenadef", "plan", "--config", "custom.yaml"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { config, .. } => {
+                assert_eq!(config, "custom.yaml");
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_debug_flag() {
+        let args = vec!["athenadef", "plan", "--debug"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { debug, .. } => {
+                assert!(debug);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_target_single() {
+        let args = vec!["athenadef", "plan", "--target", "salesdb.customers"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { target, .. } => {
+                assert_eq!(target.len(), 1);
+                assert_eq!(target[0], "salesdb.customers");
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_exclude() {
+        for command in ["plan", "apply", "export"] {
+            let args = vec![
+                "athenadef",
+                command,
+                "--target",
+                "salesdb.*",
+                "--exclude",
+                "salesdb.events",
+                "--exclude",
+                "*.tmp_*",
+            ];
+            let cli = Cli::try_parse_from(args).unwrap();
+            let exclude = match cli.command {
+                Commands::Plan { exclude, .. }
+                | Commands::Apply { exclude, .. }
+                | Commands::Export { exclude, .. } => exclude,
+                _ => panic!("Expected {} command", command),
+            };
+            assert_eq!(exclude, vec!["salesdb.events", "*.tmp_*"]);
+        }
+
+        // A saved plan is applied as reviewed
+        let args = vec!["athenadef", "apply", "plan.json", "--exclude", "salesdb.*"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_target_file() {
+        for command in ["plan", "apply", "export"] {
+            let args = vec![
+                "athenadef",
+                command,
+                "--target",
+                "salesdb.*",
+                "--target-file",
+                "targets.txt",
+            ];
+            let cli = Cli::try_parse_from(args).unwrap();
+            let target_file = match cli.command {
+                Commands::Plan { target_file, .. }
+                | Commands::Apply { target_file, .. }
+                | Commands::Export { target_file, .. } => target_file,
+                _ => panic!("Expected {} command", command),
+            };
+            assert_eq!(target_file, Some(PathBuf::from("targets.txt")));
+        }
+
+        let args = vec!["athenadef", "apply", "plan.json", "--target-file", "t.txt"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_target_multiple() {
+        let args = vec![
+            "athenadef",
+            "plan",
+            "--target",
+            "salesdb.*",
+            "--target",
+            "marketingdb.leads",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { target, .. } => {
+                assert_eq!(target.len(), 2);
+                assert_eq!(target[0], "salesdb.*");
+                assert_eq!(target[1], "marketingdb.leads");
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_command() {
+        let args = vec!["athenadef", "plan"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan {
+                target,
+                show_unchanged,
+                json,
+                ..
+            } => {
+                assert_eq!(target.len(), 0);
+                assert!(!show_unchanged);
+                assert!(!json);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_command_with_flags() {
+        let args = vec!["athenadef", "plan", "--show-unchanged", "--json"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan {
+                target,
+                show_unchanged,
+                json,
+                ..
+            } => {
+                assert_eq!(target.len(), 0);
+                assert!(show_unchanged);
+                assert!(json);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_command_with_format() {
+        let args = vec!["athenadef", "plan", "--format", "sarif"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { format, json, .. } => {
+                assert_eq!(format, PlanFormat::Sarif);
+                assert!(!json);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+
+        let args = vec!["athenadef", "plan", "--format", "markdown"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Plan { format, .. } => assert_eq!(format, PlanFormat::Markdown),
+            _ => panic!("Expected Plan command"),
+        }
+
+        let args = vec!["athenadef", "plan", "--format", "yaml"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Plan { format, .. } => assert_eq!(format, PlanFormat::Yaml),
+            _ => panic!("Expected Plan command"),
+        }
+
+        let args = vec!["athenadef", "plan", "--format", "github"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Plan { format, .. } => assert_eq!(format, PlanFormat::Github),
+            _ => panic!("Expected Plan command"),
+        }
+
+        let args = vec!["athenadef", "plan", "--format", "json", "--json"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_plan_command_show_locations() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--show-locations"]).unwrap();
+        match cli.command {
+            Commands::Plan { show_locations, .. } => assert!(show_locations),
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_command_check_projection() {
+        let cli = Cli::try_parse_from(vec![
+            "athenadef",
+            "plan",
+            "--no-remote",
+            "--check-projection",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Plan {
+                no_remote,
+                check_projection,
+                ..
+            } => {
+                assert!(no_remote);
+                assert!(check_projection);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_command_no_remote() {
+        for flag in ["--no-remote", "--local-only"] {
+            let args = vec!["athenadef", "plan", flag];
+            let cli = Cli::try_parse_from(args).unwrap();
+            match cli.command {
+                Commands::Plan { no_remote, .. } => assert!(no_remote),
+                _ => panic!("Expected Plan command"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_detailed_exitcode() {
+        let args = vec![
+            "athenadef",
+            "plan",
+            "--detailed-exitcode",
+            "--format",
+            "json",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
             Commands::Plan {
-                target,
-                show_unchanged,
-                json,
+                detailed_exitcode,
+                format,
                 ..
             } => {
-                assert_eq!(target.len(), 0);
-                assert!(show_unchanged);
-                assert!(json);
+                assert!(detailed_exitcode);
+                assert_eq!(format, PlanFormat::Json);
+            }
+            _ => panic!("Expected Plan command"),
+        }
+
+        // Without a diff there are no pending changes to signal
+        let args = vec!["athenadef", "plan", "--detailed-exitcode", "--no-remote"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_plan_command_with_ref() {
+        let args = vec!["athenadef", "plan", "--ref", "origin/main"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Plan { git_ref, .. } => {
+                assert_eq!(git_ref.as_deref(), Some("origin/main"));
             }
             _ => panic!("Expected Plan command"),
         }
@@ -314,6 +1353,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_summary_flag() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--summary", "--json"]).unwrap();
+        match cli.command {
+            Commands::Plan { summary, json, .. } => assert!(summary && json),
+            _ => panic!("Expected Plan command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "--summary"]).unwrap();
+        match cli.command {
+            Commands::Apply { summary, .. } => assert!(summary),
+            _ => panic!("Expected Apply command"),
+        }
+
+        // --no-plan prints no plan to summarize
+        let args = vec![
+            "athenadef",
+            "apply",
+            "--summary",
+            "--no-plan",
+            "--auto-approve",
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_cli_apply_command_with_flags() {
         let args = vec!["athenadef", "apply", "--auto-approve", "--dry-run"];
@@ -333,6 +1397,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_apply_continue_on_error() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "--continue-on-error"]).unwrap();
+        match cli.command {
+            Commands::Apply {
+                continue_on_error, ..
+            } => assert!(continue_on_error),
+            _ => panic!("Expected Apply command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply"]).unwrap();
+        match cli.command {
+            Commands::Apply {
+                continue_on_error, ..
+            } => assert!(!continue_on_error),
+            _ => panic!("Expected Apply command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_apply_output_json() {
+        let cli = Cli::try_parse_from(vec![
+            "athenadef",
+            "apply",
+            "--dry-run",
+            "--output-json",
+            "apply-result.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Apply {
+                output_json,
+                dry_run,
+                ..
+            } => {
+                assert_eq!(output_json, Some(PathBuf::from("apply-result.json")));
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+    }
+
     #[test]
     fn test_cli_apply_command_short_flag() {
         let args = vec!["athenadef", "apply", "-a"];
@@ -352,6 +1458,114 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_apply_command_with_ref() {
+        let args = vec!["athenadef", "apply", "--ref", "v1.2.0", "--dry-run"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Apply {
+                git_ref, dry_run, ..
+            } => {
+                assert_eq!(git_ref.as_deref(), Some("v1.2.0"));
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_plan_out_and_apply_plan_file() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "plan", "--out", "plan.json"]).unwrap();
+        match cli.command {
+            Commands::Plan { out, .. } => assert_eq!(out, Some(PathBuf::from("plan.json"))),
+            _ => panic!("Expected Plan command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "apply", "plan.json", "-a"]).unwrap();
+        match cli.command {
+            Commands::Apply {
+                plan_file,
+                auto_approve,
+                ..
+            } => {
+                assert_eq!(plan_file, Some(PathBuf::from("plan.json")));
+                assert!(auto_approve);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+
+        // A saved plan already fixes which tables change
+        assert!(
+            Cli::try_parse_from(vec!["athenadef", "apply", "plan.json", "-t", "salesdb.*"])
+                .is_err()
+        );
+        // Nothing is diffed without remote state, so there is no plan to save
+        assert!(
+            Cli::try_parse_from(vec![
+                "athenadef",
+                "plan",
+                "--no-remote",
+                "--out",
+                "plan.json"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_apply_command_with_no_plan() {
+        let args = vec!["athenadef", "apply", "--auto-approve", "--no-plan"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Apply {
+                auto_approve,
+                no_plan,
+                ..
+            } => {
+                assert!(auto_approve);
+                assert!(no_plan);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+
+        // Applying without seeing the plan must not prompt for approval
+        assert!(Cli::try_parse_from(vec!["athenadef", "apply", "--no-plan"]).is_err());
+        assert!(
+            Cli::try_parse_from(vec![
+                "athenadef",
+                "apply",
+                "--auto-approve",
+                "--no-plan",
+                "--dry-run"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_apply_command_with_resume() {
+        let args = vec![
+            "athenadef",
+            "apply",
+            "--state-file",
+            "apply-state.json",
+            "--resume",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Apply {
+                state_file, resume, ..
+            } => {
+                assert_eq!(state_file.as_deref(), Some("apply-state.json"));
+                assert!(resume);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+
+        // Resuming needs a state file to read
+        assert!(Cli::try_parse_from(vec!["athenadef", "apply", "--resume"]).is_err());
+    }
+
     #[test]
     fn test_cli_export_command() {
         let args = vec!["athenadef", "export"];
@@ -367,6 +1581,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_export_normalize() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "export", "--normalize"]).unwrap();
+        match cli.command {
+            Commands::Export { normalize, .. } => assert!(normalize),
+            _ => panic!("Expected Export command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_export_only_errors() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "export", "--only-errors"]).unwrap();
+        match cli.command {
+            Commands::Export { only_errors, .. } => assert!(only_errors),
+            _ => panic!("Expected Export command"),
+        }
+    }
+
     #[test]
     fn test_cli_export_command_with_flags() {
         let args = vec!["athenadef", "export", "--overwrite"];
@@ -382,6 +1614,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_export_command_with_dry_run() {
+        let args = vec!["athenadef", "export", "--dry-run"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Export {
+                overwrite, dry_run, ..
+            } => {
+                assert!(!overwrite);
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        let args = vec!["athenadef", "export", "--dry-run", "--fetch-ddl"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Export { fetch_ddl, .. } => assert!(fetch_ddl),
+            _ => panic!("Expected Export command"),
+        }
+
+        // Fetching DDL is only a dry-run option
+        let args = vec!["athenadef", "export", "--fetch-ddl"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_cli_combined_flags() {
         let args = vec![
@@ -400,20 +1657,223 @@ mod tests {
                 config,
                 debug,
                 target,
+                exclude,
+                target_file,
                 show_unchanged,
+                show_locations,
+                summary,
+                refresh,
                 json,
+                format,
+                no_remote,
+                check_projection,
+                git_ref,
+                out,
+                detailed_exitcode,
             } => {
                 assert_eq!(config, "prod.yaml");
                 assert!(debug);
                 assert_eq!(target.len(), 1);
                 assert_eq!(target[0], "db.table");
+                assert!(exclude.is_empty());
+                assert!(target_file.is_none());
                 assert!(!show_unchanged);
+                assert!(!show_locations);
+                assert!(!summary);
+                assert!(!refresh);
                 assert!(json);
+                assert_eq!(format, PlanFormat::Text);
+                assert!(!no_remote);
+                assert!(!check_projection);
+                assert!(git_ref.is_none());
+                assert!(out.is_none());
+                assert!(!detailed_exitcode);
             }
             _ => panic!("Expected Plan command"),
         }
     }
 
+    #[test]
+    fn test_cli_dump_remote_command() {
+        let args = vec![
+            "athenadef",
+            "dump-remote",
+            "--target",
+            "salesdb.customers",
+            "--normalized",
+            "--json",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::DumpRemote {
+                target,
+                normalized,
+                json,
+                ..
+            } => {
+                assert_eq!(target, vec!["salesdb.customers"]);
+                assert!(normalized);
+                assert!(json);
+            }
+            _ => panic!("Expected DumpRemote command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_inventory_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "inventory", "-t", "salesdb.*"]).unwrap();
+        match cli.command {
+            Commands::Inventory { config, target, .. } => {
+                assert_eq!(config, "athenadef.yaml");
+                assert_eq!(target, vec!["salesdb.*"]);
+            }
+            _ => panic!("Expected Inventory command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_destroy_command() {
+        let cli = Cli::try_parse_from(vec![
+            "athenadef",
+            "destroy",
+            "-t",
+            "scratchdb.*",
+            "--auto-approve",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Destroy {
+                target,
+                auto_approve,
+                ..
+            } => {
+                assert_eq!(target, vec!["scratchdb.*"]);
+                assert!(auto_approve);
+            }
+            _ => panic!("Expected Destroy command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_fmt_command() {
+        let cli =
+            Cli::try_parse_from(vec!["athenadef", "fmt", "--check", "-t", "salesdb.*"]).unwrap();
+        match cli.command {
+            Commands::Fmt { target, check, .. } => {
+                assert_eq!(target, vec!["salesdb.*"]);
+                assert!(check);
+            }
+            _ => panic!("Expected Fmt command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_validate_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "validate", "-c", "prod.yaml"]).unwrap();
+        match cli.command {
+            Commands::Validate { config, json, .. } => {
+                assert_eq!(config, "prod.yaml");
+                assert!(!json);
+            }
+            _ => panic!("Expected Validate command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_config_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "config", "--json"]).unwrap();
+        match cli.command {
+            Commands::Config { config, json, .. } => {
+                assert_eq!(config, "athenadef.yaml");
+                assert!(json);
+            }
+            _ => panic!("Expected Config command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_gc_command() {
+        let args = vec!["athenadef", "gc", "--older-than", "7d"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Gc {
+                config,
+                older_than,
+                dry_run,
+                ..
+            } => {
+                assert_eq!(config, "athenadef.yaml");
+                assert_eq!(older_than, "7d");
+                assert!(!dry_run);
+            }
+            _ => panic!("Expected Gc command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_gc_command_with_dry_run() {
+        let args = vec!["athenadef", "gc", "--older-than", "30d", "--dry-run"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Gc {
+                older_than,
+                dry_run,
+                ..
+            } => {
+                assert_eq!(older_than, "30d");
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Gc command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_diff_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "diff", "old", "new", "--json"]).unwrap();
+        match cli.command {
+            Commands::Diff {
+                old_dir,
+                new_dir,
+                json,
+                ..
+            } => {
+                assert_eq!(old_dir, PathBuf::from("old"));
+                assert_eq!(new_dir, PathBuf::from("new"));
+                assert!(json);
+            }
+            _ => panic!("Expected Diff command"),
+        }
+
+        assert!(Cli::try_parse_from(vec!["athenadef", "diff", "old"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_schema_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "schema"]).unwrap();
+        assert!(matches!(cli.command, Commands::Schema { debug: false }));
+    }
+
+    #[test]
+    fn test_cli_version_command() {
+        let cli = Cli::try_parse_from(vec!["athenadef", "version"]).unwrap();
+        match cli.command {
+            Commands::Version { check, .. } => assert!(!check),
+            _ => panic!("Expected Version command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["athenadef", "version", "--check"]).unwrap();
+        match cli.command {
+            Commands::Version { check, .. } => assert!(check),
+            _ => panic!("Expected Version command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_gc_requires_older_than() {
+        let args = vec!["athenadef", "gc"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_cli_init_command() {
         let args = vec!["athenadef", "init"];
@@ -1,32 +1,140 @@
 use anyhow::{Context, Result};
-use aws_sdk_athena::Client as AthenaClient;
-use console::Term;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{self, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
 use tracing::info;
 
-use crate::aws::athena::QueryExecutor;
-use crate::differ::Differ;
+use crate::api::Athenadef;
+use crate::apply_state::{ApplyState, ApplyStateOptions, ddl_hash};
+use crate::aws::athena::{QueryExecutor, QueryRunner};
+use crate::aws::region::RegionalExecutors;
+use crate::differ::extract_column_list;
+use crate::interrupt::stop_queries_on_interrupt;
 use crate::output::{
-    OutputStyles, display_diff_result, format_error, format_progress, format_success,
-    format_warning,
+    DisplayOptions, OutputStyles, display_diff_result, format_bytes, format_error, format_progress,
+    format_success, format_warning, is_quiet, print_progress,
 };
-use crate::target_filter::{parse_target_filter, resolve_targets};
-use crate::types::config::Config;
-use crate::types::diff_result::{DiffOperation, DiffResult};
+use crate::saved_plan::SavedPlan;
+use crate::sql_source::SqlSource;
+use crate::target_filter::resolve_targets;
+use crate::types::apply_result::{ApplyResult, ApplyStatus, ApplyTableResult};
+use crate::types::config::{Concurrency, Config, ConfigOverrides, DropBehavior, UpdateStrategy};
+use crate::types::diff_result::{
+    ChangeCategory, ColumnChangeType, DiffOperation, DiffResult, DiffSummary, TableDiff,
+};
+use crate::types::query_execution::{QueryResult, QueryStats};
+use crate::types::table_definition::ObjectType;
+use crate::warnings::warn;
+
+/// Bytes in the TiB Athena prices scans by
+const BYTES_PER_TB: f64 = (1u64 << 40) as f64;
+
+/// Data scanned by the queries apply ran for one table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableScan {
+    pub table: String,
+    #[serde(flatten)]
+    pub stats: QueryStats,
+}
+
+/// Data scanned by an apply, printed at the end and written with `--report`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApplyReport {
+    pub added: usize,
+    pub changed: usize,
+    pub destroyed: usize,
+    /// Tables applied in this run, in apply order; tables skipped on resume are left out
+    pub tables: Vec<TableScan>,
+    pub total: QueryStats,
+    /// Estimated from `price_per_tb`; None when it is not configured
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl ApplyReport {
+    fn new(summary: &DiffSummary, tables: Vec<TableScan>, price_per_tb: Option<f64>) -> Self {
+        let mut total = QueryStats::default();
+        for table in &tables {
+            total.merge(table.stats);
+        }
+        let estimated_cost_usd =
+            price_per_tb.map(|price| total.data_scanned_bytes.max(0) as f64 / BYTES_PER_TB * price);
+
+        Self {
+            added: summary.to_add,
+            changed: summary.to_change,
+            destroyed: summary.to_destroy,
+            tables,
+            total,
+            estimated_cost_usd,
+        }
+    }
+
+    /// Lines listing the data scanned per table and in total
+    fn scan_summary(&self) -> Vec<String> {
+        let mut lines = vec!["Data scanned:".to_string()];
+        for table in &self.tables {
+            lines.push(format!(
+                "  {}: {}",
+                table.table,
+                format_bytes(table.stats.data_scanned_bytes.max(0) as u64)
+            ));
+        }
+
+        let mut total = format!(
+            "Total data scanned: {}",
+            format_bytes(self.total.data_scanned_bytes.max(0) as u64)
+        );
+        if let Some(cost) = self.estimated_cost_usd {
+            total.push_str(&format!(" (estimated cost: ${:.4})", cost));
+        }
+        lines.push(total);
+        lines
+    }
+
+    /// Write the report as JSON
+    fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write apply report {}", path.display()))
+    }
+}
 
 /// Execute the apply command
+///
+/// With `plan_file`, the saved plan is applied instead of computing a new diff,
+/// after checking that the local SQL it was made from is unchanged. With
+/// `refresh`, cached remote definitions (`cache_ttl_seconds`) are fetched again;
+/// the cached definitions of tables being changed are always removed. With
+/// `output_json`, the status of every table is written as JSON, also when the
+/// apply fails; a dry run writes every change as planned.
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config_path: &str,
+    overrides: &ConfigOverrides,
     targets: &[String],
+    excludes: &[String],
     auto_approve: bool,
     dry_run: bool,
+    no_plan: bool,
+    state: Option<ApplyStateOptions>,
+    git_ref: Option<&str>,
+    concurrency: Option<Concurrency>,
+    plan_file: Option<&Path>,
+    report_path: Option<&Path>,
+    continue_on_error: bool,
+    summary_only: bool,
+    refresh: bool,
+    output_json: Option<&Path>,
 ) -> Result<()> {
     info!("Starting athenadef apply");
     info!("Loading configuration from {}", config_path);
 
     // Load and validate configuration
-    let config = Config::load_from_path(config_path)?;
+    let config = Config::load_with_overrides(config_path, overrides)?;
 
     info!("Configuration loaded successfully");
     info!("Workgroup: {}", config.workgroup);
@@ -42,58 +150,88 @@ pub async fn execute(
     if !effective_targets.is_empty() {
         info!("Targets: {:?}", effective_targets);
     }
+    if !excludes.is_empty() {
+        info!("Excludes: {:?}", excludes);
+    }
+    let update_strategy = config.update_strategy.unwrap_or_default();
+    let drop_behavior = config.drop_behavior.unwrap_or_default();
+
     info!("Auto approve: {}", auto_approve);
     info!("Dry run: {}", dry_run);
+    info!("No plan: {}", no_plan);
+    info!("Continue on error: {}", continue_on_error);
+    if let Some(ref state) = state {
+        info!(
+            "State file: {} (resume: {})",
+            state.path.display(),
+            state.resume
+        );
+    }
+    info!("Update strategy: {:?}", update_strategy);
+    info!("Drop behavior: {:?}", drop_behavior);
 
-    // Initialize AWS clients
-    let aws_config = if let Some(ref region) = config.region {
-        aws_config::from_env()
-            .region(aws_sdk_athena::config::Region::new(region.clone()))
-            .load()
-            .await
-    } else {
-        aws_config::load_from_env().await
-    };
+    // A saved plan reads SQL from the ref it was made from, unless --ref overrides it
+    if let Some(plan_file) = plan_file {
+        info!("Saved plan: {}", plan_file.display());
+    }
+    let saved_plan = plan_file.map(SavedPlan::load).transpose()?;
+    let git_ref = git_ref.or_else(|| {
+        saved_plan
+            .as_ref()
+            .and_then(|saved_plan| saved_plan.git_ref.as_deref())
+    });
+    if let Some(git_ref) = git_ref {
+        info!("Reading SQL files from git ref: {}", git_ref);
+    }
 
-    let athena_client = AthenaClient::new(&aws_config);
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
 
-    // Create query executor
-    let query_executor = QueryExecutor::new(
-        athena_client,
-        config.workgroup.clone(),
-        config.output_location.clone(),
-        config.query_timeout_seconds.unwrap_or(300),
-    );
+    let base_path = config.base_path(config_path);
+    let sql_source = SqlSource::resolve(&base_path, git_ref)?.with_layout(config.sql_layout());
 
-    // Create differ
-    let max_concurrent_queries = config.max_concurrent_queries.unwrap_or(5);
-    let differ = Differ::new(query_executor.clone(), max_concurrent_queries);
-
-    // Get base path from config file directory
-    let config_path_buf = Path::new(config_path);
-    let base_path = config_path_buf
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .to_path_buf();
-
-    // Parse target filter
-    let target_filter = parse_target_filter(&effective_targets);
-
-    // Calculate diff
-    println!("{}", format_progress("Calculating differences..."));
-    let diff_result = differ
-        .calculate_diff(
-            Path::new(&base_path),
-            Some(|db: &str, table: &str| target_filter(db, table)),
-        )
-        .await
-        .context("Failed to calculate differences. This could be due to:\n  - Network issues connecting to AWS\n  - Invalid AWS credentials or insufficient permissions\n  - Invalid configuration file\n\nRun with --debug flag for more details.")?;
+    let athenadef = Athenadef::new(config.clone(), &base_path)
+        .with_sql_source(sql_source.clone())
+        .with_excludes(excludes.to_vec())
+        .with_concurrency(concurrency)
+        .with_refresh(refresh)
+        .with_continue_on_error(continue_on_error);
+
+    let diff_result = if let Some(saved_plan) = saved_plan {
+        // Apply exactly what was reviewed, refusing it if the SQL changed since
+        saved_plan.verify_local_sql(&base_path, &sql_source)?;
+        saved_plan.diff_result
+    } else {
+        // Calculate diff
+        print_progress("Calculating differences...");
+        athenadef
+            .plan_with(&executors, &effective_targets)
+            .await
+            .context("Failed to calculate differences. This could be due to:\n  - Network issues connecting to AWS\n  - Invalid AWS credentials or insufficient permissions\n  - Invalid configuration file\n\nRun with --debug flag for more details.")?
+    };
 
-    // Display the plan (show_unchanged = false for apply)
-    display_diff_result(&diff_result, false)?;
+    // Display the plan (show_unchanged = false for apply), or only a notice with --no-plan
+    if no_plan {
+        println!("{}", no_plan_notice(&diff_result));
+    } else {
+        display_diff_result(
+            &diff_result,
+            DisplayOptions {
+                summary_only,
+                ..DisplayOptions::default()
+            },
+        )?;
+    }
+
+    if let Some(message) = purge_warning(&diff_result, drop_behavior) {
+        warn(message);
+    }
 
     // If dry run, stop here
     if dry_run {
+        if let Some(output_json) = output_json {
+            ApplyResult::planned(&diff_result).save(output_json)?;
+        }
         println!(
             "\n{}",
             format_warning("Dry run mode - no changes were applied.")
@@ -103,9 +241,47 @@ pub async fn execute(
 
     // If no changes, stop here
     if diff_result.no_change {
+        if let Some(output_json) = output_json {
+            ApplyResult::new(false, Vec::new()).save(output_json)?;
+        }
         return Ok(());
     }
 
+    // With an allowlist, --auto-approve only applies tables whose changes are all allowed
+    let diff_result = match (&config.auto_apply_when, auto_approve) {
+        (Some(allowlist), true) => {
+            let (allowed, held_back) =
+                split_by_allowlist(diff_result, allowlist, update_strategy, |table_diff| {
+                    sql_source.read_table_sql(
+                        &base_path,
+                        &table_diff.database_name,
+                        &table_diff.table_name,
+                    )
+                })?;
+            if !held_back.is_empty() {
+                println!(
+                    "\n{}",
+                    format_warning(&format!(
+                        "{} table(s) have changes outside auto_apply_when and were held back for manual review:",
+                        held_back.len()
+                    ))
+                );
+                for table in &held_back {
+                    println!("  - {}", table);
+                }
+            }
+            if allowed.no_change {
+                println!(
+                    "\n{}",
+                    format_warning("No changes are eligible for auto-apply.")
+                );
+                return Ok(());
+            }
+            allowed
+        }
+        _ => diff_result,
+    };
+
     // Prompt for confirmation if not auto-approve
     if !auto_approve && !prompt_for_confirmation()? {
         println!("\n{}", format_warning("Apply cancelled."));
@@ -114,10 +290,48 @@ pub async fn execute(
 
     // Apply the changes
     println!();
-    let result = apply_changes(&diff_result, &query_executor, &base_path).await;
+    let result = stop_queries_on_interrupt(
+        &executors,
+        athenadef.apply_with(&executors, &diff_result, state.as_ref(), true),
+    )
+    .await;
+
+    // Every table's status is written before a failure is reported
+    if let (Ok(outcomes), Some(output_json)) = (&result, output_json) {
+        apply_result(outcomes).save(output_json)?;
+    }
+
+    // Any failed table fails the apply; the report lists every table's outcome
+    let result = result.and_then(|outcomes| {
+        if let Some(report) = failure_report(&outcomes) {
+            return Err(anyhow::anyhow!(
+                "{}\n\nPossible causes:\n  - Invalid SQL syntax in the local SQL file\n  - Table is locked or being accessed\n  - Insufficient AWS permissions\n  - Network connectivity issues",
+                report
+            ));
+        }
+        Ok(outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome.result {
+                TableResult::Attempted(Ok(run)) => Some(TableScan {
+                    table: outcome.qualified_name,
+                    stats: run.stats,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>())
+    });
 
     match result {
-        Ok(_) => {
+        Ok(tables) => {
+            // Everything is applied, so there is nothing left to resume
+            if let Some(ref state) = state {
+                if state.path.exists() {
+                    std::fs::remove_file(&state.path).with_context(|| {
+                        format!("Failed to remove apply state file {}", state.path.display())
+                    })?;
+                }
+            }
+
             // Display summary
             println!(
                 "\n{}",
@@ -128,6 +342,15 @@ pub async fn execute(
                     diff_result.summary.to_destroy
                 ))
             );
+
+            let report = ApplyReport::new(&diff_result.summary, tables, config.price_per_tb);
+            println!();
+            for line in report.scan_summary() {
+                println!("{}", line);
+            }
+            if let Some(report_path) = report_path {
+                report.save(report_path)?;
+            }
             Ok(())
         }
         Err(e) => {
@@ -136,14 +359,102 @@ pub async fn execute(
                 "\n{}",
                 format_warning("Some changes may have been partially applied.")
             );
+            if let Some(ref state) = state {
+                println!(
+                    "Tables applied so far are recorded in {}; re-run with --resume to skip them.",
+                    state.path.display()
+                );
+            }
             println!("Run 'athenadef plan' to see the current state.");
             Err(e)
         }
     }
 }
 
+/// One-line notice printed in place of the plan when --no-plan is given
+fn no_plan_notice(diff_result: &DiffResult) -> String {
+    if diff_result.no_change {
+        return format_success("No changes. Your infrastructure matches the configuration.");
+    }
+
+    format_progress(&format!(
+        "Applying {} to add, {} to change, {} to destroy (plan display skipped with --no-plan).",
+        diff_result.summary.to_add, diff_result.summary.to_change, diff_result.summary.to_destroy
+    ))
+}
+
+/// Split a diff into tables whose changes are all allowed and those held back
+///
+/// An update is only allowed if it runs as ALTER TABLE under `update_strategy`:
+/// an allowed change that would drop and recreate the table is held back.
+///
+/// # Arguments
+/// * `diff_result` - Full diff result
+/// * `allowlist` - Change categories allowed to be applied without review
+/// * `update_strategy` - Configured update strategy
+/// * `local_sql` - Reads the local CREATE statement of an updated table
+///
+/// # Returns
+/// The diff restricted to allowed tables, and a description of each held-back table
+fn split_by_allowlist<F>(
+    diff_result: DiffResult,
+    allowlist: &[ChangeCategory],
+    update_strategy: UpdateStrategy,
+    local_sql: F,
+) -> Result<(DiffResult, Vec<String>)>
+where
+    F: Fn(&TableDiff) -> Result<String>,
+{
+    let mut allowed = Vec::new();
+    let mut held_back = Vec::new();
+
+    for table_diff in diff_result.table_diffs {
+        match table_diff.change_categories() {
+            Some(categories) => {
+                let outside: Vec<String> = categories
+                    .iter()
+                    .filter(|category| !category.is_allowed_by(allowlist))
+                    .map(|category| category.to_string())
+                    .collect();
+                if !outside.is_empty() {
+                    held_back.push(format!(
+                        "{} ({})",
+                        table_diff.qualified_name(),
+                        outside.join(", ")
+                    ));
+                } else if table_diff.operation == DiffOperation::Update
+                    && !matches!(
+                        plan_update(update_strategy, &table_diff, &local_sql(&table_diff)?),
+                        UpdatePlan::Alter(_)
+                    )
+                {
+                    held_back.push(format!(
+                        "{} (not applied with ALTER TABLE)",
+                        table_diff.qualified_name()
+                    ));
+                } else {
+                    allowed.push(table_diff);
+                }
+            }
+            None => held_back.push(format!(
+                "{} (unrecognized changes)",
+                table_diff.qualified_name()
+            )),
+        }
+    }
+
+    let summary = DiffSummary::from_table_diffs(&allowed);
+    let result = DiffResult {
+        no_change: summary.to_add == 0 && summary.to_change == 0 && summary.to_destroy == 0,
+        summary,
+        table_diffs: allowed,
+    };
+
+    Ok((result, held_back))
+}
+
 /// Prompt user for confirmation
-fn prompt_for_confirmation() -> Result<bool> {
+pub(crate) fn prompt_for_confirmation() -> Result<bool> {
     println!("\nDo you want to perform these actions?");
     println!("  athenadef will perform the actions described above.");
     println!("  Only 'yes' will be accepted to approve.");
@@ -157,207 +468,1734 @@ fn prompt_for_confirmation() -> Result<bool> {
     Ok(input.trim() == "yes")
 }
 
+/// Data scanned by the queries run for a table and the id of the last one
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TableRun {
+    stats: QueryStats,
+    execution_id: Option<String>,
+}
+
+impl TableRun {
+    /// Add a query that ran after the ones recorded so far
+    fn record(&mut self, result: &QueryResult) {
+        self.stats.record(result);
+        self.execution_id = Some(result.execution_id.clone());
+    }
+
+    /// Add queries that ran after the ones recorded so far
+    fn merge(&mut self, other: TableRun) {
+        self.stats.merge(other.stats);
+        if other.execution_id.is_some() {
+            self.execution_id = other.execution_id;
+        }
+    }
+}
+
+/// Result of applying the changes of one table
+#[derive(Debug)]
+pub(crate) struct TableOutcome {
+    qualified_name: String,
+    operation: DiffOperation,
+    result: TableResult,
+}
+
+/// What happened to one table in an apply
+#[derive(Debug)]
+enum TableResult {
+    /// Queries run on success, or why the table could not be applied
+    Attempted(Result<TableRun>),
+    /// Not attempted because another table had failed
+    NotAttempted,
+    /// Skipped on resume, as the state file records the same DDL as applied
+    AlreadyApplied,
+}
+
 /// Apply the changes by executing DDL queries
-async fn apply_changes(
+///
+/// Tables are applied concurrently, up to `max_concurrent` at a time, after the
+/// databases of new tables have been created. A database that cannot be created
+/// fails the tables to be created in it. Once a table fails, tables not yet
+/// started are skipped, unless `continue_on_error` is set. Outcomes are returned
+/// grouped by database, in plan order within each. With `print_progress`, a line
+/// is printed as each table completes.
+///
+/// # Returns
+/// One outcome per changed table, including the tables skipped on resume
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn apply_changes(
     diff_result: &DiffResult,
-    query_executor: &QueryExecutor,
+    executors: &RegionalExecutors,
     base_path: &Path,
-) -> Result<()> {
-    let styles = OutputStyles::new();
-    let term = Term::stdout();
+    sql_source: &SqlSource,
+    update_strategy: UpdateStrategy,
+    drop_behavior: DropBehavior,
+    state_options: Option<&ApplyStateOptions>,
+    max_concurrent: usize,
+    continue_on_error: bool,
+    print_progress: bool,
+) -> Result<Vec<TableOutcome>> {
+    // Only a resumed apply starts from the recorded progress
+    let state = match state_options {
+        Some(options) if options.resume => ApplyState::load(&options.path)?,
+        _ => ApplyState::default(),
+    };
+    let state = Arc::new(Mutex::new(state));
 
     let total =
         diff_result.summary.to_add + diff_result.summary.to_change + diff_result.summary.to_destroy;
-    let mut current = 0;
+    let completed = Arc::new(AtomicUsize::new(0));
+    let failed = Arc::new(AtomicBool::new(false));
+
+    // Create each database needed by new tables once, before any table is created
+    let (databases_to_create, grouped) = group_operations(&diff_result.table_diffs);
+    let failed_databases = ensure_databases(&databases_to_create, |database_name| {
+        executors.for_database(database_name)
+    })
+    .await;
+    if !failed_databases.is_empty() {
+        failed.store(true, Ordering::SeqCst);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+    // Dropping the set, as the Ctrl-C handler does, aborts tasks still waiting
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut outcomes = Vec::new();
 
-    for table_diff in &diff_result.table_diffs {
+    for (index, table_diff) in grouped.into_values().flatten().enumerate() {
         let qualified_name = table_diff.qualified_name();
 
-        match table_diff.operation {
-            DiffOperation::Create => {
-                current += 1;
-                println!(
-                    "[{}/{}] {}: {}",
-                    current,
-                    total,
-                    styles.create.apply_to(&qualified_name),
-                    format_progress("Creating...")
-                );
+        // A table cannot be created in a database that could not be created
+        if table_diff.operation == DiffOperation::Create {
+            if let Some(error) = failed_databases.get(&table_diff.database_name) {
+                let current = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                if print_progress && !is_quiet() {
+                    println!(
+                        "[{}/{}] {}: {}",
+                        current,
+                        total,
+                        OutputStyles::new().create.apply_to(&qualified_name),
+                        format_error("Failed")
+                    );
+                }
+                outcomes.push((
+                    index,
+                    TableOutcome {
+                        qualified_name,
+                        operation: table_diff.operation.clone(),
+                        result: TableResult::Attempted(Err(anyhow::anyhow!("{}", error))),
+                    },
+                ));
+                continue;
+            }
+        }
 
-                create_table(table_diff, query_executor, base_path).await.map_err(|e| {
-                    anyhow::anyhow!(
-                        "Failed to create table {}. Error: {}\n\nPossible causes:\n  - Invalid SQL syntax in {}/{}.sql\n  - Insufficient AWS permissions\n  - Network connectivity issues",
+        let ddl_hash = match state_options {
+            Some(_) if table_diff.is_change() => Some(ddl_hash(&applied_ddl(
+                table_diff,
+                base_path,
+                sql_source,
+                drop_behavior,
+            )?)),
+            _ => None,
+        };
+        if let Some(ref hash) = ddl_hash {
+            if state_options.is_some_and(|options| options.resume)
+                && lock_state(&state).is_applied(&qualified_name, hash)
+            {
+                let current = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                if print_progress && !is_quiet() {
+                    println!(
+                        "[{}/{}] {}: {}",
+                        current,
+                        total,
                         qualified_name,
-                        e,
-                        table_diff.database_name,
-                        table_diff.table_name
-                    )
-                })?;
+                        format_success("Skipped (already applied)")
+                    );
+                }
+                outcomes.push((
+                    index,
+                    TableOutcome {
+                        qualified_name,
+                        operation: table_diff.operation.clone(),
+                        result: TableResult::AlreadyApplied,
+                    },
+                ));
+                continue;
+            }
+        }
 
-                term.clear_last_lines(1)?;
-                println!(
-                    "[{}/{}] {}: {}",
-                    current,
-                    total,
-                    styles.create.apply_to(&qualified_name),
-                    format_success("Created")
+        let table_diff = table_diff.clone();
+        let query_executor = executors.for_database(&table_diff.database_name).clone();
+        let base_path = base_path.to_path_buf();
+        let sql_source = sql_source.clone();
+        let state_path = state_options.map(|options| options.path.clone());
+        let state = state.clone();
+        let semaphore = semaphore.clone();
+        let completed = completed.clone();
+        let failed = failed.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire().await.unwrap();
+            if !continue_on_error && failed.load(Ordering::SeqCst) {
+                return (
+                    index,
+                    TableOutcome {
+                        qualified_name,
+                        operation: table_diff.operation,
+                        result: TableResult::NotAttempted,
+                    },
                 );
             }
-            DiffOperation::Update => {
-                current += 1;
-                println!(
-                    "[{}/{}] {}: {}",
-                    current,
-                    total,
-                    styles.update.apply_to(&qualified_name),
-                    format_progress("Modifying...")
-                );
 
-                update_table(table_diff, query_executor, base_path).await.map_err(|e| {
-                    anyhow::anyhow!(
-                        "Failed to update table {}. Error: {}\n\nPossible causes:\n  - Invalid SQL syntax in {}/{}.sql\n  - Table is locked or being accessed\n  - Insufficient AWS permissions\n  - Network connectivity issues",
-                        qualified_name,
-                        e,
-                        table_diff.database_name,
-                        table_diff.table_name
-                    )
-                })?;
+            let mut result = apply_table(
+                &table_diff,
+                &query_executor,
+                &base_path,
+                &sql_source,
+                update_strategy,
+                drop_behavior,
+            )
+            .await;
 
-                term.clear_last_lines(1)?;
-                println!(
-                    "[{}/{}] {}: {}",
-                    current,
-                    total,
-                    styles.update.apply_to(&qualified_name),
-                    format_success("Modified")
-                );
+            if let (Ok(_), Some(path), Some(hash)) = (&result, state_path, ddl_hash) {
+                let mut state = lock_state(&state);
+                state.record(qualified_name.clone(), hash);
+                if let Err(e) = state.save(&path) {
+                    result = Err(e.context("Applied, but failed to record it in the state file"));
+                }
             }
-            DiffOperation::Delete => {
-                current += 1;
-                println!(
-                    "[{}/{}] {}: {}",
-                    current,
-                    total,
-                    styles.delete.apply_to(&qualified_name),
-                    format_progress("Destroying...")
-                );
 
-                delete_table(table_diff, query_executor).await.map_err(|e| {
-                    anyhow::anyhow!(
-                        "Failed to delete table {}. Error: {}\n\nPossible causes:\n  - Table is locked or being accessed\n  - Insufficient AWS permissions\n  - Network connectivity issues",
-                        qualified_name,
-                        e
-                    )
-                })?;
+            if result.is_err() {
+                failed.store(true, Ordering::SeqCst);
+            }
 
-                term.clear_last_lines(1)?;
+            let current = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            let styles = OutputStyles::new();
+            let (style, done) = match table_diff.operation {
+                DiffOperation::Create => (&styles.create, "Created"),
+                DiffOperation::Update => (&styles.update, "Modified"),
+                _ => (&styles.delete, "Destroyed"),
+            };
+            let status = match result {
+                Ok(_) => format_success(done),
+                Err(_) => format_error("Failed"),
+            };
+            // Failures are listed in the report at the end, also with --quiet
+            if print_progress && !is_quiet() {
                 println!(
                     "[{}/{}] {}: {}",
                     current,
                     total,
-                    styles.delete.apply_to(&qualified_name),
-                    format_success("Destroyed")
+                    style.apply_to(&qualified_name),
+                    status
                 );
             }
-            DiffOperation::NoChange => {}
-        }
+
+            (
+                index,
+                TableOutcome {
+                    qualified_name,
+                    operation: table_diff.operation,
+                    result: TableResult::Attempted(result),
+                },
+            )
+        });
     }
 
-    Ok(())
+    while let Some(task) = tasks.join_next().await {
+        outcomes.push(task.context("Task join failed")?);
+    }
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    Ok(outcomes.into_iter().map(|(_, outcome)| outcome).collect())
 }
 
-/// Create a new table
-async fn create_table(
-    table_diff: &crate::types::diff_result::TableDiff,
+fn lock_state(state: &Mutex<ApplyState>) -> std::sync::MutexGuard<'_, ApplyState> {
+    // A panic while holding the lock cannot leave the state inconsistent
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Apply the change of a single table
+async fn apply_table(
+    table_diff: &TableDiff,
     query_executor: &QueryExecutor,
     base_path: &Path,
-) -> Result<()> {
-    // Ensure the database exists first
-    let create_db_query = format!(
-        "CREATE DATABASE IF NOT EXISTS `{}`",
-        table_diff.database_name
+    sql_source: &SqlSource,
+    update_strategy: UpdateStrategy,
+    drop_behavior: DropBehavior,
+) -> Result<TableRun> {
+    match table_diff.operation {
+        DiffOperation::Create => {
+            create_table(table_diff, query_executor, base_path, sql_source).await
+        }
+        DiffOperation::Update => {
+            update_table(
+                table_diff,
+                query_executor,
+                base_path,
+                sql_source,
+                update_strategy,
+                drop_behavior,
+            )
+            .await
+        }
+        DiffOperation::Delete => delete_table(table_diff, query_executor, drop_behavior).await,
+        DiffOperation::NoChange => Ok(TableRun::default()),
+    }
+}
+
+/// Report of succeeded, failed, and skipped tables, if any table failed
+///
+/// Tables are listed in the order of the outcomes.
+fn failure_report(outcomes: &[TableOutcome]) -> Option<String> {
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    let mut not_attempted = Vec::new();
+    let mut already_applied = Vec::new();
+    for outcome in outcomes {
+        match &outcome.result {
+            TableResult::Attempted(Ok(_)) => {
+                succeeded.push(format!("  - {}", outcome.qualified_name))
+            }
+            TableResult::Attempted(Err(e)) => {
+                failed.push(format!("  - {}: {:#}", outcome.qualified_name, e))
+            }
+            TableResult::NotAttempted => {
+                not_attempted.push(format!("  - {}", outcome.qualified_name))
+            }
+            TableResult::AlreadyApplied => {
+                already_applied.push(format!("  - {}", outcome.qualified_name))
+            }
+        }
+    }
+    if failed.is_empty() {
+        return None;
+    }
+
+    let mut report = format!(
+        "{} of {} table(s) failed, {} succeeded, {} not attempted",
+        failed.len(),
+        outcomes.len(),
+        succeeded.len(),
+        not_attempted.len()
     );
-    query_executor
-        .execute_query(&create_db_query)
-        .await
-        .with_context(|| format!("Failed to create database {}", table_diff.database_name))?;
+    if !already_applied.is_empty() {
+        report.push_str(&format!(", {} already applied", already_applied.len()));
+    }
+    report.push('.');
+    for (heading, tables) in [
+        ("Failed", &failed),
+        ("Succeeded", &succeeded),
+        (
+            "Not attempted (run with --continue-on-error to apply them anyway)",
+            &not_attempted,
+        ),
+        ("Already applied", &already_applied),
+    ] {
+        if !tables.is_empty() {
+            report.push_str(&format!("\n\n{}:\n{}", heading, tables.join("\n")));
+        }
+    }
+    Some(report)
+}
 
-    // Read the local SQL file to get the CREATE TABLE statement
-    use crate::file_utils::FileUtils;
+/// Status of every table in the order of the outcomes, for `--output-json`
+pub(crate) fn apply_result(outcomes: &[TableOutcome]) -> ApplyResult {
+    let tables = outcomes
+        .iter()
+        .map(|outcome| {
+            let (status, error, execution_id) = match &outcome.result {
+                TableResult::Attempted(Ok(run)) => {
+                    (ApplyStatus::Succeeded, None, run.execution_id.clone())
+                }
+                TableResult::Attempted(Err(e)) => {
+                    (ApplyStatus::Failed, Some(format!("{:#}", e)), None)
+                }
+                TableResult::NotAttempted => (ApplyStatus::Skipped, None, None),
+                TableResult::AlreadyApplied => (ApplyStatus::AlreadyApplied, None, None),
+            };
+            ApplyTableResult {
+                table: outcome.qualified_name.clone(),
+                operation: outcome.operation.clone(),
+                status,
+                error,
+                execution_id,
+            }
+        })
+        .collect();
+    ApplyResult::new(false, tables)
+}
 
-    let file_path = FileUtils::get_table_file_path(
-        base_path,
-        &table_diff.database_name,
-        &table_diff.table_name,
-    )?;
+/// The DDL apply runs for a table, used to tell whether a recorded apply still holds
+///
+/// Creates and updates are identified by the local definition, deletes by the
+/// DROP statement.
+fn applied_ddl(
+    table_diff: &TableDiff,
+    base_path: &Path,
+    sql_source: &SqlSource,
+    drop_behavior: DropBehavior,
+) -> Result<String> {
+    match table_diff.operation {
+        DiffOperation::Create | DiffOperation::Update => {
+            sql_source.read_table_sql(base_path, &table_diff.database_name, &table_diff.table_name)
+        }
+        DiffOperation::Delete => Ok(drop_table_statement(table_diff, drop_behavior)),
+        DiffOperation::NoChange => Ok(String::new()),
+    }
+}
 
-    let sql_content = FileUtils::read_sql_file(&file_path)?;
+/// Create a new table
+async fn create_table<R: QueryRunner>(
+    table_diff: &TableDiff,
+    query_executor: &R,
+    base_path: &Path,
+    sql_source: &SqlSource,
+) -> Result<TableRun> {
+    // Read the local SQL file to get the CREATE TABLE statement
+    let sql_content =
+        sql_source.read_table_sql(base_path, &table_diff.database_name, &table_diff.table_name)?;
 
     // Execute the CREATE TABLE query
-    query_executor
+    let result = query_executor
         .execute_query(&sql_content)
         .await
         .with_context(|| {
             format!(
-                "Failed to create table {}.{}",
-                table_diff.database_name, table_diff.table_name
+                "Failed to create {} {}.{}",
+                table_diff.object_type, table_diff.database_name, table_diff.table_name
             )
         })?;
 
-    Ok(())
+    let mut run = TableRun::default();
+    run.record(&result);
+    Ok(run)
+}
+
+/// Group table operations for apply
+///
+/// # Arguments
+/// * `table_diffs` - Table diffs of the plan
+///
+/// # Returns
+/// The distinct databases new tables are created in (sorted), and the tables with
+/// a change grouped by database, each group in plan order
+fn group_operations(table_diffs: &[TableDiff]) -> (Vec<String>, BTreeMap<String, Vec<&TableDiff>>) {
+    let databases_to_create: BTreeSet<&str> = table_diffs
+        .iter()
+        .filter(|table_diff| table_diff.operation == DiffOperation::Create)
+        .map(|table_diff| table_diff.database_name.as_str())
+        .collect();
+
+    let mut grouped: BTreeMap<String, Vec<&TableDiff>> = BTreeMap::new();
+    for table_diff in table_diffs {
+        if table_diff.operation != DiffOperation::NoChange {
+            grouped
+                .entry(table_diff.database_name.clone())
+                .or_default()
+                .push(table_diff);
+        }
+    }
+
+    (
+        databases_to_create
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        grouped,
+    )
+}
+
+/// Run `CREATE DATABASE IF NOT EXISTS` once for each database that will receive new tables
+///
+/// Every database is attempted, even after one fails.
+///
+/// # Arguments
+/// * `databases` - Distinct databases to create, from `group_operations`
+/// * `runner_for` - Returns the query runner for the region a database lives in
+///
+/// # Returns
+/// The error of each database that could not be created
+async fn ensure_databases<'a, R, F>(databases: &[String], runner_for: F) -> BTreeMap<String, String>
+where
+    R: QueryRunner + 'a,
+    F: Fn(&str) -> &'a R,
+{
+    let mut failures = BTreeMap::new();
+    for database_name in databases {
+        let create_db_query = format!("CREATE DATABASE IF NOT EXISTS `{}`", database_name);
+        if let Err(e) = runner_for(database_name)
+            .execute_query(&create_db_query)
+            .await
+        {
+            failures.insert(
+                database_name.clone(),
+                format!("Failed to create database {}: {:#}", database_name, e),
+            );
+        }
+    }
+
+    failures
+}
+
+/// Build the DROP TABLE statement used by deletes and drop-create updates
+///
+/// Views are dropped with DROP VIEW; they hold no data, so PURGE does not apply.
+pub(crate) fn drop_table_statement(table_diff: &TableDiff, drop_behavior: DropBehavior) -> String {
+    if table_diff.remote_object_type() == ObjectType::View {
+        return format!(
+            "DROP VIEW IF EXISTS `{}`.`{}`",
+            table_diff.database_name, table_diff.table_name
+        );
+    }
+
+    let statement = format!(
+        "DROP TABLE IF EXISTS `{}`.`{}`",
+        table_diff.database_name, table_diff.table_name
+    );
+    match drop_behavior {
+        DropBehavior::KeepData => statement,
+        DropBehavior::Purge => format!("{} PURGE", statement),
+    }
+}
+
+/// Warning shown before applying when drops will purge table data
+///
+/// Deleted tables are always dropped; updated tables may be dropped and recreated
+/// depending on the update strategy.
+///
+/// # Returns
+/// None unless `drop_behavior` is purge and the plan deletes or updates a table
+pub(crate) fn purge_warning(
+    diff_result: &DiffResult,
+    drop_behavior: DropBehavior,
+) -> Option<String> {
+    if drop_behavior != DropBehavior::Purge {
+        return None;
+    }
+
+    let tables: Vec<String> = diff_result
+        .table_diffs
+        .iter()
+        .filter(|table_diff| {
+            matches!(
+                table_diff.operation,
+                DiffOperation::Delete | DiffOperation::Update
+            ) && table_diff.remote_object_type() == ObjectType::Table
+        })
+        .map(|table_diff| table_diff.qualified_name())
+        .collect();
+    if tables.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "drop_behavior is 'purge': DROP TABLE ... PURGE permanently deletes the data of dropped tables. Affected table(s): {}",
+        tables.join(", ")
+    ))
+}
+
+/// Statements used to bring an existing table in line with its local definition
+#[derive(Debug, Clone, PartialEq)]
+enum UpdatePlan {
+    /// ALTER TABLE statements that modify the table in place
+    Alter(Vec<String>),
+    /// A CREATE OR REPLACE statement, falling back to drop-create if Athena does not
+    /// support it for the table
+    Replace(String),
+    /// DROP TABLE followed by the local CREATE statement
+    DropCreate,
+}
+
+/// Decide how to update a table under the configured strategy
+///
+/// # Arguments
+/// * `strategy` - Configured update strategy
+/// * `table_diff` - Diff for the table being updated
+/// * `local_sql` - Local CREATE statement for the table
+///
+/// A table replaced by a view (or the other way around) is always dropped and
+/// created, and a changed view is replaced with CREATE OR REPLACE VIEW whatever
+/// the strategy, since views cannot be altered.
+fn plan_update(strategy: UpdateStrategy, table_diff: &TableDiff, local_sql: &str) -> UpdatePlan {
+    if table_diff.remote_object_type() != table_diff.object_type {
+        return UpdatePlan::DropCreate;
+    }
+    if table_diff.object_type == ObjectType::View {
+        return to_create_or_replace(local_sql)
+            .map(UpdatePlan::Replace)
+            .unwrap_or(UpdatePlan::DropCreate);
+    }
+
+    match strategy {
+        UpdateStrategy::DropCreate => UpdatePlan::DropCreate,
+        UpdateStrategy::Recreate => to_create_or_replace(local_sql)
+            .map(UpdatePlan::Replace)
+            .unwrap_or(UpdatePlan::DropCreate),
+        UpdateStrategy::AlterWhenPossible => build_alter_statements(table_diff, local_sql)
+            .map(UpdatePlan::Alter)
+            .unwrap_or(UpdatePlan::DropCreate),
+    }
+}
+
+/// Rewrite a CREATE statement into its CREATE OR REPLACE form
+///
+/// Returns None if the statement does not start with CREATE.
+fn to_create_or_replace(sql: &str) -> Option<String> {
+    let re =
+        regex::Regex::new(r"(?is)^((?:\s*--[^\n]*\n)*\s*)CREATE\s+(?:OR\s+REPLACE\s+)?").ok()?;
+    let captures = re.captures(sql)?;
+    let whole = captures.get(0)?;
+    let leading = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+
+    Some(format!(
+        "{}CREATE OR REPLACE {}",
+        leading,
+        &sql[whole.end()..]
+    ))
+}
+
+/// Build ALTER TABLE statements for a diff, if the diff can be expressed that way
+///
+/// Only added columns (appended after all existing columns) and location changes
+/// can be applied in place. Any other detected change, or a difference the
+/// differ does not model such as the table COMMENT, returns None.
+fn build_alter_statements(table_diff: &TableDiff, local_sql: &str) -> Option<Vec<String>> {
+    let details = table_diff.change_details.as_ref()?;
+    if details.unmodeled_changes
+        || (details.column_changes.is_empty() && details.property_changes.is_empty())
+    {
+        return None;
+    }
+
+    let table_ref = format!("`{}`.`{}`", table_diff.database_name, table_diff.table_name);
+    let mut statements = Vec::new();
+
+    if !details.column_changes.is_empty() {
+        if details
+            .column_changes
+            .iter()
+            .any(|c| c.change_type != ColumnChangeType::Added)
+        {
+            return None;
+        }
+
+        // ADD COLUMNS appends at the end, so the new columns must be the trailing local columns
+        let local_columns = extract_column_list(local_sql);
+        let added_count = details.column_changes.len();
+        if local_columns.len() < added_count {
+            return None;
+        }
+        let trailing = &local_columns[local_columns.len() - added_count..];
+        let all_added_trailing = trailing.iter().all(|column| {
+            details
+                .column_changes
+                .iter()
+                .any(|c| c.column_name == column.name.to_lowercase())
+        });
+        // ADD COLUMNS cannot declare constraints such as NOT NULL
+        if !all_added_trailing || trailing.iter().any(|column| column.constraints.is_some()) {
+            return None;
+        }
+
+        let column_defs: Vec<String> = trailing
+            .iter()
+            .map(|column| format!("`{}` {}", column.name.trim_matches('`'), column.data_type))
+            .collect();
+        statements.push(format!(
+            "ALTER TABLE {} ADD COLUMNS ({})",
+            table_ref,
+            column_defs.join(", ")
+        ));
+    }
+
+    for property in &details.property_changes {
+        match (property.property_name.as_str(), &property.new_value) {
+            ("location", Some(location)) if property.old_value.is_some() => {
+                statements.push(format!(
+                    "ALTER TABLE {} SET LOCATION '{}'",
+                    table_ref, location
+                ));
+            }
+            _ => return None,
+        }
+    }
+
+    Some(statements)
 }
 
 /// Update an existing table
-async fn update_table(
-    table_diff: &crate::types::diff_result::TableDiff,
-    query_executor: &QueryExecutor,
+async fn update_table<R: QueryRunner>(
+    table_diff: &TableDiff,
+    query_executor: &R,
     base_path: &Path,
-) -> Result<()> {
+    sql_source: &SqlSource,
+    update_strategy: UpdateStrategy,
+    drop_behavior: DropBehavior,
+) -> Result<TableRun> {
+    let sql_content =
+        sql_source.read_table_sql(base_path, &table_diff.database_name, &table_diff.table_name)?;
+
+    match plan_update(update_strategy, table_diff, &sql_content) {
+        UpdatePlan::Alter(statements) => {
+            let mut run = TableRun::default();
+            for statement in statements {
+                let result = query_executor
+                    .execute_query(&statement)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to alter table {}.{}",
+                            table_diff.database_name, table_diff.table_name
+                        )
+                    })?;
+                run.record(&result);
+            }
+            Ok(run)
+        }
+        UpdatePlan::Replace(statement) => match query_executor.execute_query(&statement).await {
+            Ok(result) => {
+                let mut run = TableRun::default();
+                run.record(&result);
+                Ok(run)
+            }
+            Err(e) if is_replace_not_supported(&e) => {
+                warn(format!(
+                    "CREATE OR REPLACE is not supported for {}, falling back to drop and create: {:#}",
+                    table_diff.qualified_name(),
+                    e
+                ));
+                drop_and_create_table(
+                    table_diff,
+                    query_executor,
+                    base_path,
+                    sql_source,
+                    drop_behavior,
+                )
+                .await
+            }
+            Err(e) => Err(e.context(format!(
+                "Failed to replace {} {}",
+                table_diff.object_type,
+                table_diff.qualified_name()
+            ))),
+        },
+        UpdatePlan::DropCreate => {
+            drop_and_create_table(
+                table_diff,
+                query_executor,
+                base_path,
+                sql_source,
+                drop_behavior,
+            )
+            .await
+        }
+    }
+}
+
+/// Errors Athena reports when it does not support CREATE OR REPLACE for a table,
+/// lowercased: the Hive connector's refusal, and the parsers rejecting the
+/// `OR REPLACE` keywords
+const REPLACE_NOT_SUPPORTED_MESSAGES: &[&str] = &[
+    "this connector does not support replacing tables",
+    "mismatched input 'or'",
+    "mismatched input 'replace'",
+    "cannot recognize input near 'create' 'or' 'replace'",
+];
+
+/// Whether a failed CREATE OR REPLACE was rejected because Athena does not support
+/// the statement for this table, as it does not for Hive tables
+///
+/// Any other failure, such as a syntax error in the local DDL, an unsupported
+/// column type, a missing permission, a timeout, or a cancelled query, must not
+/// lead to a drop-create: the table would be dropped and then fail to be created
+/// again.
+fn is_replace_not_supported(error: &anyhow::Error) -> bool {
+    let message = format!("{:#}", error).to_lowercase();
+    REPLACE_NOT_SUPPORTED_MESSAGES
+        .iter()
+        .any(|expected| message.contains(expected))
+}
+
+/// Update a table by dropping it and creating it from the local definition
+async fn drop_and_create_table<R: QueryRunner>(
+    table_diff: &TableDiff,
+    query_executor: &R,
+    base_path: &Path,
+    sql_source: &SqlSource,
+    drop_behavior: DropBehavior,
+) -> Result<TableRun> {
     // For Athena, updating a table requires:
     // 1. DROP TABLE (if exists)
     // 2. CREATE TABLE with new definition
 
     // Drop the existing table
-    let drop_query = format!(
-        "DROP TABLE IF EXISTS `{}`.`{}`",
-        table_diff.database_name, table_diff.table_name
-    );
+    let drop_query = drop_table_statement(table_diff, drop_behavior);
 
-    query_executor
+    let result = query_executor
         .execute_query(&drop_query)
         .await
         .with_context(|| {
             format!(
-                "Failed to drop table {}.{}",
-                table_diff.database_name, table_diff.table_name
+                "Failed to drop {} {}.{}",
+                table_diff.remote_object_type(),
+                table_diff.database_name,
+                table_diff.table_name
             )
         })?;
 
     // Create the table with new definition
-    create_table(table_diff, query_executor, base_path).await?;
+    let mut run = TableRun::default();
+    run.record(&result);
+    run.merge(create_table(table_diff, query_executor, base_path, sql_source).await?);
 
-    Ok(())
+    Ok(run)
 }
 
 /// Delete a table
-async fn delete_table(
-    table_diff: &crate::types::diff_result::TableDiff,
-    query_executor: &QueryExecutor,
-) -> Result<()> {
-    let drop_query = format!(
-        "DROP TABLE IF EXISTS `{}`.`{}`",
-        table_diff.database_name, table_diff.table_name
-    );
+async fn delete_table<R: QueryRunner>(
+    table_diff: &TableDiff,
+    query_executor: &R,
+    drop_behavior: DropBehavior,
+) -> Result<TableRun> {
+    let drop_query = drop_table_statement(table_diff, drop_behavior);
 
-    query_executor
+    let result = query_executor
         .execute_query(&drop_query)
         .await
         .with_context(|| {
             format!(
-                "Failed to delete table {}.{}",
-                table_diff.database_name, table_diff.table_name
+                "Failed to delete {} {}.{}",
+                table_diff.object_type, table_diff.database_name, table_diff.table_name
             )
         })?;
 
-    Ok(())
+    let mut run = TableRun::default();
+    run.record(&result);
+    Ok(run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::diff_result::{ChangeDetails, ColumnChange, PropertyChange};
+    use crate::types::query_execution::QueryExecutionStatus;
+
+    const LOCAL_SQL: &str = r#"CREATE EXTERNAL TABLE customers (
+    id bigint,
+    name string,
+    email string
+)
+STORED AS PARQUET
+LOCATION 's3://bucket/customers-v2/'"#;
+
+    fn update_diff(
+        column_changes: Vec<ColumnChange>,
+        property_changes: Vec<PropertyChange>,
+    ) -> TableDiff {
+        TableDiff {
+            database_name: "salesdb".to_string(),
+            table_name: "customers".to_string(),
+            operation: DiffOperation::Update,
+            text_diff: Some("diff".to_string()),
+            change_details: Some(ChangeDetails {
+                column_changes,
+                property_changes,
+                bucketing_change: None,
+                unmodeled_changes: false,
+            }),
+            location_impact: None,
+            object_type: ObjectType::Table,
+        }
+    }
+
+    fn added(name: &str, typ: &str) -> ColumnChange {
+        ColumnChange {
+            change_type: ColumnChangeType::Added,
+            column_name: name.to_string(),
+            old_type: None,
+            new_type: Some(typ.to_string()),
+            old_constraints: None,
+            new_constraints: None,
+        }
+    }
+
+    fn location_change() -> PropertyChange {
+        PropertyChange {
+            property_name: "location".to_string(),
+            old_value: Some("s3://bucket/customers/".to_string()),
+            new_value: Some("s3://bucket/customers-v2/".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_plan_update_drop_create() {
+        let diff = update_diff(vec![added("email", "string")], vec![]);
+        assert_eq!(
+            plan_update(UpdateStrategy::DropCreate, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_plan_update_recreate() {
+        let diff = update_diff(vec![added("email", "string")], vec![]);
+        match plan_update(UpdateStrategy::Recreate, &diff, LOCAL_SQL) {
+            UpdatePlan::Replace(sql) => {
+                assert!(sql.starts_with("CREATE OR REPLACE EXTERNAL TABLE customers ("));
+            }
+            other => panic!("Expected Replace, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plan_update_alter_add_columns_and_location() {
+        let diff = update_diff(vec![added("email", "string")], vec![location_change()]);
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::Alter(vec![
+                "ALTER TABLE `salesdb`.`customers` ADD COLUMNS (`email` string)".to_string(),
+                "ALTER TABLE `salesdb`.`customers` SET LOCATION 's3://bucket/customers-v2/'"
+                    .to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plan_update_alter_falls_back_on_type_change() {
+        let diff = update_diff(
+            vec![ColumnChange {
+                change_type: ColumnChangeType::TypeChanged,
+                column_name: "id".to_string(),
+                old_type: Some("int".to_string()),
+                new_type: Some("bigint".to_string()),
+                old_constraints: None,
+                new_constraints: None,
+            }],
+            vec![],
+        );
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_plan_update_alter_falls_back_on_unmodeled_changes() {
+        // e.g. a column added together with a changed table COMMENT
+        let mut diff = update_diff(vec![added("email", "string")], vec![]);
+        diff.change_details.as_mut().unwrap().unmodeled_changes = true;
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_plan_update_alter_falls_back_on_column_added_in_middle() {
+        let diff = update_diff(vec![added("name", "string")], vec![]);
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_plan_update_alter_falls_back_on_other_property_change() {
+        let diff = update_diff(
+            vec![],
+            vec![PropertyChange {
+                property_name: "format".to_string(),
+                old_value: Some("PARQUET".to_string()),
+                new_value: Some("ORC".to_string()),
+            }],
+        );
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_plan_update_alter_falls_back_without_details() {
+        let mut diff = update_diff(vec![], vec![]);
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+
+        diff.change_details = None;
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+    }
+
+    #[test]
+    fn test_resume_skips_applied_table_until_ddl_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let table_dir = temp_dir.path().join("salesdb");
+        std::fs::create_dir_all(&table_dir).unwrap();
+        std::fs::write(table_dir.join("customers.sql"), LOCAL_SQL).unwrap();
+
+        let create = TableDiff {
+            operation: DiffOperation::Create,
+            text_diff: None,
+            change_details: None,
+            ..update_diff(vec![], vec![])
+        };
+        let hash_now = || {
+            ddl_hash(
+                &applied_ddl(
+                    &create,
+                    temp_dir.path(),
+                    &SqlSource::default(),
+                    DropBehavior::KeepData,
+                )
+                .unwrap(),
+            )
+        };
+
+        let mut state = ApplyState::default();
+        state.record(create.qualified_name(), hash_now());
+        assert!(state.is_applied("salesdb.customers", &hash_now()));
+
+        // A changed definition must be applied again
+        std::fs::write(
+            table_dir.join("customers.sql"),
+            LOCAL_SQL.replace("email string", "email varchar(255)"),
+        )
+        .unwrap();
+        assert!(!state.is_applied("salesdb.customers", &hash_now()));
+    }
+
+    #[tokio::test]
+    async fn test_apply_changes_records_tables_skipped_on_resume() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let table_dir = temp_dir.path().join("salesdb");
+        std::fs::create_dir_all(&table_dir).unwrap();
+        std::fs::write(table_dir.join("customers.sql"), LOCAL_SQL).unwrap();
+
+        let update = update_diff(vec![added("email", "string")], vec![]);
+        let hash = ddl_hash(
+            &applied_ddl(
+                &update,
+                temp_dir.path(),
+                &SqlSource::default(),
+                DropBehavior::KeepData,
+            )
+            .unwrap(),
+        );
+        let state_options = ApplyStateOptions {
+            path: temp_dir.path().join("apply-state.json"),
+            resume: true,
+        };
+        let mut state = ApplyState::default();
+        state.record(update.qualified_name(), hash);
+        state.save(&state_options.path).unwrap();
+
+        // No query runs, as the only table is already applied
+        let executors = RegionalExecutors::from_config(&Config::default())
+            .await
+            .unwrap();
+        let outcomes = apply_changes(
+            &DiffResult::from_table_diffs(vec![update]),
+            &executors,
+            temp_dir.path(),
+            &SqlSource::default(),
+            UpdateStrategy::DropCreate,
+            DropBehavior::KeepData,
+            Some(&state_options),
+            1,
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(matches!(outcomes[0].result, TableResult::AlreadyApplied));
+        assert_eq!(failure_report(&outcomes), None);
+        let result = apply_result(&outcomes);
+        assert_eq!(result.tables[0].status, ApplyStatus::AlreadyApplied);
+        assert_eq!(result.totals.already_applied, 1);
+
+        let mut outcomes = outcomes;
+        outcomes.push(TableOutcome {
+            qualified_name: "salesdb.orders".to_string(),
+            operation: DiffOperation::Create,
+            result: TableResult::Attempted(Err(anyhow::anyhow!("boom"))),
+        });
+        assert_eq!(
+            failure_report(&outcomes).unwrap(),
+            "1 of 2 table(s) failed, 0 succeeded, 0 not attempted, 1 already applied.\n\n\
+             Failed:\n  - salesdb.orders: boom\n\n\
+             Already applied:\n  - salesdb.customers"
+        );
+    }
+
+    #[test]
+    fn test_applied_ddl_for_delete_follows_drop_behavior() {
+        let delete = TableDiff {
+            operation: DiffOperation::Delete,
+            ..update_diff(vec![], vec![])
+        };
+        let ddl = |drop_behavior| {
+            applied_ddl(
+                &delete,
+                Path::new("."),
+                &SqlSource::default(),
+                drop_behavior,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            ddl(DropBehavior::KeepData),
+            "DROP TABLE IF EXISTS `salesdb`.`customers`"
+        );
+        assert_ne!(
+            ddl_hash(&ddl(DropBehavior::KeepData)),
+            ddl_hash(&ddl(DropBehavior::Purge))
+        );
+    }
+
+    #[test]
+    fn test_drop_table_statement_follows_drop_behavior() {
+        let diff = update_diff(vec![], vec![]);
+        assert_eq!(
+            drop_table_statement(&diff, DropBehavior::KeepData),
+            "DROP TABLE IF EXISTS `salesdb`.`customers`"
+        );
+        assert_eq!(
+            drop_table_statement(&diff, DropBehavior::Purge),
+            "DROP TABLE IF EXISTS `salesdb`.`customers` PURGE"
+        );
+    }
+
+    fn view_diff(property_changes: Vec<PropertyChange>) -> TableDiff {
+        let mut diff = update_diff(vec![], property_changes);
+        diff.object_type = ObjectType::View;
+        diff
+    }
+
+    #[test]
+    fn test_view_updates_ignore_strategy() {
+        let view_sql = "CREATE VIEW customers AS SELECT 1";
+        let diff = view_diff(vec![]);
+        for strategy in [
+            UpdateStrategy::DropCreate,
+            UpdateStrategy::Recreate,
+            UpdateStrategy::AlterWhenPossible,
+        ] {
+            assert_eq!(
+                plan_update(strategy, &diff, view_sql),
+                UpdatePlan::Replace("CREATE OR REPLACE VIEW customers AS SELECT 1".to_string())
+            );
+        }
+        assert_eq!(
+            drop_table_statement(&diff, DropBehavior::Purge),
+            "DROP VIEW IF EXISTS `salesdb`.`customers`"
+        );
+    }
+
+    #[test]
+    fn test_object_type_change_drops_the_remote_object() {
+        let object_type_change = |old: &str, new: &str| PropertyChange {
+            property_name: "object_type".to_string(),
+            old_value: Some(old.to_string()),
+            new_value: Some(new.to_string()),
+        };
+
+        // Table replaced by a view: DROP TABLE, then CREATE VIEW
+        let diff = view_diff(vec![object_type_change("table", "view")]);
+        assert_eq!(
+            plan_update(
+                UpdateStrategy::Recreate,
+                &diff,
+                "CREATE VIEW customers AS SELECT 1"
+            ),
+            UpdatePlan::DropCreate
+        );
+        assert_eq!(
+            drop_table_statement(&diff, DropBehavior::KeepData),
+            "DROP TABLE IF EXISTS `salesdb`.`customers`"
+        );
+
+        // View replaced by a table: DROP VIEW, then CREATE TABLE
+        let diff = update_diff(vec![], vec![object_type_change("view", "table")]);
+        assert_eq!(
+            plan_update(UpdateStrategy::AlterWhenPossible, &diff, LOCAL_SQL),
+            UpdatePlan::DropCreate
+        );
+        assert_eq!(
+            drop_table_statement(&diff, DropBehavior::Purge),
+            "DROP VIEW IF EXISTS `salesdb`.`customers`"
+        );
+        assert_eq!(
+            purge_warning(
+                &DiffResult::from_table_diffs(vec![diff]),
+                DropBehavior::Purge
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_purge_warning_only_for_purged_drops() {
+        let diff_result = DiffResult::from_table_diffs(vec![update_diff(vec![], vec![])]);
+        assert_eq!(purge_warning(&diff_result, DropBehavior::KeepData), None);
+
+        let message = purge_warning(&diff_result, DropBehavior::Purge).unwrap();
+        assert!(message.contains("PURGE"));
+        assert!(message.contains("salesdb.customers"));
+
+        assert_eq!(purge_warning(&DiffResult::new(), DropBehavior::Purge), None);
+    }
+
+    #[test]
+    fn test_no_plan_notice_omits_table_details() {
+        let update = update_diff(vec![added("email", "string")], vec![]);
+        let diff_result = DiffResult::from_table_diffs(vec![update]);
+
+        let notice = console::strip_ansi_codes(&no_plan_notice(&diff_result)).to_string();
+        assert_eq!(
+            notice,
+            "Applying 0 to add, 1 to change, 0 to destroy (plan display skipped with --no-plan)."
+        );
+        assert!(!notice.contains(&diff_result.table_diffs[0].qualified_name()));
+
+        let notice = no_plan_notice(&DiffResult::new());
+        assert!(notice.contains("No changes"));
+    }
+
+    #[test]
+    fn test_split_by_allowlist_auto_approves_add_only_change() {
+        let add_only = update_diff(vec![added("email", "string")], vec![]);
+        let diff_result = DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(std::slice::from_ref(&add_only)),
+            table_diffs: vec![add_only],
+        };
+
+        let (allowed, held_back) = split_by_allowlist(
+            diff_result,
+            &[ChangeCategory::ColumnAdded],
+            UpdateStrategy::AlterWhenPossible,
+            |_| Ok(LOCAL_SQL.to_string()),
+        )
+        .unwrap();
+        assert!(held_back.is_empty());
+        assert!(!allowed.no_change);
+        assert_eq!(allowed.summary.to_change, 1);
+    }
+
+    #[test]
+    fn test_split_by_allowlist_holds_back_updates_not_run_as_alter() {
+        let add_only = update_diff(vec![added("email", "string")], vec![]);
+        let diff_result = DiffResult::from_table_diffs(vec![add_only]);
+
+        for strategy in [UpdateStrategy::DropCreate, UpdateStrategy::Recreate] {
+            let (allowed, held_back) = split_by_allowlist(
+                diff_result.clone(),
+                &[ChangeCategory::ColumnAdded],
+                strategy,
+                |_| Ok(LOCAL_SQL.to_string()),
+            )
+            .unwrap();
+            assert!(allowed.no_change);
+            assert_eq!(
+                held_back,
+                vec!["salesdb.customers (not applied with ALTER TABLE)".to_string()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_by_allowlist_holds_back_unmodeled_changes() {
+        let mut add_and_comment = update_diff(vec![added("email", "string")], vec![]);
+        add_and_comment
+            .change_details
+            .as_mut()
+            .unwrap()
+            .unmodeled_changes = true;
+        let diff_result = DiffResult::from_table_diffs(vec![add_and_comment]);
+
+        let (allowed, held_back) = split_by_allowlist(
+            diff_result,
+            &[ChangeCategory::ColumnAdded],
+            UpdateStrategy::AlterWhenPossible,
+            |_| Ok(LOCAL_SQL.to_string()),
+        )
+        .unwrap();
+        assert!(allowed.no_change);
+        assert_eq!(
+            held_back,
+            vec!["salesdb.customers (unrecognized changes)".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_split_by_allowlist_holds_back_type_change() {
+        let add_only = update_diff(vec![added("email", "string")], vec![]);
+        let mut type_change = update_diff(
+            vec![ColumnChange {
+                change_type: ColumnChangeType::TypeChanged,
+                column_name: "id".to_string(),
+                old_type: Some("int".to_string()),
+                new_type: Some("bigint".to_string()),
+                old_constraints: None,
+                new_constraints: None,
+            }],
+            vec![],
+        );
+        type_change.table_name = "orders".to_string();
+        let mut create = update_diff(vec![], vec![]);
+        create.table_name = "leads".to_string();
+        create.operation = DiffOperation::Create;
+        create.change_details = None;
+
+        let table_diffs = vec![add_only, type_change, create];
+        let diff_result = DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(&table_diffs),
+            table_diffs,
+        };
+
+        let (allowed, held_back) = split_by_allowlist(
+            diff_result,
+            &[ChangeCategory::ColumnAdded],
+            UpdateStrategy::AlterWhenPossible,
+            |_| Ok(LOCAL_SQL.to_string()),
+        )
+        .unwrap();
+        assert_eq!(allowed.table_diffs.len(), 1);
+        assert_eq!(allowed.table_diffs[0].table_name, "customers");
+        assert_eq!(allowed.summary.to_change, 1);
+        assert_eq!(allowed.summary.to_add, 0);
+        assert_eq!(
+            held_back,
+            vec![
+                "salesdb.orders (column_type_changed)".to_string(),
+                "salesdb.leads (table_created)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_by_allowlist_holds_back_unrecognized_changes() {
+        let text_only = update_diff(vec![], vec![]);
+        let diff_result = DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(std::slice::from_ref(&text_only)),
+            table_diffs: vec![text_only],
+        };
+
+        let (allowed, held_back) = split_by_allowlist(
+            diff_result,
+            &[ChangeCategory::ColumnAdded],
+            UpdateStrategy::AlterWhenPossible,
+            |_| Ok(LOCAL_SQL.to_string()),
+        )
+        .unwrap();
+        assert!(allowed.no_change);
+        assert_eq!(
+            held_back,
+            vec!["salesdb.customers (unrecognized changes)".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ensure_databases_once_per_database() {
+        use crate::aws::athena::MockQueryRunner;
+        use crate::types::query_execution::{QueryExecutionStatus, QueryResult};
+        use mockall::predicate::eq;
+
+        let create = |database: &str, table: &str| TableDiff {
+            database_name: database.to_string(),
+            table_name: table.to_string(),
+            operation: DiffOperation::Create,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        let mut update = update_diff(vec![added("email", "string")], vec![]);
+        update.database_name = "existingdb".to_string();
+
+        let table_diffs = vec![
+            create("salesdb", "customers"),
+            create("salesdb", "orders"),
+            create("marketingdb", "leads"),
+            create("salesdb", "payments"),
+            update,
+        ];
+        let diff_result = DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(&table_diffs),
+            table_diffs,
+        };
+
+        let mut runner = MockQueryRunner::new();
+        for database in ["marketingdb", "salesdb"] {
+            runner
+                .expect_execute_query()
+                .with(eq(format!("CREATE DATABASE IF NOT EXISTS `{}`", database)))
+                .times(1)
+                .returning(|_| {
+                    Box::pin(async {
+                        Ok(QueryResult::new(
+                            "id".to_string(),
+                            QueryExecutionStatus::Succeeded,
+                        ))
+                    })
+                });
+        }
+
+        let (databases, _) = group_operations(&diff_result.table_diffs);
+        assert!(ensure_databases(&databases, |_| &runner).await.is_empty());
+    }
+
+    /// A temporary base path holding `salesdb/customers.sql`
+    fn customers_base_path() -> tempfile::TempDir {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("salesdb")).unwrap();
+        std::fs::write(temp_dir.path().join("salesdb/customers.sql"), LOCAL_SQL).unwrap();
+        temp_dir
+    }
+
+    #[tokio::test]
+    async fn test_update_replace_failure_does_not_drop() {
+        use crate::aws::athena::MockQueryRunner;
+
+        let base_path = customers_base_path();
+        let mut runner = MockQueryRunner::new();
+        // Any other query, such as a DROP TABLE, fails the test as unexpected
+        runner
+            .expect_execute_query()
+            .withf(|query| query.starts_with("CREATE OR REPLACE"))
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Err(anyhow::anyhow!(
+                        "Query execution failed: line 3:5: Column 'email' cannot be resolved"
+                    ))
+                })
+            });
+
+        let diff = update_diff(vec![added("email", "string")], vec![]);
+        let result = update_table(
+            &diff,
+            &runner,
+            base_path.path(),
+            &SqlSource::default(),
+            UpdateStrategy::Recreate,
+            DropBehavior::Purge,
+        )
+        .await;
+        assert!(
+            format!("{:#}", result.unwrap_err())
+                .starts_with("Failed to replace table salesdb.customers")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_replace_not_supported_falls_back_to_drop_create() {
+        use crate::aws::athena::MockQueryRunner;
+
+        let base_path = customers_base_path();
+        let mut runner = MockQueryRunner::new();
+        let mut sequence = mockall::Sequence::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query.starts_with("CREATE OR REPLACE"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| {
+                Box::pin(async {
+                    Err(anyhow::anyhow!(
+                        "Query execution failed: NOT_SUPPORTED: This connector does not support replacing tables"
+                    ))
+                })
+            });
+        for prefix in ["DROP TABLE", "CREATE EXTERNAL TABLE"] {
+            runner
+                .expect_execute_query()
+                .withf(move |query| query.starts_with(prefix))
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| {
+                    Box::pin(async {
+                        Ok(QueryResult::new(
+                            "id".to_string(),
+                            QueryExecutionStatus::Succeeded,
+                        ))
+                    })
+                });
+        }
+
+        let diff = update_diff(vec![added("email", "string")], vec![]);
+        update_table(
+            &diff,
+            &runner,
+            base_path.path(),
+            &SqlSource::default(),
+            UpdateStrategy::Recreate,
+            DropBehavior::KeepData,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_is_replace_not_supported() {
+        assert!(is_replace_not_supported(&anyhow::anyhow!(
+            "Query execution failed: line 1:8: mismatched input 'OR'. Expecting: 'TABLE'"
+        )));
+        assert!(is_replace_not_supported(&anyhow::anyhow!(
+            "Query execution failed: NOT_SUPPORTED: This connector does not support replacing tables"
+        )));
+        assert!(!is_replace_not_supported(&anyhow::anyhow!(
+            "Query execution failed: NOT_SUPPORTED: Column type not supported: interval"
+        )));
+        assert!(!is_replace_not_supported(&anyhow::anyhow!(
+            "Query execution was cancelled"
+        )));
+        assert!(!is_replace_not_supported(&anyhow::anyhow!(
+            "Query execution timed out after 300 seconds"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_databases_continues_after_failure() {
+        use crate::aws::athena::MockQueryRunner;
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "CREATE DATABASE IF NOT EXISTS `marketingdb`")
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("AccessDeniedException")) }));
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "CREATE DATABASE IF NOT EXISTS `salesdb`")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(QueryResult::new(
+                        "id".to_string(),
+                        QueryExecutionStatus::Succeeded,
+                    ))
+                })
+            });
+
+        let failures =
+            ensure_databases(&["marketingdb".to_string(), "salesdb".to_string()], |_| {
+                &runner
+            })
+            .await;
+        assert_eq!(
+            failures,
+            BTreeMap::from([(
+                "marketingdb".to_string(),
+                "Failed to create database marketingdb: AccessDeniedException".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_group_operations() {
+        let diff = |database: &str, table: &str, operation| TableDiff {
+            database_name: database.to_string(),
+            table_name: table.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        let table_diffs = vec![
+            diff("salesdb", "customers", DiffOperation::Create),
+            diff("marketingdb", "leads", DiffOperation::Delete),
+            diff("salesdb", "orders", DiffOperation::NoChange),
+            diff("salesdb", "payments", DiffOperation::Create),
+            diff("archivedb", "events", DiffOperation::Update),
+        ];
+
+        let (databases, grouped) = group_operations(&table_diffs);
+        // Only databases receiving new tables are created, once each
+        assert_eq!(databases, vec!["salesdb".to_string()]);
+
+        let grouped: Vec<(String, Vec<String>)> = grouped
+            .into_iter()
+            .map(|(database, diffs)| {
+                (
+                    database,
+                    diffs.iter().map(|diff| diff.table_name.clone()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            grouped,
+            vec![
+                ("archivedb".to_string(), vec!["events".to_string()]),
+                ("marketingdb".to_string(), vec!["leads".to_string()]),
+                (
+                    "salesdb".to_string(),
+                    vec!["customers".to_string(), "payments".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_create_or_replace() {
+        assert_eq!(
+            to_create_or_replace("CREATE EXTERNAL TABLE t (id int)"),
+            Some("CREATE OR REPLACE EXTERNAL TABLE t (id int)".to_string())
+        );
+        assert_eq!(
+            to_create_or_replace("-- comment\ncreate table t (id int)"),
+            Some("-- comment\nCREATE OR REPLACE table t (id int)".to_string())
+        );
+        assert_eq!(
+            to_create_or_replace("CREATE OR REPLACE VIEW v AS SELECT 1"),
+            Some("CREATE OR REPLACE VIEW v AS SELECT 1".to_string())
+        );
+        assert_eq!(to_create_or_replace("SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_failure_report_lists_outcomes_in_order() {
+        let outcomes = vec![
+            TableOutcome {
+                qualified_name: "salesdb.customers".to_string(),
+                operation: DiffOperation::Create,
+                result: TableResult::Attempted(Err(anyhow::anyhow!(
+                    "Query execution failed: boom"
+                )
+                .context("Failed to create table salesdb.customers"))),
+            },
+            TableOutcome {
+                qualified_name: "salesdb.orders".to_string(),
+                operation: DiffOperation::Update,
+                result: TableResult::Attempted(Ok(TableRun {
+                    stats: QueryStats::default(),
+                    execution_id: Some("exec-2".to_string()),
+                })),
+            },
+            TableOutcome {
+                qualified_name: "salesdb.products".to_string(),
+                operation: DiffOperation::Delete,
+                result: TableResult::NotAttempted,
+            },
+        ];
+
+        assert_eq!(
+            failure_report(&outcomes).unwrap(),
+            "1 of 3 table(s) failed, 1 succeeded, 1 not attempted.\n\n\
+             Failed:\n  - salesdb.customers: Failed to create table salesdb.customers: Query execution failed: boom\n\n\
+             Succeeded:\n  - salesdb.orders\n\n\
+             Not attempted (run with --continue-on-error to apply them anyway):\n  - salesdb.products"
+        );
+
+        // Nothing to report when every attempted table succeeded
+        assert_eq!(failure_report(&outcomes[1..]), None);
+
+        let result = apply_result(&outcomes);
+        assert!(!result.dry_run);
+        assert_eq!(
+            (
+                result.totals.succeeded,
+                result.totals.failed,
+                result.totals.skipped
+            ),
+            (1, 1, 1)
+        );
+        let statuses: Vec<ApplyStatus> = result.tables.iter().map(|table| table.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ApplyStatus::Failed,
+                ApplyStatus::Succeeded,
+                ApplyStatus::Skipped
+            ]
+        );
+        assert_eq!(
+            result.tables[0].error.as_deref(),
+            Some("Failed to create table salesdb.customers: Query execution failed: boom")
+        );
+        assert_eq!(result.tables[1].execution_id.as_deref(), Some("exec-2"));
+        assert_eq!(result.tables[2].operation, DiffOperation::Delete);
+    }
+
+    #[test]
+    fn test_table_run_keeps_last_execution_id() {
+        let mut dropped = QueryResult::new("drop".to_string(), QueryExecutionStatus::Succeeded);
+        dropped.data_scanned_bytes = Some(10);
+        let created = QueryResult::new("create".to_string(), QueryExecutionStatus::Succeeded);
+
+        let mut run = TableRun::default();
+        run.record(&dropped);
+        let mut create_run = TableRun::default();
+        create_run.record(&created);
+        run.merge(create_run);
+        assert_eq!(run.execution_id.as_deref(), Some("create"));
+        assert_eq!(run.stats.data_scanned_bytes, 10);
+
+        // Merging a run without queries keeps the last id
+        run.merge(TableRun::default());
+        assert_eq!(run.execution_id.as_deref(), Some("create"));
+    }
+
+    #[test]
+    fn test_apply_report_totals_and_cost() {
+        let summary = DiffSummary {
+            to_add: 1,
+            to_change: 1,
+            to_destroy: 0,
+        };
+        let tables = vec![
+            TableScan {
+                table: "salesdb.orders".to_string(),
+                stats: QueryStats {
+                    data_scanned_bytes: 1 << 40,
+                    engine_execution_time_ms: 200,
+                },
+            },
+            TableScan {
+                table: "salesdb.customers".to_string(),
+                stats: QueryStats::default(),
+            },
+        ];
+
+        let report = ApplyReport::new(&summary, tables.clone(), Some(5.0));
+        assert_eq!(report.total.data_scanned_bytes, 1 << 40);
+        assert_eq!(report.total.engine_execution_time_ms, 200);
+        assert_eq!(report.estimated_cost_usd, Some(5.0));
+        assert_eq!(
+            report.scan_summary(),
+            vec![
+                "Data scanned:".to_string(),
+                "  salesdb.orders: 1.0 TiB".to_string(),
+                "  salesdb.customers: 0 B".to_string(),
+                "Total data scanned: 1.0 TiB (estimated cost: $5.0000)".to_string(),
+            ]
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["tables"][0]["table"], "salesdb.orders");
+        assert_eq!(json["tables"][0]["data_scanned_bytes"], 1u64 << 40);
+        assert_eq!(json["total"]["engine_execution_time_ms"], 200);
+
+        // Without price_per_tb, only bytes are reported
+        let report = ApplyReport::new(&summary, tables, None);
+        assert_eq!(report.estimated_cost_usd, None);
+        assert_eq!(
+            report.scan_summary().last().unwrap(),
+            "Total data scanned: 1.0 TiB"
+        );
+    }
 }
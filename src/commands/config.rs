@@ -0,0 +1,69 @@
+use anyhow::Result;
+use tracing::debug;
+
+use crate::types::config::{Config, ConfigOverrides, WORKGROUP_ENV_VAR};
+
+/// Execute the config command
+///
+/// Loads the configuration the same way every other command does (environment
+/// overrides, defaults, then validation) and prints the result without contacting
+/// AWS. Fails like any other command if the configuration is invalid.
+pub async fn execute(config_path: &str, overrides: &ConfigOverrides, json: bool) -> Result<()> {
+    // Logged at debug level: console logs share stdout with the printed config
+    debug!("Loading configuration from {}", config_path);
+    if std::env::var_os(WORKGROUP_ENV_VAR).is_some() {
+        debug!("workgroup is overridden by {}", WORKGROUP_ENV_VAR);
+    }
+
+    let config = Config::load_with_overrides(config_path, overrides)?;
+    print!("{}", render_config(&config, json)?);
+
+    Ok(())
+}
+
+/// Render a resolved configuration as YAML, or as pretty-printed JSON
+///
+/// Unset optional settings are included as `null` so every setting is visible.
+///
+/// # Arguments
+/// * `config` - Configuration after overrides and defaults were applied
+/// * `json` - Render JSON instead of YAML
+pub fn render_config(config: &Config, json: bool) -> Result<String> {
+    if json {
+        Ok(format!("{}\n", serde_json::to_string_pretty(config)?))
+    } else {
+        Ok(serde_yaml::to_string(config)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::config::DropBehavior;
+
+    #[test]
+    fn test_render_config_yaml_round_trips() {
+        let config = Config {
+            workgroup: "analytics".to_string(),
+            drop_behavior: Some(DropBehavior::Purge),
+            ..Default::default()
+        };
+
+        let yaml = render_config(&config, false).unwrap();
+        assert!(yaml.contains("workgroup: analytics"));
+        assert!(yaml.contains("drop_behavior: purge"));
+        assert!(yaml.contains("output_location: null"));
+
+        let parsed: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.workgroup, "analytics");
+        assert_eq!(parsed.drop_behavior, Some(DropBehavior::Purge));
+    }
+
+    #[test]
+    fn test_render_config_json() {
+        let json = render_config(&Config::default(), true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["workgroup"], "primary");
+        assert_eq!(value["max_concurrent_queries"], 5);
+    }
+}
@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::info;
+
+use crate::aws::athena::QueryRunner;
+use crate::aws::region::RegionalExecutors;
+use crate::commands::apply::{drop_table_statement, prompt_for_confirmation, purge_warning};
+use crate::differ::{list_regional_remote_tables, list_regional_remote_views};
+use crate::output::{
+    DisplayOptions, OutputStyles, display_diff_result, format_error, format_success,
+    format_warning, print_progress,
+};
+use crate::target_filter::{parse_target_filter_with_case, resolve_targets};
+use crate::types::config::{Config, ConfigOverrides, DropBehavior};
+use crate::types::diff_result::{DiffOperation, DiffResult, TableDiff};
+use crate::types::table_definition::ObjectType;
+use crate::warnings::warn;
+
+/// Tables dropped and tables whose DROP TABLE failed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DestroySummary {
+    pub destroyed: usize,
+    /// Qualified table name and error for each failed drop
+    pub failed: Vec<(String, String)>,
+}
+
+/// Execute the destroy command
+///
+/// Drops every remote table matching the targets. Only remote state is read and
+/// changed; local SQL files are never opened.
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    targets: &[String],
+    auto_approve: bool,
+) -> Result<()> {
+    info!("Starting athenadef destroy");
+    info!("Loading configuration from {}", config_path);
+
+    // Load and validate configuration
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    // Determine effective targets: use --target if provided, otherwise use config.databases
+    let effective_targets = resolve_targets(targets, config.databases.as_ref());
+    if effective_targets.is_empty() {
+        return Err(anyhow::anyhow!(
+            "destroy requires at least one target.\n\nUse --target (e.g. --target scratchdb.*) or set 'databases' in {} so that only the intended tables are dropped.",
+            Path::new(config_path).display()
+        ));
+    }
+    info!("Targets: {:?}", effective_targets);
+
+    let drop_behavior = config.drop_behavior.unwrap_or_default();
+    info!("Auto approve: {}", auto_approve);
+    info!("Drop behavior: {:?}", drop_behavior);
+
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
+
+    let target_filter = parse_target_filter_with_case(
+        &effective_targets,
+        config.table_name_case.unwrap_or_default(),
+    )?;
+
+    print_progress("Listing remote tables...");
+    let tables = list_regional_remote_tables(&executors, &config, |db: &str, table: &str| {
+        target_filter(db, table)
+    })
+    .await
+    .context("Failed to list remote tables. This could be due to:\n  - Network issues connecting to AWS\n  - Invalid AWS credentials or insufficient permissions\n\nRun with --debug flag for more details.")?;
+
+    let views = list_regional_remote_views(&executors, &tables)
+        .await
+        .context("Failed to list remote views")?;
+
+    let diff_result = destroy_plan(tables, &views);
+    display_diff_result(&diff_result, DisplayOptions::default())?;
+
+    if let Some(message) = purge_warning(&diff_result, drop_behavior) {
+        warn(message);
+    }
+
+    if diff_result.no_change {
+        return Ok(());
+    }
+
+    if !auto_approve && !prompt_for_confirmation()? {
+        println!("\n{}", format_warning("Destroy cancelled."));
+        return Ok(());
+    }
+
+    println!();
+    let summary = destroy_tables(
+        &diff_result,
+        |database_name| executors.for_database(database_name),
+        drop_behavior,
+    )
+    .await;
+
+    if summary.failed.is_empty() {
+        println!(
+            "\n{}",
+            format_success(&format!(
+                "Destroy complete! Resources: {} destroyed.",
+                summary.destroyed
+            ))
+        );
+        return Ok(());
+    }
+
+    println!(
+        "\n{}",
+        format_error(&format!(
+            "Destroy finished with errors: {} destroyed, {} failed.",
+            summary.destroyed,
+            summary.failed.len()
+        ))
+    );
+    for (table, error) in &summary.failed {
+        println!("  - {}: {}", table, error);
+    }
+    Err(anyhow::anyhow!(
+        "Failed to drop {} table(s)",
+        summary.failed.len()
+    ))
+}
+
+/// Build a plan that deletes every listed table
+///
+/// # Arguments
+/// * `tables` - Remote (database, table) pairs
+/// * `views` - Which of those pairs are views, dropped with DROP VIEW
+pub fn destroy_plan(
+    tables: Vec<(String, String)>,
+    views: &HashSet<(String, String)>,
+) -> DiffResult {
+    DiffResult::from_table_diffs(
+        tables
+            .into_iter()
+            .map(|table| {
+                let object_type = if views.contains(&table) {
+                    ObjectType::View
+                } else {
+                    ObjectType::Table
+                };
+                let (database_name, table_name) = table;
+                TableDiff {
+                    database_name,
+                    table_name,
+                    operation: DiffOperation::Delete,
+                    text_diff: None,
+                    change_details: None,
+                    location_impact: None,
+                    object_type,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Drop every table in the plan, continuing past failures
+///
+/// # Arguments
+/// * `diff_result` - Plan built by `destroy_plan`
+/// * `runner_for` - Query runner for the region a database lives in
+/// * `drop_behavior` - Whether drops purge table data
+///
+/// # Returns
+/// The number of dropped tables and each failure
+async fn destroy_tables<'a, R, F>(
+    diff_result: &DiffResult,
+    runner_for: F,
+    drop_behavior: DropBehavior,
+) -> DestroySummary
+where
+    R: QueryRunner + 'a,
+    F: Fn(&str) -> &'a R,
+{
+    let styles = OutputStyles::new();
+    let total = diff_result.table_diffs.len();
+    let mut summary = DestroySummary::default();
+
+    for (index, table_diff) in diff_result.table_diffs.iter().enumerate() {
+        let qualified_name = table_diff.qualified_name();
+        let statement = drop_table_statement(table_diff, drop_behavior);
+
+        match runner_for(&table_diff.database_name)
+            .execute_query(&statement)
+            .await
+        {
+            Ok(_) => {
+                println!(
+                    "[{}/{}] {}: {}",
+                    index + 1,
+                    total,
+                    styles.delete.apply_to(&qualified_name),
+                    format_success("Destroyed")
+                );
+                summary.destroyed += 1;
+            }
+            Err(e) => {
+                println!(
+                    "[{}/{}] {}: {}",
+                    index + 1,
+                    total,
+                    styles.delete.apply_to(&qualified_name),
+                    format_error("Failed")
+                );
+                summary.failed.push((qualified_name, format!("{:#}", e)));
+            }
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::athena::MockQueryRunner;
+    use crate::types::query_execution::{QueryExecutionStatus, QueryResult};
+
+    fn tables(names: &[(&str, &str)]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|(db, table)| (db.to_string(), table.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_destroy_plan_deletes_every_table() {
+        let plan = destroy_plan(
+            tables(&[("scratch", "a"), ("scratch", "b")]),
+            &HashSet::new(),
+        );
+        assert!(!plan.no_change);
+        assert_eq!(plan.summary.to_destroy, 2);
+        assert_eq!(plan.summary.to_add, 0);
+        assert!(
+            plan.table_diffs
+                .iter()
+                .all(|table_diff| table_diff.operation == DiffOperation::Delete)
+        );
+
+        assert!(destroy_plan(Vec::new(), &HashSet::new()).no_change);
+    }
+
+    #[tokio::test]
+    async fn test_destroy_tables_continues_after_failure() {
+        let plan = destroy_plan(
+            tables(&[("scratch", "a"), ("scratch", "b"), ("scratch", "c")]),
+            &HashSet::new(),
+        );
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "DROP TABLE IF EXISTS `scratch`.`b`")
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("table is locked")) }));
+        runner
+            .expect_execute_query()
+            .withf(|query| {
+                query == "DROP TABLE IF EXISTS `scratch`.`a`"
+                    || query == "DROP TABLE IF EXISTS `scratch`.`c`"
+            })
+            .times(2)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(QueryResult::new(
+                        "exec-123".to_string(),
+                        QueryExecutionStatus::Succeeded,
+                    ))
+                })
+            });
+
+        let summary = destroy_tables(&plan, |_| &runner, DropBehavior::KeepData).await;
+        assert_eq!(summary.destroyed, 2);
+        assert_eq!(
+            summary.failed,
+            vec![("scratch.b".to_string(), "table is locked".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_destroy_tables_purge() {
+        let plan = destroy_plan(tables(&[("scratch", "a")]), &HashSet::new());
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "DROP TABLE IF EXISTS `scratch`.`a` PURGE")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(QueryResult::new(
+                        "exec-123".to_string(),
+                        QueryExecutionStatus::Succeeded,
+                    ))
+                })
+            });
+
+        let summary = destroy_tables(&plan, |_| &runner, DropBehavior::Purge).await;
+        assert_eq!(summary.destroyed, 1);
+        assert!(summary.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_destroy_tables_drops_views_with_drop_view() {
+        let views = HashSet::from([("scratch".to_string(), "daily_totals".to_string())]);
+        let plan = destroy_plan(
+            tables(&[("scratch", "a"), ("scratch", "daily_totals")]),
+            &views,
+        );
+        assert_eq!(plan.table_diffs[1].object_type, ObjectType::View);
+
+        let mut runner = MockQueryRunner::new();
+        for statement in [
+            "DROP TABLE IF EXISTS `scratch`.`a` PURGE",
+            "DROP VIEW IF EXISTS `scratch`.`daily_totals`",
+        ] {
+            runner
+                .expect_execute_query()
+                .withf(move |query| query == statement)
+                .times(1)
+                .returning(|_| {
+                    Box::pin(async {
+                        Ok(QueryResult::new(
+                            "exec-123".to_string(),
+                            QueryExecutionStatus::Succeeded,
+                        ))
+                    })
+                });
+        }
+
+        let summary = destroy_tables(&plan, |_| &runner, DropBehavior::Purge).await;
+        assert_eq!(summary.destroyed, 2);
+        assert!(summary.failed.is_empty());
+    }
+}
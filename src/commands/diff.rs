@@ -0,0 +1,58 @@
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::differ::{DiffOptions, compute_table_diffs};
+use crate::file_utils::FileUtils;
+use crate::output::{DisplayOptions, display_diff_result};
+use crate::types::diff_result::DiffResult;
+
+/// Execute the diff command
+///
+/// Compares the SQL files of two directories the way plan compares local files
+/// with remote tables, without contacting AWS. `old_dir` takes the place of the
+/// remote side, so its tables are "destroyed" when missing from `new_dir`.
+pub async fn execute(old_dir: &Path, new_dir: &Path, json: bool) -> Result<()> {
+    let diff_result = diff_directories(old_dir, new_dir, &DiffOptions::default())?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff_result)?);
+    } else {
+        display_diff_result(&diff_result, DisplayOptions::default())?;
+    }
+
+    Ok(())
+}
+
+/// Diff the table definitions of two directories
+///
+/// # Arguments
+/// * `old_dir` - Directory of the definitions compared against, shown as remote
+/// * `new_dir` - Directory of the definitions to compare, shown as local
+/// * `options` - How names are matched and definitions compared
+///
+/// # Returns
+/// The differences, sorted by database and table name
+pub fn diff_directories(
+    old_dir: &Path,
+    new_dir: &Path,
+    options: &DiffOptions,
+) -> Result<DiffResult> {
+    for dir in [old_dir, new_dir] {
+        if !dir.is_dir() {
+            bail!("{} is not a directory", dir.display());
+        }
+    }
+
+    let old_tables: HashMap<String, String> = FileUtils::find_sql_files(old_dir)?
+        .into_iter()
+        .map(|(table_key, sql_file)| (table_key, sql_file.content))
+        .collect();
+    let new_tables = FileUtils::find_sql_files(new_dir)?;
+
+    let mut table_diffs = compute_table_diffs(&new_tables, &old_tables, options)?;
+    table_diffs
+        .sort_by(|a, b| (&a.database_name, &a.table_name).cmp(&(&b.database_name, &b.table_name)));
+
+    Ok(DiffResult::from_table_diffs(table_diffs))
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+use crate::aws::region::RegionalExecutors;
+use crate::differ::{Differ, normalize_sql};
+use crate::output::{format_progress, format_table_name, format_warning, is_quiet};
+use crate::target_filter::{parse_target_filter_with_case, resolve_targets};
+use crate::types::config::{Concurrency, Config, ConfigOverrides};
+
+/// Remote DDL for a single table, as returned by SHOW CREATE TABLE
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteDdl {
+    pub database_name: String,
+    pub table_name: String,
+    pub raw_ddl: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized_ddl: Option<String>,
+}
+
+/// Execute the dump-remote command
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    targets: &[String],
+    normalized: bool,
+    json: bool,
+    concurrency: Option<Concurrency>,
+) -> Result<()> {
+    info!("Starting athenadef dump-remote");
+    info!("Loading configuration from {}", config_path);
+
+    // Load and validate configuration
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    // Determine effective targets: use --target if provided, otherwise use config.databases
+    let effective_targets = resolve_targets(targets, config.databases.as_ref());
+
+    if !effective_targets.is_empty() {
+        info!("Targets: {:?}", effective_targets);
+    }
+
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
+    let routes = executors.routes();
+
+    let concurrency = config.concurrency(concurrency);
+    let target_filter = parse_target_filter_with_case(
+        &effective_targets,
+        config.table_name_case.unwrap_or_default(),
+    )?;
+    let excluded_databases = config.exclude_databases.as_deref().unwrap_or_default();
+
+    if !json && !is_quiet() {
+        eprintln!(
+            "{}",
+            format_progress("Fetching remote table definitions...")
+        );
+    }
+    let mut remote_tables = HashMap::new();
+    for region in routes.regions() {
+        let differ = Differ::new(executors.for_region(region).clone(), 1)
+            .with_concurrency(concurrency)
+            .with_excluded_databases(excluded_databases.to_vec())
+            .with_table_name_case(config.table_name_case.unwrap_or_default());
+        let region_tables = differ
+            .get_remote_tables(&Some(|db: &str, table: &str| {
+                routes.region_for(db) == region && target_filter(db, table)
+            }))
+            .await
+            .context("Failed to fetch remote table definitions")?;
+        remote_tables.extend(region_tables);
+    }
+
+    let entries = build_entries(remote_tables, normalized);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if entries.is_empty() {
+        println!("{}", format_warning("No matching remote tables found."));
+    } else {
+        print!("{}", render_text(&entries));
+    }
+
+    Ok(())
+}
+
+/// Convert fetched DDL into entries sorted by database and table name
+///
+/// # Arguments
+/// * `remote_tables` - Remote DDL keyed by "database.table"
+/// * `normalized` - Also include the form the differ compares against
+pub fn build_entries(remote_tables: HashMap<String, String>, normalized: bool) -> Vec<RemoteDdl> {
+    let sorted: BTreeMap<String, String> = remote_tables.into_iter().collect();
+
+    sorted
+        .into_iter()
+        .map(|(key, raw_ddl)| {
+            let (database_name, table_name) = key
+                .split_once('.')
+                .map(|(db, table)| (db.to_string(), table.to_string()))
+                .unwrap_or_else(|| (String::new(), key.clone()));
+            let normalized_ddl = normalized.then(|| normalize_sql(&raw_ddl));
+
+            RemoteDdl {
+                database_name,
+                table_name,
+                raw_ddl,
+                normalized_ddl,
+            }
+        })
+        .collect()
+}
+
+/// Render entries as human-readable text
+///
+/// Raw DDL is printed verbatim. When the normalized form is present it follows
+/// in its own section so the two can be compared.
+fn render_text(entries: &[RemoteDdl]) -> String {
+    let mut buffer = String::new();
+
+    for entry in entries {
+        let name = format!("{}.{}", entry.database_name, entry.table_name);
+        buffer.push_str(&format!("{}\n", format_table_name(&name, true)));
+        buffer.push_str("-- raw\n");
+        buffer.push_str(&entry.raw_ddl);
+        buffer.push('\n');
+        if let Some(ref normalized_ddl) = entry.normalized_ddl {
+            buffer.push_str("-- normalized\n");
+            buffer.push_str(normalized_ddl);
+            buffer.push('\n');
+        }
+        buffer.push('\n');
+    }
+
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_tables() -> HashMap<String, String> {
+        HashMap::from([
+            (
+                "salesdb.orders".to_string(),
+                "CREATE TABLE orders (id int)  \r\n".to_string(),
+            ),
+            (
+                "salesdb.customers".to_string(),
+                "CREATE TABLE customers (id int)\n".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_build_entries_sorted_raw_only() {
+        let entries = build_entries(remote_tables(), false);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].table_name, "customers");
+        assert_eq!(entries[1].database_name, "salesdb");
+        assert_eq!(entries[1].table_name, "orders");
+        assert_eq!(entries[1].raw_ddl, "CREATE TABLE orders (id int)  \r\n");
+        assert_eq!(entries[1].normalized_ddl, None);
+    }
+
+    #[test]
+    fn test_build_entries_with_normalized() {
+        let entries = build_entries(remote_tables(), true);
+        assert_eq!(
+            entries[1].normalized_ddl.as_deref(),
+            Some("CREATE TABLE orders (id int)")
+        );
+    }
+
+    #[test]
+    fn test_render_text() {
+        let entries = build_entries(remote_tables(), true);
+        let text = render_text(&entries);
+        assert!(text.contains("salesdb.customers"));
+        assert!(text.contains("-- raw\nCREATE TABLE orders (id int)  \r\n\n"));
+        assert!(text.contains("-- normalized\nCREATE TABLE orders (id int)\n"));
+    }
+
+    #[test]
+    fn test_json_output_omits_missing_normalized_ddl() {
+        let entries = build_entries(remote_tables(), false);
+        let json = serde_json::to_value(&entries).unwrap();
+        assert_eq!(json[0]["database_name"], "salesdb");
+        assert_eq!(json[0]["raw_ddl"], "CREATE TABLE customers (id int)\n");
+        assert!(json[0].get("normalized_ddl").is_none());
+    }
+}
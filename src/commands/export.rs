@@ -1,21 +1,39 @@
 use anyhow::{Context, Result};
-use aws_sdk_athena::Client as AthenaClient;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::info;
 
-use crate::aws::athena::QueryExecutor;
-use crate::file_utils::FileUtils;
-use crate::output::{format_error, format_progress, format_success, format_warning};
-use crate::target_filter::{parse_target_filter, resolve_targets};
-use crate::types::config::Config;
+use crate::aws::athena::{ParallelQueryExecutor, QueryRunner, list_tables};
+use crate::aws::region::RegionalExecutors;
+use crate::file_utils::{FileUtils, SqlLayout};
+use crate::output::{format_error, format_progress, format_success, format_warning, is_quiet};
+use crate::progress::ProgressCounter;
+use crate::sql_format::format_sql;
+use crate::target_filter::{
+    is_excluded_database, parse_target_filter_with_excludes, resolve_targets,
+};
+use crate::types::config::{Concurrency, Config, ConfigOverrides};
+use crate::warnings::warn;
 
 /// Execute the export command
-pub async fn execute(config_path: &str, targets: &[String], overwrite: bool) -> Result<()> {
+///
+/// The matching tables of every database are listed first; their DDL is then
+/// fetched with up to `max_concurrent_queries` (or `concurrency`) queries at a time.
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    targets: &[String],
+    excludes: &[String],
+    options: ExportOptions,
+    concurrency: Option<Concurrency>,
+) -> Result<()> {
     info!("Starting athenadef export");
     info!("Loading configuration from {}", config_path);
 
     // Load and validate configuration
-    let config = Config::load_from_path(config_path)?;
+    let config = Config::load_with_overrides(config_path, overrides)?;
 
     info!("Configuration loaded successfully");
     info!("Workgroup: {}", config.workgroup);
@@ -31,177 +49,475 @@ pub async fn execute(config_path: &str, targets: &[String], overwrite: bool) ->
     if !effective_targets.is_empty() {
         info!("Targets: {:?}", effective_targets);
     }
-    info!("Overwrite: {}", overwrite);
-
-    // Initialize AWS clients
-    let aws_config = if let Some(ref region) = config.region {
-        aws_config::from_env()
-            .region(aws_sdk_athena::config::Region::new(region.clone()))
-            .load()
-            .await
-    } else {
-        aws_config::load_from_env().await
-    };
-
-    let athena_client = AthenaClient::new(&aws_config);
+    if !excludes.is_empty() {
+        info!("Excludes: {:?}", excludes);
+    }
+    info!("Overwrite: {}", options.overwrite);
+    info!("Dry run: {}", options.dry_run);
 
-    // Create query executor
-    let query_executor = QueryExecutor::new(
-        athena_client,
-        config.workgroup.clone(),
-        config.output_location.clone(),
-        config.query_timeout_seconds.unwrap_or(300),
-    );
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
+    let routes = executors.routes();
 
-    // Get base path from config file directory
-    let config_path = Path::new(config_path);
-    let base_path = config_path
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .to_path_buf();
+    let base_path = config.base_path(config_path);
 
     // Parse target filter
-    let target_filter = parse_target_filter(&effective_targets);
+    let name_case = config.table_name_case.unwrap_or_default();
+    let target_filter = parse_target_filter_with_excludes(&effective_targets, excludes, name_case)?;
+    let excluded_databases = config.exclude_databases.as_deref().unwrap_or_default();
 
-    println!("{}", format_progress("Exporting table definitions..."));
-    println!();
+    if !is_quiet() {
+        println!("{}", format_progress("Exporting table definitions..."));
+        println!();
+    }
 
-    // Get list of databases
-    let databases: Vec<String> = if effective_targets.is_empty() {
-        // No filter, get all databases using SHOW DATABASES
-        query_executor
-            .get_databases()
-            .await
-            .context("Failed to get databases from Athena. This could be due to:\n  - Network issues connecting to AWS\n  - Invalid AWS credentials or insufficient permissions\n  - Invalid region configuration\n\nRun with --debug flag for more details.")?
+    // Get list of databases. Wildcard and regex database patterns need the full list
+    let target_dbs: Vec<&str> = effective_targets
+        .iter()
+        .filter_map(|pattern| pattern.split_once('.').map(|(db_pattern, _)| db_pattern))
+        .collect();
+    let list_all_databases = effective_targets.is_empty()
+        || target_dbs
+            .iter()
+            .any(|db_pattern| db_pattern.contains('*') || db_pattern.starts_with("re:"));
+    let mut databases: Vec<String> = if list_all_databases {
+        // Get all databases using SHOW DATABASES in each region, keeping only the
+        // databases routed to that region
+        let mut databases = Vec::new();
+        for region in routes.regions() {
+            let region_databases = executors
+                .for_region(region)
+                .get_databases()
+                .await
+                .context("Failed to get databases from Athena. This could be due to:\n  - Network issues connecting to AWS\n  - Invalid AWS credentials or insufficient permissions\n  - Invalid region configuration\n\nRun with --debug flag for more details.")?;
+            databases.extend(region_databases.into_iter().filter(|database_name| {
+                routes.region_for(database_name) == region
+                    && !is_excluded_database(database_name, excluded_databases, name_case)
+            }));
+        }
+        databases
     } else {
         // Extract unique database names from target patterns (no need to query SHOW DATABASES)
-        let target_dbs: std::collections::HashSet<String> = effective_targets
-            .iter()
-            .filter_map(|pattern| {
-                if let Some((db_pattern, _)) = pattern.split_once('.') {
-                    Some(db_pattern.to_string())
-                } else {
-                    None
-                }
+        target_dbs
+            .into_iter()
+            .filter(|database_name| {
+                !is_excluded_database(database_name, excluded_databases, name_case)
             })
-            .collect();
-
-        target_dbs.into_iter().collect()
+            .map(str::to_string)
+            .collect()
     };
+    databases.sort();
+    databases.dedup();
 
-    let mut exported_count = 0;
-    let mut skipped_count = 0;
-    let mut error_count = 0;
-
-    // Process each database
-    for database_name in databases {
-        println!("Database: {}", database_name);
-        // Get tables in this database using SHOW TABLES
-        let tables = query_executor
-            .get_tables(&database_name)
+    // Enumerate the target tables of every database before fetching any DDL
+    let mut tables_by_region: BTreeMap<Option<&str>, Vec<(String, String)>> = BTreeMap::new();
+    for database_name in &databases {
+        let tables = list_tables(executors.for_database(database_name), database_name)
             .await
             .with_context(|| format!("Failed to get tables from database {}", database_name))?;
+        tables_by_region
+            .entry(routes.region_for(database_name))
+            .or_default()
+            .extend(
+                tables
+                    .into_iter()
+                    .filter(|table_name| target_filter(database_name, table_name))
+                    .map(|table_name| (database_name.clone(), table_name)),
+            );
+    }
 
-        for table_name in tables {
-            // Apply target filter
-            if !target_filter(&database_name, &table_name) {
-                continue;
+    let concurrency = config.concurrency(concurrency);
+    let mut counts = ExportCounts::default();
+    for (region, tables) in tables_by_region {
+        let region_counts = export_tables(
+            executors.for_region(region).clone(),
+            tables,
+            &base_path,
+            options,
+            config.export_header.as_deref().unwrap_or_default(),
+            &config.sql_layout(),
+            concurrency,
+            &mut std::io::stdout(),
+        )
+        .await?;
+        counts.add(&region_counts);
+    }
+
+    println!();
+    if options.dry_run {
+        let mut summary = format!(
+            "Dry run complete! {} would be created, {} would be overwritten, {} would be skipped",
+            counts.exported - counts.overwritten,
+            counts.overwritten,
+            counts.skipped
+        );
+        if counts.errors > 0 {
+            summary.push_str(&format!(", {} errors", counts.errors));
+        }
+        summary.push('.');
+        if counts.errors > 0 {
+            println!("{}", format_warning(&summary));
+        } else {
+            println!("{}", format_success(&summary));
+        }
+        let notice = if options.fetch_ddl {
+            "Dry run mode - no files were written."
+        } else {
+            "Dry run mode - no DDL was fetched and no files were written."
+        };
+        println!("\n{}", format_warning(notice));
+        return Ok(());
+    }
+
+    let summary = if counts.skipped > 0 || counts.errors > 0 {
+        format!(
+            "Export complete! {} exported, {} skipped, {} errors.",
+            counts.exported, counts.skipped, counts.errors
+        )
+    } else {
+        format!("Export complete! {} tables exported.", counts.exported)
+    };
+
+    if counts.errors > 0 {
+        println!("{}", format_warning(&summary));
+        println!(
+            "\n{}",
+            format_warning("Some tables failed to export. Check the output above for details.")
+        );
+    } else {
+        println!("{}", format_success(&summary));
+    }
+
+    Ok(())
+}
+
+/// Number of tables exported, skipped, and failed
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct ExportCounts {
+    /// Tables whose file was (or in a dry run, would be) written, including overwrites
+    exported: usize,
+    /// Exported tables that replaced an existing file
+    overwritten: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+impl ExportCounts {
+    fn add(&mut self, other: &ExportCounts) {
+        self.exported += other.exported;
+        self.overwritten += other.overwritten;
+        self.skipped += other.skipped;
+        self.errors += other.errors;
+    }
+
+    /// Count a table whose action succeeded
+    fn record(&mut self, action: &ExportAction) {
+        match action {
+            ExportAction::Create(_) => self.exported += 1,
+            ExportAction::Overwrite(_) => {
+                self.exported += 1;
+                self.overwritten += 1;
             }
+            ExportAction::Skip { .. } => self.skipped += 1,
+        }
+    }
+}
 
-            // Get the file path for this table
-            let file_path =
-                FileUtils::get_table_file_path(&base_path, &database_name, &table_name)?;
-
-            // Check if file already exists and overwrite is false
-            if file_path.exists() && !overwrite {
-                println!(
-                    "  {} {}.{}: {}",
-                    format_warning("⊘"),
-                    database_name,
-                    table_name,
-                    format_warning("Skipped (file exists, use --overwrite to replace)")
-                );
-                skipped_count += 1;
-                continue;
+/// How tables are exported and reported
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ExportOptions {
+    /// Replace existing files instead of skipping them
+    pub overwrite: bool,
+    /// Only report what would be exported
+    pub dry_run: bool,
+    /// In a dry run, still fetch each table's DDL so failing queries show up
+    pub fetch_ddl: bool,
+    /// Print only failed tables, not exported or skipped ones
+    pub only_errors: bool,
+    /// Write the DDL in the layout of `athenadef fmt` instead of as returned
+    pub normalize: bool,
+}
+
+/// Export tables whose DDL is fetched through one region's runner
+///
+/// The action for each table's file is decided first: tables whose file exists
+/// are skipped (unless `overwrite`) before any query is issued. The DDL of the
+/// remaining tables is fetched in parallel, and each file is written and reported
+/// as its query completes. In a dry run nothing is written, and without
+/// `fetch_ddl` no DDL is fetched either.
+///
+/// # Arguments
+/// * `runner` - Query runner for the region the tables live in
+/// * `tables` - Tables to export as (database, table) pairs
+/// * `base_path` - Root directory to write SQL files under
+/// * `options` - Overwrite, dry-run, and reporting options
+/// * `header` - `export_header` template written above each DDL, empty for none
+/// * `layout` - File extension and default database of the written files
+/// * `concurrency` - How many SHOW CREATE TABLE queries run at a time
+/// * `out` - Where the per-table lines are written
+#[allow(clippy::too_many_arguments)]
+async fn export_tables<R>(
+    runner: R,
+    tables: Vec<(String, String)>,
+    base_path: &Path,
+    options: ExportOptions,
+    header: &str,
+    layout: &SqlLayout,
+    concurrency: Concurrency,
+    out: &mut dyn Write,
+) -> Result<ExportCounts>
+where
+    R: QueryRunner + Clone + Send + Sync + 'static,
+{
+    let mut counts = ExportCounts::default();
+
+    // Without per-table lines, a counter shows that a large export is moving
+    let progress = options
+        .only_errors
+        .then(|| Arc::new(ProgressCounter::stderr("Exporting", tables.len())));
+
+    let mut queries: Vec<((String, String, ExportAction), String)> = Vec::new();
+    for (database_name, table_name) in tables {
+        let action = decide_export_action(base_path, &database_name, &table_name, options, layout)?;
+
+        // Skips, and writes in a dry run without --fetch-ddl, need no query
+        let needs_ddl = match action {
+            ExportAction::Skip { .. } => false,
+            ExportAction::Create(_) | ExportAction::Overwrite(_) => {
+                !options.dry_run || options.fetch_ddl
+            }
+        };
+        if !needs_ddl {
+            report_action(out, &database_name, &table_name, &action, options)?;
+            counts.record(&action);
+            if let Some(ref progress) = progress {
+                progress.inc();
             }
+            continue;
+        }
+
+        let query = format!("SHOW CREATE TABLE `{}`.`{}`", database_name, table_name);
+        queries.push(((database_name, table_name, action), query));
+    }
+
+    if queries.is_empty() {
+        return Ok(counts);
+    }
 
-            // Execute SHOW CREATE TABLE to get DDL
-            let query = format!("SHOW CREATE TABLE `{}`.`{}`", database_name, table_name);
-            match query_executor.execute_query(&query).await {
-                Ok(result) => {
-                    // Extract DDL from query result
-                    if let Some(ddl) = extract_ddl_from_query_result(&result) {
-                        // Write DDL to file
-                        match FileUtils::write_sql_file(&file_path, &ddl) {
-                            Ok(_) => {
-                                println!(
-                                    "  {} {}.{}: Exported to {}",
-                                    format_success("✓"),
-                                    database_name,
-                                    table_name,
-                                    file_path.display()
-                                );
-                                exported_count += 1;
-                            }
-                            Err(e) => {
-                                println!(
-                                    "  {} {}.{}: {}",
-                                    format_error("✗"),
-                                    database_name,
-                                    table_name,
-                                    format_error(&format!("Failed to write file - {}", e))
-                                );
-                                error_count += 1;
-                            }
-                        }
-                    } else {
-                        println!(
-                            "  {} {}.{}: {}",
-                            format_error("✗"),
-                            database_name,
-                            table_name,
-                            format_error("Failed to extract DDL from query result")
+    let mut parallel_executor =
+        ParallelQueryExecutor::new(runner, concurrency.resolve(queries.len()));
+    if let Some(progress) = progress {
+        parallel_executor = parallel_executor.with_progress(progress);
+    }
+
+    // Results are handled one at a time on this task, so lines never interleave
+    parallel_executor
+        .for_each_completed(queries, |(database_name, table_name, action), result| {
+            let failure = match result {
+                Ok(result) => match extract_ddl_from_query_result(&result) {
+                    Some(ddl) => {
+                        let content = format!(
+                            "{}{}",
+                            render_export_header(header, &database_name, &table_name),
+                            export_content(ddl, &database_name, &table_name, options.normalize)
                         );
-                        error_count += 1;
+                        let content = escape_for_layout(content, &action, layout);
+                        perform_export_action(&action, &content, options.dry_run)
+                            .err()
+                            .map(|e| format!("Failed to write file - {}", e))
                     }
+                    None => Some("Failed to extract DDL from query result".to_string()),
+                },
+                Err(e) => Some(format!("Failed to get DDL - {}", e)),
+            };
+
+            match failure {
+                None => {
+                    report_action(out, &database_name, &table_name, &action, options)?;
+                    counts.record(&action);
                 }
-                Err(e) => {
-                    println!(
+                Some(message) => {
+                    writeln!(
+                        out,
                         "  {} {}.{}: {}",
                         format_error("✗"),
                         database_name,
                         table_name,
-                        format_error(&format!("Failed to get DDL - {}", e))
-                    );
-                    error_count += 1;
+                        format_error(&message)
+                    )?;
+                    counts.errors += 1;
                 }
             }
-        }
+            Ok(())
+        })
+        .await?;
+
+    Ok(counts)
+}
+
+/// What export does with a table's file
+#[derive(Debug, Clone, PartialEq)]
+enum ExportAction {
+    /// Write a file that does not exist yet
+    Create(PathBuf),
+    /// Replace an existing file (`--overwrite`)
+    Overwrite(PathBuf),
+    /// Leave the existing file as it is
+    Skip { path: PathBuf, reason: SkipReason },
+}
+
+/// Why an existing file is left as it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SkipReason {
+    /// The file exists and `--overwrite` was not given
+    Exists,
+    /// The table is defined by a template, which is never replaced with rendered DDL
+    Template,
+}
+
+/// Decide what export does with a table's file, without touching the filesystem
+fn decide_export_action(
+    base_path: &Path,
+    database_name: &str,
+    table_name: &str,
+    options: ExportOptions,
+    layout: &SqlLayout,
+) -> Result<ExportAction> {
+    let path = FileUtils::find_table_file(base_path, database_name, table_name, layout)?;
+    if !path.exists() {
+        return Ok(ExportAction::Create(path));
     }
 
-    println!();
-    let summary = if skipped_count > 0 || error_count > 0 {
-        format!(
-            "Export complete! {} exported, {} skipped, {} errors.",
-            exported_count, skipped_count, error_count
-        )
+    if layout.is_template(&path) {
+        Ok(ExportAction::Skip {
+            path,
+            reason: SkipReason::Template,
+        })
+    } else if options.overwrite {
+        Ok(ExportAction::Overwrite(path))
     } else {
-        format!("Export complete! {} tables exported.", exported_count)
-    };
+        Ok(ExportAction::Skip {
+            path,
+            reason: SkipReason::Exists,
+        })
+    }
+}
 
-    if error_count > 0 {
-        println!("{}", format_warning(&summary));
-        println!(
-            "\n{}",
-            format_warning("Some tables failed to export. Check the output above for details.")
-        );
+/// Escape `${` in content written to a plain file while `variables` is configured
+///
+/// Such files have their `${name}` tokens substituted when read, so DDL like
+/// partition projection's `storage.location.template` is written as `$${name}`
+/// to read back as the remote DDL. Templates are left as they are.
+fn escape_for_layout(content: String, action: &ExportAction, layout: &SqlLayout) -> String {
+    let path = match action {
+        ExportAction::Create(path) | ExportAction::Overwrite(path) => path,
+        ExportAction::Skip { .. } => return content,
+    };
+    if layout.variables.is_some() && !layout.is_template(path) {
+        FileUtils::escape_variables(&content)
     } else {
-        println!("{}", format_success(&summary));
+        content
     }
+}
 
+/// Carry out an export action with the table's rendered content
+///
+/// Nothing is written in a dry run or for a skip.
+fn perform_export_action(action: &ExportAction, content: &str, dry_run: bool) -> Result<()> {
+    match action {
+        ExportAction::Create(path) | ExportAction::Overwrite(path) if !dry_run => {
+            FileUtils::write_sql_file(path, content)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Print the line of a table whose action was decided (and performed, outside a dry run)
+fn report_action(
+    out: &mut dyn Write,
+    database_name: &str,
+    table_name: &str,
+    action: &ExportAction,
+    options: ExportOptions,
+) -> Result<()> {
+    if options.only_errors {
+        return Ok(());
+    }
+
+    let line = match (action, options.dry_run) {
+        (ExportAction::Create(path), true) => format!("Would create {}", path.display()),
+        (ExportAction::Create(path), false) => format!("Exported to {}", path.display()),
+        (ExportAction::Overwrite(path), true) => format!("Would overwrite {}", path.display()),
+        (ExportAction::Overwrite(path), false) => {
+            format!("Exported to {} (replaced existing file)", path.display())
+        }
+        (ExportAction::Skip { reason, .. }, dry_run) => {
+            let message = match (reason, dry_run) {
+                (SkipReason::Template, true) => "Would skip (defined by a template)",
+                (SkipReason::Template, false) => "Skipped (defined by a template)",
+                (SkipReason::Exists, true) => {
+                    "Would skip (file exists, use --overwrite to replace)"
+                }
+                (SkipReason::Exists, false) => "Skipped (file exists, use --overwrite to replace)",
+            };
+            writeln!(
+                out,
+                "  {} {}.{}: {}",
+                format_warning("⊘"),
+                database_name,
+                table_name,
+                format_warning(message)
+            )?;
+            return Ok(());
+        }
+    };
+    writeln!(
+        out,
+        "  {} {}.{}: {}",
+        format_success("✓"),
+        database_name,
+        table_name,
+        line
+    )?;
     Ok(())
 }
 
+/// Render the `export_header` template for a table
+///
+/// `{table}` is replaced with `database.table`; each comment line is trimmed and
+/// ends with a newline, in the layout `athenadef fmt` keeps. An empty template
+/// renders nothing.
+fn render_export_header(template: &str, database_name: &str, table_name: &str) -> String {
+    let table = format!("{}.{}", database_name, table_name);
+    template
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{}\n", line.replace("{table}", &table)))
+        .collect()
+}
+
+/// Content written for a table's DDL
+///
+/// With `normalize`, the DDL is laid out by `format_sql`, so a later
+/// `athenadef fmt` leaves the file unchanged. DDL the formatter rejects, such as
+/// a view, is written as returned with a warning.
+fn export_content(ddl: String, database_name: &str, table_name: &str, normalize: bool) -> String {
+    if !normalize {
+        return ddl;
+    }
+    match format_sql(&ddl) {
+        Ok(formatted) => formatted,
+        Err(e) => {
+            warn(format!(
+                "Exported {}.{} without normalizing: {}",
+                database_name, table_name, e
+            ));
+            ddl
+        }
+    }
+}
+
 /// Extract DDL from SHOW CREATE TABLE query result
 ///
 /// # Arguments
@@ -240,8 +556,411 @@ fn extract_ddl_from_query_result(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::config::DEFAULT_EXPORT_HEADER;
     use crate::types::query_execution::{QueryExecutionStatus, QueryResult, QueryRow};
 
+    use crate::aws::athena::MockQueryRunner;
+    use tempfile::TempDir;
+
+    fn show_tables_result(tables: &[&str]) -> QueryResult {
+        let mut result = QueryResult::new("exec-123".to_string(), QueryExecutionStatus::Succeeded);
+        for table in tables {
+            result.rows.push(QueryRow::new(vec![table.to_string()]));
+        }
+        result
+    }
+
+    fn salesdb_tables(tables: &[&str]) -> Vec<(String, String)> {
+        tables
+            .iter()
+            .map(|table| ("salesdb".to_string(), table.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_dry_run_does_not_fetch_ddl() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        std::fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        std::fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE c").unwrap();
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query.starts_with("SHOW CREATE TABLE"))
+            .times(0);
+
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["customers", "orders"]),
+            base_path,
+            ExportOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            "",
+            &SqlLayout::default(),
+            Concurrency::Fixed(5),
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            counts,
+            ExportCounts {
+                exported: 1,
+                overwritten: 0,
+                skipped: 1,
+                errors: 0,
+            }
+        );
+        assert!(!base_path.join("salesdb/orders.sql").exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_dry_run_with_fetch_ddl_writes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        std::fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        std::fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE c").unwrap();
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW CREATE TABLE `salesdb`.`returns`")
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("access denied")) }));
+        runner
+            .expect_execute_query()
+            .withf(|query| query.starts_with("SHOW CREATE TABLE"))
+            .times(2)
+            .returning(|_| {
+                Box::pin(async { Ok(show_tables_result(&["CREATE EXTERNAL TABLE remote"])) })
+            });
+
+        let mut out = Vec::new();
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["customers", "orders", "returns"]),
+            base_path,
+            ExportOptions {
+                overwrite: true,
+                dry_run: true,
+                fetch_ddl: true,
+                ..Default::default()
+            },
+            DEFAULT_EXPORT_HEADER,
+            &SqlLayout::default(),
+            Concurrency::Fixed(1),
+            &mut out,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            counts,
+            ExportCounts {
+                exported: 2,
+                overwritten: 1,
+                skipped: 0,
+                errors: 1,
+            }
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("salesdb.customers: Would overwrite"));
+        assert!(out.contains("salesdb.orders: Would create"));
+        assert!(out.contains("Failed to get DDL - access denied"));
+
+        // No file was created or replaced
+        assert!(!base_path.join("salesdb/orders.sql").exists());
+        assert!(!base_path.join("salesdb/returns.sql").exists());
+        assert_eq!(
+            std::fs::read_to_string(base_path.join("salesdb/customers.sql")).unwrap(),
+            "CREATE TABLE c"
+        );
+        assert_eq!(
+            std::fs::read_dir(base_path.join("salesdb"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_decide_export_action() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        std::fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        std::fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE c").unwrap();
+        let layout = SqlLayout::default();
+
+        let decide = |table_name: &str, overwrite: bool| {
+            let options = ExportOptions {
+                overwrite,
+                ..Default::default()
+            };
+            decide_export_action(base_path, "salesdb", table_name, options, &layout).unwrap()
+        };
+
+        assert_eq!(
+            decide("orders", false),
+            ExportAction::Create(base_path.join("salesdb/orders.sql"))
+        );
+        assert_eq!(
+            decide("customers", false),
+            ExportAction::Skip {
+                path: base_path.join("salesdb/customers.sql"),
+                reason: SkipReason::Exists,
+            }
+        );
+        assert_eq!(
+            decide("customers", true),
+            ExportAction::Overwrite(base_path.join("salesdb/customers.sql"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_writes_ddl() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW CREATE TABLE `salesdb`.`orders`")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async { Ok(show_tables_result(&["CREATE EXTERNAL TABLE orders"])) })
+            });
+
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["orders"]),
+            base_path,
+            ExportOptions::default(),
+            DEFAULT_EXPORT_HEADER,
+            &SqlLayout::default(),
+            Concurrency::Fixed(5),
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(counts.exported, 1);
+        assert_eq!(
+            std::fs::read_to_string(base_path.join("salesdb/orders.sql")).unwrap(),
+            "-- Managed by athenadef. Do not edit by hand.\n-- Source: salesdb.orders\nCREATE EXTERNAL TABLE orders"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_escapes_tokens_when_variables_are_configured() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        let ddl = "CREATE EXTERNAL TABLE events\nTBLPROPERTIES ('storage.location.template'='s3://data/events/${dt}/')";
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(show_tables_result(&[ddl])) }));
+
+        let layout = SqlLayout {
+            variables: Some(BTreeMap::from([("bucket".to_string(), "data".to_string())])),
+            ..SqlLayout::default()
+        };
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["events"]),
+            base_path,
+            ExportOptions::default(),
+            "",
+            &layout,
+            Concurrency::Fixed(5),
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(counts.exported, 1);
+        let path = base_path.join("salesdb/events.sql");
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            written.contains("'s3://data/events/$${dt}/'"),
+            "{}",
+            written
+        );
+        assert_eq!(
+            FileUtils::render_table_sql(&path, &written, &layout).unwrap(),
+            ddl
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_writes_default_database_to_base_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        let mut runner = MockQueryRunner::new();
+        runner.expect_execute_query().times(1).returning(|_| {
+            Box::pin(async { Ok(show_tables_result(&["CREATE EXTERNAL TABLE orders"])) })
+        });
+
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["orders"]),
+            base_path,
+            ExportOptions::default(),
+            "",
+            &SqlLayout {
+                default_database: Some("salesdb".to_string()),
+                ..SqlLayout::default()
+            },
+            Concurrency::Fixed(5),
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(counts.exported, 1);
+        assert!(base_path.join("orders.sql").exists());
+        assert!(!base_path.join("salesdb").exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_normalize_round_trips_through_fmt() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW CREATE TABLE `salesdb`.`orders`")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(show_tables_result(&[
+                        "CREATE EXTERNAL TABLE `salesdb`.`orders`(",
+                        "  `id` bigint, ",
+                        "  `amount` decimal(10,2))",
+                        "PARTITIONED BY ( ",
+                        "  `dt` string)",
+                        "row format serde ",
+                        "  'org.apache.hadoop.hive.ql.io.parquet.serde.ParquetHiveSerDe' ",
+                        "STORED AS INPUTFORMAT ",
+                        "  'org.apache.hadoop.hive.ql.io.parquet.MapredParquetInputFormat' ",
+                        "OUTPUTFORMAT ",
+                        "  'org.apache.hadoop.hive.ql.io.parquet.MapredParquetOutputFormat'",
+                        "LOCATION",
+                        "  's3://bucket/orders'",
+                        "TBLPROPERTIES (",
+                        "  'parquet.compression'='SNAPPY')",
+                    ]))
+                })
+            });
+
+        let options = ExportOptions {
+            normalize: true,
+            ..ExportOptions::default()
+        };
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["orders"]),
+            base_path,
+            options,
+            DEFAULT_EXPORT_HEADER,
+            &SqlLayout::default(),
+            Concurrency::Fixed(5),
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(counts.exported, 1);
+
+        let exported = std::fs::read_to_string(base_path.join("salesdb/orders.sql")).unwrap();
+        assert!(exported.ends_with('\n'));
+        assert!(exported.contains("\nROW FORMAT SERDE"));
+        // Running fmt on the exported file is a no-op
+        assert_eq!(format_sql(&exported).unwrap(), exported);
+    }
+
+    #[test]
+    fn test_render_export_header() {
+        assert_eq!(
+            render_export_header(DEFAULT_EXPORT_HEADER, "salesdb", "orders"),
+            "-- Managed by athenadef. Do not edit by hand.\n-- Source: salesdb.orders\n"
+        );
+        assert_eq!(
+            render_export_header("  -- {table} \n\n-- owned by data-eng\n", "db", "t"),
+            "-- db.t\n-- owned by data-eng\n"
+        );
+        assert_eq!(render_export_header("", "db", "t"), "");
+    }
+
+    #[test]
+    fn test_export_content_falls_back_to_raw_ddl() {
+        let view = "CREATE VIEW v AS SELECT 1".to_string();
+        assert_eq!(export_content(view.clone(), "salesdb", "v", true), view);
+        assert_eq!(
+            export_content("create table t (id int)".to_string(), "db", "t", false),
+            "create table t (id int)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_tables_only_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        std::fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        std::fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE c").unwrap();
+
+        let mut runner = MockQueryRunner::new();
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW CREATE TABLE `salesdb`.`orders`")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async { Ok(show_tables_result(&["CREATE EXTERNAL TABLE orders"])) })
+            });
+        runner
+            .expect_execute_query()
+            .withf(|query| query == "SHOW CREATE TABLE `salesdb`.`returns`")
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("access denied")) }));
+
+        let mut out = Vec::new();
+        let counts = export_tables(
+            Arc::new(runner),
+            salesdb_tables(&["customers", "orders", "returns"]),
+            base_path,
+            ExportOptions {
+                only_errors: true,
+                ..Default::default()
+            },
+            "",
+            &SqlLayout::default(),
+            Concurrency::Fixed(5),
+            &mut out,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            counts,
+            ExportCounts {
+                exported: 1,
+                overwritten: 0,
+                skipped: 1,
+                errors: 1,
+            }
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 1);
+        assert!(out.contains("salesdb.returns"));
+        assert!(out.contains("Failed to get DDL - access denied"));
+    }
+
     #[test]
     fn test_extract_ddl_from_query_result_success() {
         let mut result = QueryResult::new("exec-123".to_string(), QueryExecutionStatus::Succeeded);
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use tracing::info;
+
+use crate::file_utils::FileUtils;
+use crate::output::{format_success, format_warning};
+use crate::sql_format::format_sql;
+use crate::target_filter::{parse_target_filter_with_case, resolve_targets};
+use crate::types::config::{Config, ConfigOverrides};
+use crate::warnings::warn;
+
+/// Execute the fmt command
+///
+/// Rewrites each local SQL file in the canonical layout of `sql_format`. With
+/// `check`, no file is written; the command fails if any file would change.
+/// Files the formatter cannot lay out are skipped with a warning.
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    targets: &[String],
+    check: bool,
+) -> Result<()> {
+    info!("Starting athenadef fmt");
+    info!("Loading configuration from {}", config_path);
+
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    // Determine effective targets: use --target if provided, otherwise use config.databases
+    let effective_targets = resolve_targets(targets, config.databases.as_ref());
+    if !effective_targets.is_empty() {
+        info!("Targets: {:?}", effective_targets);
+    }
+    info!("Check: {}", check);
+
+    let base_path = config.base_path(config_path);
+
+    let name_case = config.table_name_case.unwrap_or_default();
+    let target_filter = parse_target_filter_with_case(&effective_targets, name_case)?;
+
+    let layout = config.sql_layout();
+    let sql_files = FileUtils::find_sql_files_with_layout(&base_path, &layout)?;
+    // Templates are left alone; other files are formatted as written, since their
+    // content has `variables` substituted
+    let mut sql_files: Vec<_> = sql_files
+        .into_values()
+        .filter(|sql_file| target_filter(&sql_file.database_name, &sql_file.table_name))
+        .filter(|sql_file| !layout.is_template(&sql_file.file_path))
+        .collect();
+    sql_files.sort_by_key(|sql_file| sql_file.qualified_name());
+
+    let mut changed = 0;
+    for sql_file in &sql_files {
+        let content = FileUtils::read_sql_file(&sql_file.file_path)?;
+        let formatted = match format_sql(&content) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                warn(format!("Skipped {}: {}", sql_file.file_path.display(), e));
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+
+        changed += 1;
+        if check {
+            println!("Would reformat {}", sql_file.file_path.display());
+        } else {
+            FileUtils::write_sql_file(&sql_file.file_path, &formatted)?;
+            println!("Formatted {}", sql_file.file_path.display());
+        }
+    }
+
+    if check && changed > 0 {
+        println!(
+            "\n{}",
+            format_warning(&format!(
+                "{} of {} file(s) would be reformatted.",
+                changed,
+                sql_files.len()
+            ))
+        );
+        return Err(anyhow::anyhow!(
+            "{} file(s) are not formatted. Run 'athenadef fmt' to format them.",
+            changed
+        ));
+    }
+
+    let summary = if check {
+        format!("All {} file(s) are formatted.", sql_files.len())
+    } else {
+        format!(
+            "{} file(s) formatted, {} unchanged.",
+            changed,
+            sql_files.len() - changed
+        )
+    };
+    println!("\n{}", format_success(&summary));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("athenadef.yaml"),
+            "workgroup: primary\n",
+        )
+        .unwrap();
+        for (file, content) in files {
+            let path = temp_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        temp_dir
+    }
+
+    #[tokio::test]
+    async fn test_execute_check_then_format() {
+        let temp_dir = write_project(&[
+            ("salesdb/orders.sql", "create table orders (id int)"),
+            (
+                "salesdb/notes.sql",
+                "CREATE TABLE notes (id int) -- not formatted",
+            ),
+            ("marketingdb/leads.sql", "create table leads (id int)"),
+        ]);
+        let config_path = temp_dir.path().join("athenadef.yaml");
+        let config_path = config_path.to_str().unwrap();
+        let orders_path = temp_dir.path().join("salesdb/orders.sql");
+
+        let err = execute(
+            config_path,
+            &ConfigOverrides::default(),
+            &["salesdb.*".to_string()],
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().starts_with("1 file(s) are not formatted"));
+        assert_eq!(
+            std::fs::read_to_string(&orders_path).unwrap(),
+            "create table orders (id int)"
+        );
+
+        execute(
+            config_path,
+            &ConfigOverrides::default(),
+            &["salesdb.*".to_string()],
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&orders_path).unwrap(),
+            "CREATE TABLE orders (\n    id int\n)\n"
+        );
+        // Outside the target and skipped files are left alone
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("marketingdb/leads.sql")).unwrap(),
+            "create table leads (id int)"
+        );
+
+        execute(
+            config_path,
+            &ConfigOverrides::default(),
+            &["salesdb.*".to_string()],
+            true,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_keeps_variable_tokens() {
+        let temp_dir = write_project(&[(
+            "salesdb/events.sql",
+            "create external table events (id int) location 's3://${bucket}/events/' \
+             tblproperties ('storage.location.template' = 's3://${bucket}/events/$${dt}/')",
+        )]);
+        std::fs::write(
+            temp_dir.path().join("athenadef.yaml"),
+            "workgroup: primary\nvariables:\n  bucket: data-prod\n",
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join("athenadef.yaml");
+        let config_path = config_path.to_str().unwrap();
+
+        execute(config_path, &ConfigOverrides::default(), &[], false)
+            .await
+            .unwrap();
+
+        let formatted =
+            std::fs::read_to_string(temp_dir.path().join("salesdb/events.sql")).unwrap();
+        assert!(
+            formatted.starts_with("CREATE EXTERNAL TABLE events"),
+            "{}",
+            formatted
+        );
+        assert!(
+            formatted.contains("'s3://${bucket}/events/'"),
+            "{}",
+            formatted
+        );
+        assert!(
+            formatted.contains("'s3://${bucket}/events/$${dt}/'"),
+            "{}",
+            formatted
+        );
+        assert!(!formatted.contains("data-prod"), "{}", formatted);
+    }
+}
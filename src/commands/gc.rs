@@ -0,0 +1,339 @@
+use anyhow::{Context, Result};
+use std::time::{Duration, SystemTime};
+use tracing::info;
+
+use crate::aws::s3::{MAX_DELETE_BATCH_SIZE, ObjectStore, S3Manager, S3Object};
+use crate::aws::sdk_config::load_sdk_config;
+use crate::output::{format_bytes, format_success, format_warning, print_progress};
+use crate::types::config::{Config, ConfigOverrides};
+
+/// Outcome of a prune run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PruneSummary {
+    pub deleted_objects: usize,
+    pub reclaimed_bytes: u64,
+    pub batches: usize,
+}
+
+/// Execute the gc command
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    older_than: &str,
+    dry_run: bool,
+) -> Result<()> {
+    info!("Starting athenadef gc");
+    info!("Loading configuration from {}", config_path);
+
+    // Load and validate configuration
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    let max_age = parse_age(older_than)?;
+    let output_location = config
+        .output_location
+        .as_deref()
+        .filter(|location| !location.is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "gc requires 'output_location' to be set in {}.\n\nThe workgroup's default output location cannot be pruned automatically.",
+                config_path
+            )
+        })?;
+
+    info!("Output location: {}", output_location);
+    info!("Older than: {:?}", max_age);
+    info!("Dry run: {}", dry_run);
+
+    // Initialize AWS clients
+    let aws_config = load_sdk_config(&config).await?;
+
+    let s3_manager =
+        S3Manager::from_sdk_config(&aws_config, config.s3_force_path_style.unwrap_or(false));
+
+    print_progress(&format!(
+        "Scanning {} for query results older than {}...",
+        output_location, older_than
+    ));
+
+    let cutoff = SystemTime::now()
+        .checked_sub(max_age)
+        .ok_or_else(|| anyhow::anyhow!("Age '{}' is too large", older_than))?;
+
+    let summary = prune_results(&s3_manager, output_location, cutoff, dry_run).await?;
+
+    println!();
+    if dry_run {
+        println!(
+            "{}",
+            format_warning(&format!(
+                "Dry run mode - {} objects ({}) would be deleted.",
+                summary.deleted_objects,
+                format_bytes(summary.reclaimed_bytes)
+            ))
+        );
+    } else {
+        println!(
+            "{}",
+            format_success(&format!(
+                "GC complete! {} objects deleted, {} reclaimed.",
+                summary.deleted_objects,
+                format_bytes(summary.reclaimed_bytes)
+            ))
+        );
+    }
+
+    Ok(())
+}
+
+/// Delete objects under `s3_prefix` last modified before `cutoff`
+///
+/// Deletions are issued in batches of at most `MAX_DELETE_BATCH_SIZE` keys.
+/// In dry-run mode, expired objects are only reported.
+///
+/// # Arguments
+/// * `store` - Object store to list and delete from
+/// * `s3_prefix` - S3 prefix to scan (e.g., "s3://bucket/athena-results/")
+/// * `cutoff` - Objects modified before this time are deleted
+/// * `dry_run` - Report without deleting
+///
+/// # Returns
+/// PruneSummary with counts of deleted objects and reclaimed bytes
+pub async fn prune_results<S: ObjectStore>(
+    store: &S,
+    s3_prefix: &str,
+    cutoff: SystemTime,
+    dry_run: bool,
+) -> Result<PruneSummary> {
+    let (bucket, _) = S3Manager::parse_s3_prefix(s3_prefix)?;
+
+    let objects = store.list_objects(s3_prefix).await?;
+    let expired = filter_expired_objects(objects, cutoff);
+
+    let mut summary = PruneSummary {
+        deleted_objects: expired.len(),
+        reclaimed_bytes: expired.iter().map(|o| o.size.max(0) as u64).sum(),
+        batches: 0,
+    };
+
+    if dry_run {
+        for object in &expired {
+            println!(
+                "  - s3://{}/{} ({})",
+                bucket,
+                object.key,
+                format_bytes(object.size.max(0) as u64)
+            );
+        }
+        return Ok(summary);
+    }
+
+    let keys: Vec<String> = expired.into_iter().map(|o| o.key).collect();
+    for batch in keys.chunks(MAX_DELETE_BATCH_SIZE) {
+        store
+            .delete_objects(&bucket, batch)
+            .await
+            .with_context(|| format!("Failed to delete a batch of {} objects", batch.len()))?;
+        summary.batches += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Keep only objects last modified strictly before `cutoff`
+///
+/// Objects without a modification time are never considered expired, since their age is unknown.
+pub fn filter_expired_objects(objects: Vec<S3Object>, cutoff: SystemTime) -> Vec<S3Object> {
+    objects
+        .into_iter()
+        .filter(|object| matches!(object.last_modified, Some(modified) if modified < cutoff))
+        .collect()
+}
+
+/// Parse an age such as "30s", "15m", "12h", "7d", or "2w" into a Duration
+pub fn parse_age(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split_at);
+
+    let amount: u64 = amount.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid age '{}'. Expected a number followed by a unit, e.g. '7d' or '12h'",
+            value
+        )
+    })?;
+
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Invalid age unit in '{}'. Supported units: s, m, h, d, w",
+                value
+            ));
+        }
+    };
+
+    let seconds = amount
+        .checked_mul(seconds_per_unit)
+        .ok_or_else(|| anyhow::anyhow!("Age '{}' is too large", value))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::s3::MockObjectStore;
+    use mockall::predicate::eq;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn object(key: &str, size: i64, age: Duration, now: SystemTime) -> S3Object {
+        S3Object {
+            key: key.to_string(),
+            size,
+            last_modified: Some(now - age),
+        }
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_age("15m").unwrap(), Duration::from_secs(15 * 60));
+        assert_eq!(parse_age("12h").unwrap(), Duration::from_secs(12 * 60 * 60));
+        assert_eq!(parse_age("7d").unwrap(), 7 * DAY);
+        assert_eq!(parse_age("2w").unwrap(), 14 * DAY);
+    }
+
+    #[test]
+    fn test_parse_age_invalid() {
+        assert!(parse_age("").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("7").is_err());
+        assert!(parse_age("7y").is_err());
+        assert!(parse_age("-7d").is_err());
+    }
+
+    #[test]
+    fn test_parse_age_too_large() {
+        assert_eq!(
+            parse_age("99999999999999w").unwrap_err().to_string(),
+            "Age '99999999999999w' is too large"
+        );
+    }
+
+    #[test]
+    fn test_filter_expired_objects() {
+        let now = SystemTime::now();
+        let objects = vec![
+            object("old.csv", 10, 10 * DAY, now),
+            object("new.csv", 10, DAY, now),
+            S3Object {
+                key: "unknown.csv".to_string(),
+                size: 10,
+                last_modified: None,
+            },
+        ];
+
+        let expired = filter_expired_objects(objects, now - 7 * DAY);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].key, "old.csv");
+    }
+
+    #[tokio::test]
+    async fn test_prune_results_deletes_only_expired_objects() {
+        let now = SystemTime::now();
+        let listed = vec![
+            object("results/old.csv", 100, 30 * DAY, now),
+            object("results/old.csv.metadata", 20, 30 * DAY, now),
+            object("results/recent.csv", 100, DAY, now),
+        ];
+
+        let mut store = MockObjectStore::new();
+        store
+            .expect_list_objects()
+            .with(eq("s3://bucket/results/"))
+            .times(1)
+            .returning(move |_| {
+                let listed = listed.clone();
+                Box::pin(async move { Ok(listed) })
+            });
+        store
+            .expect_delete_objects()
+            .withf(|bucket, keys| {
+                bucket == "bucket"
+                    && keys
+                        == [
+                            "results/old.csv".to_string(),
+                            "results/old.csv.metadata".to_string(),
+                        ]
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let summary = prune_results(&store, "s3://bucket/results/", now - 7 * DAY, false)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.deleted_objects, 2);
+        assert_eq!(summary.reclaimed_bytes, 120);
+        assert_eq!(summary.batches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_prune_results_batches_deletes() {
+        let now = SystemTime::now();
+        let listed: Vec<S3Object> = (0..2500)
+            .map(|i| object(&format!("results/{}.csv", i), 1, 30 * DAY, now))
+            .collect();
+
+        let mut store = MockObjectStore::new();
+        store.expect_list_objects().times(1).returning(move |_| {
+            let listed = listed.clone();
+            Box::pin(async move { Ok(listed) })
+        });
+        store
+            .expect_delete_objects()
+            .withf(|_, keys| keys.len() == MAX_DELETE_BATCH_SIZE)
+            .times(2)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        store
+            .expect_delete_objects()
+            .withf(|_, keys| keys.len() == 500)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let summary = prune_results(&store, "s3://bucket/results/", now - 7 * DAY, false)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.deleted_objects, 2500);
+        assert_eq!(summary.reclaimed_bytes, 2500);
+        assert_eq!(summary.batches, 3);
+    }
+
+    #[tokio::test]
+    async fn test_prune_results_dry_run_does_not_delete() {
+        let now = SystemTime::now();
+        let listed = vec![object("results/old.csv", 100, 30 * DAY, now)];
+
+        let mut store = MockObjectStore::new();
+        store.expect_list_objects().times(1).returning(move |_| {
+            let listed = listed.clone();
+            Box::pin(async move { Ok(listed) })
+        });
+        store.expect_delete_objects().never();
+
+        let summary = prune_results(&store, "s3://bucket/results/", now - 7 * DAY, true)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.deleted_objects, 1);
+        assert_eq!(summary.reclaimed_bytes, 100);
+        assert_eq!(summary.batches, 0);
+    }
+}
@@ -6,6 +6,7 @@ use crate::output::{format_error, format_success, format_warning};
 
 const DEFAULT_CONFIG_CONTENT: &str = r#"# AWS Athena Workgroup
 # The Athena workgroup to use for query execution
+# The ATHENADEF_WORKGROUP environment variable overrides this value
 # Default: "primary"
 workgroup: "primary"
 
@@ -17,20 +18,217 @@ workgroup: "primary"
 
 # AWS Region (Optional)
 # AWS region for Athena operations
-# If not specified, uses the default AWS region from your environment
+# If not specified, uses the default AWS region from your environment; the global
+# --region flag overrides it
 # Example: "us-east-1"
 # region: ""
 
+# Exclude Databases (Optional)
+# Databases that are never queried or managed, even without --target
+# Supports '*' wildcards, e.g. "team_*"; case is matched as set by table_name_case
+# Default: ["information_schema"]; set to [] to scan every database
+# exclude_databases:
+#   - information_schema
+
+# Table Name Case (Optional)
+# How --target patterns and local file names are matched against remote tables
+# insensitive: ignore case, as Athena does (SalesDB/Customers.sql matches salesdb.customers)
+# sensitive: require an exact match
+# Default: insensitive
+# table_name_case: insensitive
+
+# Comparison Mode (Optional)
+# How local files are compared with remote tables
+# text: diff SHOW CREATE TABLE output against the local files
+# semantic: compare Glue Data Catalog metadata with the parsed local files, ignoring
+#   formatting, property order, quoting, and defaults Athena adds
+#   (needs athena:GetTableMetadata)
+# Default: text
+# comparison_mode: semantic
+
+# Ignore Type Synonyms (Optional)
+# Compare column types in a canonical spelling, so integer and int, numeric and
+# decimal, or DECIMAL(10, 2) and decimal(10,2) are not reported as changes
+# Default: false
+# ignore_type_synonyms: true
+
+# Normalize Whitespace (Optional)
+# Ignore indentation, runs of spaces, and blank lines when deciding whether a table
+# changed; the plan still shows a diff of the original text
+# Default: false
+# normalize_whitespace: true
+
+# Region Overrides (Optional)
+# Databases whose catalog lives in a region other than the one above
+# Example:
+#   region_overrides:
+#     eu_salesdb: "eu-west-1"
+# region_overrides: {}
+
+# Assume Role (Optional)
+# IAM role to assume using the default AWS credentials chain
+# (environment, AWS_PROFILE, web identity / EKS IRSA, ECS, EC2 instance profile)
+# Example: "arn:aws:iam::123456789012:role/athenadef"
+# assume_role_arn: ""
+# For roles in another account whose trust policy requires an external ID, use the
+# assume_role section instead:
+# assume_role:
+#   role_arn: "arn:aws:iam::210987654321:role/athenadef"
+#   external_id: "shared-secret"
+#   session_name: "athenadef"
+
+# Catalog ID (Optional)
+# ID of a Glue Data Catalog shared from another account (e.g. Lake Formation).
+# Athena reaches it through a registered data catalog:
+#   aws athena create-data-catalog --name shared_lake --type GLUE --parameters catalog-id=210987654321
+# catalog_id: "210987654321"
+
+# Data Catalog (Optional)
+# Name of the Athena data catalog to manage, such as a federated connector's.
+# Cannot be combined with catalog_id
+# Default: AwsDataCatalog
+# data_catalog: dynamo_connector
+
+# Profile (Optional)
+# Named profile from ~/.aws/config or ~/.aws/credentials to take credentials and
+# the default region from; the global --profile flag overrides it
+# profile: "staging"
+
 # Query Timeout (Optional)
 # Maximum time in seconds to wait for a query to complete
 # Default: 300
 # query_timeout_seconds: 300
 
+# Result Reuse (Optional)
+# Let Athena return cached results of identical queries up to this many minutes old
+# (1 to 10080). Speeds up repeated plans and lowers cost, but SHOW CREATE TABLE may
+# then return a definition that is up to this old, so a very recent change can be missed.
+# Cannot be combined with cleanup_query_results
+# Default: disabled
+# result_reuse_max_age_minutes: 15
+
+# Output Location Region Check (Optional)
+# Look up the region of the output_location bucket and compare it with the Athena
+# region; results written across regions fail in ways that are hard to diagnose
+# Options: off, warn, error
+# Default: off
+# output_location_region_check: warn
+
+# Retries (Optional)
+# Athena API calls that are throttled (ThrottlingException, TooManyRequestsException)
+# or hit a server error are retried with exponential backoff and jitter; other
+# errors such as access denied or invalid SQL fail immediately
+# Default: 5 retries, starting at 200 ms; set max_retries: 0 to disable
+# max_retries: 5
+# retry_base_delay_ms: 200
+
+# Remote Definition Cache (Optional)
+# Keep SHOW CREATE TABLE results in .athenadef-cache/ next to this file for this
+# many seconds, so an apply right after a plan does not query every table again.
+# Apply removes the entries of the tables it changes; --refresh ignores the cache.
+# Add .athenadef-cache/ to .gitignore
+# Default: no cache
+# cache_ttl_seconds: 300
+
+# Export Header (Optional)
+# Comment lines export writes above each table's DDL; {table} becomes database.table.
+# Leading comment lines are ignored when comparing, so the header never shows up
+# in a plan. Set to "" to write files exactly as Athena returns them
+# Default: "-- Managed by athenadef. Do not edit by hand.\n-- Source: {table}"
+# export_header: "-- Generated by athenadef from {table}"
+
+# Query Result Cleanup (Optional)
+# Delete the result files each query leaves under the output location (such as the
+# .csv/.txt and .metadata objects of SHOW CREATE TABLE) once the query succeeded.
+# Needs s3:DeleteObject; files that cannot be deleted are left in place. Cannot be
+# combined with result_reuse_max_age_minutes, whose reused results need the files
+# Default: false (use `athenadef gc` to prune old results instead)
+# cleanup_query_results: true
+
+# Path (Optional)
+# Directory of the table definition files, relative to this file. The --path
+# flag overrides it
+# Default: this file's directory
+# path: sql
+
+# Default Database (Optional)
+# Read table_name.sql files placed directly in this directory as tables of this
+# database, next to the usual database_name/table_name.sql files. Export writes
+# the database's new tables there too
+# Default: none (only database_name/table_name.sql)
+# default_database: salesdb
+
+# File Extension (Optional)
+# Extension of table definition files, without the dot. Files with any other
+# extension, including .sql when this is changed, are ignored
+# Default: sql
+# file_extension: ddl
+
+# Variables (Optional)
+# Replace ${name} in table definitions with these values before they are compared
+# or applied, e.g. LOCATION 's3://${bucket}/orders/'. Undefined names are an error;
+# write $${year} for a literal ${year}, as in partition projection templates
+# (export writes ${ that way while variables is set).
+# Files named table_name.sql.tera are Tera templates rendered with the same
+# variables ({{ bucket }}); templating: true renders every file that way.
+# Values may use ${ENV_VAR} like workgroup. fmt and export leave templates alone
+# Default: no substitution, templating: false
+# templating: false
+# variables:
+#   bucket: "${DATA_BUCKET:-my-data-bucket}"
+
+# Price Per TB (Optional)
+# USD per TiB scanned in your region, used by apply to estimate the cost of the
+# DDL it ran next to the bytes scanned
+# Default: no estimate
+# price_per_tb: 5.0
+
+# Endpoint URL (Optional)
+# Send every AWS request (Athena, S3, STS) to this URL instead of the AWS
+# endpoints, e.g. to run against LocalStack in tests. S3 mocks usually also need
+# path-style addressing (http://host/bucket/key)
+# endpoint_url: "http://localhost:4566"
+# s3_force_path_style: true
+
 # Max Concurrent Queries (Optional)
 # Maximum number of queries to run concurrently
 # Default: 5
 # max_concurrent_queries: 5
 
+# Max Concurrent Glue Requests (Optional)
+# Maximum number of Glue Data Catalog requests in flight per region when
+# comparison_mode is semantic
+# Default: 10
+# max_concurrent_glue_requests: 4
+
+# Update Strategy (Optional)
+# How apply updates tables that already exist
+# drop_create: DROP TABLE then CREATE TABLE
+# recreate: CREATE OR REPLACE, falling back to drop_create only if unsupported
+# alter_when_possible: ALTER TABLE for appended columns and location changes
+# Default: drop_create
+# update_strategy: drop_create
+
+# Drop Behavior (Optional)
+# Whether DROP TABLE statements issued by apply also delete table data
+# keep_data: plain DROP TABLE; data of external tables stays in S3
+# purge: DROP TABLE ... PURGE; table data is permanently deleted where supported
+# Default: keep_data
+# drop_behavior: keep_data
+
+# Auto Apply Allowlist (Optional)
+# With --auto-approve, only apply tables whose changes all fall in these categories
+# Other tables are held back for manual review, as are updates that would not
+# run as ALTER TABLE under update_strategy
+# Categories: table_created, table_deleted, column_added, column_removed,
+#             column_type_changed, column_constraint_changed, comment_changed,
+#             property: <name>
+# Example:
+# auto_apply_when:
+#   - column_added
+#   - comment_changed
+#   - "property: tblproperties"
+
 # Databases (Optional)
 # List of databases to manage
 # If specified and --target is not provided, only these databases will be processed
@@ -39,6 +237,19 @@ workgroup: "primary"
 # databases:
 #   - salesdb
 #   - marketingdb
+
+# Workspaces (Optional)
+# Per-environment settings in one file; select one with the global --workspace
+# flag (the "default" workspace is used without it). Each setting a workspace
+# gives replaces the shared one above
+# Example:
+# workspaces:
+#   default:
+#     output_location: "s3://dev-athena-results/"
+#   prod:
+#     workgroup: "production"
+#     region: "eu-west-1"
+#     output_location: "s3://prod-athena-results/"
 "#;
 
 /// Execute the init command
@@ -177,7 +388,27 @@ mod tests {
         assert!(content.contains("region"));
         assert!(content.contains("query_timeout_seconds"));
         assert!(content.contains("max_concurrent_queries"));
+        assert!(content.contains("update_strategy"));
+        assert!(content.contains("drop_behavior"));
+        assert!(content.contains("table_name_case"));
+        assert!(content.contains("comparison_mode"));
+        assert!(content.contains("ignore_type_synonyms"));
+        assert!(content.contains("normalize_whitespace"));
+        assert!(content.contains("result_reuse_max_age_minutes"));
+        assert!(content.contains("max_retries"));
+        assert!(content.contains("cache_ttl_seconds"));
+        assert!(content.contains("export_header"));
+        assert!(content.contains("cleanup_query_results"));
+        assert!(content.contains("default_database"));
+        assert!(content.contains("file_extension"));
+        assert!(content.contains("templating"));
+        assert!(content.contains("variables"));
+        assert!(content.contains("price_per_tb"));
+        assert!(content.contains("output_location_region_check"));
+        assert!(content.contains("auto_apply_when"));
         assert!(content.contains("databases"));
+        assert!(content.contains("workspaces"));
+        assert!(content.contains("catalog_id"));
 
         // Verify helpful comments exist
         assert!(content.contains("AWS Athena Workgroup"));
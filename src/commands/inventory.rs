@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::info;
+
+use crate::aws::region::RegionalExecutors;
+use crate::differ::calculate_regional_diff;
+use crate::sql_source::SqlSource;
+use crate::target_filter::{parse_target_filter_with_case, resolve_targets};
+use crate::types::config::{Concurrency, Config, ConfigOverrides};
+use crate::types::diff_result::{DiffOperation, DiffResult};
+
+/// A table known locally, remotely, or both
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InventoryEntry {
+    pub database_name: String,
+    pub table_name: String,
+    /// What plan would do with the table
+    pub operation: DiffOperation,
+    /// Whether a local SQL file defines the table
+    pub local_present: bool,
+    /// Whether the table exists in Athena
+    pub remote_present: bool,
+}
+
+/// Every table in scope, with counts of what is tracked and what exists
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Inventory {
+    pub local_tables: usize,
+    pub remote_tables: usize,
+    pub tables: Vec<InventoryEntry>,
+}
+
+/// Execute the inventory command
+///
+/// Prints every local and remote table within the targets as JSON, including
+/// tables whose definitions already match.
+pub async fn execute(
+    config_path: &str,
+    overrides: &ConfigOverrides,
+    targets: &[String],
+    concurrency: Option<Concurrency>,
+) -> Result<()> {
+    info!("Starting athenadef inventory");
+    info!("Loading configuration from {}", config_path);
+
+    // Load and validate configuration
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    // Determine effective targets: use --target if provided, otherwise use config.databases
+    let effective_targets = resolve_targets(targets, config.databases.as_ref());
+
+    if !effective_targets.is_empty() {
+        info!("Targets: {:?}", effective_targets);
+    }
+
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
+
+    let base_path = config.base_path(config_path);
+
+    let target_filter = parse_target_filter_with_case(
+        &effective_targets,
+        config.table_name_case.unwrap_or_default(),
+    )?;
+
+    let diff_result = calculate_regional_diff(
+        &executors,
+        &config,
+        config.concurrency(concurrency),
+        &SqlSource::default().with_layout(config.sql_layout()),
+        &base_path,
+        true,
+        None,
+        |db: &str, table: &str| target_filter(db, table),
+    )
+    .await
+    .context("Failed to build the table inventory")?;
+
+    let inventory = build_inventory(&diff_result);
+    println!("{}", serde_json::to_string_pretty(&inventory)?);
+
+    Ok(())
+}
+
+/// Build an inventory from a diff that includes unchanged tables
+///
+/// Presence follows from the operation: creates exist only locally, deletes only
+/// remotely, and updates and unchanged tables in both places.
+///
+/// # Arguments
+/// * `diff_result` - Diff computed with unchanged tables included
+///
+/// # Returns
+/// Inventory entries sorted by database and table name
+pub fn build_inventory(diff_result: &DiffResult) -> Inventory {
+    let mut tables: Vec<InventoryEntry> = diff_result
+        .table_diffs
+        .iter()
+        .map(|table_diff| InventoryEntry {
+            database_name: table_diff.database_name.clone(),
+            table_name: table_diff.table_name.clone(),
+            operation: table_diff.operation.clone(),
+            local_present: table_diff.operation != DiffOperation::Delete,
+            remote_present: table_diff.operation != DiffOperation::Create,
+        })
+        .collect();
+    tables
+        .sort_by(|a, b| (&a.database_name, &a.table_name).cmp(&(&b.database_name, &b.table_name)));
+
+    Inventory {
+        local_tables: tables.iter().filter(|entry| entry.local_present).count(),
+        remote_tables: tables.iter().filter(|entry| entry.remote_present).count(),
+        tables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::diff_result::TableDiff;
+    use crate::types::table_definition::ObjectType;
+
+    fn table_diff(database_name: &str, table_name: &str, operation: DiffOperation) -> TableDiff {
+        TableDiff {
+            database_name: database_name.to_string(),
+            table_name: table_name.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        }
+    }
+
+    #[test]
+    fn test_build_inventory_presence_flags() {
+        let diff_result = DiffResult::from_table_diffs(vec![
+            table_diff("salesdb", "orders", DiffOperation::Delete),
+            table_diff("salesdb", "customers", DiffOperation::NoChange),
+            table_diff("marketingdb", "leads", DiffOperation::Create),
+            table_diff("salesdb", "products", DiffOperation::Update),
+        ]);
+
+        let inventory = build_inventory(&diff_result);
+        let flags: Vec<(&str, DiffOperation, bool, bool)> = inventory
+            .tables
+            .iter()
+            .map(|entry| {
+                (
+                    entry.table_name.as_str(),
+                    entry.operation.clone(),
+                    entry.local_present,
+                    entry.remote_present,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            flags,
+            vec![
+                ("leads", DiffOperation::Create, true, false),
+                ("customers", DiffOperation::NoChange, true, true),
+                ("orders", DiffOperation::Delete, false, true),
+                ("products", DiffOperation::Update, true, true),
+            ]
+        );
+        assert_eq!(inventory.local_tables, 3);
+        assert_eq!(inventory.remote_tables, 3);
+    }
+}
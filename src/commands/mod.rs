@@ -1,4 +1,14 @@
 pub mod apply;
+pub mod config;
+pub mod destroy;
+pub mod diff;
+pub mod dump_remote;
 pub mod export;
+pub mod fmt;
+pub mod gc;
 pub mod init;
+pub mod inventory;
 pub mod plan;
+pub mod schema;
+pub mod validate;
+pub mod version;
@@ -1,27 +1,100 @@
 use anyhow::Result;
-use aws_sdk_athena::Client as AthenaClient;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::Path;
 use tracing::info;
 
-use crate::aws::athena::QueryExecutor;
-use crate::differ::Differ;
-use crate::output::{display_diff_result, format_progress};
-use crate::target_filter::{parse_target_filter, resolve_targets};
-use crate::types::config::Config;
+use crate::api::Athenadef;
+use crate::aws::region::RegionalExecutors;
+use crate::commands::validate::orphaned_file_warning;
+use crate::file_utils::FileUtils;
+use crate::interrupt::stop_queries_on_interrupt;
+use crate::output::{
+    DisplayOptions, display_diff_result, display_github_annotations, display_markdown,
+    display_yaml, format_success, format_warning, print_progress,
+};
+use crate::projection::check_projection;
+use crate::sarif::{build_sarif, sql_file_uri};
+use crate::saved_plan::SavedPlan;
+use crate::sql_source::SqlSource;
+use crate::target_filter::{parse_target_filter_with_excludes, resolve_targets};
+use crate::types::config::{Concurrency, Config, ConfigOverrides, TableNameCase};
 use crate::types::diff_result::DiffResult;
+use crate::warnings::warn;
+
+/// Output format for plan results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PlanFormat {
+    /// Human-readable diff
+    #[default]
+    Text,
+    /// Structured diff result as JSON
+    Json,
+    /// SARIF 2.1.0 report of destructive changes
+    Sarif,
+    /// Markdown without ANSI colors, for pull request comments
+    Markdown,
+    /// Structured diff result as YAML
+    Yaml,
+    /// GitHub Actions workflow commands annotating each changed table's SQL file
+    Github,
+}
+
+/// Local tables found without contacting AWS
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct LocalInventory {
+    /// Table names grouped by database, both sorted
+    pub databases: BTreeMap<String, Vec<String>>,
+    /// Files that could not be parsed, as "path: reason"
+    pub issues: Vec<String>,
+}
+
+impl LocalInventory {
+    /// Total number of tables across all databases
+    pub fn table_count(&self) -> usize {
+        self.databases.values().map(|tables| tables.len()).sum()
+    }
+}
+
+/// Exit code of `plan --detailed-exitcode` when there are changes to apply
+pub const EXIT_CODE_CHANGES: i32 = 2;
 
 /// Execute the plan command
+///
+/// With `local_only`, only local files are discovered and validated; no AWS
+/// clients are created and no diff is computed. With `check_projection`, partition
+/// projection settings in local files are checked first and reported as warnings.
+/// With `out`, the diff is also saved for a later `apply <PLAN_FILE>`. With
+/// `refresh`, cached remote definitions (`cache_ttl_seconds`) are fetched again.
+///
+/// # Returns
+/// The exit code: `EXIT_CODE_CHANGES` with `detailed_exitcode` when the plan has
+/// changes, otherwise 0. The plan is printed either way.
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config_path: &str,
+    overrides: &ConfigOverrides,
     targets: &[String],
-    show_unchanged: bool,
-    json: bool,
-) -> Result<()> {
+    excludes: &[String],
+    display_options: DisplayOptions,
+    format: PlanFormat,
+    git_ref: Option<&str>,
+    local_only: bool,
+    check_projection: bool,
+    concurrency: Option<Concurrency>,
+    out: Option<&Path>,
+    refresh: bool,
+    detailed_exitcode: bool,
+) -> Result<i32> {
     info!("Starting athenadef plan");
+    if local_only {
+        check_local_format(format)?;
+    }
     info!("Loading configuration from {}", config_path);
 
     // Load and validate configuration
-    let config = Config::load_from_path(config_path)?;
+    let config = Config::load_with_overrides(config_path, overrides)?;
 
     info!("Configuration loaded successfully");
     info!("Workgroup: {}", config.workgroup);
@@ -37,61 +110,244 @@ pub async fn execute(
     if !effective_targets.is_empty() {
         info!("Targets: {:?}", effective_targets);
     }
-    info!("Show unchanged: {}", show_unchanged);
+    if !excludes.is_empty() {
+        info!("Excludes: {:?}", excludes);
+    }
+    info!("Show unchanged: {}", display_options.show_unchanged);
+    if let Some(git_ref) = git_ref {
+        info!("Reading SQL files from git ref: {}", git_ref);
+    }
 
-    // Initialize AWS clients
-    let aws_config = if let Some(ref region) = config.region {
-        aws_config::from_env()
-            .region(aws_sdk_athena::config::Region::new(region.clone()))
-            .load()
-            .await
-    } else {
-        aws_config::load_from_env().await
-    };
+    let base_path = config.base_path(config_path);
+    let sql_source = SqlSource::resolve(&base_path, git_ref)?.with_layout(config.sql_layout());
 
-    let athena_client = AthenaClient::new(&aws_config);
-
-    // Create query executor
-    let query_executor = QueryExecutor::new(
-        athena_client,
-        config.workgroup.clone(),
-        config.output_location.clone(),
-        config.query_timeout_seconds.unwrap_or(300),
-    );
-
-    // Create differ
-    let max_concurrent_queries = config.max_concurrent_queries.unwrap_or(5);
-    let differ = Differ::new(query_executor, max_concurrent_queries);
-
-    // Get base path from config file directory
-    let config_path_buf = Path::new(config_path);
-    let base_path = config_path_buf
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .to_path_buf();
-
-    // Parse target filter
-    let target_filter = parse_target_filter(&effective_targets);
-
-    // Calculate diff
-    println!("{}", format_progress("Calculating differences..."));
-    let diff_result = differ
-        .calculate_diff(
-            Path::new(&base_path),
-            Some(|db: &str, table: &str| target_filter(db, table)),
-        )
-        .await?;
+    // Files at a git ref are only listed in the layout, so only the working tree is checked
+    if sql_source.git_ref().is_none() {
+        for path in FileUtils::find_orphaned_sql_files_with_layout(&base_path, sql_source.layout())
+        {
+            warn(orphaned_file_warning(&path));
+        }
+    }
+
+    let name_case = config.table_name_case.unwrap_or_default();
+
+    if check_projection {
+        check_local_projection(
+            &base_path,
+            &sql_source,
+            &effective_targets,
+            excludes,
+            name_case,
+        )?;
+    }
+
+    if local_only {
+        let inventory = collect_local_inventory(
+            &base_path,
+            &sql_source,
+            &effective_targets,
+            excludes,
+            name_case,
+        )?;
+        display_local_inventory(&inventory, format)?;
+        return Ok(0);
+    }
+
+    // Initialize a query executor for each region databases are routed to
+    let executors = RegionalExecutors::from_config(&config).await?;
+
+    // Calculate diff (progress only for text, so machine-readable output stays valid)
+    if format == PlanFormat::Text {
+        print_progress("Calculating differences...");
+    }
+    let athenadef = Athenadef::new(config, &base_path)
+        .with_sql_source(sql_source.clone())
+        .with_excludes(excludes.to_vec())
+        .with_concurrency(concurrency)
+        .with_unchanged(display_options.show_unchanged)
+        .with_refresh(refresh);
+    let diff_result = stop_queries_on_interrupt(
+        &executors,
+        athenadef.plan_with(&executors, &effective_targets),
+    )
+    .await?;
 
     // Display results
-    if json {
-        display_json(&diff_result)?;
+    match format {
+        PlanFormat::Text => display_diff_result(&diff_result, display_options)?,
+        PlanFormat::Json => display_json(&diff_result)?,
+        PlanFormat::Markdown => display_markdown(&diff_result),
+        PlanFormat::Yaml => display_yaml(&diff_result)?,
+        PlanFormat::Sarif => {
+            let sarif = build_sarif(&diff_result, &base_path, &sql_source);
+            println!("{}", serde_json::to_string_pretty(&sarif)?);
+        }
+        PlanFormat::Github => display_github_annotations(&diff_result, |table_diff| {
+            sql_file_uri(&base_path, &sql_source, table_diff)
+        }),
+    }
+
+    let exit_code = if detailed_exitcode && !diff_result.no_change {
+        EXIT_CODE_CHANGES
     } else {
-        display_diff_result(&diff_result, show_unchanged)?;
+        0
+    };
+
+    if let Some(out) = out {
+        SavedPlan::new(diff_result, &base_path, &sql_source, git_ref)?.save(out)?;
+        info!("Saved plan to {}", out.display());
+        if format == PlanFormat::Text {
+            println!(
+                "\n{}",
+                format_success(&format!(
+                    "Saved plan to {}. Apply it with 'athenadef apply {}'.",
+                    out.display(),
+                    out.display()
+                ))
+            );
+        }
+    }
+
+    Ok(exit_code)
+}
+
+/// Discover local tables without contacting AWS
+///
+/// # Arguments
+/// * `base_path` - Root directory containing SQL files
+/// * `sql_source` - Where to read SQL files from
+/// * `targets` - Effective target patterns
+/// * `excludes` - Patterns of tables to leave out
+/// * `name_case` - How target patterns match table names
+fn collect_local_inventory(
+    base_path: &Path,
+    sql_source: &SqlSource,
+    targets: &[String],
+    excludes: &[String],
+    name_case: TableNameCase,
+) -> Result<LocalInventory> {
+    let target_filter = parse_target_filter_with_excludes(targets, excludes, name_case)?;
+
+    let mut scan = sql_source.scan_sql_files(base_path)?;
+    scan.sql_files
+        .retain(|_, sql_file| target_filter(&sql_file.database_name, &sql_file.table_name));
+    FileUtils::check_remote_name_collisions(&scan.sql_files)?;
+
+    let mut inventory = LocalInventory {
+        issues: scan.issues,
+        ..LocalInventory::default()
+    };
+    for sql_file in scan.sql_files.into_values() {
+        inventory
+            .databases
+            .entry(sql_file.database_name)
+            .or_default()
+            .push(sql_file.table_name);
+    }
+    for tables in inventory.databases.values_mut() {
+        tables.sort();
+    }
+
+    if !targets.is_empty() && inventory.databases.is_empty() {
+        warn(format!(
+            "Target(s) {} matched no local tables",
+            targets.join(", ")
+        ));
+    }
+
+    Ok(inventory)
+}
+
+/// Warn about incomplete partition projection settings in local files
+///
+/// # Returns
+/// The number of problems found
+fn check_local_projection(
+    base_path: &Path,
+    sql_source: &SqlSource,
+    targets: &[String],
+    excludes: &[String],
+    name_case: TableNameCase,
+) -> Result<usize> {
+    let target_filter = parse_target_filter_with_excludes(targets, excludes, name_case)?;
+
+    // Parse failures are reported by the plan itself, so only parsed files are checked
+    let scan = sql_source.scan_sql_files(base_path)?;
+    let mut sql_files: Vec<_> = scan
+        .sql_files
+        .values()
+        .filter(|sql_file| target_filter(&sql_file.database_name, &sql_file.table_name))
+        .collect();
+    sql_files.sort_by_key(|sql_file| sql_file.qualified_name());
+
+    let mut count = 0;
+    for sql_file in sql_files {
+        for issue in check_projection(&sql_file.content) {
+            warn(format!("{}: {}", sql_file.qualified_name(), issue));
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Display the local inventory, failing if any file could not be parsed
+fn display_local_inventory(inventory: &LocalInventory, format: PlanFormat) -> Result<()> {
+    match format {
+        PlanFormat::Text => {
+            println!(
+                "Found {} table(s) in {} database(s):",
+                inventory.table_count(),
+                inventory.databases.len()
+            );
+            for (database_name, tables) in &inventory.databases {
+                println!("  {}: {} table(s)", database_name, tables.len());
+            }
+        }
+        PlanFormat::Json => println!("{}", serde_json::to_string_pretty(inventory)?),
+        PlanFormat::Yaml => print!("{}", serde_yaml::to_string(inventory)?),
+        PlanFormat::Sarif | PlanFormat::Markdown | PlanFormat::Github => {
+            check_local_format(format)?
+        }
+    }
+
+    if !inventory.issues.is_empty() {
+        for issue in &inventory.issues {
+            eprintln!("{}", format_warning(&format!("Failed to parse {}", issue)));
+        }
+        return Err(anyhow::anyhow!(
+            "{} local file(s) could not be parsed",
+            inventory.issues.len()
+        ));
+    }
+
+    if format == PlanFormat::Text {
+        println!(
+            "\n{}",
+            format_success("All local files are valid. Remote state was not checked.")
+        );
     }
 
     Ok(())
 }
 
+/// Reject formats that only describe a diff, which `--no-remote` never computes
+fn check_local_format(format: PlanFormat) -> Result<()> {
+    match format {
+        PlanFormat::Text | PlanFormat::Json | PlanFormat::Yaml => Ok(()),
+        PlanFormat::Sarif | PlanFormat::Markdown | PlanFormat::Github => {
+            let name = format
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "--format {} is not supported with --no-remote. Use text, json, or yaml",
+                name
+            ))
+        }
+    }
+}
+
 /// Display diff results in JSON format
 fn display_json(diff_result: &DiffResult) -> Result<()> {
     let json = serde_json::to_string_pretty(diff_result)?;
@@ -103,6 +359,204 @@ fn display_json(diff_result: &DiffResult) -> Result<()> {
 mod tests {
     use super::*;
     use crate::types::diff_result::{DiffOperation, DiffSummary, TableDiff};
+    use crate::types::table_definition::ObjectType;
+
+    fn write_local_project(files: &[&str]) -> tempfile::TempDir {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("athenadef.yaml"),
+            "workgroup: primary\n",
+        )
+        .unwrap();
+        for file in files {
+            let path = temp_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "CREATE EXTERNAL TABLE t (id int)").unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_collect_local_inventory_groups_by_database() {
+        let temp_dir = write_local_project(&[
+            "salesdb/orders.sql",
+            "salesdb/customers.sql",
+            "marketingdb/leads.sql",
+            "bad db/table.sql",
+        ]);
+
+        let inventory = collect_local_inventory(
+            temp_dir.path(),
+            &SqlSource::default(),
+            &[],
+            &[],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert_eq!(inventory.table_count(), 3);
+        assert_eq!(
+            inventory.databases["salesdb"],
+            vec!["customers".to_string(), "orders".to_string()]
+        );
+        assert_eq!(
+            inventory.databases["marketingdb"],
+            vec!["leads".to_string()]
+        );
+        assert_eq!(inventory.issues.len(), 1);
+
+        let inventory = collect_local_inventory(
+            temp_dir.path(),
+            &SqlSource::default(),
+            &["SalesDB.*".to_string()],
+            &["salesdb.orders".to_string()],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert_eq!(inventory.table_count(), 1);
+        assert_eq!(
+            inventory.databases["salesdb"],
+            vec!["customers".to_string()]
+        );
+        assert!(!inventory.databases.contains_key("marketingdb"));
+    }
+
+    #[test]
+    fn test_check_local_projection_counts_issues() {
+        let temp_dir = write_local_project(&["salesdb/customers.sql"]);
+        let events_dir = temp_dir.path().join("logsdb");
+        std::fs::create_dir_all(&events_dir).unwrap();
+        std::fs::write(
+            events_dir.join("events.sql"),
+            "CREATE EXTERNAL TABLE events (id int)\nPARTITIONED BY (dt string)\nTBLPROPERTIES ('projection.enabled'='true')",
+        )
+        .unwrap();
+
+        let count = check_local_projection(
+            temp_dir.path(),
+            &SqlSource::default(),
+            &[],
+            &[],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        let count = check_local_projection(
+            temp_dir.path(),
+            &SqlSource::default(),
+            &["salesdb.*".to_string()],
+            &[],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_local_only_without_aws() {
+        // Local-only mode never builds AWS clients, so this succeeds without credentials
+        let temp_dir = write_local_project(&["salesdb/customers.sql"]);
+        let config_path = temp_dir.path().join("athenadef.yaml");
+
+        let result = execute(
+            config_path.to_str().unwrap(),
+            &ConfigOverrides::default(),
+            &[],
+            &[],
+            DisplayOptions::default(),
+            PlanFormat::Text,
+            None,
+            true,
+            false,
+            None,
+            None,
+            false,
+            false,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_local_only_fails_on_parse_issues() {
+        let temp_dir = write_local_project(&["salesdb/customers.sql", "bad db/table.sql"]);
+        let config_path = temp_dir.path().join("athenadef.yaml");
+
+        let err = execute(
+            config_path.to_str().unwrap(),
+            &ConfigOverrides::default(),
+            &[],
+            &[],
+            DisplayOptions::default(),
+            PlanFormat::Json,
+            None,
+            true,
+            false,
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("1 local file(s) could not be parsed")
+        );
+    }
+
+    #[test]
+    fn test_display_local_inventory_formats() {
+        let inventory = LocalInventory {
+            databases: BTreeMap::from([("salesdb".to_string(), vec!["customers".to_string()])]),
+            issues: vec![],
+        };
+
+        for format in [PlanFormat::Text, PlanFormat::Json, PlanFormat::Yaml] {
+            assert!(display_local_inventory(&inventory, format).is_ok());
+        }
+        for (format, name) in [
+            (PlanFormat::Sarif, "sarif"),
+            (PlanFormat::Markdown, "markdown"),
+            (PlanFormat::Github, "github"),
+        ] {
+            let err = display_local_inventory(&inventory, format).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "--format {} is not supported with --no-remote. Use text, json, or yaml",
+                    name
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_local_only_rejects_diff_formats() {
+        let temp_dir = write_local_project(&["salesdb/customers.sql"]);
+        let config_path = temp_dir.path().join("athenadef.yaml");
+
+        for format in [PlanFormat::Sarif, PlanFormat::Markdown, PlanFormat::Github] {
+            let err = execute(
+                config_path.to_str().unwrap(),
+                &ConfigOverrides::default(),
+                &[],
+                &[],
+                DisplayOptions::default(),
+                format,
+                None,
+                true,
+                false,
+                None,
+                None,
+                false,
+                false,
+            )
+            .await
+            .unwrap_err();
+            assert!(err.to_string().contains("not supported with --no-remote"));
+        }
+    }
 
     #[test]
     fn test_display_json() {
@@ -119,6 +573,8 @@ mod tests {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             }],
         };
 
@@ -128,7 +584,7 @@ mod tests {
 
     #[test]
     fn test_display_diff_result_no_changes() {
-        use crate::output::display_diff_result;
+        use crate::output::{DisplayOptions, display_diff_result};
 
         let diff_result = DiffResult {
             no_change: true,
@@ -140,13 +596,13 @@ mod tests {
             table_diffs: vec![],
         };
 
-        let result = display_diff_result(&diff_result, false);
+        let result = display_diff_result(&diff_result, DisplayOptions::default());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_display_diff_result_with_changes() {
-        use crate::output::display_diff_result;
+        use crate::output::{DisplayOptions, display_diff_result};
 
         let diff_result = DiffResult {
             no_change: false,
@@ -162,6 +618,8 @@ mod tests {
                     operation: DiffOperation::Create,
                     text_diff: None,
                     change_details: None,
+                    location_impact: None,
+                    object_type: ObjectType::Table,
                 },
                 TableDiff {
                     database_name: "testdb".to_string(),
@@ -169,6 +627,8 @@ mod tests {
                     operation: DiffOperation::Update,
                     text_diff: Some("--- remote\n+++ local\n-old\n+new".to_string()),
                     change_details: None,
+                    location_impact: None,
+                    object_type: ObjectType::Table,
                 },
                 TableDiff {
                     database_name: "testdb".to_string(),
@@ -176,17 +636,19 @@ mod tests {
                     operation: DiffOperation::Delete,
                     text_diff: None,
                     change_details: None,
+                    location_impact: None,
+                    object_type: ObjectType::Table,
                 },
             ],
         };
 
-        let result = display_diff_result(&diff_result, false);
+        let result = display_diff_result(&diff_result, DisplayOptions::default());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_display_diff_result_show_unchanged() {
-        use crate::output::display_diff_result;
+        use crate::output::{DisplayOptions, display_diff_result};
 
         let diff_result = DiffResult {
             no_change: false,
@@ -201,10 +663,19 @@ mod tests {
                 operation: DiffOperation::NoChange,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             }],
         };
 
-        let result = display_diff_result(&diff_result, true);
+        let result = display_diff_result(
+            &diff_result,
+            DisplayOptions {
+                show_unchanged: true,
+                show_locations: true,
+                summary_only: false,
+            },
+        );
         assert!(result.is_ok());
     }
 }
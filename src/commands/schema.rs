@@ -0,0 +1,107 @@
+use anyhow::Result;
+use schemars::Schema;
+
+use crate::types::diff_result::DiffResult;
+
+/// Execute the schema command
+///
+/// Prints the JSON Schema of the `plan --json` output.
+pub async fn execute() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&plan_schema())?);
+    Ok(())
+}
+
+/// JSON Schema of the `plan --json` output
+///
+/// Generated from the serialized types, so it always matches what plan prints.
+pub fn plan_schema() -> Schema {
+    schemars::schema_for!(DiffResult)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::diff_result::{
+        Bucketing, BucketingChange, ChangeDetails, ColumnChange, ColumnChangeType, DiffOperation,
+        LocationImpact, PropertyChange, TableDiff,
+    };
+    use crate::types::table_definition::ObjectType;
+
+    /// A plan using every optional part of the output
+    fn sample_diff_result() -> DiffResult {
+        DiffResult::from_table_diffs(vec![
+            TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "customers".to_string(),
+                operation: DiffOperation::Update,
+                text_diff: Some("--- remote\n+++ local\n".to_string()),
+                change_details: Some(ChangeDetails {
+                    column_changes: vec![ColumnChange {
+                        change_type: ColumnChangeType::ConstraintChanged,
+                        column_name: "id".to_string(),
+                        old_type: Some("bigint".to_string()),
+                        new_type: Some("bigint".to_string()),
+                        old_constraints: None,
+                        new_constraints: Some("NOT NULL".to_string()),
+                    }],
+                    property_changes: vec![PropertyChange {
+                        property_name: "location".to_string(),
+                        old_value: Some("s3://bucket/old/".to_string()),
+                        new_value: Some("s3://bucket/new/".to_string()),
+                    }],
+                    bucketing_change: Some(BucketingChange {
+                        old_bucketing: None,
+                        new_bucketing: Some(Bucketing {
+                            columns: vec!["id".to_string()],
+                            num_buckets: 8,
+                        }),
+                    }),
+                    unmodeled_changes: false,
+                }),
+                location_impact: Some(LocationImpact {
+                    old_location: Some("s3://bucket/old/".to_string()),
+                    new_location: Some("s3://bucket/new/".to_string()),
+                }),
+                object_type: ObjectType::Table,
+            },
+            TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "daily_totals".to_string(),
+                operation: DiffOperation::Create,
+                text_diff: None,
+                change_details: None,
+                location_impact: None,
+                object_type: ObjectType::View,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_plan_output_matches_schema() {
+        let schema = serde_json::to_value(plan_schema()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let output = serde_json::to_value(sample_diff_result()).unwrap();
+        let errors: Vec<String> = validator
+            .iter_errors(&output)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert!(validator.is_valid(&serde_json::to_value(DiffResult::new()).unwrap()));
+    }
+
+    #[test]
+    fn test_schema_rejects_unknown_values() {
+        let schema = serde_json::to_value(plan_schema()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let mut output = serde_json::to_value(sample_diff_result()).unwrap();
+        output["table_diffs"][0]["operation"] = "Rename".into();
+        assert!(!validator.is_valid(&output));
+
+        let mut output = serde_json::to_value(sample_diff_result()).unwrap();
+        output["summary"].as_object_mut().unwrap().remove("to_add");
+        assert!(!validator.is_valid(&output));
+    }
+}
@@ -0,0 +1,278 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::debug;
+
+use crate::file_utils::{FileUtils, SqlLayout};
+use crate::output::{format_error, format_success};
+use crate::types::config::{Config, ConfigOverrides};
+use crate::warnings::warn;
+
+/// Problems found in the local configuration and SQL files
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ValidationReport {
+    /// Table names grouped by database, both sorted
+    pub databases: BTreeMap<String, Vec<String>>,
+    /// Problems that make plan and apply fail or act on the wrong table
+    pub errors: Vec<String>,
+    /// Problems athenadef works around, such as ignored files
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Total number of tables across all databases
+    pub fn table_count(&self) -> usize {
+        self.databases.values().map(|tables| tables.len()).sum()
+    }
+}
+
+/// Execute the validate command
+///
+/// Loads the configuration and checks the local SQL files next to it without
+/// contacting AWS. Fails if the configuration is invalid or any error is found;
+/// warnings only fail the run under `--fail-on-warnings`.
+pub async fn execute(config_path: &str, overrides: &ConfigOverrides, json: bool) -> Result<()> {
+    // Logged at debug level: console logs share stdout with the JSON report
+    debug!("Loading configuration from {}", config_path);
+    let config = Config::load_with_overrides(config_path, overrides)?;
+
+    let base_path = config.base_path(config_path);
+
+    let report = validate_local_files(&base_path, &config.sql_layout())?;
+    display_report(&report, json)
+}
+
+/// Check the SQL files under a directory
+///
+/// Errors are files that cannot be parsed (including names rejected as database or
+/// table identifiers), files that resolve to the same remote table, and empty files.
+/// Warnings are table definition files outside the `database/table.<ext>` layout
+/// (or directly under `base_path` with a default database), which every other
+/// command ignores.
+///
+/// # Arguments
+/// * `base_path` - Root directory containing SQL files
+/// * `layout` - File extension and default database
+pub fn validate_local_files(base_path: &Path, layout: &SqlLayout) -> Result<ValidationReport> {
+    let scan = FileUtils::scan_sql_files_with_layout(base_path, layout)?;
+
+    let mut report = ValidationReport {
+        errors: scan
+            .issues
+            .iter()
+            .map(|issue| format!("Failed to parse {}", issue))
+            .collect(),
+        ..ValidationReport::default()
+    };
+
+    report.errors.extend(scan.name_collisions());
+
+    let mut sql_files: Vec<_> = scan.sql_files.values().collect();
+    sql_files.sort_by_key(|sql_file| sql_file.qualified_name());
+    for sql_file in sql_files {
+        if sql_file.content.trim().is_empty() {
+            report
+                .errors
+                .push(format!("{} is empty", sql_file.file_path.display()));
+        }
+        report
+            .databases
+            .entry(sql_file.database_name.clone())
+            .or_default()
+            .push(sql_file.table_name.clone());
+    }
+
+    report.warnings = FileUtils::find_orphaned_sql_files_with_layout(base_path, layout)
+        .iter()
+        .map(|path| orphaned_file_warning(path))
+        .collect();
+
+    Ok(report)
+}
+
+/// Warning for a file outside the `database/table.sql` layout
+pub fn orphaned_file_warning(path: &Path) -> String {
+    format!(
+        "{} is not in the database/table.sql layout and is ignored",
+        path.display()
+    )
+}
+
+/// Display the report, failing if it has any errors
+fn display_report(report: &ValidationReport, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(report)?);
+    } else {
+        println!(
+            "Found {} table(s) in {} database(s):",
+            report.table_count(),
+            report.databases.len()
+        );
+        for (database_name, tables) in &report.databases {
+            println!("  {}: {} table(s)", database_name, tables.len());
+        }
+    }
+
+    for warning in &report.warnings {
+        warn(warning.as_str());
+    }
+
+    if !report.errors.is_empty() {
+        for error in &report.errors {
+            eprintln!("{}", format_error(error));
+        }
+        return Err(anyhow::anyhow!(
+            "Validation failed with {} error(s)",
+            report.errors.len()
+        ));
+    }
+
+    if !json {
+        println!(
+            "\n{}",
+            format_success(
+                "Configuration and local files are valid. Remote state was not checked."
+            )
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_files(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for (file, content) in files {
+            let path = temp_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_validate_local_files_clean() {
+        let temp_dir = write_files(&[
+            (
+                "salesdb/orders.sql",
+                "CREATE EXTERNAL TABLE orders (id int)",
+            ),
+            (
+                "salesdb/customers.sql",
+                "CREATE EXTERNAL TABLE customers (id int)",
+            ),
+            (
+                "marketingdb/leads.sql",
+                "CREATE EXTERNAL TABLE leads (id int)",
+            ),
+            (".git/hooks/sample.sql", ""),
+        ]);
+
+        let report = validate_local_files(temp_dir.path(), &SqlLayout::default()).unwrap();
+        assert_eq!(report.table_count(), 3);
+        assert_eq!(
+            report.databases["salesdb"],
+            vec!["customers".to_string(), "orders".to_string()]
+        );
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_local_files_reports_problems() {
+        let temp_dir = write_files(&[
+            (
+                "salesdb/orders.sql",
+                "CREATE EXTERNAL TABLE orders (id int)",
+            ),
+            ("salesdb/empty.sql", "  \n"),
+            (
+                "SalesDB/Orders.sql",
+                "CREATE EXTERNAL TABLE orders (id int)",
+            ),
+            ("bad db/table.sql", "CREATE EXTERNAL TABLE t (id int)"),
+            ("loose.sql", "CREATE EXTERNAL TABLE loose (id int)"),
+            (
+                "salesdb/nested/deep.sql",
+                "CREATE EXTERNAL TABLE deep (id int)",
+            ),
+        ]);
+
+        let report = validate_local_files(temp_dir.path(), &SqlLayout::default()).unwrap();
+        assert_eq!(report.errors.len(), 3);
+        assert!(report.errors[0].contains("invalid characters"));
+        assert!(report.errors[1].starts_with("salesdb.orders is defined by multiple files"));
+        assert!(report.errors[2].ends_with("empty.sql is empty"));
+
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].contains("loose.sql"));
+        assert!(report.warnings[1].contains("deep.sql"));
+    }
+
+    #[test]
+    fn test_validate_local_files_with_default_database() {
+        let temp_dir = write_files(&[
+            ("customers.sql", "CREATE EXTERNAL TABLE customers (id int)"),
+            (
+                "salesdb/orders.sql",
+                "CREATE EXTERNAL TABLE orders (id int)",
+            ),
+            (
+                "marketingdb/leads.sql",
+                "CREATE EXTERNAL TABLE leads (id int)",
+            ),
+        ]);
+
+        let report = validate_local_files(temp_dir.path(), &SqlLayout::default()).unwrap();
+        assert_eq!(report.table_count(), 2);
+        assert_eq!(report.warnings.len(), 1);
+
+        let layout = SqlLayout {
+            default_database: Some("salesdb".to_string()),
+            ..SqlLayout::default()
+        };
+        let report = validate_local_files(temp_dir.path(), &layout).unwrap();
+        assert_eq!(
+            report.databases["salesdb"],
+            vec!["customers".to_string(), "orders".to_string()]
+        );
+        assert_eq!(report.databases["marketingdb"], vec!["leads".to_string()]);
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_fails_on_errors_without_aws() {
+        let temp_dir = write_files(&[
+            ("athenadef.yaml", "workgroup: primary\n"),
+            (
+                "salesdb/orders.sql",
+                "CREATE EXTERNAL TABLE orders (id int)",
+            ),
+        ]);
+        let config_path = temp_dir.path().join("athenadef.yaml");
+        assert!(
+            execute(
+                config_path.to_str().unwrap(),
+                &ConfigOverrides::default(),
+                false
+            )
+            .await
+            .is_ok()
+        );
+
+        std::fs::write(temp_dir.path().join("salesdb/empty.sql"), "").unwrap();
+        let err = execute(
+            config_path.to_str().unwrap(),
+            &ConfigOverrides::default(),
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Validation failed with 1 error(s)");
+    }
+}
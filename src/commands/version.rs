@@ -0,0 +1,88 @@
+use anyhow::Result;
+
+use crate::output::{format_success, format_warning};
+use crate::update_check::{
+    CURRENT_VERSION, GitHubReleases, RELEASES_PAGE_URL, ReleaseFetcher, UpdateStatus,
+    check_for_update,
+};
+use crate::warnings::warn;
+
+/// Execute the version command
+///
+/// Prints the version of this build and, with `check`, whether a newer release
+/// exists. A failed lookup is only a warning, never an error.
+pub async fn execute(check: bool) -> Result<()> {
+    println!("athenadef {}", CURRENT_VERSION);
+
+    if check {
+        report_update(&GitHubReleases, CURRENT_VERSION).await;
+    }
+
+    Ok(())
+}
+
+/// Print whether a newer release than `current_version` exists
+///
+/// # Returns
+/// The result of the check, or None if the latest release could not be looked up
+async fn report_update<F: ReleaseFetcher>(
+    fetcher: &F,
+    current_version: &str,
+) -> Option<UpdateStatus> {
+    let status = match check_for_update(fetcher, current_version).await {
+        Ok(status) => status,
+        Err(e) => {
+            warn(format!("Could not check for updates: {:#}", e));
+            return None;
+        }
+    };
+
+    match status {
+        UpdateStatus::UpToDate => {
+            println!("{}", format_success("athenadef is up to date."));
+        }
+        UpdateStatus::Available { ref latest } => {
+            println!(
+                "{}",
+                format_warning(&format!(
+                    "A newer version is available: {} (running {})",
+                    latest, current_version
+                ))
+            );
+            println!("Download it from {}", RELEASES_PAGE_URL);
+        }
+    }
+
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_check::MockReleaseFetcher;
+
+    #[tokio::test]
+    async fn test_report_update_ignores_network_errors() {
+        let mut fetcher = MockReleaseFetcher::new();
+        fetcher
+            .expect_latest_tag()
+            .returning(|| Box::pin(async { Err(anyhow::anyhow!("dns error")) }));
+
+        assert_eq!(report_update(&fetcher, "0.1.1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_report_update_newer_release() {
+        let mut fetcher = MockReleaseFetcher::new();
+        fetcher
+            .expect_latest_tag()
+            .returning(|| Box::pin(async { Ok("v0.2.0".to_string()) }));
+
+        assert_eq!(
+            report_update(&fetcher, "0.1.1").await,
+            Some(UpdateStatus::Available {
+                latest: "v0.2.0".to_string()
+            })
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::{Context, Result, anyhow};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::types::table_definition::ObjectType;
+use crate::warnings::warn;
+
 /// Represents a SQL file with its metadata
 #[derive(Debug, Clone, PartialEq)]
 pub struct SqlFile {
@@ -10,10 +13,12 @@ pub struct SqlFile {
     pub table_name: String,
     pub file_path: PathBuf,
     pub content: String,
+    /// Whether the file defines a table or a view, from its DDL
+    pub object_type: ObjectType,
 }
 
 impl SqlFile {
-    /// Create a new SqlFile instance
+    /// Create a new SqlFile instance; the object type is read from the content
     pub fn new(
         database_name: String,
         table_name: String,
@@ -24,6 +29,7 @@ impl SqlFile {
             database_name,
             table_name,
             file_path,
+            object_type: ObjectType::from_ddl(&content),
             content,
         }
     }
@@ -32,6 +38,190 @@ impl SqlFile {
     pub fn qualified_name(&self) -> String {
         format!("{}.{}", self.database_name, self.table_name)
     }
+
+    /// Get the name this table will have in Athena (database.table)
+    ///
+    /// Athena stores database and table names in lowercase, so files whose
+    /// paths differ only by case resolve to the same remote table.
+    pub fn remote_name(&self) -> String {
+        self.qualified_name().to_lowercase()
+    }
+}
+
+/// Result of scanning a directory for SQL files
+#[derive(Debug, Default)]
+pub struct SqlFileScan {
+    /// Parsed files keyed by "database.table"
+    pub sql_files: HashMap<String, SqlFile>,
+    /// Files that could not be parsed, as "path: reason"
+    pub issues: Vec<String>,
+}
+
+impl SqlFileScan {
+    /// Add a parsed file; `nested` tells whether it is a `database/table.sql` file
+    ///
+    /// A table defined both directly under the base path (for the default
+    /// database) and in its database directory keeps the `database/table.sql`
+    /// file, and the other one is recorded as an issue.
+    pub fn insert(&mut self, sql_file: SqlFile, nested: bool) {
+        let key = sql_file.qualified_name();
+        if let Some(existing) = self.sql_files.get(&key) {
+            let (kept, ignored) = if nested {
+                (&sql_file.file_path, &existing.file_path)
+            } else {
+                (&existing.file_path, &sql_file.file_path)
+            };
+            self.issues.push(format!(
+                "{}: table {} is already defined in {}",
+                ignored.display(),
+                key,
+                kept.display()
+            ));
+            if !nested {
+                return;
+            }
+        }
+        self.sql_files.insert(key, sql_file);
+    }
+
+    /// Describe each remote table defined by more than one file
+    ///
+    /// Athena stores names in lowercase, so `salesdb/Customers.sql` and
+    /// `salesdb/customers.sql` define the same table and only one of them is used.
+    ///
+    /// # Returns
+    /// One message per colliding remote name, sorted by that name
+    pub fn name_collisions(&self) -> Vec<String> {
+        FileUtils::find_remote_name_collisions(&self.sql_files)
+            .into_iter()
+            .map(|(remote_name, paths)| {
+                let paths: Vec<String> = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                format!(
+                    "{} is defined by multiple files: {}",
+                    remote_name,
+                    paths.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Print a warning for each file that could not be parsed
+    pub fn print_parse_warnings(&self) {
+        for issue in &self.issues {
+            warn(format!("Failed to parse {}", issue));
+        }
+    }
+
+    /// Print a warning for each file that could not be parsed and each table
+    /// defined by more than one file
+    pub fn print_warnings(&self) {
+        self.print_parse_warnings();
+        for collision in self.name_collisions() {
+            warn(collision);
+        }
+    }
+}
+
+/// Default extension of table definition files
+pub const DEFAULT_FILE_EXTENSION: &str = "sql";
+
+/// Extension appended to table definition files that are Tera templates
+pub const TEMPLATE_EXTENSION: &str = "tera";
+
+/// How table definition files are laid out under the base path and read
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlLayout {
+    /// Extension of table definition files, without the leading dot
+    pub file_extension: String,
+    /// Database of `table_name.<ext>` files directly under the base path
+    pub default_database: Option<String>,
+    /// Render every file as a template, not only `table_name.<ext>.tera` files
+    pub templating: bool,
+    /// Values of the variables templates refer to; when set, `${name}` tokens
+    /// in other files are replaced too
+    pub variables: Option<BTreeMap<String, String>>,
+}
+
+impl Default for SqlLayout {
+    fn default() -> Self {
+        Self {
+            file_extension: DEFAULT_FILE_EXTENSION.to_string(),
+            default_database: None,
+            templating: false,
+            variables: None,
+        }
+    }
+}
+
+impl SqlLayout {
+    /// File name of a table's definition, such as `orders.sql`
+    pub fn file_name(&self, table_name: &str) -> String {
+        format!("{}.{}", table_name, self.file_extension)
+    }
+
+    /// Whether `path` has the extension of table definition files, with or
+    /// without the template extension
+    pub fn has_file_extension(&self, path: &Path) -> bool {
+        has_extension(path, &self.file_extension)
+    }
+
+    /// Whether the file at `path` is rendered as a template before use
+    pub fn is_template(&self, path: &Path) -> bool {
+        self.templating || is_template_file(path)
+    }
+
+    /// Paths relative to the base path a table's definition may be read from, in
+    /// order of preference
+    ///
+    /// These are `database/table.<ext>` and its template, then the same names
+    /// directly under the base path for the default database.
+    pub fn table_file_candidates(&self, database_name: &str, table_name: &str) -> Vec<String> {
+        let file_name = self.file_name(table_name);
+        let mut candidates = vec![
+            format!("{}/{}", database_name, file_name),
+            format!("{}/{}.{}", database_name, file_name, TEMPLATE_EXTENSION),
+        ];
+        if self.is_default_database(database_name) {
+            candidates.push(file_name.clone());
+            candidates.push(format!("{}.{}", file_name, TEMPLATE_EXTENSION));
+        }
+        candidates
+    }
+
+    /// Whether `database_name` is the default database
+    ///
+    /// Athena database names are case-insensitive.
+    pub fn is_default_database(&self, database_name: &str) -> bool {
+        self.default_database
+            .as_deref()
+            .is_some_and(|default| default.eq_ignore_ascii_case(database_name))
+    }
+}
+
+/// Whether `path` ends in `.<file_extension>` or `.<file_extension>.tera`
+fn has_extension(path: &Path, file_extension: &str) -> bool {
+    let path = if is_template_file(path) {
+        Path::new(path.file_stem().unwrap_or_default())
+    } else {
+        path
+    };
+    path.extension().and_then(|s| s.to_str()) == Some(file_extension)
+}
+
+/// Whether `path` ends in `.tera`
+fn is_template_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some(TEMPLATE_EXTENSION)
+}
+
+/// Whether `name` can be used in a `${name}` token
+fn is_variable_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 /// File system operations for SQL files
@@ -41,6 +231,7 @@ impl FileUtils {
     /// Find all SQL files in the given directory
     ///
     /// Expected directory structure: database_name/table_name.sql
+    /// Files that cannot be parsed are skipped with a warning.
     ///
     /// # Arguments
     /// * `base_path` - Root directory to search for SQL files
@@ -48,6 +239,47 @@ impl FileUtils {
     /// # Returns
     /// A HashMap where keys are "database.table" and values are SQL file contents
     pub fn find_sql_files(base_path: &Path) -> Result<HashMap<String, SqlFile>> {
+        Self::find_sql_files_with_layout(base_path, &SqlLayout::default())
+    }
+
+    /// Find all table definition files in the given layout
+    ///
+    /// Only files with the layout's extension are read. With a default database,
+    /// files directly under `base_path` are read as tables of that database.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory to search for SQL files
+    /// * `layout` - File extension and default database
+    pub fn find_sql_files_with_layout(
+        base_path: &Path,
+        layout: &SqlLayout,
+    ) -> Result<HashMap<String, SqlFile>> {
+        let scan = Self::scan_sql_files_with_layout(base_path, layout)?;
+        scan.print_warnings();
+        Ok(scan.sql_files)
+    }
+
+    /// Find all SQL files in the given directory, collecting files that cannot be parsed
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory to search for SQL files
+    ///
+    /// # Returns
+    /// The parsed SQL files and a description of each file that failed to parse
+    pub fn scan_sql_files(base_path: &Path) -> Result<SqlFileScan> {
+        Self::scan_sql_files_with_layout(base_path, &SqlLayout::default())
+    }
+
+    /// Find all table definition files in the given layout, collecting files that
+    /// cannot be parsed
+    ///
+    /// With a default database, `table_name.<ext>` files directly under `base_path`
+    /// are read as tables of that database, next to the `database/table.<ext>` files.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory to search for SQL files
+    /// * `layout` - File extension and default database
+    pub fn scan_sql_files_with_layout(base_path: &Path, layout: &SqlLayout) -> Result<SqlFileScan> {
         if !base_path.exists() {
             return Err(anyhow!("Directory does not exist: {}", base_path.display()));
         }
@@ -56,45 +288,158 @@ impl FileUtils {
             return Err(anyhow!("Path is not a directory: {}", base_path.display()));
         }
 
-        let mut sql_files = HashMap::new();
+        let mut scan = SqlFileScan::default();
 
+        // Direct children are only tables when they belong to the default database
+        let min_depth = if layout.default_database.is_some() {
+            1
+        } else {
+            2
+        };
         for entry in WalkDir::new(base_path)
-            .min_depth(2) // Skip root and direct children (need db/table structure)
+            .min_depth(min_depth)
             .max_depth(2) // Only go two levels deep (database/table.sql)
             .into_iter()
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
 
-            // Only process .sql files
-            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("sql") {
+            // Only process files with the configured extension
+            if !path.is_file() || !layout.has_file_extension(path) {
                 continue;
             }
 
-            match Self::parse_sql_file(path) {
-                Ok(sql_file) => {
-                    let key = sql_file.qualified_name();
-                    sql_files.insert(key, sql_file);
+            let parsed = match layout.default_database.as_deref() {
+                Some(database_name) if entry.depth() == 1 => {
+                    Self::parse_default_database_file(path, database_name, layout)
+                }
+                _ => Self::parse_sql_file(path, layout),
+            };
+            match parsed {
+                Ok(mut sql_file) => {
+                    // Unlike a misplaced file, a table that fails to render must not
+                    // look like it was removed, so this stops the scan
+                    sql_file.content = Self::render_table_sql(path, &sql_file.content, layout)?;
+                    scan.insert(sql_file, entry.depth() == 2)
                 }
                 Err(e) => {
-                    // Log the error but continue processing other files
-                    eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                    // Record the error but continue processing other files
+                    scan.issues.push(format!("{}: {}", path.display(), e));
                 }
             }
         }
 
-        Ok(sql_files)
+        Ok(scan)
+    }
+
+    /// Find `.sql` files outside the `database/table.sql` layout
+    ///
+    /// Such files, directly under `base_path` or nested more than one directory
+    /// deep, are ignored by every command.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory to search for SQL files
+    ///
+    /// # Returns
+    /// The sorted paths of the ignored files
+    pub fn find_orphaned_sql_files(base_path: &Path) -> Vec<PathBuf> {
+        Self::find_orphaned_sql_files_with_layout(base_path, &SqlLayout::default())
+    }
+
+    /// Find table definition files outside the given layout
+    ///
+    /// With a default database, files directly under `base_path` are in the layout.
+    /// Hidden directories such as `.git` are not searched.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory to search for SQL files
+    /// * `layout` - File extension and default database
+    pub fn find_orphaned_sql_files_with_layout(
+        base_path: &Path,
+        layout: &SqlLayout,
+    ) -> Vec<PathBuf> {
+        let mut orphaned: Vec<PathBuf> = WalkDir::new(base_path)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let in_layout =
+                    entry.depth() == 2 || (entry.depth() == 1 && layout.default_database.is_some());
+                !in_layout && entry.file_type().is_file() && layout.has_file_extension(entry.path())
+            })
+            .map(|entry| entry.into_path())
+            .collect();
+        orphaned.sort();
+        orphaned
+    }
+
+    /// Find local files that resolve to the same remote table
+    ///
+    /// # Arguments
+    /// * `sql_files` - Local SQL files keyed by "database.table"
+    ///
+    /// # Returns
+    /// The sorted source paths of each colliding remote name, keyed by that name
+    pub fn find_remote_name_collisions(
+        sql_files: &HashMap<String, SqlFile>,
+    ) -> BTreeMap<String, Vec<&Path>> {
+        let mut by_remote_name: BTreeMap<String, Vec<&Path>> = BTreeMap::new();
+        for sql_file in sql_files.values() {
+            by_remote_name
+                .entry(sql_file.remote_name())
+                .or_default()
+                .push(&sql_file.file_path);
+        }
+
+        by_remote_name.retain(|_, paths| paths.len() > 1);
+        for paths in by_remote_name.values_mut() {
+            paths.sort();
+        }
+        by_remote_name
+    }
+
+    /// Ensure no two local files resolve to the same remote table
+    ///
+    /// # Arguments
+    /// * `sql_files` - Local SQL files keyed by "database.table"
+    ///
+    /// # Returns
+    /// An error listing the conflicting source paths if any collision is found
+    pub fn check_remote_name_collisions(sql_files: &HashMap<String, SqlFile>) -> Result<()> {
+        let collisions: Vec<String> = Self::find_remote_name_collisions(sql_files)
+            .into_iter()
+            .map(|(remote_name, paths)| {
+                let sources: Vec<String> = paths
+                    .iter()
+                    .map(|path| format!("    - {}", path.display()))
+                    .collect();
+                format!("  {}:\n{}", remote_name, sources.join("\n"))
+            })
+            .collect();
+
+        if collisions.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "Multiple local files resolve to the same remote table:\n{}\n\nAthena stores database and table names in lowercase. Rename or remove the conflicting files.",
+            collisions.join("\n")
+        ))
     }
 
     /// Parse a SQL file and extract database/table names from its path
     ///
     /// # Arguments
     /// * `path` - Path to the SQL file (expected format: database_name/table_name.sql)
+    /// * `layout` - Expected extension
     ///
     /// # Returns
-    /// A SqlFile instance with database name, table name, and file content
-    pub fn parse_sql_file(path: &Path) -> Result<SqlFile> {
-        Self::validate_sql_file_path(path)?;
+    /// A SqlFile instance with database name, table name, and file content as
+    /// written; see `render_table_sql` for the DDL of a template
+    pub fn parse_sql_file(path: &Path, layout: &SqlLayout) -> Result<SqlFile> {
+        Self::validate_sql_file_path(path, &layout.file_extension)?;
 
         let (database_name, table_name) = Self::extract_database_table_from_path(path)?;
         let content = Self::read_sql_file(path)?;
@@ -107,6 +452,49 @@ impl FileUtils {
         ))
     }
 
+    /// Parse a `table_name.sql` file directly under the base path as a table of
+    /// the default database
+    ///
+    /// # Arguments
+    /// * `path` - Path to the SQL file
+    /// * `database_name` - Configured `default_database`
+    /// * `layout` - Expected extension
+    pub fn parse_default_database_file(
+        path: &Path,
+        database_name: &str,
+        layout: &SqlLayout,
+    ) -> Result<SqlFile> {
+        Self::validate_sql_file_path(path, &layout.file_extension)?;
+
+        let table_name = Self::extract_table_from_path(path)?;
+        Self::validate_identifier(database_name, "database name")?;
+        let content = Self::read_sql_file(path)?;
+
+        Ok(SqlFile::new(
+            database_name.to_string(),
+            table_name,
+            path.to_path_buf(),
+            content,
+        ))
+    }
+
+    /// Extract the table name from a file path (the file name without its
+    /// extension and `.tera`)
+    pub fn extract_table_from_path(path: &Path) -> Result<String> {
+        let path = if is_template_file(path) {
+            Path::new(path.file_stem().unwrap_or_default())
+        } else {
+            path
+        };
+        let table_name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("Cannot extract table name from path: {}", path.display()))?
+            .to_string();
+        Self::validate_identifier(&table_name, "table name")?;
+        Ok(table_name)
+    }
+
     /// Extract database and table names from a file path
     ///
     /// # Arguments
@@ -123,16 +511,9 @@ impl FileUtils {
             .ok_or_else(|| anyhow!("Cannot extract database name from path: {}", path.display()))?
             .to_string();
 
-        // Get the file name without extension (table name)
-        let table_name = path
-            .file_stem()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow!("Cannot extract table name from path: {}", path.display()))?
-            .to_string();
-
         // Validate names (basic validation - no empty strings, no special characters)
         Self::validate_identifier(&database_name, "database name")?;
-        Self::validate_identifier(&table_name, "table name")?;
+        let table_name = Self::extract_table_from_path(path)?;
 
         Ok((database_name, table_name))
     }
@@ -149,6 +530,142 @@ impl FileUtils {
             .with_context(|| format!("Failed to read SQL file: {}", path.display()))
     }
 
+    /// Read a table definition file and render it (see `render_table_sql`)
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    /// * `layout` - Which files are templates, and the values of their variables
+    pub fn read_table_file(path: &Path, layout: &SqlLayout) -> Result<String> {
+        let content = Self::read_sql_file(path)?;
+        Self::render_table_sql(path, &content, layout)
+    }
+
+    /// Turn the content of a table definition file into the table's DDL
+    ///
+    /// Templates are rendered with Tera. Other files have their `${name}` tokens
+    /// replaced when `variables` is configured, and are used as written otherwise.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file, used to tell templates apart and in errors
+    /// * `content` - Content of the file
+    /// * `layout` - Which files are templates, and the values of their variables
+    pub fn render_table_sql(path: &Path, content: &str, layout: &SqlLayout) -> Result<String> {
+        if layout.is_template(path) {
+            let no_variables = BTreeMap::new();
+            let variables = layout.variables.as_ref().unwrap_or(&no_variables);
+            Self::render_sql(path, content, variables)
+        } else if let Some(ref variables) = layout.variables {
+            Self::substitute_variables(path, content, variables)
+        } else {
+            Ok(content.to_string())
+        }
+    }
+
+    /// Replace `${name}` tokens with the values of `variables`
+    ///
+    /// `$${name}` is kept as a literal `${name}`, as partition projection's
+    /// `storage.location.template` needs. Fails, listing every name, when
+    /// tokens have no value.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file, used in error messages
+    /// * `content` - Content of the file
+    /// * `variables` - Values of the variables
+    pub fn substitute_variables(
+        path: &Path,
+        content: &str,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<String> {
+        let mut result = String::with_capacity(content.len());
+        let mut missing = BTreeSet::new();
+        let mut rest = content;
+        while let Some(start) = rest.find('$') {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            if let Some(escaped) = after.strip_prefix("${") {
+                result.push_str("${");
+                rest = escaped;
+                continue;
+            }
+
+            let name = after
+                .strip_prefix('{')
+                .and_then(|token| token.find('}').map(|end| &token[..end]))
+                .filter(|name| is_variable_name(name));
+            match name {
+                Some(name) => {
+                    match variables.get(name) {
+                        Some(value) => result.push_str(value),
+                        None => {
+                            missing.insert(name);
+                        }
+                    }
+                    rest = &after[name.len() + 2..];
+                }
+                None => {
+                    // Not a token, such as a lone '$' in a regex
+                    result.push('$');
+                    rest = after;
+                }
+            }
+        }
+        result.push_str(rest);
+
+        if !missing.is_empty() {
+            let missing: Vec<&str> = missing.into_iter().collect();
+            return Err(anyhow!(
+                "Undefined variable(s) in {}: {}. Define them under 'variables' in the config file, or write $${{name}} for a literal ${{name}}",
+                path.display(),
+                missing.join(", ")
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Escape `${` as `$${`, so `substitute_variables` gives `content` back
+    ///
+    /// Used for DDL written to files that are read with `variables` configured,
+    /// such as exported partition projection templates.
+    pub fn escape_variables(content: &str) -> String {
+        content.replace("${", "$${")
+    }
+
+    /// Render a Tera template into table DDL
+    ///
+    /// Fails, naming the variable, when the template refers to a variable that
+    /// is not defined.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the template, used in error messages
+    /// * `template` - Template source
+    /// * `variables` - Values of the variables the template refers to
+    pub fn render_sql(
+        path: &Path,
+        template: &str,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<String> {
+        let name = path.display().to_string();
+        let mut tera = tera::Tera::default();
+        // DDL is not HTML; quotes and ampersands must be kept as written
+        tera.autoescape_on(vec![]);
+
+        let mut context = tera::Context::new();
+        for (variable, value) in variables {
+            context.insert(variable, value);
+        }
+
+        tera.add_raw_template(&name, template)
+            .and_then(|()| tera.render(&name, &context))
+            .map_err(|e| {
+                // Tera wraps the cause, such as an undefined variable, in a generic message
+                let mut cause: &dyn std::error::Error = &e;
+                while let Some(source) = cause.source() {
+                    cause = source;
+                }
+                anyhow!("Failed to render template: {}", cause)
+            })
+    }
+
     /// Write SQL content to a file
     ///
     /// # Arguments
@@ -170,8 +687,9 @@ impl FileUtils {
     /// Checks:
     /// - Path exists
     /// - Path is a file
-    /// - File has .sql extension
-    pub fn validate_sql_file_path(path: &Path) -> Result<()> {
+    /// - File has the expected extension (`sql` unless configured otherwise),
+    ///   optionally followed by `.tera`
+    pub fn validate_sql_file_path(path: &Path, file_extension: &str) -> Result<()> {
         if !path.exists() {
             return Err(anyhow!("File does not exist: {}", path.display()));
         }
@@ -180,9 +698,10 @@ impl FileUtils {
             return Err(anyhow!("Path is not a file: {}", path.display()));
         }
 
-        if path.extension().and_then(|s| s.to_str()) != Some("sql") {
+        if !has_extension(path, file_extension) {
             return Err(anyhow!(
-                "File does not have .sql extension: {}",
+                "File does not have .{} extension: {}",
+                file_extension,
                 path.display()
             ));
         }
@@ -237,6 +756,7 @@ impl FileUtils {
     /// * `base_path` - Root directory
     /// * `database_name` - Database name
     /// * `table_name` - Table name
+    /// * `file_extension` - Extension of the file, without the leading dot
     ///
     /// # Returns
     /// The path where the SQL file should be located
@@ -244,16 +764,72 @@ impl FileUtils {
         base_path: &Path,
         database_name: &str,
         table_name: &str,
+        file_extension: &str,
     ) -> Result<PathBuf> {
         Self::validate_identifier(database_name, "database name")?;
         Self::validate_identifier(table_name, "table name")?;
 
         let file_path = base_path
             .join(database_name)
-            .join(format!("{}.sql", table_name));
+            .join(format!("{}.{}", table_name, file_extension));
 
         Ok(file_path)
     }
+
+    /// Get the file path for a table in the given layout
+    ///
+    /// Tables of the default database go directly under `base_path`, unless they
+    /// already have a `database/table.<ext>` file.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory
+    /// * `database_name` - Database name
+    /// * `table_name` - Table name
+    /// * `layout` - File extension and default database
+    pub fn get_table_file_path_with_layout(
+        base_path: &Path,
+        database_name: &str,
+        table_name: &str,
+        layout: &SqlLayout,
+    ) -> Result<PathBuf> {
+        let file_path = Self::get_table_file_path(
+            base_path,
+            database_name,
+            table_name,
+            &layout.file_extension,
+        )?;
+        if !layout.is_default_database(database_name) || file_path.exists() {
+            return Ok(file_path);
+        }
+
+        Ok(base_path.join(layout.file_name(table_name)))
+    }
+
+    /// Find the existing file defining a table in the given layout
+    ///
+    /// Tries each of `SqlLayout::table_file_candidates`, so a template is found
+    /// as well. When none exists, returns where a new file would be written.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory
+    /// * `database_name` - Database name
+    /// * `table_name` - Table name
+    /// * `layout` - File extension and default database
+    pub fn find_table_file(
+        base_path: &Path,
+        database_name: &str,
+        table_name: &str,
+        layout: &SqlLayout,
+    ) -> Result<PathBuf> {
+        let new_file_path =
+            Self::get_table_file_path_with_layout(base_path, database_name, table_name, layout)?;
+        Ok(layout
+            .table_file_candidates(database_name, table_name)
+            .into_iter()
+            .map(|candidate| base_path.join(candidate))
+            .find(|path| path.is_file())
+            .unwrap_or(new_file_path))
+    }
 }
 
 #[cfg(test)]
@@ -343,13 +919,13 @@ mod tests {
         let file_path = temp_dir.path().join("test.sql");
         fs::write(&file_path, "SELECT 1;").unwrap();
 
-        assert!(FileUtils::validate_sql_file_path(&file_path).is_ok());
+        assert!(FileUtils::validate_sql_file_path(&file_path, "sql").is_ok());
     }
 
     #[test]
     fn test_validate_sql_file_path_not_exists() {
         let path = Path::new("nonexistent.sql");
-        let result = FileUtils::validate_sql_file_path(path);
+        let result = FileUtils::validate_sql_file_path(path, "sql");
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
@@ -357,7 +933,7 @@ mod tests {
     #[test]
     fn test_validate_sql_file_path_not_file() {
         let temp_dir = TempDir::new().unwrap();
-        let result = FileUtils::validate_sql_file_path(temp_dir.path());
+        let result = FileUtils::validate_sql_file_path(temp_dir.path(), "sql");
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not a file"));
     }
@@ -368,7 +944,7 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "SELECT 1;").unwrap();
 
-        let result = FileUtils::validate_sql_file_path(&file_path);
+        let result = FileUtils::validate_sql_file_path(&file_path, "sql");
         assert!(result.is_err());
         assert!(
             result
@@ -462,22 +1038,424 @@ mod tests {
     #[test]
     fn test_get_table_file_path() {
         let base_path = Path::new("/var/data");
-        let file_path = FileUtils::get_table_file_path(base_path, "salesdb", "customers").unwrap();
+        let file_path =
+            FileUtils::get_table_file_path(base_path, "salesdb", "customers", "sql").unwrap();
 
         assert_eq!(file_path, PathBuf::from("/var/data/salesdb/customers.sql"));
     }
 
+    fn default_database_layout() -> SqlLayout {
+        SqlLayout {
+            default_database: Some("salesdb".to_string()),
+            ..SqlLayout::default()
+        }
+    }
+
+    #[test]
+    fn test_find_sql_files_with_default_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::create_dir_all(base_path.join("marketingdb")).unwrap();
+        fs::write(base_path.join("customers.sql"), "CREATE TABLE customers").unwrap();
+        fs::write(base_path.join("salesdb/orders.sql"), "CREATE TABLE orders").unwrap();
+        fs::write(
+            base_path.join("marketingdb/leads.sql"),
+            "CREATE TABLE leads",
+        )
+        .unwrap();
+
+        // Without a default database, files at the root are ignored
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        assert_eq!(sql_files.len(), 2);
+        assert!(!sql_files.contains_key("salesdb.customers"));
+
+        let sql_files =
+            FileUtils::find_sql_files_with_layout(base_path, &default_database_layout()).unwrap();
+        assert_eq!(sql_files.len(), 3);
+        assert_eq!(
+            sql_files["salesdb.customers"].file_path,
+            base_path.join("customers.sql")
+        );
+        assert_eq!(
+            sql_files["salesdb.customers"].content,
+            "CREATE TABLE customers"
+        );
+        assert!(sql_files.contains_key("salesdb.orders"));
+        assert!(sql_files.contains_key("marketingdb.leads"));
+    }
+
+    #[test]
+    fn test_scan_sql_files_with_default_database_prefers_database_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        for table_name in ["customers", "zones"] {
+            fs::write(base_path.join(format!("{}.sql", table_name)), "root").unwrap();
+            fs::write(
+                base_path.join(format!("salesdb/{}.sql", table_name)),
+                "nested",
+            )
+            .unwrap();
+        }
+
+        let scan =
+            FileUtils::scan_sql_files_with_layout(base_path, &default_database_layout()).unwrap();
+        assert_eq!(scan.sql_files.len(), 2);
+        assert_eq!(scan.sql_files["salesdb.customers"].content, "nested");
+        assert_eq!(scan.sql_files["salesdb.zones"].content, "nested");
+        assert_eq!(scan.issues.len(), 2);
+        assert!(
+            scan.issues
+                .iter()
+                .all(|issue| issue.contains("is already defined in"))
+        );
+    }
+
+    #[test]
+    fn test_get_table_file_path_with_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/orders.sql"), "CREATE TABLE orders").unwrap();
+
+        let layout = default_database_layout();
+        let path = |database_name: &str, table_name: &str, layout: &SqlLayout| {
+            FileUtils::get_table_file_path_with_layout(base_path, database_name, table_name, layout)
+                .unwrap()
+        };
+
+        // New tables of the default database go to the root
+        assert_eq!(
+            path("salesdb", "customers", &layout),
+            base_path.join("customers.sql")
+        );
+        assert_eq!(
+            path("SalesDB", "customers", &layout),
+            base_path.join("customers.sql")
+        );
+        // An existing database/table.sql file keeps being used
+        assert_eq!(
+            path("salesdb", "orders", &layout),
+            base_path.join("salesdb/orders.sql")
+        );
+        // Other databases and no default keep the two-level layout
+        assert_eq!(
+            path("marketingdb", "leads", &layout),
+            base_path.join("marketingdb/leads.sql")
+        );
+        assert_eq!(
+            path("salesdb", "customers", &SqlLayout::default()),
+            base_path.join("salesdb/customers.sql")
+        );
+
+        let ddl_layout = SqlLayout {
+            file_extension: "ddl".to_string(),
+            ..layout
+        };
+        assert_eq!(
+            path("marketingdb", "leads", &ddl_layout),
+            base_path.join("marketingdb/leads.ddl")
+        );
+        assert_eq!(
+            path("salesdb", "customers", &ddl_layout),
+            base_path.join("customers.ddl")
+        );
+    }
+
+    #[test]
+    fn test_find_sql_files_with_custom_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/orders.ddl"), "CREATE TABLE orders").unwrap();
+        fs::write(base_path.join("salesdb/legacy.sql"), "CREATE TABLE legacy").unwrap();
+        fs::write(base_path.join("salesdb/notes.txt"), "notes").unwrap();
+
+        let layout = SqlLayout {
+            file_extension: "ddl".to_string(),
+            ..SqlLayout::default()
+        };
+        let sql_files = FileUtils::find_sql_files_with_layout(base_path, &layout).unwrap();
+        assert_eq!(sql_files.len(), 1);
+        assert_eq!(sql_files["salesdb.orders"].table_name, "orders");
+        assert_eq!(
+            sql_files["salesdb.orders"].file_path,
+            base_path.join("salesdb/orders.ddl")
+        );
+
+        // The default layout still only reads .sql files
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        assert_eq!(sql_files.len(), 1);
+        assert!(sql_files.contains_key("salesdb.legacy"));
+    }
+
+    fn template_layout() -> SqlLayout {
+        SqlLayout {
+            variables: Some(BTreeMap::from([(
+                "bucket".to_string(),
+                "data-prod".to_string(),
+            )])),
+            ..SqlLayout::default()
+        }
+    }
+
+    #[test]
+    fn test_render_sql_substitutes_variables() {
+        let rendered = FileUtils::render_sql(
+            Path::new("salesdb/orders.sql.tera"),
+            "CREATE EXTERNAL TABLE orders (id int)\nLOCATION 's3://{{ bucket }}/orders/'",
+            &template_layout().variables.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "CREATE EXTERNAL TABLE orders (id int)\nLOCATION 's3://data-prod/orders/'"
+        );
+    }
+
+    #[test]
+    fn test_render_sql_missing_variable() {
+        let err = FileUtils::render_sql(
+            Path::new("salesdb/orders.sql.tera"),
+            "LOCATION 's3://{{ bucket }}/{{ prefix }}/'",
+            &template_layout().variables.unwrap(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.starts_with("Failed to render template"), "{}", err);
+        assert!(err.contains("`prefix` not found"), "{}", err);
+        assert!(err.contains("salesdb/orders.sql.tera"), "{}", err);
+    }
+
+    #[test]
+    fn test_find_sql_files_renders_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(
+            base_path.join("salesdb/orders.sql.tera"),
+            "LOCATION 's3://{{ bucket }}/orders/'",
+        )
+        .unwrap();
+        fs::write(
+            base_path.join("salesdb/customers.sql"),
+            "LOCATION 's3://{{ bucket }}/customers/'",
+        )
+        .unwrap();
+
+        let layout = template_layout();
+        let sql_files = FileUtils::find_sql_files_with_layout(base_path, &layout).unwrap();
+        assert_eq!(sql_files.len(), 2);
+        // The rendered DDL is kept together with the template's path
+        assert_eq!(
+            sql_files["salesdb.orders"].content,
+            "LOCATION 's3://data-prod/orders/'"
+        );
+        assert_eq!(
+            sql_files["salesdb.orders"].file_path,
+            base_path.join("salesdb/orders.sql.tera")
+        );
+        // Plain files are only rendered with `templating`
+        assert_eq!(
+            sql_files["salesdb.customers"].content,
+            "LOCATION 's3://{{ bucket }}/customers/'"
+        );
+
+        let layout = SqlLayout {
+            templating: true,
+            ..template_layout()
+        };
+        let sql_files = FileUtils::find_sql_files_with_layout(base_path, &layout).unwrap();
+        assert_eq!(
+            sql_files["salesdb.customers"].content,
+            "LOCATION 's3://data-prod/customers/'"
+        );
+        assert_eq!(
+            FileUtils::find_table_file(base_path, "salesdb", "orders", &layout).unwrap(),
+            base_path.join("salesdb/orders.sql.tera")
+        );
+    }
+
+    #[test]
+    fn test_scan_sql_files_fails_on_template_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(
+            base_path.join("salesdb/orders.sql.tera"),
+            "LOCATION 's3://{{ missing }}/'",
+        )
+        .unwrap();
+
+        // A table that fails to render must not look like it was removed
+        let err = FileUtils::scan_sql_files_with_layout(base_path, &template_layout())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`missing` not found"), "{}", err);
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = template_layout().variables.unwrap();
+        let path = Path::new("salesdb/orders.sql");
+
+        let content = "LOCATION 's3://${bucket}/orders/'\n\
+                       'storage.location.template' = 's3://${bucket}/orders/$${year}/'\n\
+                       'pattern' = '^a$|${ not a token }'";
+        assert_eq!(
+            FileUtils::substitute_variables(path, content, &variables).unwrap(),
+            "LOCATION 's3://data-prod/orders/'\n\
+             'storage.location.template' = 's3://data-prod/orders/${year}/'\n\
+             'pattern' = '^a$|${ not a token }'"
+        );
+    }
+
+    #[test]
+    fn test_escape_variables_round_trips() {
+        let variables = template_layout().variables.unwrap();
+        let path = Path::new("salesdb/orders.sql");
+
+        for content in [
+            "'storage.location.template' = 's3://data/orders/${year}/${month}/'",
+            "'pattern' = '^a$|$${kept}|${ not a token }'",
+            "CREATE EXTERNAL TABLE orders (id bigint)",
+        ] {
+            let escaped = FileUtils::escape_variables(content);
+            assert_eq!(
+                FileUtils::substitute_variables(path, &escaped, &variables).unwrap(),
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_substitute_variables_lists_missing_names() {
+        let variables = template_layout().variables.unwrap();
+        let err = FileUtils::substitute_variables(
+            Path::new("salesdb/orders.sql"),
+            "s3://${bucket}/${stage}/${prefix}/${stage}",
+            &variables,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.starts_with("Undefined variable(s) in salesdb/orders.sql: prefix, stage."),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_find_sql_files_substitutes_variables_only_when_configured() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(
+            base_path.join("salesdb/orders.sql"),
+            "LOCATION 's3://${bucket}/orders/'",
+        )
+        .unwrap();
+
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        assert_eq!(
+            sql_files["salesdb.orders"].content,
+            "LOCATION 's3://${bucket}/orders/'"
+        );
+
+        let sql_files =
+            FileUtils::find_sql_files_with_layout(base_path, &template_layout()).unwrap();
+        assert_eq!(
+            sql_files["salesdb.orders"].content,
+            "LOCATION 's3://data-prod/orders/'"
+        );
+
+        let layout = SqlLayout {
+            variables: Some(BTreeMap::new()),
+            ..SqlLayout::default()
+        };
+        assert!(FileUtils::scan_sql_files_with_layout(base_path, &layout).is_err());
+    }
+
+    #[test]
+    fn test_validate_sql_file_path_custom_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("orders.hql");
+        fs::write(&file_path, "CREATE TABLE orders").unwrap();
+
+        assert!(FileUtils::validate_sql_file_path(&file_path, "hql").is_ok());
+        let err = FileUtils::validate_sql_file_path(&file_path, "sql")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("does not have .sql extension"));
+    }
+
+    #[test]
+    fn test_find_orphaned_sql_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        for path in [
+            "stray.sql",
+            "salesdb/customers.sql",
+            "salesdb/archive/old_orders.sql",
+            "salesdb/archive/2023/q1.sql",
+            "salesdb/notes.txt",
+            ".git/hooks/check.sql",
+        ] {
+            let file_path = base_path.join(path);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(&file_path, "CREATE TABLE t").unwrap();
+        }
+
+        assert_eq!(
+            FileUtils::find_orphaned_sql_files(base_path),
+            vec![
+                base_path.join("salesdb/archive/2023/q1.sql"),
+                base_path.join("salesdb/archive/old_orders.sql"),
+                base_path.join("stray.sql"),
+            ]
+        );
+
+        // Root files belong to the default database
+        let layout = SqlLayout {
+            default_database: Some("salesdb".to_string()),
+            ..SqlLayout::default()
+        };
+        assert_eq!(
+            FileUtils::find_orphaned_sql_files_with_layout(base_path, &layout),
+            vec![
+                base_path.join("salesdb/archive/2023/q1.sql"),
+                base_path.join("salesdb/archive/old_orders.sql"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_orphaned_sql_files_none() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE c").unwrap();
+
+        assert!(FileUtils::find_orphaned_sql_files(base_path).is_empty());
+    }
+
     #[test]
     fn test_get_table_file_path_invalid_database() {
         let base_path = Path::new("/var/data");
-        let result = FileUtils::get_table_file_path(base_path, "invalid.db", "customers");
+        let result = FileUtils::get_table_file_path(base_path, "invalid.db", "customers", "sql");
         assert!(result.is_err());
     }
 
     #[test]
     fn test_get_table_file_path_invalid_table() {
         let base_path = Path::new("/var/data");
-        let result = FileUtils::get_table_file_path(base_path, "salesdb", "invalid@table");
+        let result = FileUtils::get_table_file_path(base_path, "salesdb", "invalid@table", "sql");
         assert!(result.is_err());
     }
 
@@ -494,7 +1472,7 @@ mod tests {
         fs::write(&file_path, content).unwrap();
 
         // Parse the file
-        let sql_file = FileUtils::parse_sql_file(&file_path).unwrap();
+        let sql_file = FileUtils::parse_sql_file(&file_path, &SqlLayout::default()).unwrap();
 
         assert_eq!(sql_file.database_name, "testdb");
         assert_eq!(sql_file.table_name, "testtable");
@@ -521,4 +1499,121 @@ mod tests {
         assert_eq!(sql_files.len(), 1);
         assert!(sql_files.contains_key("validdb.valid"));
     }
+
+    #[test]
+    fn test_scan_sql_files_reports_issues() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("validdb")).unwrap();
+        fs::create_dir_all(base_path.join("bad db")).unwrap();
+        fs::write(base_path.join("validdb/valid.sql"), "CREATE TABLE valid").unwrap();
+        fs::write(base_path.join("bad db/table.sql"), "CREATE TABLE t").unwrap();
+
+        let scan = FileUtils::scan_sql_files(base_path).unwrap();
+        assert_eq!(scan.sql_files.len(), 1);
+        assert_eq!(scan.issues.len(), 1);
+        assert!(scan.issues[0].contains("table.sql"));
+        assert!(scan.issues[0].contains("invalid characters"));
+    }
+
+    #[test]
+    fn test_sql_file_remote_name() {
+        let sql_file = SqlFile::new(
+            "SalesDB".to_string(),
+            "Customers".to_string(),
+            PathBuf::from("SalesDB/Customers.sql"),
+            "CREATE TABLE ...".to_string(),
+        );
+        assert_eq!(sql_file.remote_name(), "salesdb.customers");
+    }
+
+    #[test]
+    fn test_scan_name_collisions() {
+        let mut scan = SqlFileScan::default();
+        for (database_name, table_name) in [
+            ("salesdb", "customers"),
+            ("salesdb", "Customers"),
+            ("salesdb", "orders"),
+        ] {
+            scan.insert(
+                SqlFile::new(
+                    database_name.to_string(),
+                    table_name.to_string(),
+                    PathBuf::from(format!("{}/{}.sql", database_name, table_name)),
+                    "CREATE TABLE t".to_string(),
+                ),
+                true,
+            );
+        }
+
+        assert_eq!(scan.sql_files.len(), 3);
+        assert_eq!(
+            scan.name_collisions(),
+            vec![
+                "salesdb.customers is defined by multiple files: salesdb/Customers.sql, salesdb/customers.sql"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_sql_files_warns_on_name_collisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE a").unwrap();
+        fs::write(base_path.join("salesdb/Customers.sql"), "CREATE TABLE b").unwrap();
+
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        if sql_files.len() < 2 {
+            // Case-insensitive file system; the collision cannot be reproduced
+            return;
+        }
+
+        let expected = format!(
+            "salesdb.customers is defined by multiple files: {}, {}",
+            base_path.join("salesdb/Customers.sql").display(),
+            base_path.join("salesdb/customers.sql").display()
+        );
+        assert!(crate::warnings::recorded_warnings().contains(&expected));
+    }
+
+    #[test]
+    fn test_check_remote_name_collisions_none() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE a").unwrap();
+        fs::write(base_path.join("salesdb/orders.sql"), "CREATE TABLE b").unwrap();
+
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        assert!(FileUtils::check_remote_name_collisions(&sql_files).is_ok());
+    }
+
+    #[test]
+    fn test_check_remote_name_collisions_case_folding() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::create_dir_all(base_path.join("SalesDB")).unwrap();
+        fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE a").unwrap();
+        fs::write(base_path.join("SalesDB/Customers.sql"), "CREATE TABLE b").unwrap();
+        fs::write(base_path.join("salesdb/orders.sql"), "CREATE TABLE c").unwrap();
+
+        let sql_files = FileUtils::find_sql_files(base_path).unwrap();
+        if sql_files.len() < 3 {
+            // Case-insensitive file system; the collision cannot be reproduced
+            return;
+        }
+
+        let err = FileUtils::check_remote_name_collisions(&sql_files)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("salesdb.customers"));
+        assert!(err.contains("SalesDB/Customers.sql"));
+        assert!(err.contains("salesdb/customers.sql"));
+        assert!(!err.contains("orders"));
+    }
 }
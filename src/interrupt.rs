@@ -0,0 +1,47 @@
+use anyhow::Result;
+use std::future::Future;
+
+use crate::aws::region::RegionalExecutors;
+use crate::output::format_warning;
+
+/// Exit code after a second Ctrl-C, following the 128 + SIGINT convention
+const HARD_ABORT_EXIT_CODE: i32 = 130;
+
+/// Run work that starts Athena queries, stopping them if the user presses Ctrl-C
+///
+/// On the first Ctrl-C the work is abandoned, the executors refuse to start new
+/// queries, every query still in flight is stopped with StopQueryExecution, and an
+/// error is returned so the command exits with a non-zero code. Tasks the work
+/// spawned should be owned by it (e.g. in a `JoinSet`) so they are aborted with it;
+/// any that keep running fail on their next query instead. Queries that already
+/// finished are left alone. A second
+/// Ctrl-C while queries are being stopped exits immediately.
+///
+/// Only wrap work that no longer prompts the user: once installed, the handler
+/// keeps Ctrl-C from terminating the process for the rest of the run.
+///
+/// # Arguments
+/// * `executors` - Executors whose queries are stopped on interrupt
+/// * `work` - The command's work
+pub async fn stop_queries_on_interrupt<T>(
+    executors: &RegionalExecutors,
+    work: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        result = work => result,
+        _ = tokio::signal::ctrl_c() => {
+            eprintln!(
+                "\n{}",
+                format_warning("Interrupted. Stopping running queries (press Ctrl-C again to abort immediately)...")
+            );
+            let stopped = tokio::select! {
+                stopped = executors.stop_in_flight_queries() => stopped,
+                _ = tokio::signal::ctrl_c() => std::process::exit(HARD_ABORT_EXIT_CODE),
+            };
+            Err(anyhow::anyhow!(
+                "Interrupted by Ctrl-C; stopped {} running query(ies)",
+                stopped
+            ))
+        }
+    }
+}
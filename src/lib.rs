@@ -1,9 +1,24 @@
+pub mod api;
+pub mod apply_state;
 pub mod aws;
 pub mod cli;
 pub mod commands;
 pub mod context;
 pub mod differ;
 pub mod file_utils;
+pub mod interrupt;
+pub mod logging;
 pub mod output;
+pub mod progress;
+pub mod projection;
+pub mod remote_cache;
+pub mod sarif;
+pub mod saved_plan;
+pub mod sql_format;
+pub mod sql_source;
 pub mod target_filter;
 pub mod types;
+pub mod update_check;
+pub mod warnings;
+
+pub use api::Athenadef;
@@ -0,0 +1,113 @@
+/// Tracing setup shared by every command
+///
+/// The console shows `info` (`warn` with `--quiet`, `debug` with `--debug`, or
+/// whatever `RUST_LOG` selects), colored only when stdout output is (see `output::configure_colors`). With `--log-file`, a second layer writes athenadef's debug events,
+/// including every query with its execution id, final state, and duration, to a
+/// file regardless of the console level.
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::output::Verbosity;
+
+/// Filter for the log file: athenadef at debug, dependencies at info
+const LOG_FILE_FILTER: &str = "info,athenadef=debug";
+
+/// Install the global tracing subscriber
+///
+/// # Arguments
+/// * `debug` - Whether `--debug` was given
+/// * `verbosity` - Whether `--quiet` was given
+/// * `log_file` - Path given with `--log-file`, created or truncated
+pub fn init(debug: bool, verbosity: Verbosity, log_file: Option<&Path>) -> Result<()> {
+    let file = log_file
+        .map(|path| {
+            File::create(path)
+                .with_context(|| format!("Failed to create log file {}", path.display()))
+        })
+        .transpose()?;
+
+    tracing::subscriber::set_global_default(build_subscriber(debug, verbosity, file))
+        .context("Failed to initialize logging")
+}
+
+/// Build the console subscriber, with a file layer when a log file is given
+///
+/// # Arguments
+/// * `debug` - Whether `--debug` was given
+/// * `verbosity` - Whether `--quiet` was given
+/// * `log_file` - Open file receiving debug-level events
+pub fn build_subscriber(
+    debug: bool,
+    verbosity: Verbosity,
+    log_file: Option<File>,
+) -> impl Subscriber + Send + Sync {
+    let console_filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(console_level(debug, verbosity)));
+
+    let file_layer = log_file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(Mutex::new(file))
+            .with_ansi(false)
+            .with_filter(EnvFilter::new(LOG_FILE_FILTER))
+    });
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(console::colors_enabled())
+                .with_filter(console_filter),
+        )
+        .with(file_layer)
+}
+
+/// Console log level; `--debug` wins over `--quiet`
+pub fn console_level(debug: bool, verbosity: Verbosity) -> &'static str {
+    match (debug, verbosity) {
+        (true, _) => "debug",
+        (false, Verbosity::Quiet) => "warn",
+        (false, Verbosity::Normal) => "info",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_file_captures_debug_events() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("athenadef.log");
+        let file = File::create(&path).unwrap();
+
+        tracing::subscriber::with_default(
+            build_subscriber(false, Verbosity::Normal, Some(file)),
+            || {
+                tracing::debug!(
+                    execution_id = "abc-123",
+                    query = "SHOW DATABASES",
+                    "Started query execution"
+                );
+                tracing::info!("Starting athenadef plan");
+            },
+        );
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Started query execution"));
+        assert!(content.contains("execution_id=\"abc-123\""));
+        assert!(content.contains("Starting athenadef plan"));
+    }
+
+    #[test]
+    fn test_console_level() {
+        assert_eq!(console_level(false, Verbosity::Normal), "info");
+        assert_eq!(console_level(false, Verbosity::Quiet), "warn");
+        // --debug keeps diagnostics even with --quiet
+        assert_eq!(console_level(true, Verbosity::Quiet), "debug");
+        assert_eq!(console_level(true, Verbosity::Normal), "debug");
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use athenadef::cli::{Cli, Commands};
+use athenadef::output::Verbosity;
 use clap::Parser;
 use console::Style;
 use std::process;
@@ -8,38 +9,69 @@ use std::process;
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Decide on colors before anything is printed
+    athenadef::output::configure_colors(cli.no_color);
+    let verbosity = if cli.quiet {
+        Verbosity::Quiet
+    } else {
+        Verbosity::Normal
+    };
+    athenadef::output::set_verbosity(verbosity);
+
     // Extract debug flag from the command
     let debug = match &cli.command {
         Commands::Init { debug, .. } => *debug,
         Commands::Plan { debug, .. } => *debug,
         Commands::Apply { debug, .. } => *debug,
+        Commands::Destroy { debug, .. } => *debug,
         Commands::Export { debug, .. } => *debug,
+        Commands::DumpRemote { debug, .. } => *debug,
+        Commands::Inventory { debug, .. } => *debug,
+        Commands::Fmt { debug, .. } => *debug,
+        Commands::Diff { debug, .. } => *debug,
+        Commands::Validate { debug, .. } => *debug,
+        Commands::Config { debug, .. } => *debug,
+        Commands::Gc { debug, .. } => *debug,
+        Commands::Schema { debug } => *debug,
+        Commands::Version { debug, .. } => *debug,
     };
 
-    // Initialize tracing subscriber with debug level if --debug flag is set
-    let log_level = if debug { "debug" } else { "info" };
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(log_level)),
-        )
-        .init();
+    // Console logging at info (debug with --debug), plus a debug-level file with --log-file
+    athenadef::logging::init(debug, verbosity, cli.log_file.as_deref())?;
+
+    // Every query of this run carries the run id in its client request token
+    let run_id = athenadef::aws::athena::new_run_id();
+    athenadef::aws::athena::set_run_id(&run_id);
+    tracing::debug!(run_id, "Starting athenadef run");
 
     // Run the CLI and handle errors with better formatting
-    if let Err(e) = cli.run().await {
-        let error_style = Style::new().red().bold();
-        eprintln!("\n{}", error_style.apply_to("Error:"));
-        eprintln!("{}", e);
-
-        // Print the error chain if available
-        if e.chain().count() > 1 {
-            eprintln!("\n{}", Style::new().dim().apply_to("Caused by:"));
-            for cause in e.chain().skip(1) {
-                eprintln!("  {}", Style::new().dim().apply_to(format!("{}", cause)));
+    match cli.run().await {
+        Ok(0) => {}
+        Ok(exit_code) => process::exit(exit_code),
+        Err(e) => {
+            let error_style = Style::new().red().bold().for_stderr();
+            eprintln!("\n{}", error_style.apply_to("Error:"));
+            eprintln!("{}", e);
+
+            // Print the error chain if available
+            if e.chain().count() > 1 {
+                eprintln!(
+                    "\n{}",
+                    Style::new().dim().for_stderr().apply_to("Caused by:")
+                );
+                for cause in e.chain().skip(1) {
+                    eprintln!(
+                        "  {}",
+                        Style::new()
+                            .dim()
+                            .for_stderr()
+                            .apply_to(format!("{}", cause))
+                    );
+                }
             }
-        }
 
-        process::exit(1);
+            process::exit(1);
+        }
     }
 
     Ok(())
@@ -1,7 +1,8 @@
 use anyhow::Result;
-use console::Style;
+use console::{Style, Term};
+use std::sync::OnceLock;
 
-use crate::types::diff_result::{DiffOperation, DiffResult};
+use crate::types::diff_result::{DiffOperation, DiffResult, DiffSummary, TableDiff};
 
 /// Styles for different types of output
 pub struct OutputStyles {
@@ -38,6 +39,84 @@ impl Default for OutputStyles {
     }
 }
 
+/// Decide whether colored output is turned off for a stream
+///
+/// Colors are off with `--no-color`, when `NO_COLOR` is set to a non-empty value,
+/// or when the stream is not a terminal unless `CLICOLOR_FORCE` asks for them.
+///
+/// # Arguments
+/// * `no_color` - Whether `--no-color` was given
+/// * `no_color_env` - Value of the `NO_COLOR` environment variable
+/// * `force_env` - Value of the `CLICOLOR_FORCE` environment variable
+/// * `is_term` - Whether the stream is a terminal
+pub fn colors_disabled(
+    no_color: bool,
+    no_color_env: Option<&str>,
+    force_env: Option<&str>,
+    is_term: bool,
+) -> bool {
+    let forced = force_env.is_some_and(|value| !value.is_empty() && value != "0");
+    no_color || no_color_env.is_some_and(|value| !value.is_empty()) || (!is_term && !forced)
+}
+
+/// Turn off colored output for stdout and stderr where `colors_disabled` says so
+///
+/// Must run before anything is printed. Styles only ever get turned off here;
+/// otherwise `console`'s own terminal detection applies.
+///
+/// # Arguments
+/// * `no_color` - Whether `--no-color` was given
+pub fn configure_colors(no_color: bool) {
+    let no_color_env = std::env::var("NO_COLOR").ok();
+    let force_env = std::env::var("CLICOLOR_FORCE").ok();
+    let disabled = |term: Term| {
+        colors_disabled(
+            no_color,
+            no_color_env.as_deref(),
+            force_env.as_deref(),
+            term.is_term(),
+        )
+    };
+    if disabled(Term::stdout()) {
+        console::set_colors_enabled(false);
+    }
+    if disabled(Term::stderr()) {
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+/// How much informational output commands print
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Only errors, warnings, results, and summaries (`--quiet`)
+    Quiet,
+    /// Progress lines and per-table steps as well
+    #[default]
+    Normal,
+}
+
+/// Verbosity selected with the global `--quiet` flag
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Set how much informational output every command prints
+///
+/// Called once by main before a command runs; later calls are ignored.
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+/// Whether `--quiet` was given
+pub fn is_quiet() -> bool {
+    VERBOSITY.get().copied().unwrap_or_default() == Verbosity::Quiet
+}
+
+/// Print a progress message, unless `--quiet` was given
+pub fn print_progress(message: &str) {
+    if !is_quiet() {
+        println!("{}", format_progress(message));
+    }
+}
+
 /// Format a progress message
 pub fn format_progress(message: &str) -> String {
     let style = Style::new().cyan();
@@ -62,6 +141,24 @@ pub fn format_warning(message: &str) -> String {
     format!("{}", style.apply_to(message))
 }
 
+/// Format a byte count using binary units (e.g., "1.5 MiB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
 /// Format a create operation indicator
 pub fn format_create() -> String {
     let style = Style::new().green().bold();
@@ -90,20 +187,79 @@ pub fn format_table_name(name: &str, is_bold: bool) -> String {
     }
 }
 
+/// Format the plan summary line
+///
+/// With colors, each segment carries its operation's style and icon, and counts
+/// are right-aligned to the widest one so repeated runs line up. Without colors
+/// the plain "Plan: X to add, Y to change, Z to destroy." line is kept, so piped
+/// output stays stable for scripts.
+///
+/// # Arguments
+/// * `summary` - Counts to display
+/// * `colors` - Whether to emit styled output (see `console::colors_enabled`)
+pub fn format_plan_summary(summary: &DiffSummary, colors: bool) -> String {
+    if !colors {
+        return format!(
+            "Plan: {} to add, {} to change, {} to destroy.",
+            summary.to_add, summary.to_change, summary.to_destroy
+        );
+    }
+
+    let styles = OutputStyles::new();
+    let width = [summary.to_add, summary.to_change, summary.to_destroy]
+        .iter()
+        .map(|count| count.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let segment = |style: &Style, icon: &str, count: usize, label: &str| {
+        style
+            .clone()
+            .force_styling(true)
+            .apply_to(format!("{} {:>width$} {}", icon, count, label))
+            .to_string()
+    };
+
+    format!(
+        "{} {}, {}, {}.",
+        styles.bold.clone().force_styling(true).apply_to("Plan:"),
+        segment(&styles.create, "+", summary.to_add, "to add"),
+        segment(&styles.update, "~", summary.to_change, "to change"),
+        segment(&styles.delete, "-", summary.to_destroy, "to destroy")
+    )
+}
+
+/// Options for the human-readable diff display
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayOptions {
+    /// Show tables with no changes (only for plan command)
+    pub show_unchanged: bool,
+    /// Show the S3 locations affected by each change
+    pub show_locations: bool,
+    /// Show only the summary line and counts per database, without table diffs
+    pub summary_only: bool,
+}
+
 /// Display diff result in human-readable format
 ///
 /// # Arguments
 /// * `diff_result` - The diff result to display
-/// * `show_unchanged` - Whether to show tables with no changes (only for plan command)
-pub fn display_diff_result(diff_result: &DiffResult, show_unchanged: bool) -> Result<()> {
+/// * `options` - Which optional details to display
+pub fn display_diff_result(diff_result: &DiffResult, options: DisplayOptions) -> Result<()> {
+    if options.summary_only {
+        println!(
+            "{}",
+            format_summary_only(diff_result, console::colors_enabled())
+        );
+        return Ok(());
+    }
+
     let styles = OutputStyles::new();
 
     // Print summary with colors
-    let summary_msg = format!(
-        "Plan: {} to add, {} to change, {} to destroy.",
-        diff_result.summary.to_add, diff_result.summary.to_change, diff_result.summary.to_destroy
+    println!(
+        "{}",
+        format_plan_summary(&diff_result.summary, console::colors_enabled())
     );
-    println!("{}", styles.bold.apply_to(summary_msg));
 
     if diff_result.no_change {
         println!(
@@ -153,6 +309,7 @@ pub fn display_diff_result(diff_result: &DiffResult, show_unchanged: bool) -> Re
                     styles.create.apply_to(&qualified_name)
                 );
                 println!("  Will create table");
+                print_location_impact(table_diff, options);
                 println!();
             }
             DiffOperation::Update => {
@@ -162,6 +319,7 @@ pub fn display_diff_result(diff_result: &DiffResult, show_unchanged: bool) -> Re
                     styles.update.apply_to(&qualified_name)
                 );
                 println!("  Will update table");
+                print_location_impact(table_diff, options);
                 if let Some(ref text_diff) = table_diff.text_diff {
                     // Color the diff lines
                     for line in text_diff.lines() {
@@ -183,10 +341,11 @@ pub fn display_diff_result(diff_result: &DiffResult, show_unchanged: bool) -> Re
                     styles.delete.apply_to(&qualified_name)
                 );
                 println!("  Will destroy table");
+                print_location_impact(table_diff, options);
                 println!();
             }
             DiffOperation::NoChange => {
-                if show_unchanged {
+                if options.show_unchanged {
                     println!("  {}", styles.unchanged.apply_to(&qualified_name));
                     println!("  No changes");
                     println!();
@@ -198,9 +357,294 @@ pub fn display_diff_result(diff_result: &DiffResult, show_unchanged: bool) -> Re
     Ok(())
 }
 
+/// Format the summary line followed by the counts of each database
+///
+/// Used by `--summary` in place of the per-table diffs.
+///
+/// # Arguments
+/// * `diff_result` - The diff result to summarize
+/// * `colors` - Whether to emit styled output
+pub fn format_summary_only(diff_result: &DiffResult, colors: bool) -> String {
+    let mut lines = vec![format_plan_summary(&diff_result.summary, colors)];
+    if diff_result.no_change {
+        lines.push(String::new());
+        lines.push("No changes. Your infrastructure matches the configuration.".to_string());
+        return lines.join("\n");
+    }
+    lines.push(String::new());
+    lines.extend(format_database_breakdown(diff_result));
+    lines.join("\n")
+}
+
+/// Count the changes of each database, one line per database with changes
+///
+/// Databases are sorted by name; databases whose tables are all unchanged are
+/// left out.
+///
+/// # Arguments
+/// * `diff_result` - The diff result to summarize
+pub fn format_database_breakdown(diff_result: &DiffResult) -> Vec<String> {
+    let mut counts: std::collections::BTreeMap<&str, DiffSummary> =
+        std::collections::BTreeMap::new();
+    for table_diff in &diff_result.table_diffs {
+        let summary = counts.entry(table_diff.database_name.as_str()).or_default();
+        match table_diff.operation {
+            DiffOperation::Create => summary.to_add += 1,
+            DiffOperation::Update => summary.to_change += 1,
+            DiffOperation::Delete => summary.to_destroy += 1,
+            DiffOperation::NoChange => {}
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, summary)| summary.to_add + summary.to_change + summary.to_destroy > 0)
+        .map(|(database_name, summary)| {
+            format!(
+                "  {}: {} to add, {} to change, {} to destroy",
+                database_name, summary.to_add, summary.to_change, summary.to_destroy
+            )
+        })
+        .collect()
+}
+
+/// Render a diff result as GitHub-flavored Markdown, without any ANSI styling
+///
+/// The summary is a table; created, destroyed, and unchanged tables are bullet
+/// lists grouped by database, and each updated table gets a fenced `diff` block
+/// of its text diff.
+///
+/// # Arguments
+/// * `diff_result` - The diff result to render
+pub fn format_markdown(diff_result: &DiffResult) -> String {
+    let summary = &diff_result.summary;
+    let mut lines = vec![
+        "## athenadef plan".to_string(),
+        String::new(),
+        "| To add | To change | To destroy |".to_string(),
+        "|-------:|----------:|-----------:|".to_string(),
+        format!(
+            "| {} | {} | {} |",
+            summary.to_add, summary.to_change, summary.to_destroy
+        ),
+    ];
+
+    if diff_result.no_change {
+        lines.push(String::new());
+        lines.push("No changes. Your infrastructure matches the configuration.".to_string());
+    }
+
+    let tables_by_database = |operation: DiffOperation| {
+        let mut grouped: std::collections::BTreeMap<&str, Vec<&str>> =
+            std::collections::BTreeMap::new();
+        for table_diff in &diff_result.table_diffs {
+            if table_diff.operation == operation {
+                grouped
+                    .entry(table_diff.database_name.as_str())
+                    .or_default()
+                    .push(table_diff.table_name.as_str());
+            }
+        }
+        grouped
+    };
+    for (heading, operation) in [
+        ("To create", DiffOperation::Create),
+        ("To destroy", DiffOperation::Delete),
+        ("Unchanged", DiffOperation::NoChange),
+    ] {
+        let grouped = tables_by_database(operation);
+        if grouped.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("### {}", heading));
+        for (database_name, mut tables) in grouped {
+            tables.sort();
+            lines.push(String::new());
+            lines.push(format!("**{}**", database_name));
+            lines.push(String::new());
+            for table_name in tables {
+                lines.push(format!("- `{}.{}`", database_name, table_name));
+            }
+        }
+    }
+
+    let mut updates: Vec<&TableDiff> = diff_result
+        .table_diffs
+        .iter()
+        .filter(|table_diff| table_diff.operation == DiffOperation::Update)
+        .collect();
+    updates.sort_by_key(|table_diff| table_diff.qualified_name());
+    if !updates.is_empty() {
+        lines.push(String::new());
+        lines.push("### To update".to_string());
+        for table_diff in updates {
+            lines.push(String::new());
+            lines.push(format!("#### `{}`", table_diff.qualified_name()));
+            if let Some(ref text_diff) = table_diff.text_diff {
+                lines.push(String::new());
+                lines.push("```diff".to_string());
+                lines.extend(text_diff.lines().map(str::to_string));
+                lines.push("```".to_string());
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Display diff result as Markdown, e.g. for pull request comments
+///
+/// # Arguments
+/// * `diff_result` - The diff result to display
+pub fn display_markdown(diff_result: &DiffResult) {
+    println!("{}", format_markdown(diff_result));
+}
+
+/// Render a diff result as YAML
+///
+/// Multi-line strings such as `text_diff` are emitted as literal block scalars,
+/// so diffs stay readable line by line.
+///
+/// # Arguments
+/// * `diff_result` - The diff result to render
+pub fn format_yaml(diff_result: &DiffResult) -> Result<String> {
+    Ok(serde_yaml::to_string(diff_result)?)
+}
+
+/// Display diff result as YAML
+///
+/// # Arguments
+/// * `diff_result` - The diff result to display
+pub fn display_yaml(diff_result: &DiffResult) -> Result<()> {
+    print!("{}", format_yaml(diff_result)?);
+    Ok(())
+}
+
+/// Render a diff result as GitHub Actions workflow commands, one per changed table
+///
+/// Creates and updates become `::notice` annotations and deletes `::warning`
+/// annotations, each pointing at the table's SQL file. Tables without changes
+/// are left out.
+///
+/// # Arguments
+/// * `diff_result` - The diff result to render
+/// * `file_path` - Path of a table's SQL file relative to the repository root; for
+///   deletes, where the removed file was, so the annotation still names it
+pub fn format_github_annotations<F>(diff_result: &DiffResult, file_path: F) -> Vec<String>
+where
+    F: Fn(&TableDiff) -> String,
+{
+    diff_result
+        .table_diffs
+        .iter()
+        .filter_map(|table_diff| {
+            let (level, action) = match table_diff.operation {
+                DiffOperation::Create => ("notice", "create"),
+                DiffOperation::Update => ("notice", "update"),
+                DiffOperation::Delete => ("warning", "destroy"),
+                DiffOperation::NoChange => return None,
+            };
+            Some(format!(
+                "::{} file={}::{}",
+                level,
+                escape_workflow_property(&file_path(table_diff)),
+                escape_workflow_data(&format!(
+                    "Will {} {} {}",
+                    action,
+                    table_diff.object_type,
+                    table_diff.qualified_name()
+                ))
+            ))
+        })
+        .collect()
+}
+
+/// Display diff result as GitHub Actions annotations
+///
+/// # Arguments
+/// * `diff_result` - The diff result to display
+/// * `file_path` - See `format_github_annotations`
+pub fn display_github_annotations<F>(diff_result: &DiffResult, file_path: F)
+where
+    F: Fn(&TableDiff) -> String,
+{
+    for line in format_github_annotations(diff_result, file_path) {
+        println!("{}", line);
+    }
+}
+
+/// Escape the message of a workflow command
+fn escape_workflow_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value (such as `file=`) of a workflow command
+fn escape_workflow_property(value: &str) -> String {
+    escape_workflow_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Print the affected S3 location of a table diff when `show_locations` is set
+fn print_location_impact(table_diff: &TableDiff, options: DisplayOptions) {
+    if !options.show_locations {
+        return;
+    }
+    if let Some(ref impact) = table_diff.location_impact {
+        println!("  Location: {}", impact);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::table_definition::ObjectType;
+
+    #[test]
+    fn test_format_github_annotations() {
+        let table_diff = |table_name: &str, operation: DiffOperation| TableDiff {
+            database_name: "salesdb".to_string(),
+            table_name: table_name.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        let mut view = table_diff("daily_totals", DiffOperation::Create);
+        view.object_type = ObjectType::View;
+        let diff_result = DiffResult::from_table_diffs(vec![
+            table_diff("customers", DiffOperation::Update),
+            view,
+            table_diff("orders", DiffOperation::Delete),
+            table_diff("events", DiffOperation::NoChange),
+        ]);
+
+        let lines = format_github_annotations(&diff_result, |table_diff| {
+            format!(
+                "sql/{}/{}.sql",
+                table_diff.database_name, table_diff.table_name
+            )
+        });
+        assert_eq!(
+            lines,
+            vec![
+                "::notice file=sql/salesdb/customers.sql::Will update table salesdb.customers",
+                "::notice file=sql/salesdb/daily_totals.sql::Will create view salesdb.daily_totals",
+                "::warning file=sql/salesdb/orders.sql::Will destroy table salesdb.orders",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workflow_command_escaping() {
+        assert_eq!(escape_workflow_data("100%\nnext"), "100%25%0Anext");
+        assert_eq!(escape_workflow_property("C:\\a,b"), "C%3A\\a%2Cb");
+    }
 
     #[test]
     fn test_output_styles_new() {
@@ -233,6 +677,15 @@ mod tests {
         assert!(message.contains("Warning message"));
     }
 
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
     #[test]
     fn test_format_operations() {
         assert!(!format_create().is_empty());
@@ -240,6 +693,59 @@ mod tests {
         assert!(!format_delete().is_empty());
     }
 
+    #[test]
+    fn test_format_plan_summary_plain() {
+        let summary = DiffSummary {
+            to_add: 2,
+            to_change: 1,
+            to_destroy: 0,
+        };
+        assert_eq!(
+            format_plan_summary(&summary, false),
+            "Plan: 2 to add, 1 to change, 0 to destroy."
+        );
+    }
+
+    #[test]
+    fn test_format_plan_summary_colored_segments() {
+        let summary = DiffSummary {
+            to_add: 12,
+            to_change: 1,
+            to_destroy: 3,
+        };
+        let styles = OutputStyles::new();
+        let styled = |style: &Style, text: &str| {
+            style.clone().force_styling(true).apply_to(text).to_string()
+        };
+
+        let line = format_plan_summary(&summary, true);
+
+        assert!(line.contains(&styled(&styles.create, "+ 12 to add")));
+        assert!(line.contains(&styled(&styles.update, "~  1 to change")));
+        assert!(line.contains(&styled(&styles.delete, "-  3 to destroy")));
+        assert_eq!(
+            console::strip_ansi_codes(&line),
+            "Plan: + 12 to add, ~  1 to change, -  3 to destroy."
+        );
+    }
+
+    #[test]
+    fn test_colors_disabled() {
+        assert!(!colors_disabled(false, None, None, true));
+        assert!(colors_disabled(true, None, None, true));
+        assert!(colors_disabled(false, Some("1"), None, true));
+        // An empty NO_COLOR does not count as set
+        assert!(!colors_disabled(false, Some(""), None, true));
+
+        // Redirected output is plain unless CLICOLOR_FORCE asks for colors
+        assert!(colors_disabled(false, None, None, false));
+        assert!(colors_disabled(false, None, Some("0"), false));
+        assert!(!colors_disabled(false, None, Some("1"), false));
+        // --no-color and NO_COLOR win over CLICOLOR_FORCE
+        assert!(colors_disabled(true, None, Some("1"), false));
+        assert!(colors_disabled(false, Some("1"), Some("1"), true));
+    }
+
     #[test]
     fn test_format_table_name() {
         let name = format_table_name("test_table", false);
@@ -248,4 +754,116 @@ mod tests {
         let bold_name = format_table_name("test_table", true);
         assert!(bold_name.contains("test_table"));
     }
+    #[test]
+    fn test_format_markdown() {
+        let table_diff = |database: &str, table: &str, operation: DiffOperation| TableDiff {
+            database_name: database.to_string(),
+            table_name: table.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        let mut update = table_diff("marketingdb", "leads", DiffOperation::Update);
+        update.text_diff = Some(
+            "--- remote: marketingdb.leads\n+++ local:  marketingdb.leads\n-    score int,\n+    score double,"
+                .to_string(),
+        );
+        let diff_result = DiffResult::from_table_diffs(vec![
+            table_diff("salesdb", "orders", DiffOperation::Create),
+            table_diff("salesdb", "customers", DiffOperation::Create),
+            table_diff("marketingdb", "old_leads", DiffOperation::Delete),
+            update,
+        ]);
+
+        let markdown = format_markdown(&diff_result);
+        assert!(!markdown.contains('\u{1b}'));
+        assert!(markdown.contains("| 2 | 1 | 1 |"));
+        assert!(
+            markdown.contains(
+                "### To create\n\n**salesdb**\n\n- `salesdb.customers`\n- `salesdb.orders`"
+            )
+        );
+        assert!(
+            markdown.contains("### To destroy\n\n**marketingdb**\n\n- `marketingdb.old_leads`")
+        );
+        assert!(markdown.contains(
+            "#### `marketingdb.leads`\n\n```diff\n--- remote: marketingdb.leads\n+++ local:  marketingdb.leads\n-    score int,\n+    score double,\n```"
+        ));
+
+        let markdown = format_markdown(&DiffResult::from_table_diffs(vec![]));
+        assert!(markdown.contains("| 0 | 0 | 0 |"));
+        assert!(markdown.contains("No changes."));
+    }
+
+    #[test]
+    fn test_format_yaml_round_trip() {
+        let diff_result = DiffResult::from_table_diffs(vec![
+            TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "orders".to_string(),
+                operation: DiffOperation::Update,
+                text_diff: Some(
+                    "--- remote: salesdb.orders\n+++ local:  salesdb.orders\n-    total int\n+    total bigint"
+                        .to_string(),
+                ),
+                change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
+            },
+            TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "customers".to_string(),
+                operation: DiffOperation::Create,
+                text_diff: None,
+                change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
+            },
+        ]);
+
+        let yaml = format_yaml(&diff_result).unwrap();
+        assert!(!yaml.contains('\u{1b}'));
+        // The text diff is a literal block scalar, one diff line per YAML line
+        assert!(yaml.contains("text_diff: |-\n"));
+        assert!(yaml.contains("\n    -    total int\n    +    total bigint\n"));
+
+        let parsed: DiffResult = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, diff_result);
+    }
+
+    #[test]
+    fn test_format_summary_only_suppresses_table_diffs() {
+        let table_diff = |database: &str, table: &str, operation: DiffOperation| TableDiff {
+            database_name: database.to_string(),
+            table_name: table.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        let mut update = table_diff("salesdb", "orders", DiffOperation::Update);
+        update.text_diff = Some("-    total int\n+    total bigint".to_string());
+        let diff_result = DiffResult::from_table_diffs(vec![
+            table_diff("salesdb", "customers", DiffOperation::Create),
+            update,
+            table_diff("marketingdb", "old_leads", DiffOperation::Delete),
+            table_diff("marketingdb", "leads", DiffOperation::NoChange),
+            table_diff("archivedb", "events", DiffOperation::NoChange),
+        ]);
+
+        let output = format_summary_only(&diff_result, false);
+        assert_eq!(
+            output,
+            "Plan: 1 to add, 1 to change, 1 to destroy.\n\n  marketingdb: 0 to add, 0 to change, 1 to destroy\n  salesdb: 1 to add, 1 to change, 0 to destroy"
+        );
+        // No table names or diff lines
+        assert!(!output.contains("orders"));
+        assert!(!output.contains("total bigint"));
+
+        let output = format_summary_only(&DiffResult::from_table_diffs(vec![]), false);
+        assert!(output.ends_with("No changes. Your infrastructure matches the configuration."));
+    }
 }
@@ -0,0 +1,158 @@
+/// Progress counters for long-running batches of queries
+///
+/// On a terminal the counter is rewritten in place. Anywhere else (CI logs, files,
+/// pipes) it prints a plain line every tenth of the way, without cursor control
+/// codes. Progress goes to stderr so stdout stays clean for `--format json`.
+use console::Term;
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::output::is_quiet;
+
+/// Number of plain progress lines printed over a whole batch
+const PLAIN_UPDATES: usize = 10;
+
+/// Where progress is written
+enum ProgressOutput {
+    /// A terminal, where the counter line is updated in place
+    Terminal(Term),
+    /// Anything else, written as plain lines
+    Plain(Mutex<Box<dyn Write + Send>>),
+}
+
+/// Shared counter of completed items, safe to update from parallel tasks
+pub struct ProgressCounter {
+    action: String,
+    total: usize,
+    completed: AtomicUsize,
+    output: ProgressOutput,
+}
+
+impl ProgressCounter {
+    /// Create a counter on stderr, updating in place only when stderr is a terminal
+    ///
+    /// With `--quiet`, nothing is written.
+    /// # Arguments
+    /// * `action` - Verb shown before the count, e.g. "Fetched" for "Fetched 120/300 tables"
+    /// * `total` - Number of items in the batch
+    pub fn stderr(action: &str, total: usize) -> Self {
+        let term = Term::stderr();
+        let output = if is_quiet() {
+            ProgressOutput::Plain(Mutex::new(Box::new(std::io::sink())))
+        } else if term.is_term() {
+            ProgressOutput::Terminal(term)
+        } else {
+            ProgressOutput::Plain(Mutex::new(Box::new(std::io::stderr())))
+        };
+        Self::with_output(action, total, output)
+    }
+
+    /// Create a counter that writes plain lines to `writer`
+    pub fn plain(action: &str, total: usize, writer: Box<dyn Write + Send>) -> Self {
+        Self::with_output(action, total, ProgressOutput::Plain(Mutex::new(writer)))
+    }
+
+    fn with_output(action: &str, total: usize, output: ProgressOutput) -> Self {
+        Self {
+            action: action.to_string(),
+            total,
+            completed: AtomicUsize::new(0),
+            output,
+        }
+    }
+
+    /// Record one completed item and report progress
+    pub fn inc(&self) {
+        let completed = self.completed.fetch_add(1, Ordering::SeqCst) + 1;
+        let line = format!("{} {}/{} tables", self.action, completed, self.total);
+
+        // Progress is best effort; a closed stderr must not fail the command
+        match &self.output {
+            ProgressOutput::Terminal(term) => {
+                let _ = term.clear_line();
+                let _ = term.write_str(&line);
+                if completed >= self.total {
+                    let _ = term.write_line("");
+                }
+            }
+            ProgressOutput::Plain(writer) => {
+                let step = self.total.div_ceil(PLAIN_UPDATES).max(1);
+                if completed % step == 0 || completed >= self.total {
+                    let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+                    let _ = writeln!(writer, "{}", line);
+                }
+            }
+        }
+    }
+}
+
+/// Remove the previous line so it can be replaced, only on a terminal
+///
+/// Outside a terminal the previous line is kept, so logs show both the start and
+/// the result of each step instead of cursor control codes.
+pub fn clear_last_line(term: &Term) -> std::io::Result<()> {
+    if term.is_term() {
+        term.clear_last_lines(1)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Writer whose content can be read after it has been moved into a counter
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plain_progress_prints_periodic_lines_without_escape_codes() {
+        let buffer = SharedBuffer::default();
+        let counter = ProgressCounter::plain("Fetched", 25, Box::new(buffer.clone()));
+        for _ in 0..25 {
+            counter.inc();
+        }
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Fetched 3/25 tables",
+                "Fetched 6/25 tables",
+                "Fetched 9/25 tables",
+                "Fetched 12/25 tables",
+                "Fetched 15/25 tables",
+                "Fetched 18/25 tables",
+                "Fetched 21/25 tables",
+                "Fetched 24/25 tables",
+                "Fetched 25/25 tables",
+            ]
+        );
+        assert!(!output.contains('\x1b'));
+        assert!(!output.contains('\r'));
+    }
+
+    #[test]
+    fn test_plain_progress_small_batch_prints_every_item() {
+        let buffer = SharedBuffer::default();
+        let counter = ProgressCounter::plain("Fetched", 2, Box::new(buffer.clone()));
+        counter.inc();
+        counter.inc();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output, "Fetched 1/2 tables\nFetched 2/2 tables\n");
+    }
+}
@@ -0,0 +1,151 @@
+/// Local validation of partition projection settings
+///
+/// Athena only reports missing `projection.<column>.*` properties at query time, so
+/// these checks catch them from the DDL before the table is applied.
+use crate::differ::{extract_partitioned_by, extract_tblproperties, split_column_definitions};
+
+/// Check that every partition column of a projection-enabled table is configured
+///
+/// Tables without `'projection.enabled'='true'` are not checked.
+///
+/// # Arguments
+/// * `sql` - CREATE TABLE DDL
+///
+/// # Returns
+/// A description of each missing or invalid projection property
+pub fn check_projection(sql: &str) -> Vec<String> {
+    let Some(properties) = extract_tblproperties(sql) else {
+        return Vec::new();
+    };
+    let property = |key: &str| {
+        properties
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    };
+
+    if property("projection.enabled") != Some("true") {
+        return Vec::new();
+    }
+
+    let mut issues = Vec::new();
+    for column in partition_columns(sql) {
+        let type_key = format!("projection.{}.type", column);
+        let Some(projection_type) = property(&type_key) else {
+            issues.push(format!(
+                "partition column '{}' has no '{}' property",
+                column, type_key
+            ));
+            continue;
+        };
+
+        let required: &[&str] = match projection_type.to_lowercase().as_str() {
+            "integer" => &["range"],
+            "date" => &["range", "format"],
+            "enum" => &["values"],
+            "injected" => &[],
+            other => {
+                issues.push(format!(
+                    "partition column '{}' has unknown projection type '{}' (expected integer, date, enum, or injected)",
+                    column, other
+                ));
+                continue;
+            }
+        };
+
+        for setting in required {
+            let key = format!("projection.{}.{}", column, setting);
+            if property(&key).is_none() {
+                issues.push(format!(
+                    "partition column '{}' uses projection type '{}' but has no '{}' property",
+                    column, projection_type, key
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Partition column names from PARTITIONED BY, lowercased and without backticks
+fn partition_columns(sql: &str) -> Vec<String> {
+    let Some(partitioned_by) = extract_partitioned_by(sql) else {
+        return Vec::new();
+    };
+
+    split_column_definitions(&partitioned_by)
+        .iter()
+        .filter_map(|definition| definition.split_whitespace().next())
+        .map(|name| name.trim_matches('`').to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_projection_complete() {
+        let sql = r#"CREATE EXTERNAL TABLE events (
+  id bigint
+)
+PARTITIONED BY (`dt` string, region string, hour int)
+LOCATION 's3://bucket/events/'
+TBLPROPERTIES (
+  'projection.enabled'='true',
+  'projection.dt.type'='date',
+  'projection.dt.range'='2024-01-01,NOW',
+  'projection.dt.format'='yyyy-MM-dd',
+  'projection.region.type'='enum',
+  'projection.region.values'='us,eu',
+  'projection.hour.type'='integer',
+  'projection.hour.range'='0,23'
+)"#;
+
+        assert!(check_projection(sql).is_empty());
+    }
+
+    #[test]
+    fn test_check_projection_incomplete() {
+        let sql = r#"CREATE EXTERNAL TABLE events (
+  id bigint
+)
+PARTITIONED BY (dt string, region string, tenant string)
+LOCATION 's3://bucket/events/'
+TBLPROPERTIES (
+  'projection.enabled'='TRUE',
+  'projection.dt.type'='date',
+  'projection.dt.range'='2024-01-01,NOW',
+  'projection.tenant.type'='enum'
+)"#;
+
+        let issues = check_projection(sql);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].contains("'projection.dt.format'"));
+        assert!(issues[1].contains("'projection.region.type'"));
+        assert!(issues[2].contains("'projection.tenant.values'"));
+    }
+
+    #[test]
+    fn test_check_projection_disabled_or_absent() {
+        let sql = r#"CREATE EXTERNAL TABLE events (id bigint)
+PARTITIONED BY (dt string)
+TBLPROPERTIES ('projection.enabled'='false')"#;
+        assert!(check_projection(sql).is_empty());
+
+        let sql = "CREATE EXTERNAL TABLE events (id bigint)\nPARTITIONED BY (dt string)";
+        assert!(check_projection(sql).is_empty());
+    }
+
+    #[test]
+    fn test_check_projection_unknown_type() {
+        let sql = r#"CREATE EXTERNAL TABLE events (id bigint)
+PARTITIONED BY (dt string)
+TBLPROPERTIES ('projection.enabled'='true', 'projection.dt.type'='timestamp')"#;
+
+        let issues = check_projection(sql);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("unknown projection type 'timestamp'"));
+    }
+}
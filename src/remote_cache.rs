@@ -0,0 +1,281 @@
+/// On-disk cache of remote table definitions
+///
+/// With `cache_ttl_seconds` set, each `SHOW CREATE TABLE` result is stored under
+/// `.athenadef-cache/<region>/<database>.<table>.json` next to the config file,
+/// with the time it was fetched and a hash of the DDL. A later plan or apply
+/// within the TTL uses the stored DDL instead of querying Athena again. Stale,
+/// corrupt, or missing entries fall through to a live query, and apply removes
+/// the entries of every table it changes.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+use crate::apply_state::ddl_hash;
+use crate::types::config::Config;
+
+/// Cache directory, relative to the config file's directory
+pub const CACHE_DIR: &str = ".athenadef-cache";
+
+/// Subdirectory for the region taken from the environment
+const DEFAULT_REGION_DIR: &str = "default";
+
+/// A cached SHOW CREATE TABLE result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    /// "database.table" the DDL belongs to
+    table: String,
+    /// Seconds since the Unix epoch when the DDL was fetched
+    fetched_at: u64,
+    /// SHA-256 of `ddl`, checked on read
+    ddl_hash: String,
+    ddl: String,
+}
+
+/// Cached remote table definitions with a time to live
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteCache {
+    dir: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+}
+
+impl RemoteCache {
+    /// Create a cache stored in `dir`
+    ///
+    /// # Arguments
+    /// * `dir` - Cache directory
+    /// * `ttl` - How long an entry is used after it was fetched
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self {
+            dir,
+            ttl,
+            refresh: false,
+        }
+    }
+
+    /// Create the cache configured by `cache_ttl_seconds`, or None when it is unset
+    ///
+    /// # Arguments
+    /// * `config` - Loaded athenadef configuration
+    /// * `base_path` - Directory of the config file
+    /// * `refresh` - Whether `--refresh` was given
+    pub fn from_config(config: &Config, base_path: &Path, refresh: bool) -> Option<Self> {
+        let ttl = Duration::from_secs(config.cache_ttl_seconds?);
+        Some(Self::new(base_path.join(CACHE_DIR), ttl).with_refresh(refresh))
+    }
+
+    /// Ignore stored entries, while still storing freshly fetched ones
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Get the cached DDL of a table if it is still fresh
+    ///
+    /// # Arguments
+    /// * `region` - Region the table was read from, None for the default region
+    /// * `database` - Database name
+    /// * `table` - Table name
+    /// * `now` - Current time
+    pub fn get(
+        &self,
+        region: Option<&str>,
+        database: &str,
+        table: &str,
+        now: SystemTime,
+    ) -> Option<String> {
+        if self.refresh {
+            return None;
+        }
+
+        let path = self.entry_path(region, database, table);
+        let content = std::fs::read_to_string(&path).ok()?;
+        let entry: CacheEntry = match serde_json::from_str(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!("Ignoring unreadable cache entry {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        let qualified_name = format!("{}.{}", database, table);
+        if entry.table != qualified_name || entry.ddl_hash != ddl_hash(&entry.ddl) {
+            debug!("Ignoring corrupt cache entry {}", path.display());
+            return None;
+        }
+        if !is_fresh(entry.fetched_at, now, self.ttl) {
+            debug!("Cache entry for {} expired", qualified_name);
+            return None;
+        }
+        Some(entry.ddl)
+    }
+
+    /// Store the DDL of a table fetched at `now`
+    ///
+    /// # Arguments
+    /// * `region` - Region the table was read from, None for the default region
+    /// * `database` - Database name
+    /// * `table` - Table name
+    /// * `ddl` - SHOW CREATE TABLE result
+    /// * `now` - When the DDL was fetched
+    pub fn put(
+        &self,
+        region: Option<&str>,
+        database: &str,
+        table: &str,
+        ddl: &str,
+        now: SystemTime,
+    ) -> Result<()> {
+        let path = self.entry_path(region, database, table);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory {}", parent.display())
+            })?;
+        }
+
+        let entry = CacheEntry {
+            table: format!("{}.{}", database, table),
+            fetched_at: unix_seconds(now),
+            ddl_hash: ddl_hash(ddl),
+            ddl: ddl.to_string(),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&entry)?)
+            .with_context(|| format!("Failed to write cache entry {}", path.display()))
+    }
+
+    /// Remove the cached DDL of a table, e.g. after changing it
+    ///
+    /// # Arguments
+    /// * `region` - Region the table lives in, None for the default region
+    /// * `database` - Database name
+    /// * `table` - Table name
+    pub fn invalidate(&self, region: Option<&str>, database: &str, table: &str) -> Result<()> {
+        let path = self.entry_path(region, database, table);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove cache entry {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn entry_path(&self, region: Option<&str>, database: &str, table: &str) -> PathBuf {
+        self.dir
+            .join(region.unwrap_or(DEFAULT_REGION_DIR))
+            .join(format!("{}.{}.json", database, table))
+    }
+}
+
+/// Whether an entry fetched at `fetched_at` is younger than `ttl`
+///
+/// Entries from the future (after a clock change) count as stale.
+///
+/// # Arguments
+/// * `fetched_at` - Seconds since the Unix epoch when the entry was fetched
+/// * `now` - Current time
+/// * `ttl` - Time to live of entries
+pub fn is_fresh(fetched_at: u64, now: SystemTime, ttl: Duration) -> bool {
+    unix_seconds(now)
+        .checked_sub(fetched_at)
+        .is_some_and(|age| age < ttl.as_secs())
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DDL: &str = "CREATE EXTERNAL TABLE `salesdb`.`orders`(\n  `id` bigint)\nLOCATION\n  's3://bucket/orders'";
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let ttl = Duration::from_secs(300);
+        assert!(is_fresh(1_000, at(1_000), ttl));
+        assert!(is_fresh(1_000, at(1_299), ttl));
+        assert!(!is_fresh(1_000, at(1_300), ttl));
+        // Entries from the future are stale
+        assert!(!is_fresh(2_000, at(1_000), ttl));
+        // A zero TTL never hits
+        assert!(!is_fresh(1_000, at(1_000), Duration::ZERO));
+    }
+
+    #[test]
+    fn test_cache_hit_miss_and_expiry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = RemoteCache::new(temp_dir.path().to_path_buf(), Duration::from_secs(60));
+
+        assert_eq!(cache.get(None, "salesdb", "orders", at(1_000)), None);
+
+        cache
+            .put(None, "salesdb", "orders", DDL, at(1_000))
+            .unwrap();
+        assert_eq!(
+            cache.get(None, "salesdb", "orders", at(1_030)).as_deref(),
+            Some(DDL)
+        );
+        // Other tables and regions do not share the entry
+        assert_eq!(cache.get(None, "salesdb", "customers", at(1_030)), None);
+        assert_eq!(
+            cache.get(Some("eu-west-1"), "salesdb", "orders", at(1_030)),
+            None
+        );
+        // Expired
+        assert_eq!(cache.get(None, "salesdb", "orders", at(1_060)), None);
+
+        // --refresh skips stored entries
+        let refreshing = cache.clone().with_refresh(true);
+        assert_eq!(refreshing.get(None, "salesdb", "orders", at(1_030)), None);
+
+        cache.invalidate(None, "salesdb", "orders").unwrap();
+        assert_eq!(cache.get(None, "salesdb", "orders", at(1_030)), None);
+        // Invalidating a missing entry is fine
+        cache.invalidate(None, "salesdb", "orders").unwrap();
+    }
+
+    #[test]
+    fn test_cache_ignores_corrupt_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = RemoteCache::new(temp_dir.path().to_path_buf(), Duration::from_secs(60));
+        cache
+            .put(None, "salesdb", "orders", DDL, at(1_000))
+            .unwrap();
+
+        let path = cache.entry_path(None, "salesdb", "orders");
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("bigint", "string");
+        std::fs::write(&path, tampered).unwrap();
+        assert_eq!(cache.get(None, "salesdb", "orders", at(1_010)), None);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(cache.get(None, "salesdb", "orders", at(1_010)), None);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let base_path = Path::new("/project");
+        assert_eq!(
+            RemoteCache::from_config(&Config::default(), base_path, false),
+            None
+        );
+
+        let config = Config {
+            cache_ttl_seconds: Some(600),
+            ..Config::default()
+        };
+        let cache = RemoteCache::from_config(&config, base_path, true).unwrap();
+        assert_eq!(cache.dir, base_path.join(CACHE_DIR));
+        assert_eq!(cache.ttl, Duration::from_secs(600));
+        assert!(cache.refresh);
+    }
+}
@@ -0,0 +1,329 @@
+/// SARIF output for plan results
+///
+/// Destructive or risky changes are reported as SARIF results so they can be
+/// surfaced by code scanning tools such as GitHub code scanning.
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::sql_source::SqlSource;
+use crate::types::diff_result::{ColumnChangeType, DiffOperation, DiffResult, TableDiff};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule reported when a table would be deleted
+pub const RULE_DESTROY: &str = "athenadef/destroy";
+/// Rule reported when a column would be removed
+pub const RULE_COLUMN_REMOVED: &str = "athenadef/column-removed";
+/// Rule reported when a column type would change
+pub const RULE_TYPE_CHANGE: &str = "athenadef/type-change";
+/// Rule reported when a table location would change
+pub const RULE_LOCATION_CHANGE: &str = "athenadef/location-change";
+
+/// Rule id, SARIF level, and description for each reported change kind
+const RULES: [(&str, &str, &str); 4] = [
+    (RULE_DESTROY, "error", "Table will be deleted"),
+    (RULE_COLUMN_REMOVED, "error", "Column will be removed"),
+    (RULE_TYPE_CHANGE, "warning", "Column type will change"),
+    (
+        RULE_LOCATION_CHANGE,
+        "warning",
+        "Table location will change",
+    ),
+];
+
+/// Build a SARIF 2.1.0 document from a diff result
+///
+/// # Arguments
+/// * `diff_result` - Diff result to report
+/// * `base_path` - Root directory containing SQL files, used for result locations
+/// * `sql_source` - Where SQL files are read from, and their layout
+///
+/// # Returns
+/// SARIF log as a JSON value
+pub fn build_sarif(diff_result: &DiffResult, base_path: &Path, sql_source: &SqlSource) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, level, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = diff_result
+        .table_diffs
+        .iter()
+        .flat_map(|table_diff| table_results(table_diff, base_path, sql_source))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "athenadef",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/riseshia/athenadef",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// Build SARIF results for a single table diff
+fn table_results(table_diff: &TableDiff, base_path: &Path, sql_source: &SqlSource) -> Vec<Value> {
+    let qualified_name = table_diff.qualified_name();
+    let mut results = Vec::new();
+
+    match table_diff.operation {
+        DiffOperation::Delete => {
+            results.push((
+                RULE_DESTROY,
+                format!("Table {} will be deleted", qualified_name),
+            ));
+        }
+        DiffOperation::Update => {
+            if let Some(ref details) = table_diff.change_details {
+                for change in &details.column_changes {
+                    match change.change_type {
+                        ColumnChangeType::Removed => results.push((
+                            RULE_COLUMN_REMOVED,
+                            format!(
+                                "Column {} will be removed from {}",
+                                change.column_name, qualified_name
+                            ),
+                        )),
+                        ColumnChangeType::TypeChanged => results.push((
+                            RULE_TYPE_CHANGE,
+                            format!(
+                                "Column {} in {} will change type from {} to {}",
+                                change.column_name,
+                                qualified_name,
+                                change.old_type.as_deref().unwrap_or("(none)"),
+                                change.new_type.as_deref().unwrap_or("(none)")
+                            ),
+                        )),
+                        ColumnChangeType::Added | ColumnChangeType::ConstraintChanged => {}
+                    }
+                }
+                for change in &details.property_changes {
+                    if change.property_name == "location" {
+                        results.push((
+                            RULE_LOCATION_CHANGE,
+                            format!(
+                                "Location of {} will change from {} to {}",
+                                qualified_name,
+                                change.old_value.as_deref().unwrap_or("(none)"),
+                                change.new_value.as_deref().unwrap_or("(none)")
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        DiffOperation::Create | DiffOperation::NoChange => {}
+    }
+
+    let uri = sql_file_uri(base_path, sql_source, table_diff);
+    results
+        .into_iter()
+        .map(|(rule_id, message)| {
+            json!({
+                "ruleId": rule_id,
+                "level": rule_level(rule_id),
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                    }
+                }],
+            })
+        })
+        .collect()
+}
+
+/// Look up the SARIF level for a rule
+fn rule_level(rule_id: &str) -> &'static str {
+    RULES
+        .iter()
+        .find(|(id, _, _)| *id == rule_id)
+        .map(|(_, level, _)| *level)
+        .unwrap_or("warning")
+}
+
+/// Path of the table's SQL file as a forward-slash URI
+///
+/// The table's existing file, or for a deleted table the path its file would
+/// have in the configured layout.
+pub(crate) fn sql_file_uri(
+    base_path: &Path,
+    sql_source: &SqlSource,
+    table_diff: &TableDiff,
+) -> String {
+    let path = sql_source
+        .table_file_path(base_path, &table_diff.database_name, &table_diff.table_name)
+        .unwrap_or_else(|_| {
+            base_path
+                .join(&table_diff.database_name)
+                .join(format!("{}.sql", table_diff.table_name))
+        });
+    let uri = path.to_string_lossy().replace('\\', "/");
+    uri.strip_prefix("./").unwrap_or(&uri).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_utils::SqlLayout;
+    use crate::types::diff_result::{ChangeDetails, ColumnChange, DiffSummary, PropertyChange};
+    use crate::types::table_definition::ObjectType;
+
+    fn diff_result(table_diffs: Vec<TableDiff>) -> DiffResult {
+        DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(&table_diffs),
+            table_diffs,
+        }
+    }
+
+    #[test]
+    fn test_build_sarif_structure() {
+        let sarif = build_sarif(&diff_result(vec![]), Path::new("."), &SqlSource::default());
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["$schema"], SARIF_SCHEMA);
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "athenadef");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 4);
+        assert_eq!(run["results"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_build_sarif_delete() {
+        let sarif = build_sarif(
+            &diff_result(vec![TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "old_orders".to_string(),
+                operation: DiffOperation::Delete,
+                text_diff: None,
+                change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
+            }]),
+            Path::new("."),
+            &SqlSource::default(),
+        );
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], RULE_DESTROY);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Table salesdb.old_orders will be deleted"
+        );
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "salesdb/old_orders.sql"
+        );
+    }
+
+    #[test]
+    fn test_build_sarif_type_change() {
+        let sarif = build_sarif(
+            &diff_result(vec![
+                TableDiff {
+                    database_name: "salesdb".to_string(),
+                    table_name: "customers".to_string(),
+                    operation: DiffOperation::Update,
+                    text_diff: Some("diff".to_string()),
+                    change_details: Some(ChangeDetails {
+                        column_changes: vec![
+                            ColumnChange {
+                                change_type: ColumnChangeType::TypeChanged,
+                                column_name: "id".to_string(),
+                                old_type: Some("bigint".to_string()),
+                                new_type: Some("int".to_string()),
+                                old_constraints: None,
+                                new_constraints: None,
+                            },
+                            ColumnChange {
+                                change_type: ColumnChangeType::Added,
+                                column_name: "email".to_string(),
+                                old_type: None,
+                                new_type: Some("string".to_string()),
+                                old_constraints: None,
+                                new_constraints: None,
+                            },
+                        ],
+                        property_changes: vec![PropertyChange {
+                            property_name: "format".to_string(),
+                            old_value: Some("PARQUET".to_string()),
+                            new_value: Some("ORC".to_string()),
+                        }],
+                        bucketing_change: None,
+                        unmodeled_changes: false,
+                    }),
+                    location_impact: None,
+                    object_type: ObjectType::Table,
+                },
+                TableDiff {
+                    database_name: "salesdb".to_string(),
+                    table_name: "new_table".to_string(),
+                    operation: DiffOperation::Create,
+                    text_diff: None,
+                    change_details: None,
+                    location_impact: None,
+                    object_type: ObjectType::Table,
+                },
+            ]),
+            Path::new("schemas"),
+            &SqlSource::default(),
+        );
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], RULE_TYPE_CHANGE);
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Column id in salesdb.customers will change type from bigint to int"
+        );
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "schemas/salesdb/customers.sql"
+        );
+    }
+
+    #[test]
+    fn test_build_sarif_uses_configured_file_extension() {
+        let sql_source = SqlSource::default().with_layout(SqlLayout {
+            file_extension: "ddl".to_string(),
+            ..SqlLayout::default()
+        });
+        let sarif = build_sarif(
+            &diff_result(vec![TableDiff {
+                database_name: "salesdb".to_string(),
+                table_name: "old_orders".to_string(),
+                operation: DiffOperation::Delete,
+                text_diff: None,
+                change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
+            }]),
+            Path::new("schemas"),
+            &sql_source,
+        );
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "schemas/salesdb/old_orders.ddl"
+        );
+    }
+}
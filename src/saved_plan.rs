@@ -0,0 +1,245 @@
+/// A plan written by `plan --out` and applied later by `apply <PLAN_FILE>`
+///
+/// Besides the diff, the file records a hash of the local SQL for every table
+/// the plan creates or updates. Apply refuses a plan whose format version it does
+/// not understand, or whose SQL files changed since the plan was made, so only
+/// what was reviewed is applied.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::apply_state::ddl_hash;
+use crate::sql_source::SqlSource;
+use crate::types::diff_result::{DiffOperation, DiffResult};
+
+/// Version of the plan file format, bumped on incompatible changes
+pub const PLAN_FORMAT_VERSION: u32 = 1;
+
+/// A saved plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlan {
+    pub format_version: u32,
+    /// athenadef version that wrote the plan, for error messages
+    pub athenadef_version: String,
+    /// Git ref the local SQL was read from, if any
+    pub git_ref: Option<String>,
+    /// SHA-256 of the local SQL of each created or updated table, keyed by "database.table"
+    pub local_sql_hashes: BTreeMap<String, String>,
+    pub diff_result: DiffResult,
+}
+
+/// Just the version, read before the rest so old or new formats fail clearly
+#[derive(Deserialize)]
+struct PlanHeader {
+    format_version: Option<u32>,
+    athenadef_version: Option<String>,
+}
+
+impl SavedPlan {
+    /// Build a saved plan, hashing the local SQL of created and updated tables
+    ///
+    /// # Arguments
+    /// * `diff_result` - Diff computed by plan
+    /// * `base_path` - Root directory containing SQL files
+    /// * `sql_source` - Where the local SQL was read from
+    /// * `git_ref` - Git ref given to plan, if any
+    pub fn new(
+        diff_result: DiffResult,
+        base_path: &Path,
+        sql_source: &SqlSource,
+        git_ref: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self {
+            format_version: PLAN_FORMAT_VERSION,
+            athenadef_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_ref: git_ref.map(str::to_string),
+            local_sql_hashes: hash_local_sql(&diff_result, base_path, sql_source)?,
+            diff_result,
+        })
+    }
+
+    /// Write the plan as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write plan file {}", path.display()))
+    }
+
+    /// Read a plan, failing if it was written in another format version
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plan file {}", path.display()))?;
+
+        let header: PlanHeader = serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse plan file {}\n\nPlan files are written by 'athenadef plan --out <PATH>'.",
+                path.display()
+            )
+        })?;
+        if header.format_version != Some(PLAN_FORMAT_VERSION) {
+            return Err(anyhow::anyhow!(
+                "Plan file {} has format version {} (written by athenadef {}), but this athenadef (version {}) reads format version {}.\n\nRun 'athenadef plan --out {}' again with this version.",
+                path.display(),
+                header
+                    .format_version
+                    .map_or_else(|| "unknown".to_string(), |version| version.to_string()),
+                header.athenadef_version.as_deref().unwrap_or("unknown"),
+                env!("CARGO_PKG_VERSION"),
+                PLAN_FORMAT_VERSION,
+                path.display()
+            ));
+        }
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse plan file {}", path.display()))
+    }
+
+    /// Check that the local SQL still matches what the plan was made from
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory containing SQL files
+    /// * `sql_source` - Where to read the local SQL from
+    pub fn verify_local_sql(&self, base_path: &Path, sql_source: &SqlSource) -> Result<()> {
+        let current = hash_local_sql(&self.diff_result, base_path, sql_source)?;
+        let changed: Vec<&String> = self
+            .local_sql_hashes
+            .iter()
+            .filter(|(table, hash)| current.get(*table) != Some(*hash))
+            .map(|(table, _)| table)
+            .collect();
+
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow::anyhow!(
+            "The plan is stale: the local SQL of {} changed after the plan was made.\n\nRun 'athenadef plan --out <PATH>' again and review the new plan.",
+            changed
+                .iter()
+                .map(|table| table.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+/// Hash the local SQL of every table a diff creates or updates
+fn hash_local_sql(
+    diff_result: &DiffResult,
+    base_path: &Path,
+    sql_source: &SqlSource,
+) -> Result<BTreeMap<String, String>> {
+    diff_result
+        .table_diffs
+        .iter()
+        .filter(|table_diff| {
+            matches!(
+                table_diff.operation,
+                DiffOperation::Create | DiffOperation::Update
+            )
+        })
+        .map(|table_diff| {
+            let sql = sql_source.read_table_sql(
+                base_path,
+                &table_diff.database_name,
+                &table_diff.table_name,
+            )?;
+            Ok((table_diff.qualified_name(), ddl_hash(&sql)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::diff_result::TableDiff;
+    use crate::types::table_definition::ObjectType;
+
+    fn plan_dir() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("salesdb")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("salesdb/customers.sql"),
+            "CREATE EXTERNAL TABLE customers (id bigint)",
+        )
+        .unwrap();
+        temp_dir
+    }
+
+    fn diff_result() -> DiffResult {
+        let table_diff = |table_name: &str, operation| TableDiff {
+            database_name: "salesdb".to_string(),
+            table_name: table_name.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        };
+        DiffResult::from_table_diffs(vec![
+            table_diff("customers", DiffOperation::Create),
+            table_diff("orders", DiffOperation::Delete),
+        ])
+    }
+
+    #[test]
+    fn test_saved_plan_round_trip() {
+        let temp_dir = plan_dir();
+        let plan =
+            SavedPlan::new(diff_result(), temp_dir.path(), &SqlSource::default(), None).unwrap();
+        assert_eq!(plan.format_version, PLAN_FORMAT_VERSION);
+        assert_eq!(
+            plan.local_sql_hashes.keys().collect::<Vec<_>>(),
+            vec!["salesdb.customers"]
+        );
+
+        let path = temp_dir.path().join("plan.json");
+        plan.save(&path).unwrap();
+        let loaded = SavedPlan::load(&path).unwrap();
+        assert_eq!(loaded, plan);
+        loaded
+            .verify_local_sql(temp_dir.path(), &SqlSource::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_saved_plan_rejects_other_format_version() {
+        let temp_dir = plan_dir();
+        let mut plan =
+            SavedPlan::new(diff_result(), temp_dir.path(), &SqlSource::default(), None).unwrap();
+        plan.format_version = PLAN_FORMAT_VERSION + 1;
+        let path = temp_dir.path().join("plan.json");
+        plan.save(&path).unwrap();
+
+        let err = SavedPlan::load(&path).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&format!("has format version {}", PLAN_FORMAT_VERSION + 1))
+        );
+
+        // A plain diff result (e.g. from plan --format json) has no version at all
+        std::fs::write(&path, serde_json::to_string(&diff_result()).unwrap()).unwrap();
+        let err = SavedPlan::load(&path).unwrap_err();
+        assert!(err.to_string().contains("has format version unknown"));
+    }
+
+    #[test]
+    fn test_saved_plan_detects_changed_local_sql() {
+        let temp_dir = plan_dir();
+        let plan =
+            SavedPlan::new(diff_result(), temp_dir.path(), &SqlSource::default(), None).unwrap();
+
+        std::fs::write(
+            temp_dir.path().join("salesdb/customers.sql"),
+            "CREATE EXTERNAL TABLE customers (id bigint, name string)",
+        )
+        .unwrap();
+
+        let err = plan
+            .verify_local_sql(temp_dir.path(), &SqlSource::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("The plan is stale"));
+        assert!(err.to_string().contains("salesdb.customers"));
+    }
+}
@@ -0,0 +1,550 @@
+/// Canonical layout for local CREATE TABLE files
+///
+/// The formatter only changes whitespace and keyword casing: clause keywords are
+/// upper-cased and start their own line, the column list, `PARTITIONED BY`,
+/// `WITH SERDEPROPERTIES`, and `TBLPROPERTIES` put one entry per line indented by
+/// four spaces, and the file ends with a single newline. Identifiers, data types,
+/// and quoted text are kept as written. Leading `--` comment lines, such as the
+/// header `export` writes, are kept above the statement. Statements it cannot lay
+/// out safely, such as ones with other SQL comments or views, are rejected
+/// instead of rewritten.
+use anyhow::{Result, anyhow};
+
+/// Indentation of list entries and ROW FORMAT DELIMITED sub-clauses
+const INDENT: &str = "    ";
+
+/// Words upper-cased outside parentheses
+const KEYWORDS: &[&str] = &[
+    "AS",
+    "BUCKETS",
+    "BY",
+    "CLUSTERED",
+    "COLLECTION",
+    "COMMENT",
+    "CREATE",
+    "DEFINED",
+    "DELIMITED",
+    "ESCAPED",
+    "EXISTS",
+    "EXTERNAL",
+    "FIELDS",
+    "FORMAT",
+    "IF",
+    "INPUTFORMAT",
+    "INTO",
+    "ITEMS",
+    "KEYS",
+    "LINES",
+    "LOCATION",
+    "MAP",
+    "NOT",
+    "NULL",
+    "OUTPUTFORMAT",
+    "PARTITIONED",
+    "ROW",
+    "SERDE",
+    "SERDEPROPERTIES",
+    "STORED",
+    "TABLE",
+    "TBLPROPERTIES",
+    "TERMINATED",
+    "WITH",
+];
+
+/// Words upper-cased inside a column definition, after the column name
+const COLUMN_KEYWORDS: &[&str] = &["COMMENT", "NOT", "NULL"];
+
+/// A lexical token of a DDL statement
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifier, keyword, type name, or number
+    Word(String),
+    /// Quoted text ('...', "...", or `...`), including the quotes
+    Quoted(String),
+    /// Single punctuation character
+    Punct(char),
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Word(text) | Token::Quoted(text) => text.clone(),
+            Token::Punct(ch) => ch.to_string(),
+        }
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Token::Word(text) if text.eq_ignore_ascii_case(word))
+    }
+
+    fn is_punct(&self, ch: char) -> bool {
+        *self == Token::Punct(ch)
+    }
+}
+
+/// Format a CREATE TABLE statement
+///
+/// Formatting is idempotent: formatting the output again returns it unchanged.
+///
+/// # Arguments
+/// * `sql` - Contents of a local SQL file
+///
+/// # Returns
+/// The formatted statement ending with a single newline, or an error if the
+/// statement is not one the formatter can lay out without changing its meaning
+pub fn format_sql(sql: &str) -> Result<String> {
+    let (comments, sql) = split_leading_comments(sql);
+    let mut tokens = tokenize(sql)?;
+
+    let has_semicolon = tokens.last().is_some_and(|token| token.is_punct(';'));
+    if has_semicolon {
+        tokens.pop();
+    }
+    if tokens.iter().any(|token| token.is_punct(';')) {
+        return Err(anyhow!("multiple statements are not supported"));
+    }
+
+    let is_create_table = tokens.first().is_some_and(|token| token.is_word("create"))
+        && tokens.get(1).is_some_and(|token| {
+            token.is_word("table")
+                || (token.is_word("external")
+                    && tokens.get(2).is_some_and(|token| token.is_word("table")))
+        });
+    if !is_create_table {
+        return Err(anyhow!("only CREATE TABLE statements are supported"));
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    let mut prev: Option<Token> = None;
+    let mut clause = String::new();
+    let mut seen_columns = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+
+        if token.is_punct('(') {
+            let end = matching_paren(&tokens, i)?;
+            let items = split_items(&tokens[i + 1..end]);
+            // The first list is the columns; only a few clauses take long lists
+            let multiline = !items.is_empty()
+                && (!seen_columns
+                    || matches!(
+                        clause.as_str(),
+                        "PARTITIONED" | "SERDEPROPERTIES" | "TBLPROPERTIES"
+                    ));
+            seen_columns = true;
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            if multiline {
+                line.push('(');
+                lines.push(std::mem::take(&mut line));
+                let count = items.len();
+                for (index, item) in items.iter().enumerate() {
+                    let separator = if index + 1 < count { "," } else { "" };
+                    lines.push(format!("{}{}{}", INDENT, format_item(item), separator));
+                }
+                line.push(')');
+            } else {
+                let items: Vec<String> = items.iter().map(|item| format_item(item)).collect();
+                line.push_str(&format!("({})", items.join(", ")));
+            }
+            prev = Some(Token::Punct(')'));
+            i = end + 1;
+            continue;
+        }
+        if token.is_punct(')') {
+            return Err(anyhow!("unbalanced parentheses"));
+        }
+        if token.is_word("select") {
+            return Err(anyhow!("CREATE TABLE AS SELECT is not supported"));
+        }
+
+        let next = tokens.get(i + 1);
+        let keyword = match token {
+            Token::Word(word) => is_keyword(word, prev.as_ref(), next),
+            _ => false,
+        };
+        if let Some(indent) = clause_start(token, next, seen_columns).filter(|_| keyword) {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(indent);
+            if let Token::Word(word) = token {
+                clause = word.to_ascii_uppercase();
+            }
+        }
+        // WITH SERDEPROPERTIES starts its clause with WITH, but the list belongs to it
+        if token.is_word("serdeproperties") {
+            clause = "SERDEPROPERTIES".to_string();
+        }
+
+        let text = match token {
+            Token::Word(word) if keyword => word.to_ascii_uppercase(),
+            // The file format in STORED AS PARQUET reads like a keyword too
+            Token::Word(word)
+                if prev.as_ref().is_some_and(|prev| prev.is_word("as")) && clause == "STORED" =>
+            {
+                word.to_ascii_uppercase()
+            }
+            _ => token.text(),
+        };
+        push_token(&mut line, prev.as_ref(), token, &text);
+        prev = Some(token.clone());
+        i += 1;
+    }
+
+    if has_semicolon {
+        line.push(';');
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    let header: String = comments
+        .iter()
+        .map(|comment| format!("{}\n", comment))
+        .collect();
+    Ok(format!("{}{}\n", header, lines.join("\n")))
+}
+
+/// Split off the `--` comment lines before a statement
+///
+/// Blank lines between the comments are skipped.
+///
+/// # Returns
+/// The trimmed comment lines and the rest of the SQL
+pub fn split_leading_comments(sql: &str) -> (Vec<&str>, &str) {
+    let mut comments = Vec::new();
+    let mut rest = sql;
+    while !rest.is_empty() {
+        let (line, remainder) = rest.split_once('\n').unwrap_or((rest, ""));
+        let trimmed = line.trim();
+        if trimmed.starts_with("--") {
+            comments.push(trimmed);
+        } else if !trimmed.is_empty() {
+            break;
+        }
+        rest = remainder;
+    }
+    (comments, rest)
+}
+
+/// Split SQL into tokens, rejecting comments and unterminated quotes
+fn tokenize(sql: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        if ch.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if (ch == '-' && chars.get(i + 1) == Some(&'-'))
+            || (ch == '/' && chars.get(i + 1) == Some(&'*'))
+        {
+            return Err(anyhow!("SQL comments are not supported"));
+        }
+
+        if matches!(ch, '\'' | '"' | '`') {
+            let start = i;
+            i += 1;
+            loop {
+                let Some(&current) = chars.get(i) else {
+                    return Err(anyhow!("unterminated {} quote", ch));
+                };
+                if current == '\\' && ch != '`' {
+                    i += 2;
+                    continue;
+                }
+                i += 1;
+                if current == ch {
+                    // A doubled quote is an escaped quote, not the end
+                    if chars.get(i) == Some(&ch) {
+                        i += 1;
+                        continue;
+                    }
+                    break;
+                }
+            }
+            tokens.push(Token::Quoted(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if is_punct(ch) {
+            tokens.push(Token::Punct(ch));
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len()
+            && !chars[i].is_whitespace()
+            && !is_punct(chars[i])
+            && !matches!(chars[i], '\'' | '"' | '`')
+        {
+            i += 1;
+        }
+        tokens.push(Token::Word(chars[start..i].iter().collect()));
+    }
+
+    Ok(tokens)
+}
+
+fn is_punct(ch: char) -> bool {
+    matches!(ch, '(' | ')' | '<' | '>' | ',' | '.' | ':' | '=' | ';')
+}
+
+/// Index of the `)` closing the `(` at `open`
+fn matching_paren(tokens: &[Token], open: usize) -> Result<usize> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth -= 1;
+            if depth == 0 {
+                return Ok(index);
+            }
+        }
+    }
+    Err(anyhow!("unbalanced parentheses"))
+}
+
+/// Split the contents of a parenthesized list on its top-level commas
+fn split_items(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '<') => depth += 1,
+            Token::Punct(')' | '>') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                items.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        items.push(&tokens[start..]);
+    }
+    items
+}
+
+/// Format one list entry: a column definition or a `'key' = 'value'` pair
+fn format_item(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    let mut prev: Option<&Token> = None;
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        let token_text = match token {
+            Token::Word(word)
+                if index > 0
+                    && depth == 0
+                    && COLUMN_KEYWORDS.contains(&word.to_ascii_uppercase().as_str()) =>
+            {
+                word.to_ascii_uppercase()
+            }
+            _ => token.text(),
+        };
+        match token {
+            Token::Punct('(' | '<') => depth += 1,
+            Token::Punct(')' | '>') => depth -= 1,
+            _ => {}
+        }
+        // Nested types are written compactly, as in struct<a:string,b:int>
+        let space = match (prev, token) {
+            (None, _) => false,
+            (Some(Token::Punct('=')), _) | (_, Token::Punct('=')) => depth == 0,
+            (Some(prev), token) => needs_space(prev, token),
+        };
+        if space {
+            text.push(' ');
+        }
+        text.push_str(&token_text);
+        prev = Some(token);
+    }
+    text
+}
+
+/// Append a token to a line outside parentheses
+fn push_token(line: &mut String, prev: Option<&Token>, token: &Token, text: &str) {
+    let at_line_start = line.trim().is_empty();
+    if !at_line_start && prev.is_some_and(|prev| needs_space(prev, token)) {
+        line.push(' ');
+    }
+    line.push_str(text);
+}
+
+/// Whether a space separates two adjacent tokens
+fn needs_space(prev: &Token, next: &Token) -> bool {
+    // Types hug their brackets, as in decimal(10,2) and array<string>
+    !matches!(
+        (prev, next),
+        (Token::Punct('(' | '<' | '.' | ':' | ','), _)
+            | (
+                _,
+                Token::Punct('(' | ')' | '<' | '>' | '.' | ':' | ',' | ';')
+            )
+    )
+}
+
+/// Whether a word outside parentheses is a keyword rather than a name
+fn is_keyword(word: &str, prev: Option<&Token>, next: Option<&Token>) -> bool {
+    if !KEYWORDS.contains(&word.to_ascii_uppercase().as_str()) {
+        return false;
+    }
+    match prev {
+        // The table name follows TABLE (or IF NOT EXISTS) and may be qualified
+        Some(prev) if prev.is_punct('.') => false,
+        Some(prev) if prev.is_word("exists") => false,
+        Some(prev) if prev.is_word("table") => {
+            word.eq_ignore_ascii_case("if") && next.is_some_and(|next| next.is_word("not"))
+        }
+        _ => true,
+    }
+}
+
+/// Indentation for a word that starts a new line, or None if it continues one
+fn clause_start(token: &Token, next: Option<&Token>, seen_columns: bool) -> Option<&'static str> {
+    let Token::Word(word) = token else {
+        return None;
+    };
+    let next_is = |expected: &str| next.is_some_and(|next| next.is_word(expected));
+    match word.to_ascii_uppercase().as_str() {
+        "PARTITIONED" | "CLUSTERED" | "STORED" | "OUTPUTFORMAT" | "LOCATION" | "TBLPROPERTIES" => {
+            Some("")
+        }
+        "ROW" if next_is("format") => Some(""),
+        "WITH" if next_is("serdeproperties") => Some(""),
+        // A table comment follows the columns; COMMENT inside a column stays put
+        "COMMENT" if seen_columns => Some(""),
+        "FIELDS" | "COLLECTION" | "LINES" if next_is("terminated") => Some(INDENT),
+        "MAP" if next_is("keys") => Some(INDENT),
+        "ESCAPED" if next_is("by") => Some(INDENT),
+        "NULL" if next_is("defined") => Some(INDENT),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sql_basic_table() {
+        let sql = "create external table Orders(\n  order_id   bigint,\n\ttotal decimal(10, 2) comment 'Total, with tax'\n)\nstored as parquet\nlocation 's3://bucket/orders/'\ntblproperties ('parquet.compression'='SNAPPY');\n\n\n";
+        let expected = "CREATE EXTERNAL TABLE Orders (\n    order_id bigint,\n    total decimal(10,2) COMMENT 'Total, with tax'\n)\nSTORED AS PARQUET\nLOCATION 's3://bucket/orders/'\nTBLPROPERTIES (\n    'parquet.compression' = 'SNAPPY'\n);\n";
+        assert_eq!(format_sql(sql).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sql_partitioned_table() {
+        let sql = "CREATE EXTERNAL TABLE IF NOT EXISTS `salesdb`.`events` (`id` string, `payload` string) PARTITIONED BY (dt string, region string) ROW FORMAT SERDE 'org.openx.data.jsonserde.JsonSerDe' WITH SERDEPROPERTIES ('ignore.malformed.json'='true') LOCATION 's3://bucket/events/'";
+        let expected = "\
+CREATE EXTERNAL TABLE IF NOT EXISTS `salesdb`.`events` (
+    `id` string,
+    `payload` string
+)
+PARTITIONED BY (
+    dt string,
+    region string
+)
+ROW FORMAT SERDE 'org.openx.data.jsonserde.JsonSerDe'
+WITH SERDEPROPERTIES (
+    'ignore.malformed.json' = 'true'
+)
+LOCATION 's3://bucket/events/'
+";
+        assert_eq!(format_sql(sql).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sql_nested_struct_columns() {
+        let sql = "CREATE EXTERNAL TABLE users (\n  id bigint not null,\n  address struct< street : string, geo: struct<lat:double, lng:double> >,\n  tags array<string>,\n  attrs map<string, array<struct<k:string,v:int>>>\n)\nSTORED AS ORC";
+        let expected = "\
+CREATE EXTERNAL TABLE users (
+    id bigint NOT NULL,
+    address struct<street:string,geo:struct<lat:double,lng:double>>,
+    tags array<string>,
+    attrs map<string,array<struct<k:string,v:int>>>
+)
+STORED AS ORC
+";
+        assert_eq!(format_sql(sql).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sql_row_format_delimited_and_buckets() {
+        let sql = "CREATE EXTERNAL TABLE logs (line string) comment 'raw logs' clustered by (line) into 4 buckets row format delimited fields terminated by '\\t' lines terminated by '\\n' stored as textfile location 's3://bucket/logs/'";
+        let expected = "\
+CREATE EXTERNAL TABLE logs (
+    line string
+)
+COMMENT 'raw logs'
+CLUSTERED BY (line) INTO 4 BUCKETS
+ROW FORMAT DELIMITED
+    FIELDS TERMINATED BY '\\t'
+    LINES TERMINATED BY '\\n'
+STORED AS TEXTFILE
+LOCATION 's3://bucket/logs/'
+";
+        assert_eq!(format_sql(sql).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sql_keeps_names_that_look_like_keywords() {
+        let sql = "CREATE TABLE location (comment string, map map<string,string>)";
+        let expected =
+            "CREATE TABLE location (\n    comment string,\n    map map<string,string>\n)\n";
+        assert_eq!(format_sql(sql).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sql_is_idempotent() {
+        let sql = "create external table t (a struct<b:int>, c string comment 'it''s') partitioned by (dt string) stored as inputformat 'in.Format' outputformat 'out.Format' tblproperties ('a'='1', 'b'='2')";
+        let formatted = format_sql(sql).unwrap();
+        assert!(formatted.contains("STORED AS INPUTFORMAT 'in.Format'\nOUTPUTFORMAT 'out.Format'"));
+        assert!(formatted.contains("COMMENT 'it''s'"));
+        assert_eq!(format_sql(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_sql_keeps_leading_comments() {
+        let sql = "\n-- Managed by athenadef.  \n\n  -- Source: salesdb.orders\ncreate table orders (id int)";
+        let expected = "-- Managed by athenadef.\n-- Source: salesdb.orders\nCREATE TABLE orders (\n    id int\n)\n";
+        let formatted = format_sql(sql).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_sql(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_sql_rejects_unsupported_input() {
+        let cases = [
+            ("CREATE TABLE t (a int) -- orders", "comments"),
+            ("CREATE TABLE t (a int /* id */)", "comments"),
+            (
+                "CREATE TABLE t (a int); DROP TABLE t",
+                "multiple statements",
+            ),
+            ("CREATE OR REPLACE VIEW v AS SELECT 1", "only CREATE TABLE"),
+            ("CREATE TABLE t AS SELECT 1", "AS SELECT"),
+            ("CREATE TABLE t (a int", "unbalanced"),
+            (
+                "CREATE TABLE t (a int) LOCATION 's3://bucket",
+                "unterminated",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let err = format_sql(sql).unwrap_err();
+            assert!(err.to_string().contains(expected), "{:?}: {}", sql, err);
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::file_utils::{FileUtils, SqlFile, SqlFileScan, SqlLayout};
+
+/// Where local SQL definitions are read from
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SqlSource {
+    /// Git revision to read files at without checking it out; the files on disk
+    /// under the base path when None
+    git_ref: Option<String>,
+    /// File extension and default database of the files
+    layout: SqlLayout,
+}
+
+impl SqlSource {
+    /// Create a source for the given git ref, or the working tree if none is given
+    ///
+    /// Fails if `base_path` is not inside a git repository or the ref does not
+    /// resolve to a commit.
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory containing SQL files
+    /// * `git_ref` - Optional git revision (branch, tag, or commit)
+    pub fn resolve(base_path: &Path, git_ref: Option<&str>) -> Result<Self> {
+        let Some(git_ref) = git_ref else {
+            return Ok(SqlSource::default());
+        };
+
+        run_git(base_path, &["rev-parse", "--git-dir"]).map_err(|_| {
+            anyhow!(
+                "--ref requires a git repository, but {} is not inside one",
+                base_path.display()
+            )
+        })?;
+
+        run_git(
+            base_path,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", git_ref),
+            ],
+        )
+        .map_err(|_| anyhow!("Unknown git ref '{}'", git_ref))?;
+
+        Ok(SqlSource {
+            git_ref: Some(git_ref.to_string()),
+            layout: SqlLayout::default(),
+        })
+    }
+
+    /// Read files in the given layout instead of `database/table.sql`
+    pub fn with_layout(mut self, layout: SqlLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Git revision files are read at, if any
+    pub fn git_ref(&self) -> Option<&str> {
+        self.git_ref.as_deref()
+    }
+
+    /// File extension and default database of the files
+    pub fn layout(&self) -> &SqlLayout {
+        &self.layout
+    }
+
+    /// Find all SQL files in the given directory
+    ///
+    /// Behaves like `FileUtils::find_sql_files`, reading from the git ref when set.
+    /// Tables defined by more than one file are not warned about: plan and apply
+    /// reject them with `FileUtils::check_remote_name_collisions` instead.
+    ///
+    /// # Returns
+    /// A HashMap where keys are "database.table" and values are SqlFile instances
+    pub fn find_sql_files(&self, base_path: &Path) -> Result<HashMap<String, SqlFile>> {
+        let scan = self.scan_sql_files(base_path)?;
+        scan.print_parse_warnings();
+        Ok(scan.sql_files)
+    }
+
+    /// Find all SQL files, collecting files that cannot be parsed instead of warning
+    pub fn scan_sql_files(&self, base_path: &Path) -> Result<SqlFileScan> {
+        match self.git_ref() {
+            None => FileUtils::scan_sql_files_with_layout(base_path, &self.layout),
+            Some(git_ref) => scan_sql_files_at_ref(base_path, git_ref, &self.layout),
+        }
+    }
+
+    /// Read the SQL definition for a single table
+    ///
+    /// # Arguments
+    /// * `base_path` - Root directory containing SQL files
+    /// * `database_name` - Database name
+    /// * `table_name` - Table name
+    pub fn read_table_sql(
+        &self,
+        base_path: &Path,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String> {
+        let file_path = self.table_file_path(base_path, database_name, table_name)?;
+        let Some(git_ref) = self.git_ref() else {
+            return FileUtils::read_table_file(&file_path, &self.layout);
+        };
+
+        // The first candidate that exists at the ref, like in the working tree
+        let found = self
+            .layout
+            .table_file_candidates(database_name, table_name)
+            .into_iter()
+            .find_map(|candidate| {
+                read_blob(base_path, git_ref, &candidate)
+                    .ok()
+                    .map(|content| (candidate, content))
+            });
+        let Some((relative_path, content)) = found else {
+            return Err(anyhow!(
+                "Failed to read SQL file {} at ref '{}'",
+                file_path.display(),
+                git_ref
+            ));
+        };
+
+        FileUtils::render_table_sql(Path::new(&relative_path), &content, &self.layout)
+    }
+
+    /// Path of the file defining a table in the working tree
+    ///
+    /// Behaves like `FileUtils::find_table_file` with this source's layout.
+    pub fn table_file_path(
+        &self,
+        base_path: &Path,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<PathBuf> {
+        FileUtils::find_table_file(base_path, database_name, table_name, &self.layout)
+    }
+}
+
+/// List `database/table.sql` files under `base_path` at `git_ref` and read their contents
+///
+/// Files use the layout's extension, and with a default database, `table.sql`
+/// files directly under `base_path` are read too.
+fn scan_sql_files_at_ref(
+    base_path: &Path,
+    git_ref: &str,
+    layout: &SqlLayout,
+) -> Result<SqlFileScan> {
+    // Paths are printed relative to base_path since git runs from there
+    let listing = run_git(
+        base_path,
+        &["ls-tree", "-r", "--name-only", git_ref, "--", "."],
+    )
+    .with_context(|| format!("Failed to list files at ref '{}'", git_ref))?;
+
+    let mut scan = SqlFileScan::default();
+
+    for relative_path in listing.lines() {
+        // Only database/table.sql (and table.sql with a default database),
+        // matching the working tree layout
+        let path = Path::new(relative_path);
+        let depth = relative_path.matches('/').count();
+        let at_root = depth == 0 && layout.default_database.is_some();
+        if !(depth == 1 || at_root) || !layout.has_file_extension(path) {
+            continue;
+        }
+
+        let names = match layout.default_database.as_deref() {
+            Some(database_name) if at_root => FileUtils::extract_table_from_path(path)
+                .map(|table_name| (database_name.to_string(), table_name)),
+            _ => FileUtils::extract_database_table_from_path(path),
+        };
+        let (database_name, table_name) = match names {
+            Ok(names) => names,
+            Err(e) => {
+                scan.issues
+                    .push(format!("{} at ref '{}': {}", relative_path, git_ref, e));
+                continue;
+            }
+        };
+
+        let content = read_blob(base_path, git_ref, relative_path)?;
+        let content = FileUtils::render_table_sql(path, &content, layout)
+            .with_context(|| format!("Failed to read {} at ref '{}'", relative_path, git_ref))?;
+        let sql_file = SqlFile::new(
+            database_name,
+            table_name,
+            base_path.join(relative_path),
+            content,
+        );
+        scan.insert(sql_file, !at_root);
+    }
+
+    Ok(scan)
+}
+
+/// Read a file at `git_ref`, with `relative_path` relative to `base_path`
+fn read_blob(base_path: &Path, git_ref: &str, relative_path: &str) -> Result<String> {
+    run_git(
+        base_path,
+        &[
+            "cat-file",
+            "blob",
+            &format!("{}:./{}", git_ref, relative_path),
+        ],
+    )
+}
+
+/// Run a git command in `dir` and return its stdout
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git. Is it installed and on PATH?")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8(output.stdout).context("git produced non UTF-8 output")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=athenadef",
+                "-c",
+                "user.email=athenadef@example.com",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Repository with SQL files under `schemas/`:
+    /// - v1: salesdb/customers.sql (v1)
+    /// - v2: salesdb/customers.sql (v2), salesdb/orders.sql
+    fn create_repo_with_two_commits() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        let db_dir = repo.join("schemas/salesdb");
+        fs::create_dir_all(&db_dir).unwrap();
+
+        git(repo, &["init", "--quiet"]);
+        fs::write(db_dir.join("customers.sql"), "CREATE TABLE customers_v1").unwrap();
+        git(repo, &["add", "-A"]);
+        git(repo, &["commit", "--quiet", "-m", "v1"]);
+        git(repo, &["tag", "v1"]);
+
+        fs::write(db_dir.join("customers.sql"), "CREATE TABLE customers_v2").unwrap();
+        fs::write(db_dir.join("orders.sql"), "CREATE TABLE orders").unwrap();
+        git(repo, &["add", "-A"]);
+        git(repo, &["commit", "--quiet", "-m", "v2"]);
+
+        // Uncommitted change that must not be visible through a ref
+        fs::write(db_dir.join("customers.sql"), "CREATE TABLE customers_wip").unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_resolve_without_ref_uses_working_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = SqlSource::resolve(temp_dir.path(), None).unwrap();
+        assert_eq!(source, SqlSource::default());
+    }
+
+    #[test]
+    fn test_find_sql_files_at_ref() {
+        let temp_dir = create_repo_with_two_commits();
+        let base_path = temp_dir.path().join("schemas");
+
+        let v1 = SqlSource::resolve(&base_path, Some("v1")).unwrap();
+        let files = v1.find_sql_files(&base_path).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files["salesdb.customers"].content,
+            "CREATE TABLE customers_v1"
+        );
+        assert_eq!(
+            files["salesdb.customers"].file_path,
+            base_path.join("salesdb/customers.sql")
+        );
+
+        let head = SqlSource::resolve(&base_path, Some("HEAD")).unwrap();
+        let files = head.find_sql_files(&base_path).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files["salesdb.customers"].content,
+            "CREATE TABLE customers_v2"
+        );
+        assert_eq!(files["salesdb.orders"].content, "CREATE TABLE orders");
+
+        let working_tree = SqlSource::default().find_sql_files(&base_path).unwrap();
+        assert_eq!(
+            working_tree["salesdb.customers"].content,
+            "CREATE TABLE customers_wip"
+        );
+    }
+
+    #[test]
+    fn test_find_sql_files_does_not_warn_on_name_collisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("salesdb")).unwrap();
+        fs::write(base_path.join("salesdb/customers.sql"), "CREATE TABLE a").unwrap();
+        fs::write(base_path.join("salesdb/Customers.sql"), "CREATE TABLE b").unwrap();
+
+        let sql_files = SqlSource::default().find_sql_files(base_path).unwrap();
+        if sql_files.len() < 2 {
+            // Case-insensitive file system; the collision cannot be reproduced
+            return;
+        }
+
+        // The collision is reported once, as the plan error
+        let base_path_display = base_path.display().to_string();
+        assert!(
+            !crate::warnings::recorded_warnings()
+                .iter()
+                .any(|warning| warning.contains(&base_path_display))
+        );
+        assert!(FileUtils::check_remote_name_collisions(&sql_files).is_err());
+    }
+
+    #[test]
+    fn test_read_table_sql_at_ref() {
+        let temp_dir = create_repo_with_two_commits();
+        let base_path = temp_dir.path().join("schemas");
+
+        let v1 = SqlSource::resolve(&base_path, Some("v1")).unwrap();
+        assert_eq!(
+            v1.read_table_sql(&base_path, "salesdb", "customers")
+                .unwrap(),
+            "CREATE TABLE customers_v1"
+        );
+        assert!(v1.read_table_sql(&base_path, "salesdb", "orders").is_err());
+    }
+
+    #[test]
+    fn test_default_database_at_ref() {
+        let temp_dir = create_repo_with_two_commits();
+        let base_path = temp_dir.path().join("schemas");
+        fs::write(base_path.join("regions.sql"), "CREATE TABLE regions").unwrap();
+        git(temp_dir.path(), &["add", "schemas/regions.sql"]);
+        git(temp_dir.path(), &["commit", "--quiet", "-m", "v3"]);
+
+        let head = SqlSource::resolve(&base_path, Some("HEAD")).unwrap();
+        assert_eq!(head.find_sql_files(&base_path).unwrap().len(), 2);
+
+        let head = head.with_layout(SqlLayout {
+            default_database: Some("salesdb".to_string()),
+            ..SqlLayout::default()
+        });
+        let files = head.find_sql_files(&base_path).unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files["salesdb.regions"].content, "CREATE TABLE regions");
+        assert_eq!(
+            head.read_table_sql(&base_path, "salesdb", "regions")
+                .unwrap(),
+            "CREATE TABLE regions"
+        );
+        assert_eq!(
+            head.read_table_sql(&base_path, "salesdb", "orders")
+                .unwrap(),
+            "CREATE TABLE orders"
+        );
+    }
+
+    #[test]
+    fn test_resolve_unknown_ref() {
+        let temp_dir = create_repo_with_two_commits();
+        let err = SqlSource::resolve(temp_dir.path(), Some("no-such-ref"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown git ref 'no-such-ref'"));
+    }
+
+    #[test]
+    fn test_resolve_outside_git_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = SqlSource::resolve(temp_dir.path(), Some("HEAD"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("requires a git repository"));
+    }
+}
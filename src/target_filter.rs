@@ -4,17 +4,65 @@
 /// - `salesdb.customers` - specific table
 /// - `salesdb.*` - all tables in salesdb
 /// - `*.customers` - all customers tables across databases
+///
+/// A segment prefixed with `re:` is a regex matched against the whole name:
+/// - `salesdb.re:.*_staging` - tables in salesdb ending in `_staging`
+use anyhow::{Context, Result};
 use regex::Regex;
+use std::path::Path;
+
+use crate::types::config::TableNameCase;
 
 /// Type alias for a target filter function
 pub type TargetFilter = Box<dyn Fn(&str, &str) -> bool>;
 
-/// Resolve effective targets from command line arguments and config
+/// Environment variable holding default target patterns
+pub const TARGETS_ENV_VAR: &str = "ATHENADEF_TARGETS";
+
+/// Prefix marking a pattern segment as a regex
+const REGEX_PREFIX: &str = "re:";
+
+/// Merge `--target` patterns with those read from a `--target-file`
+///
+/// # Arguments
+/// * `cli_targets` - Target patterns from command line (--target option)
+/// * `target_file` - File with one pattern per line, if given
+///
+/// # Returns
+/// The command line patterns followed by the file's patterns
+pub fn merge_target_file(
+    cli_targets: &[String],
+    target_file: Option<&Path>,
+) -> Result<Vec<String>> {
+    let mut targets = cli_targets.to_vec();
+    if let Some(path) = target_file {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read target file {}", path.display()))?;
+        targets.extend(parse_target_file(&content));
+    }
+    Ok(targets)
+}
+
+/// Parse newline-delimited target patterns
+///
+/// Lines are trimmed; blank lines and lines starting with `#` are skipped.
+pub fn parse_target_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Resolve effective targets from command line arguments, environment, and config
 ///
 /// Priority:
 /// 1. If `cli_targets` is not empty, use it
-/// 2. If `config_databases` is provided, convert to `{database}.*` patterns
-/// 3. Otherwise, return empty vector (no filtering)
+/// 2. If `ATHENADEF_TARGETS` is set and non-empty, use its space- or comma-separated patterns
+///    (commas inside a `re:` pattern are kept)
+/// 3. If `config_databases` is provided, convert to `{database}.*` patterns
+/// 4. Otherwise, return empty vector (no filtering)
 ///
 /// # Arguments
 /// * `cli_targets` - Target patterns from command line (--target option)
@@ -26,8 +74,29 @@ pub fn resolve_targets(
     cli_targets: &[String],
     config_databases: Option<&Vec<String>>,
 ) -> Vec<String> {
+    let env_targets = std::env::var(TARGETS_ENV_VAR).ok();
+    resolve_targets_with_env(cli_targets, env_targets.as_deref(), config_databases)
+}
+
+/// Resolve effective targets with an explicit environment value
+///
+/// See `resolve_targets` for the priority order.
+///
+/// # Arguments
+/// * `cli_targets` - Target patterns from command line (--target option)
+/// * `env_targets` - Value of `ATHENADEF_TARGETS`, if set
+/// * `config_databases` - Database names from config file
+pub fn resolve_targets_with_env(
+    cli_targets: &[String],
+    env_targets: Option<&str>,
+    config_databases: Option<&Vec<String>>,
+) -> Vec<String> {
+    let env_targets = split_env_targets(env_targets.unwrap_or_default());
+
     if !cli_targets.is_empty() {
         cli_targets.to_vec()
+    } else if !env_targets.is_empty() {
+        env_targets
     } else if let Some(databases) = config_databases {
         // Convert database names to target patterns (database.*)
         databases.iter().map(|db| format!("{}.*", db)).collect()
@@ -36,38 +105,184 @@ pub fn resolve_targets(
     }
 }
 
+/// Split an `ATHENADEF_TARGETS` value into target patterns
+///
+/// Patterns are separated by whitespace or commas. Once a pattern contains a
+/// `re:` segment, commas belong to the regex (e.g. `re:t.{2,3}`) and only
+/// whitespace ends the pattern.
+fn split_env_targets(value: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for token in value.split_whitespace() {
+        let mut rest = token;
+        loop {
+            match rest.split_once(',') {
+                Some((head, tail)) if !head.contains(REGEX_PREFIX) => {
+                    if !head.is_empty() {
+                        targets.push(head.to_string());
+                    }
+                    rest = tail;
+                }
+                _ => {
+                    if !rest.is_empty() {
+                        targets.push(rest.to_string());
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    targets
+}
+
 /// Parse target filters from command line arguments
 ///
+/// Patterns match case-insensitively, as Athena does for identifiers.
+///
+/// # Arguments
+/// * `targets` - Vector of target patterns in format `<database>.<table>`
+///
+/// # Returns
+/// A closure that returns true if the database.table should be included, or an
+/// error if a `re:` segment is not a valid regex
+pub fn parse_target_filter(targets: &[String]) -> Result<TargetFilter> {
+    parse_target_filter_with_case(targets, TableNameCase::Insensitive)
+}
+
+/// Parse target filters, comparing names as configured by `table_name_case`
+///
+/// # Arguments
+/// * `targets` - Vector of target patterns in format `<database>.<table>`
+/// * `name_case` - Whether matching ignores case
+///
+/// # Returns
+/// A closure that returns true if the database.table should be included, or an
+/// error if a `re:` segment is not a valid regex
+pub fn parse_target_filter_with_case(
+    targets: &[String],
+    name_case: TableNameCase,
+) -> Result<TargetFilter> {
+    parse_target_filter_with_excludes(targets, &[], name_case)
+}
+
+/// Parse target filters with patterns of tables to leave out (`--exclude`)
+///
+/// A table is included when it matches any target (or no targets are given)
+/// and matches no exclude pattern.
+///
 /// # Arguments
 /// * `targets` - Vector of target patterns in format `<database>.<table>`
+/// * `excludes` - Patterns of tables to leave out, in the same format
+/// * `name_case` - Whether matching ignores case
 ///
 /// # Returns
-/// A closure that returns true if the database.table should be included
-pub fn parse_target_filter(targets: &[String]) -> TargetFilter {
-    if targets.is_empty() {
+/// A closure that returns true if the database.table should be included, or an
+/// error if a `re:` segment is not a valid regex
+pub fn parse_target_filter_with_excludes(
+    targets: &[String],
+    excludes: &[String],
+    name_case: TableNameCase,
+) -> Result<TargetFilter> {
+    if targets.is_empty() && excludes.is_empty() {
         // No filter specified, include all tables
-        return Box::new(|_, _| true);
+        return Ok(Box::new(|_, _| true));
     }
 
-    // Parse each target pattern into database and table patterns
-    let patterns: Vec<(String, String)> = targets
-        .iter()
-        .filter_map(|target| {
-            let parts: Vec<&str> = target.split('.').collect();
-            if parts.len() == 2 {
-                Some((parts[0].to_string(), parts[1].to_string()))
-            } else {
-                None
+    let includes = parse_patterns(targets, name_case)?;
+    let excludes = parse_patterns(excludes, name_case)?;
+    let include_all = targets.is_empty();
+
+    // Return a closure that checks if database.table matches an include and no exclude
+    Ok(Box::new(move |database: &str, table: &str| {
+        let database = name_case.normalize(database);
+        let table = name_case.normalize(table);
+        let matches = |(db_pattern, table_pattern): &(SegmentPattern, SegmentPattern)| {
+            db_pattern.matches(&database) && table_pattern.matches(&table)
+        };
+        (include_all || includes.iter().any(matches)) && !excludes.iter().any(matches)
+    }))
+}
+
+/// Pattern for the database or table part of a target
+enum SegmentPattern {
+    /// Name with optional `*` wildcards
+    Glob(String),
+    /// `re:` pattern, matched against the whole name
+    Regex(Regex),
+}
+
+impl SegmentPattern {
+    fn parse(segment: &str, name_case: TableNameCase) -> Result<Self> {
+        match segment.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => {
+                let case_flag = match name_case {
+                    TableNameCase::Insensitive => "(?i)",
+                    TableNameCase::Sensitive => "",
+                };
+                Regex::new(&format!("^{}(?:{})$", case_flag, regex))
+                    .map(SegmentPattern::Regex)
+                    .with_context(|| format!("Invalid regex in target pattern '{}'", segment))
             }
-        })
-        .collect();
+            None => Ok(SegmentPattern::Glob(name_case.normalize(segment))),
+        }
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            SegmentPattern::Glob(pattern) => matches_pattern(value, pattern),
+            SegmentPattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
 
-    // Return a closure that checks if database.table matches any pattern
-    Box::new(move |database: &str, table: &str| {
-        patterns.iter().any(|(db_pattern, table_pattern)| {
-            matches_pattern(database, db_pattern) && matches_pattern(table, table_pattern)
+/// Split `<database>.<table>` patterns, ignoring ones in another format
+///
+/// Regex segments may contain dots, so the table segment starts at the first
+/// `.re:` if there is one; after a regex database segment it starts at the last
+/// dot, since glob segments never contain dots.
+fn parse_patterns(
+    targets: &[String],
+    name_case: TableNameCase,
+) -> Result<Vec<(SegmentPattern, SegmentPattern)>> {
+    targets
+        .iter()
+        .filter_map(|target| split_target(target))
+        .map(|(database, table)| {
+            Ok((
+                SegmentPattern::parse(database, name_case)?,
+                SegmentPattern::parse(table, name_case)?,
+            ))
         })
-    })
+        .collect()
+}
+
+fn split_target(target: &str) -> Option<(&str, &str)> {
+    let table_regex = format!(".{}", REGEX_PREFIX);
+    if let Some(index) = target.find(&table_regex) {
+        return Some((&target[..index], &target[index + 1..]));
+    }
+    if target.starts_with(REGEX_PREFIX) {
+        return target.rsplit_once('.');
+    }
+
+    let parts: Vec<&str> = target.split('.').collect();
+    if parts.len() == 2 {
+        Some((parts[0], parts[1]))
+    } else {
+        None
+    }
+}
+
+/// Check whether a database matches any `exclude_databases` pattern
+///
+/// # Arguments
+/// * `database` - Database name
+/// * `excluded` - Patterns supporting '*' wildcards, e.g. `team_*`
+/// * `name_case` - Whether matching ignores case, as for target patterns
+pub fn is_excluded_database(database: &str, excluded: &[String], name_case: TableNameCase) -> bool {
+    let database = name_case.normalize(database);
+    excluded
+        .iter()
+        .any(|pattern| matches_pattern(&database, &name_case.normalize(pattern)))
 }
 
 /// Check if a string matches a pattern with wildcard support
@@ -101,6 +316,45 @@ fn matches_pattern(value: &str, pattern: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_excluded_database() {
+        let excluded = vec!["information_schema".to_string(), "team_*".to_string()];
+        let name_case = TableNameCase::Insensitive;
+        assert!(is_excluded_database(
+            "information_schema",
+            &excluded,
+            name_case
+        ));
+        assert!(is_excluded_database("team_growth", &excluded, name_case));
+        assert!(!is_excluded_database("salesdb", &excluded, name_case));
+        assert!(!is_excluded_database("salesdb", &[], name_case));
+    }
+
+    #[test]
+    fn test_is_excluded_database_follows_name_case() {
+        let excluded = vec!["Scratch*".to_string()];
+        assert!(is_excluded_database(
+            "scratchdb",
+            &excluded,
+            TableNameCase::Insensitive
+        ));
+        assert!(is_excluded_database(
+            "SCRATCHDB",
+            &excluded,
+            TableNameCase::Insensitive
+        ));
+        assert!(!is_excluded_database(
+            "scratchdb",
+            &excluded,
+            TableNameCase::Sensitive
+        ));
+        assert!(is_excluded_database(
+            "Scratchdb",
+            &excluded,
+            TableNameCase::Sensitive
+        ));
+    }
+
     #[test]
     fn test_matches_pattern_exact() {
         assert!(matches_pattern("salesdb", "salesdb"));
@@ -123,14 +377,14 @@ mod tests {
 
     #[test]
     fn test_parse_target_filter_empty() {
-        let filter = parse_target_filter(&[]);
+        let filter = parse_target_filter(&[]).unwrap();
         assert!(filter("salesdb", "customers"));
         assert!(filter("marketingdb", "leads"));
     }
 
     #[test]
     fn test_parse_target_filter_specific_table() {
-        let filter = parse_target_filter(&["salesdb.customers".to_string()]);
+        let filter = parse_target_filter(&["salesdb.customers".to_string()]).unwrap();
         assert!(filter("salesdb", "customers"));
         assert!(!filter("salesdb", "orders"));
         assert!(!filter("marketingdb", "customers"));
@@ -138,7 +392,7 @@ mod tests {
 
     #[test]
     fn test_parse_target_filter_all_tables_in_database() {
-        let filter = parse_target_filter(&["salesdb.*".to_string()]);
+        let filter = parse_target_filter(&["salesdb.*".to_string()]).unwrap();
         assert!(filter("salesdb", "customers"));
         assert!(filter("salesdb", "orders"));
         assert!(!filter("marketingdb", "customers"));
@@ -146,7 +400,7 @@ mod tests {
 
     #[test]
     fn test_parse_target_filter_table_across_databases() {
-        let filter = parse_target_filter(&["*.customers".to_string()]);
+        let filter = parse_target_filter(&["*.customers".to_string()]).unwrap();
         assert!(filter("salesdb", "customers"));
         assert!(filter("marketingdb", "customers"));
         assert!(!filter("salesdb", "orders"));
@@ -155,21 +409,180 @@ mod tests {
     #[test]
     fn test_parse_target_filter_multiple_patterns() {
         let filter =
-            parse_target_filter(&["salesdb.customers".to_string(), "marketingdb.*".to_string()]);
+            parse_target_filter(&["salesdb.customers".to_string(), "marketingdb.*".to_string()])
+                .unwrap();
         assert!(filter("salesdb", "customers"));
         assert!(!filter("salesdb", "orders"));
         assert!(filter("marketingdb", "leads"));
         assert!(filter("marketingdb", "campaigns"));
     }
 
+    #[test]
+    fn test_parse_target_filter_name_case() {
+        let targets = ["SalesDB.Customers".to_string(), "MarketingDB.*".to_string()];
+
+        let filter = parse_target_filter(&targets).unwrap();
+        assert!(filter("salesdb", "customers"));
+        assert!(filter("SALESDB", "CUSTOMERS"));
+        assert!(filter("marketingdb", "Leads"));
+
+        let filter = parse_target_filter_with_case(&targets, TableNameCase::Sensitive).unwrap();
+        assert!(filter("SalesDB", "Customers"));
+        assert!(!filter("salesdb", "customers"));
+        assert!(filter("MarketingDB", "leads"));
+        assert!(!filter("marketingdb", "leads"));
+    }
+
     #[test]
     fn test_parse_target_filter_invalid_format() {
         // Invalid format is ignored
-        let filter = parse_target_filter(&["invalid".to_string()]);
+        let filter = parse_target_filter(&["invalid".to_string()]).unwrap();
         // Since no valid patterns, it should reject all
         assert!(!filter("salesdb", "customers"));
     }
 
+    #[test]
+    fn test_parse_target_filter_with_excludes() {
+        let excludes = ["salesdb.events".to_string()];
+
+        // Includes and excludes together
+        let filter = parse_target_filter_with_excludes(
+            &["salesdb.*".to_string()],
+            &excludes,
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert!(filter("salesdb", "customers"));
+        assert!(filter("salesdb", "orders"));
+        assert!(!filter("salesdb", "events"));
+        assert!(!filter("SalesDB", "Events"));
+        assert!(!filter("marketingdb", "leads"));
+
+        // Excludes alone leave everything else in
+        let filter =
+            parse_target_filter_with_excludes(&[], &excludes, TableNameCase::Insensitive).unwrap();
+        assert!(filter("salesdb", "customers"));
+        assert!(filter("marketingdb", "events"));
+        assert!(!filter("salesdb", "events"));
+
+        // Wildcard excludes, and excludes win over includes
+        let filter = parse_target_filter_with_excludes(
+            &["*.*".to_string(), "salesdb.events".to_string()],
+            &["*.events".to_string(), "tmp_*.*".to_string()],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert!(filter("salesdb", "customers"));
+        assert!(!filter("salesdb", "events"));
+        assert!(!filter("marketingdb", "events"));
+        assert!(!filter("tmp_scratch", "customers"));
+
+        // Exclude patterns follow table_name_case
+        let filter = parse_target_filter_with_excludes(
+            &["salesdb.*".to_string()],
+            &["salesdb.Events".to_string()],
+            TableNameCase::Sensitive,
+        )
+        .unwrap();
+        assert!(!filter("salesdb", "Events"));
+        assert!(filter("salesdb", "events"));
+    }
+
+    #[test]
+    fn test_parse_target_filter_regex_segments() {
+        let filter = parse_target_filter(&[
+            "salesdb.re:.*_staging".to_string(),
+            "re:team_(growth|ads).events".to_string(),
+            "re:tmp.*.re:t\\d+".to_string(),
+            "marketingdb.*".to_string(),
+        ])
+        .unwrap();
+        // Regex table segment, matched against the whole name
+        assert!(filter("salesdb", "orders_staging"));
+        assert!(filter("SalesDB", "Orders_Staging"));
+        assert!(!filter("salesdb", "orders_staging_old"));
+        assert!(!filter("salesdb", "orders"));
+        // Regex database segment with a glob table segment
+        assert!(filter("team_growth", "events"));
+        assert!(filter("team_ads", "events"));
+        assert!(!filter("team_growth", "leads"));
+        assert!(!filter("team_growthx", "events"));
+        // Both segments regexes
+        assert!(filter("tmp_scratch", "t42"));
+        assert!(!filter("tmp_scratch", "tx"));
+        // Globs keep working alongside regexes
+        assert!(filter("marketingdb", "leads"));
+        assert!(!filter("analyticsdb", "orders_staging"));
+
+        // Case-sensitive regexes
+        let filter = parse_target_filter_with_case(
+            &["salesdb.re:[a-z]+_staging".to_string()],
+            TableNameCase::Sensitive,
+        )
+        .unwrap();
+        assert!(filter("salesdb", "orders_staging"));
+        assert!(!filter("salesdb", "Orders_staging"));
+
+        // Regex excludes
+        let filter = parse_target_filter_with_excludes(
+            &["salesdb.*".to_string()],
+            &["salesdb.re:.*_(staging|tmp)".to_string()],
+            TableNameCase::Insensitive,
+        )
+        .unwrap();
+        assert!(filter("salesdb", "orders"));
+        assert!(!filter("salesdb", "orders_tmp"));
+    }
+
+    #[test]
+    fn test_parse_target_filter_invalid_regex() {
+        let err = parse_target_filter(&["salesdb.re:orders(".to_string()])
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("Invalid regex in target pattern 're:orders('")
+        );
+
+        let err = parse_target_filter_with_excludes(
+            &[],
+            &["re:[sales.*".to_string()],
+            TableNameCase::Insensitive,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("re:[sales"));
+    }
+
+    #[test]
+    fn test_parse_target_file() {
+        let content = "# Sales tables\nsalesdb.*\n\n  marketingdb.leads  \n\t# indented comment\n   \nre:team_.*.events\r\n";
+        assert_eq!(
+            parse_target_file(content),
+            vec!["salesdb.*", "marketingdb.leads", "re:team_.*.events"]
+        );
+        assert!(parse_target_file("").is_empty());
+    }
+
+    #[test]
+    fn test_merge_target_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("targets.txt");
+        std::fs::write(&path, "marketingdb.*\n# comment\n").unwrap();
+
+        let cli_targets = vec!["salesdb.customers".to_string()];
+        assert_eq!(
+            merge_target_file(&cli_targets, Some(&path)).unwrap(),
+            vec!["salesdb.customers", "marketingdb.*"]
+        );
+        assert_eq!(merge_target_file(&cli_targets, None).unwrap(), cli_targets);
+
+        let err = merge_target_file(&[], Some(&temp_dir.path().join("missing.txt")))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Failed to read target file"));
+    }
+
     #[test]
     fn test_resolve_targets_cli_takes_priority() {
         let cli_targets = vec!["salesdb.customers".to_string()];
@@ -205,4 +618,61 @@ mod tests {
         let result = resolve_targets(&cli_targets, config_databases.as_ref());
         assert_eq!(result, Vec::<String>::new());
     }
+
+    #[test]
+    fn test_resolve_targets_with_env_cli_takes_precedence() {
+        let cli_targets = vec!["salesdb.customers".to_string()];
+        let config_databases = Some(vec!["marketingdb".to_string()]);
+
+        let result = resolve_targets_with_env(
+            &cli_targets,
+            Some("analyticsdb.*"),
+            config_databases.as_ref(),
+        );
+        assert_eq!(result, vec!["salesdb.customers"]);
+    }
+
+    #[test]
+    fn test_resolve_targets_with_env_over_config() {
+        let config_databases = Some(vec!["marketingdb".to_string()]);
+
+        let result = resolve_targets_with_env(
+            &[],
+            Some("salesdb.* marketingdb.leads"),
+            config_databases.as_ref(),
+        );
+        assert_eq!(result, vec!["salesdb.*", "marketingdb.leads"]);
+    }
+
+    #[test]
+    fn test_resolve_targets_with_env_comma_separated() {
+        let result = resolve_targets_with_env(&[], Some("salesdb.*, marketingdb.leads,"), None);
+        assert_eq!(result, vec!["salesdb.*", "marketingdb.leads"]);
+    }
+
+    #[test]
+    fn test_resolve_targets_with_env_keeps_regex_quantifier() {
+        let result = resolve_targets_with_env(
+            &[],
+            Some("re:salesdb.t.{2,3} marketingdb.*,salesdb.re:c{1,2}x"),
+            None,
+        );
+        assert_eq!(
+            result,
+            vec!["re:salesdb.t.{2,3}", "marketingdb.*", "salesdb.re:c{1,2}x"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_targets_with_env_empty_is_ignored() {
+        let config_databases = Some(vec!["marketingdb".to_string()]);
+
+        for env_value in [None, Some(""), Some("  , ")] {
+            let result = resolve_targets_with_env(&[], env_value, config_databases.as_ref());
+            assert_eq!(result, vec!["marketingdb.*"]);
+        }
+
+        let result = resolve_targets_with_env(&[], Some(""), None);
+        assert_eq!(result, Vec::<String>::new());
+    }
 }
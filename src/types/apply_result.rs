@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::types::diff_result::{DiffOperation, DiffResult};
+
+/// Machine-readable result of an apply, written with `apply --output-json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub dry_run: bool,
+    /// Every changed table of the plan, in apply order
+    pub tables: Vec<ApplyTableResult>,
+    pub totals: ApplyTotals,
+}
+
+/// Result of applying the change of one table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplyTableResult {
+    /// "database.table"
+    pub table: String,
+    pub operation: DiffOperation,
+    pub status: ApplyStatus,
+    /// Why the table failed to apply
+    pub error: Option<String>,
+    /// Id of the last query run for the table, once it succeeded
+    pub execution_id: Option<String>,
+}
+
+/// Status of one table in an apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyStatus {
+    Succeeded,
+    Failed,
+    /// Not attempted because another table had failed
+    Skipped,
+    /// Would be applied; only used by dry runs
+    Planned,
+    /// Skipped on resume, as the state file records it as applied
+    AlreadyApplied,
+}
+
+/// Number of tables with each status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ApplyTotals {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub planned: usize,
+    #[serde(default)]
+    pub already_applied: usize,
+}
+
+impl ApplyResult {
+    /// Create a result from per-table results, counting the totals
+    pub fn new(dry_run: bool, tables: Vec<ApplyTableResult>) -> Self {
+        let mut totals = ApplyTotals::default();
+        for table in &tables {
+            match table.status {
+                ApplyStatus::Succeeded => totals.succeeded += 1,
+                ApplyStatus::Failed => totals.failed += 1,
+                ApplyStatus::Skipped => totals.skipped += 1,
+                ApplyStatus::Planned => totals.planned += 1,
+                ApplyStatus::AlreadyApplied => totals.already_applied += 1,
+            }
+        }
+
+        Self {
+            dry_run,
+            tables,
+            totals,
+        }
+    }
+
+    /// Result of a dry run: every changed table of the plan is planned
+    pub fn planned(diff_result: &DiffResult) -> Self {
+        let tables = diff_result
+            .table_diffs
+            .iter()
+            .filter(|table_diff| table_diff.is_change())
+            .map(|table_diff| ApplyTableResult {
+                table: table_diff.qualified_name(),
+                operation: table_diff.operation.clone(),
+                status: ApplyStatus::Planned,
+                error: None,
+                execution_id: None,
+            })
+            .collect();
+        Self::new(true, tables)
+    }
+
+    /// Write the result as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write apply result {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::diff_result::{DiffSummary, TableDiff};
+    use crate::types::table_definition::ObjectType;
+
+    fn table_diff(table_name: &str, operation: DiffOperation) -> TableDiff {
+        TableDiff {
+            database_name: "salesdb".to_string(),
+            table_name: table_name.to_string(),
+            operation,
+            text_diff: None,
+            change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
+        }
+    }
+
+    #[test]
+    fn test_planned_result_lists_changed_tables() {
+        let table_diffs = vec![
+            table_diff("orders", DiffOperation::Create),
+            table_diff("customers", DiffOperation::NoChange),
+            table_diff("legacy", DiffOperation::Delete),
+        ];
+        let diff_result = DiffResult {
+            no_change: false,
+            summary: DiffSummary::from_table_diffs(&table_diffs),
+            table_diffs,
+        };
+
+        let result = ApplyResult::planned(&diff_result);
+        assert!(result.dry_run);
+        assert_eq!(
+            result.totals,
+            ApplyTotals {
+                planned: 2,
+                ..ApplyTotals::default()
+            }
+        );
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["tables"][0]["table"], "salesdb.orders");
+        assert_eq!(json["tables"][0]["operation"], "Create");
+        assert_eq!(json["tables"][0]["status"], "planned");
+        assert_eq!(json["tables"][1]["table"], "salesdb.legacy");
+        assert_eq!(json["tables"][1]["execution_id"], serde_json::Value::Null);
+    }
+}
@@ -1,4 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::aws::retry::{DEFAULT_MAX_RETRIES, DEFAULT_RETRY_BASE_DELAY_MS};
+use crate::file_utils::{DEFAULT_FILE_EXTENSION, SqlLayout};
+use crate::types::diff_result::ChangeCategory;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -7,7 +13,259 @@ pub struct Config {
     pub region: Option<String>,
     pub query_timeout_seconds: Option<u64>,
     pub max_concurrent_queries: Option<usize>,
+    pub max_concurrent_glue_requests: Option<usize>, // Optional: catalog metadata requests in flight per region in semantic comparison mode (default: DEFAULT_MAX_CONCURRENT_GLUE_REQUESTS)
     pub databases: Option<Vec<String>>, // Optional: databases to manage (used when --target is not specified)
+    pub update_strategy: Option<UpdateStrategy>, // Optional: how apply updates existing tables (default: drop_create)
+    pub drop_behavior: Option<DropBehavior>, // Optional: whether apply's DROP TABLE purges data (default: keep_data)
+    pub auto_apply_when: Option<Vec<ChangeCategory>>, // Optional: change categories --auto-approve may apply without review
+    pub assume_role_arn: Option<String>, // Optional: role assumed on top of the default credentials chain
+    pub assume_role: Option<AssumeRoleConfig>, // Optional: role assumed with an external ID or session name (instead of assume_role_arn)
+    pub profile: Option<String>, // Optional: named AWS profile used instead of the default credentials chain (--profile overrides it)
+    pub region_overrides: Option<BTreeMap<String, String>>, // Optional: database name -> region holding it
+    pub exclude_databases: Option<Vec<String>>, // Optional: databases never queried, '*' wildcards allowed
+    pub table_name_case: Option<TableNameCase>, // Optional: how targets and local/remote names are matched (default: insensitive)
+    pub comparison_mode: Option<ComparisonMode>, // Optional: how local files are compared with remote tables (default: text)
+    pub ignore_type_synonyms: Option<bool>, // Optional: treat type synonyms such as integer and int as equal (default: false)
+    pub normalize_whitespace: Option<bool>, // Optional: ignore indentation, internal whitespace runs, and blank lines when comparing (default: false)
+    pub result_reuse_max_age_minutes: Option<u64>, // Optional: reuse cached query results up to this age (default: off)
+    pub output_location_region_check: Option<RegionCheck>, // Optional: compare the output_location bucket region with the Athena region (default: off)
+    pub max_retries: Option<u32>, // Optional: retries of throttled or failed Athena API calls (default: 5)
+    pub retry_base_delay_ms: Option<u64>, // Optional: delay before the first retry, doubled for each later one (default: 200)
+    pub price_per_tb: Option<f64>, // Optional: USD per TiB scanned, used to estimate the cost of apply (default: no estimate)
+    pub endpoint_url: Option<String>, // Optional: send every AWS request to this URL, e.g. LocalStack (default: AWS endpoints)
+    pub s3_force_path_style: Option<bool>, // Optional: path-style S3 addressing, as S3 mocks expect (default: false)
+    pub catalog_id: Option<String>, // Optional: Glue Data Catalog ID of a catalog shared from another account (default: the account's own catalog)
+    pub data_catalog: Option<String>, // Optional: Athena data catalog to manage, such as a federated connector's (default: AwsDataCatalog)
+    pub cache_ttl_seconds: Option<u64>, // Optional: reuse SHOW CREATE TABLE results cached in .athenadef-cache/ for this long (default: no cache)
+    pub export_header: Option<String>, // Optional: comment lines export writes above each table, {table} is replaced (default: DEFAULT_EXPORT_HEADER, "" disables)
+    pub cleanup_query_results: Option<bool>, // Optional: delete each query's result files from S3 once it succeeded (default: false)
+    pub default_database: Option<String>, // Optional: database of table_name.sql files placed directly in the base path (default: none, only database/table_name.sql)
+    pub file_extension: Option<String>, // Optional: extension of table definition files, without the dot (default: DEFAULT_FILE_EXTENSION)
+    pub templating: Option<bool>, // Optional: render every table definition file as a Tera template, not only *.sql.tera (default: false)
+    pub variables: Option<BTreeMap<String, String>>, // Optional: values of ${name} tokens in table definitions and of template variables (default: no substitution)
+    pub path: Option<String>, // Optional: directory of the table definition files, relative to the config file (default: the config file's directory)
+    /// Directory of the table definition files given with `--path`, taking
+    /// precedence over `path`; never read from the config file
+    #[serde(skip)]
+    pub path_override: Option<PathBuf>,
+}
+
+/// Environment variable overriding `workgroup`
+pub const WORKGROUP_ENV_VAR: &str = "ATHENADEF_WORKGROUP";
+
+/// Top-level key holding per-workspace overrides
+pub const WORKSPACES_KEY: &str = "workspaces";
+
+/// Workspace used when `--workspace` is not given
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Check that an Athena data catalog name is a simple identifier
+///
+/// Letters, digits, `_`, and `-` are accepted, as Athena allows for catalog
+/// names; quotes, dots, and spaces are not.
+pub fn validate_data_catalog_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid data catalog name: '{}'. Use letters, digits, '_', and '-' only",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Settings given with global CLI flags, applied over the config file of every command
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    /// Directory of the table definition files (`--path`), relative to the current directory
+    pub path: Option<PathBuf>,
+    /// Athena data catalog (`--catalog`), replacing `data_catalog` and `catalog_id`
+    pub data_catalog: Option<String>,
+    /// Workspace (`--workspace`) selected instead of `default`
+    pub workspace: Option<String>,
+}
+
+/// Directory holding the table definition files
+///
+/// In order of precedence: `cli_path` as given, the config's `path` relative
+/// to the config file's directory, then the config file's directory.
+pub fn resolve_base_path(
+    cli_path: Option<&Path>,
+    config_path: &Path,
+    config_base_path: Option<&str>,
+) -> PathBuf {
+    if let Some(cli_path) = cli_path {
+        return cli_path.to_path_buf();
+    }
+
+    // A bare file name such as `athenadef.yaml` has an empty parent
+    let config_dir = config_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    match config_base_path {
+        Some(path) => config_dir.join(path),
+        None => config_dir.to_path_buf(),
+    }
+}
+
+/// Comment lines `export` writes above each table's DDL
+///
+/// `{table}` is replaced with the table's `database.table` name.
+pub const DEFAULT_EXPORT_HEADER: &str =
+    "-- Managed by athenadef. Do not edit by hand.\n-- Source: {table}";
+
+/// Largest result reuse age Athena accepts (7 days)
+pub const MAX_RESULT_REUSE_AGE_MINUTES: u64 = 10080;
+
+/// Glue Data Catalog requests in flight per region unless `max_concurrent_glue_requests` is set
+pub const DEFAULT_MAX_CONCURRENT_GLUE_REQUESTS: usize = 10;
+
+/// System databases excluded from remote scans unless `exclude_databases` is set
+pub const DEFAULT_EXCLUDED_DATABASES: &[&str] = &["information_schema"];
+
+fn default_excluded_databases() -> Vec<String> {
+    DEFAULT_EXCLUDED_DATABASES
+        .iter()
+        .map(|database| database.to_string())
+        .collect()
+}
+
+/// How `apply` brings an existing table in line with its local definition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStrategy {
+    /// DROP TABLE followed by CREATE TABLE
+    #[default]
+    DropCreate,
+    /// Attempt CREATE OR REPLACE, falling back to drop_create only if Athena does not support it
+    Recreate,
+    /// ALTER TABLE for added columns and location changes, drop_create for anything else
+    AlterWhenPossible,
+}
+
+/// Upper bound for `--concurrency auto`, to stay clear of Athena's throttling limits
+pub const AUTO_MAX_CONCURRENT_QUERIES: usize = 25;
+
+/// How many queries run in parallel when fetching remote definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Concurrency {
+    /// Size the pool from the number of queries, capped at `AUTO_MAX_CONCURRENT_QUERIES`
+    Auto,
+    /// A fixed number of concurrent queries
+    Fixed(usize),
+}
+
+impl Concurrency {
+    /// Number of concurrent queries to use for a batch
+    ///
+    /// # Arguments
+    /// * `query_count` - Number of queries in the batch
+    pub fn resolve(self, query_count: usize) -> usize {
+        match self {
+            Concurrency::Auto => query_count.clamp(1, AUTO_MAX_CONCURRENT_QUERIES),
+            Concurrency::Fixed(max_concurrent) => max_concurrent,
+        }
+    }
+}
+
+impl std::str::FromStr for Concurrency {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(Concurrency::Auto);
+        }
+        match value.parse::<usize>() {
+            Ok(max_concurrent) if max_concurrent > 0 => Ok(Concurrency::Fixed(max_concurrent)),
+            _ => Err(format!(
+                "invalid concurrency '{}': expected 'auto' or a positive number",
+                value
+            )),
+        }
+    }
+}
+
+/// Role assumed with STS before any AWS client is created
+///
+/// Used to reach tables in another account whose role trust policy may require
+/// an external ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssumeRoleConfig {
+    pub role_arn: String,
+    pub external_id: Option<String>, // Optional: external ID required by the role's trust policy
+    pub session_name: Option<String>, // Optional: role session name shown in CloudTrail (default: athenadef)
+}
+
+/// Whether a string looks like an IAM role ARN, e.g. `arn:aws:iam::123456789012:role/athenadef`
+fn is_iam_role_arn(arn: &str) -> bool {
+    regex::Regex::new(r"^arn:aws[a-z-]*:iam::\d{12}:role/[\w+=,.@/-]+$")
+        .map(|re| re.is_match(arn))
+        .unwrap_or(false)
+}
+
+/// How database and table names are compared in targets and between local and remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TableNameCase {
+    /// Ignore case, as Athena does for identifiers
+    #[default]
+    Insensitive,
+    /// Require an exact match
+    Sensitive,
+}
+
+impl TableNameCase {
+    /// Normalize a name or "database.table" key for comparison
+    pub fn normalize(self, name: &str) -> String {
+        match self {
+            TableNameCase::Insensitive => name.to_lowercase(),
+            TableNameCase::Sensitive => name.to_string(),
+        }
+    }
+}
+
+/// How local SQL files are compared with remote tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMode {
+    /// Diff the normalized SHOW CREATE TABLE output against the local files
+    #[default]
+    Text,
+    /// Compare Glue Data Catalog metadata with the parsed local files, ignoring
+    /// formatting, property order, quoting, and defaults Athena adds
+    Semantic,
+}
+
+/// What to do when the `output_location` bucket is in a different region than Athena
+///
+/// Athena fails in confusing ways when query results go to a bucket in another
+/// region, so the bucket region can be looked up (GetBucketLocation) and compared
+/// before any query runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionCheck {
+    /// Do not look up the bucket region
+    #[default]
+    Off,
+    /// Print a warning on a mismatch
+    Warn,
+    /// Fail the command on a mismatch
+    Error,
+}
+
+/// Whether the DROP TABLE statements issued by `apply` also delete table data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DropBehavior {
+    /// Plain DROP TABLE, which leaves the data of external tables in S3
+    #[default]
+    KeepData,
+    /// DROP TABLE ... PURGE, which also deletes the table data where supported
+    Purge,
 }
 
 impl Default for Config {
@@ -18,14 +276,60 @@ impl Default for Config {
             region: None,
             query_timeout_seconds: Some(300),
             max_concurrent_queries: Some(5),
+            max_concurrent_glue_requests: Some(DEFAULT_MAX_CONCURRENT_GLUE_REQUESTS),
             databases: None,
+            update_strategy: Some(UpdateStrategy::DropCreate),
+            drop_behavior: Some(DropBehavior::KeepData),
+            auto_apply_when: None,
+            assume_role_arn: None,
+            assume_role: None,
+            profile: None,
+            region_overrides: None,
+            exclude_databases: Some(default_excluded_databases()),
+            table_name_case: Some(TableNameCase::Insensitive),
+            comparison_mode: Some(ComparisonMode::Text),
+            ignore_type_synonyms: None,
+            normalize_whitespace: None,
+            result_reuse_max_age_minutes: None,
+            output_location_region_check: Some(RegionCheck::Off),
+            max_retries: Some(DEFAULT_MAX_RETRIES),
+            retry_base_delay_ms: Some(DEFAULT_RETRY_BASE_DELAY_MS),
+            price_per_tb: None,
+            endpoint_url: None,
+            s3_force_path_style: None,
+            catalog_id: None,
+            data_catalog: None,
+            cache_ttl_seconds: None,
+            export_header: None,
+            cleanup_query_results: None,
+            default_database: None,
+            file_extension: None,
+            templating: None,
+            variables: None,
+            path: None,
+            path_override: None,
         }
     }
 }
 
 impl Config {
     /// Load configuration from a YAML file
+    ///
+    /// When the file has a `workspaces` map, the selected workspace's settings
+    /// (see `select_workspace`) replace those at the top level. Then `${VAR}` and
+    /// `${VAR:-default}` references in `workgroup`, `output_location`, `region`,
+    /// `databases`, and `variables` are replaced with environment variables.
     pub fn load_from_path(path: &str) -> anyhow::Result<Self> {
+        Self::load_from_path_with_workspace(path, None)
+    }
+
+    /// Load configuration from a YAML file with the given workspace selected
+    ///
+    /// None selects `default`. See `load_from_path`.
+    pub fn load_from_path_with_workspace(
+        path: &str,
+        workspace: Option<&str>,
+    ) -> anyhow::Result<Self> {
         let content = std::fs::read_to_string(path).map_err(|e| {
             anyhow::anyhow!(
                 "Failed to read config file '{}': {}\n\nMake sure the file exists and you have read permissions.\nYou can specify a custom config file with: --config <path>",
@@ -34,20 +338,40 @@ impl Config {
             )
         })?;
 
-        let config: Config = serde_yaml::from_str(&content).map_err(|e| {
+        let parse_error = |e: serde_yaml::Error| {
             anyhow::anyhow!(
                 "Failed to parse YAML configuration: {}\n\nCheck that your {} file has valid YAML syntax.\n\nExample minimal configuration:\n  workgroup: \"primary\"",
                 e,
                 path
             )
-        })?;
+        };
+        let value: serde_yaml::Value = serde_yaml::from_str(&content).map_err(parse_error)?;
+        let value = select_workspace(value, workspace)?;
+        let config: Config = serde_yaml::from_value(value).map_err(parse_error)?;
 
-        let config = config.with_defaults();
+        let config = config.with_env_interpolation(|name| std::env::var(name).ok())?;
+        let env_workgroup = std::env::var(WORKGROUP_ENV_VAR).ok();
+        let config = config
+            .with_workgroup_override(env_workgroup.as_deref())
+            .with_defaults();
         config.validate()?;
 
         Ok(config)
     }
 
+    /// Load configuration from a YAML file, then apply the global CLI flags
+    ///
+    /// See `load_from_path`.
+    pub fn load_with_overrides(path: &str, overrides: &ConfigOverrides) -> anyhow::Result<Self> {
+        let mut config = Self::load_from_path_with_workspace(path, overrides.workspace.as_deref())?;
+        config.path_override = overrides.path.clone();
+        if let Some(ref data_catalog) = overrides.data_catalog {
+            config.data_catalog = Some(data_catalog.clone());
+            config.catalog_id = None;
+        }
+        Ok(config)
+    }
+
     /// Validate configuration values
     pub fn validate(&self) -> anyhow::Result<()> {
         if self.workgroup.is_empty() {
@@ -72,6 +396,168 @@ impl Config {
             }
         }
 
+        if let Some(ref role_arn) = self.assume_role_arn {
+            if !role_arn.is_empty() && !role_arn.starts_with("arn:") {
+                return Err(anyhow::anyhow!(
+                    "Invalid assume_role_arn: '{}'. Expected an IAM role ARN such as 'arn:aws:iam::123456789012:role/athenadef'",
+                    role_arn
+                ));
+            }
+        }
+
+        if let Some(ref export_header) = self.export_header {
+            if let Some(line) = export_header
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with("--"))
+            {
+                return Err(anyhow::anyhow!(
+                    "Invalid export_header line: '{}'. Every line must be an SQL comment starting with '--'",
+                    line
+                ));
+            }
+        }
+
+        if self.path.as_deref().is_some_and(str::is_empty) {
+            return Err(anyhow::anyhow!(
+                "path cannot be empty (omit it to read files next to the config file)"
+            ));
+        }
+
+        if self.default_database.as_deref().is_some_and(str::is_empty) {
+            return Err(anyhow::anyhow!(
+                "default_database cannot be empty (omit it to only read database/table_name.sql files)"
+            ));
+        }
+
+        if let Some(ref file_extension) = self.file_extension {
+            if file_extension.is_empty()
+                || !file_extension.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(anyhow::anyhow!(
+                    "Invalid file_extension: '{}'. Give the extension without the dot, such as 'sql' or 'ddl'",
+                    file_extension
+                ));
+            }
+        }
+
+        if self.cache_ttl_seconds == Some(0) {
+            return Err(anyhow::anyhow!(
+                "cache_ttl_seconds must be greater than 0 (omit it to disable the cache)"
+            ));
+        }
+
+        if let Some(ref catalog_id) = self.catalog_id {
+            if catalog_id.len() != 12 || !catalog_id.chars().all(|c| c.is_ascii_digit()) {
+                return Err(anyhow::anyhow!(
+                    "Invalid catalog_id: '{}'. Expected the 12-digit ID of the AWS account owning the Glue Data Catalog",
+                    catalog_id
+                ));
+            }
+        }
+
+        if let Some(ref data_catalog) = self.data_catalog {
+            validate_data_catalog_name(data_catalog)?;
+            if self.catalog_id.is_some() {
+                return Err(anyhow::anyhow!(
+                    "Both catalog_id and data_catalog are set. Use catalog_id for a shared Glue catalog, or data_catalog to name the Athena data catalog directly"
+                ));
+            }
+        }
+
+        if let Some(ref assume_role) = self.assume_role {
+            if self.assume_role_arn.is_some() {
+                return Err(anyhow::anyhow!(
+                    "Both assume_role_arn and assume_role are set. Use only assume_role, which also accepts external_id and session_name"
+                ));
+            }
+            if !is_iam_role_arn(&assume_role.role_arn) {
+                return Err(anyhow::anyhow!(
+                    "Invalid assume_role.role_arn: '{}'. Expected an IAM role ARN such as 'arn:aws:iam::123456789012:role/athenadef'",
+                    assume_role.role_arn
+                ));
+            }
+            if let Some(ref external_id) = assume_role.external_id {
+                if external_id.len() < 2 || external_id.len() > 1224 {
+                    return Err(anyhow::anyhow!(
+                        "Invalid assume_role.external_id: STS accepts 2 to 1224 characters"
+                    ));
+                }
+            }
+            if let Some(ref session_name) = assume_role.session_name {
+                let valid_chars = session_name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_+=,.@-".contains(c));
+                if session_name.len() < 2 || session_name.len() > 64 || !valid_chars {
+                    return Err(anyhow::anyhow!(
+                        "Invalid assume_role.session_name: '{}'. STS accepts 2 to 64 letters, digits, or _+=,.@- characters",
+                        session_name
+                    ));
+                }
+            }
+        }
+
+        if let Some(ref profile) = self.profile {
+            if profile.is_empty() || profile.chars().any(char::is_whitespace) {
+                return Err(anyhow::anyhow!(
+                    "Invalid profile: '{}'. Expected the name of a profile in ~/.aws/config or ~/.aws/credentials, without spaces",
+                    profile
+                ));
+            }
+        }
+
+        if let Some(ref overrides) = self.region_overrides {
+            for (database_name, region) in overrides {
+                if region.trim().is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Region for database '{}' in region_overrides cannot be empty",
+                        database_name
+                    ));
+                }
+            }
+        }
+
+        if let Some(max_age) = self.result_reuse_max_age_minutes {
+            if max_age == 0 || max_age > MAX_RESULT_REUSE_AGE_MINUTES {
+                return Err(anyhow::anyhow!(
+                    "Invalid result_reuse_max_age_minutes: {}. Athena accepts 1 to {} minutes (7 days); omit it to disable result reuse",
+                    max_age,
+                    MAX_RESULT_REUSE_AGE_MINUTES
+                ));
+            }
+            if self.cleanup_query_results == Some(true) {
+                return Err(anyhow::anyhow!(
+                    "cleanup_query_results cannot be combined with result_reuse_max_age_minutes: reused results point at the result files cleanup deletes. Use `athenadef gc` to prune old results instead"
+                ));
+            }
+        }
+
+        if self.retry_base_delay_ms == Some(0) {
+            return Err(anyhow::anyhow!(
+                "Retry base delay must be greater than 0 milliseconds (set max_retries: 0 to disable retries)"
+            ));
+        }
+
+        if let Some(price) = self.price_per_tb {
+            if !price.is_finite() || price < 0.0 {
+                return Err(anyhow::anyhow!(
+                    "Invalid price_per_tb: {}. It must be a non-negative amount in USD",
+                    price
+                ));
+            }
+        }
+
+        if let Some(ref endpoint_url) = self.endpoint_url {
+            let valid = url::Url::parse(endpoint_url)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+            if !valid {
+                return Err(anyhow::anyhow!(
+                    "Invalid endpoint_url: '{}'. Expected an http(s) URL such as 'http://localhost:4566'",
+                    endpoint_url
+                ));
+            }
+        }
+
         if let Some(max_concurrent) = self.max_concurrent_queries {
             if max_concurrent == 0 {
                 return Err(anyhow::anyhow!(
@@ -80,9 +566,80 @@ impl Config {
             }
         }
 
+        if self.max_concurrent_glue_requests == Some(0) {
+            return Err(anyhow::anyhow!(
+                "max_concurrent_glue_requests must be greater than 0"
+            ));
+        }
+
         Ok(())
     }
 
+    /// Replace `workgroup` with the value of `ATHENADEF_WORKGROUP`, if set and non-empty
+    ///
+    /// # Arguments
+    /// * `env_workgroup` - Value of `ATHENADEF_WORKGROUP`, if set
+    pub fn with_workgroup_override(mut self, env_workgroup: Option<&str>) -> Self {
+        if let Some(workgroup) = env_workgroup.map(str::trim).filter(|w| !w.is_empty()) {
+            self.workgroup = workgroup.to_string();
+        }
+        self
+    }
+
+    /// Expand environment variable references in `workgroup`, `output_location`,
+    /// `region`, `databases`, and the values of `variables`
+    ///
+    /// See `interpolate_env` for the syntax. Fails, naming the field, when a
+    /// variable is unset and has no default.
+    ///
+    /// # Arguments
+    /// * `lookup` - Returns the value of an environment variable, if set
+    pub fn with_env_interpolation(
+        mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<Self> {
+        let expand = |field: &str, value: &str| {
+            interpolate_env(value, &lookup)
+                .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", field, e))
+        };
+
+        self.workgroup = expand("workgroup", &self.workgroup)?;
+        if let Some(ref output_location) = self.output_location {
+            self.output_location = Some(expand("output_location", output_location)?);
+        }
+        if let Some(ref region) = self.region {
+            self.region = Some(expand("region", region)?);
+        }
+        if let Some(ref databases) = self.databases {
+            self.databases = Some(
+                databases
+                    .iter()
+                    .map(|database| expand("databases", database))
+                    .collect::<anyhow::Result<_>>()?,
+            );
+        }
+        if let Some(ref variables) = self.variables {
+            self.variables = Some(
+                variables
+                    .iter()
+                    .map(|(name, value)| {
+                        Ok((name.clone(), expand(&format!("variables.{}", name), value)?))
+                    })
+                    .collect::<anyhow::Result<_>>()?,
+            );
+        }
+        Ok(self)
+    }
+
+    /// Concurrency to use, preferring a command line override over `max_concurrent_queries`
+    ///
+    /// # Arguments
+    /// * `cli_concurrency` - Value of `--concurrency`, if given
+    pub fn concurrency(&self, cli_concurrency: Option<Concurrency>) -> Concurrency {
+        cli_concurrency
+            .unwrap_or_else(|| Concurrency::Fixed(self.max_concurrent_queries.unwrap_or(5)))
+    }
+
     /// Apply default values to optional fields if not set
     pub fn with_defaults(mut self) -> Self {
         if self.query_timeout_seconds.is_none() {
@@ -91,8 +648,193 @@ impl Config {
         if self.max_concurrent_queries.is_none() {
             self.max_concurrent_queries = Some(5);
         }
+        if self.max_concurrent_glue_requests.is_none() {
+            self.max_concurrent_glue_requests = Some(DEFAULT_MAX_CONCURRENT_GLUE_REQUESTS);
+        }
+        if self.update_strategy.is_none() {
+            self.update_strategy = Some(UpdateStrategy::DropCreate);
+        }
+        if self.drop_behavior.is_none() {
+            self.drop_behavior = Some(DropBehavior::KeepData);
+        }
+        if self.exclude_databases.is_none() {
+            self.exclude_databases = Some(default_excluded_databases());
+        }
+        if self.table_name_case.is_none() {
+            self.table_name_case = Some(TableNameCase::Insensitive);
+        }
+        if self.comparison_mode.is_none() {
+            self.comparison_mode = Some(ComparisonMode::Text);
+        }
+        if self.output_location_region_check.is_none() {
+            self.output_location_region_check = Some(RegionCheck::Off);
+        }
+        if self.max_retries.is_none() {
+            self.max_retries = Some(DEFAULT_MAX_RETRIES);
+        }
+        if self.retry_base_delay_ms.is_none() {
+            self.retry_base_delay_ms = Some(DEFAULT_RETRY_BASE_DELAY_MS);
+        }
+        if self.export_header.is_none() {
+            self.export_header = Some(DEFAULT_EXPORT_HEADER.to_string());
+        }
+        if self.file_extension.is_none() {
+            self.file_extension = Some(DEFAULT_FILE_EXTENSION.to_string());
+        }
         self
     }
+
+    /// Directory of the table definition files for the config loaded from `config_path`
+    ///
+    /// See `resolve_base_path`; `path_override` (`--path`) takes precedence over `path`.
+    pub fn base_path(&self, config_path: &str) -> PathBuf {
+        resolve_base_path(
+            self.path_override.as_deref(),
+            Path::new(config_path),
+            self.path.as_deref(),
+        )
+    }
+
+    /// Layout of the table definition files under the base path
+    pub fn sql_layout(&self) -> SqlLayout {
+        SqlLayout {
+            file_extension: self
+                .file_extension
+                .clone()
+                .unwrap_or_else(|| DEFAULT_FILE_EXTENSION.to_string()),
+            default_database: self.default_database.clone(),
+            templating: self.templating.unwrap_or(false),
+            variables: self.variables.clone(),
+        }
+    }
+}
+
+/// Merge a workspace's settings onto the shared settings of a config document
+///
+/// Each top-level key set in the workspace replaces the shared value as a whole;
+/// maps such as `region_overrides` are not merged key by key. Without
+/// `workspace`, the `default` workspace is used when it exists and the shared
+/// settings alone otherwise. Fails if `workspace` is given but not defined.
+///
+/// # Arguments
+/// * `value` - Parsed config document
+/// * `workspace` - Workspace given with `--workspace`
+pub fn select_workspace(
+    value: serde_yaml::Value,
+    workspace: Option<&str>,
+) -> anyhow::Result<serde_yaml::Value> {
+    let serde_yaml::Value::Mapping(mut base) = value else {
+        return match workspace {
+            Some(name) => Err(anyhow::anyhow!(
+                "Workspace '{}' is not defined: the config file has no workspaces",
+                name
+            )),
+            None => Ok(value),
+        };
+    };
+
+    let workspaces = match base.remove(WORKSPACES_KEY) {
+        None | Some(serde_yaml::Value::Null) => serde_yaml::Mapping::new(),
+        Some(serde_yaml::Value::Mapping(workspaces)) => workspaces,
+        Some(_) => {
+            return Err(anyhow::anyhow!(
+                "'{}' must map workspace names to settings",
+                WORKSPACES_KEY
+            ));
+        }
+    };
+
+    let name = workspace.unwrap_or(DEFAULT_WORKSPACE);
+    let overrides = match workspaces.get(name) {
+        Some(serde_yaml::Value::Mapping(overrides)) => overrides.clone(),
+        Some(serde_yaml::Value::Null) => serde_yaml::Mapping::new(),
+        Some(_) => {
+            return Err(anyhow::anyhow!(
+                "Workspace '{}' must be a map of settings",
+                name
+            ));
+        }
+        None if workspace.is_none() => serde_yaml::Mapping::new(),
+        None => {
+            let mut names: Vec<&str> = workspaces.keys().filter_map(|key| key.as_str()).collect();
+            names.sort_unstable();
+            return Err(anyhow::anyhow!(
+                "Workspace '{}' is not defined. Available workspaces: {}",
+                name,
+                if names.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ));
+        }
+    };
+
+    for (key, value) in overrides {
+        if key.as_str() == Some(WORKSPACES_KEY) {
+            return Err(anyhow::anyhow!(
+                "Workspace '{}' cannot define nested workspaces",
+                name
+            ));
+        }
+        base.insert(key, value);
+    }
+    Ok(serde_yaml::Value::Mapping(base))
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` references with environment variables
+///
+/// As in the shell, the default is used when the variable is unset or empty. A
+/// `$` not followed by `{` is kept as is.
+///
+/// # Arguments
+/// * `value` - Config value that may contain references
+/// * `lookup` - Returns the value of an environment variable, if set
+pub fn interpolate_env(
+    value: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> anyhow::Result<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let end = reference
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("unterminated '${{' in '{}'", value))?;
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(anyhow::anyhow!(
+                "invalid environment variable name '{}' in '{}'",
+                name,
+                value
+            ));
+        }
+
+        match (lookup(name), default) {
+            (Some(env_value), Some(default)) if env_value.is_empty() => result.push_str(default),
+            (Some(env_value), _) => result.push_str(&env_value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "environment variable '{}' is not set. Set it, or give a default with '${{{}:-default}}'",
+                    name,
+                    name
+                ));
+            }
+        }
+        rest = &reference[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -135,81 +877,606 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_zero_max_concurrent() {
+    fn test_validate_zero_retry_base_delay() {
         let config = Config {
-            max_concurrent_queries: Some(0),
+            retry_base_delay_ms: Some(0),
             ..Default::default()
         };
         assert!(config.validate().is_err());
-    }
 
-    #[test]
-    fn test_validate_invalid_s3_path() {
+        // Retries can be turned off entirely
         let config = Config {
-            output_location: Some("invalid-s3-path".to_string()),
+            max_retries: Some(0),
             ..Default::default()
         };
-        let result = config.validate();
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .to_string()
-                .contains("must start with 's3://'")
-        );
+        assert!(config.validate().is_ok());
     }
 
     #[test]
-    fn test_validate_valid_s3_path() {
+    fn test_validate_price_per_tb() {
+        for price in [-1.0, f64::NAN, f64::INFINITY] {
+            let config = Config {
+                price_per_tb: Some(price),
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+
         let config = Config {
-            output_location: Some("s3://my-bucket/path/".to_string()),
+            price_per_tb: Some(5.0),
             ..Default::default()
         };
         assert!(config.validate().is_ok());
     }
 
     #[test]
-    fn test_validate_empty_s3_path() {
+    fn test_validate_endpoint_url() {
+        for endpoint_url in [
+            "localhost:4566",
+            "not a url",
+            "ftp://localhost:4566",
+            "http://",
+        ] {
+            let config = Config {
+                endpoint_url: Some(endpoint_url.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                config
+                    .validate()
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Invalid endpoint_url"),
+                "{} should be rejected",
+                endpoint_url
+            );
+        }
+
+        for endpoint_url in ["http://localhost:4566", "https://athena.example.com/"] {
+            let config = Config {
+                endpoint_url: Some(endpoint_url.to_string()),
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validate_catalog_id() {
+        for catalog_id in [
+            "",
+            "21098765432",
+            "arn:aws:glue:us-east-1:210987654321:catalog",
+        ] {
+            let config = Config {
+                catalog_id: Some(catalog_id.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                config
+                    .validate()
+                    .unwrap_err()
+                    .to_string()
+                    .starts_with("Invalid catalog_id"),
+                "{} should be rejected",
+                catalog_id
+            );
+        }
+
         let config = Config {
-            output_location: Some("".to_string()),
+            catalog_id: Some("210987654321".to_string()),
             ..Default::default()
         };
-        assert!(config.validate().is_ok()); // Empty string is allowed (treated as None)
+        assert!(config.validate().is_ok());
     }
 
     #[test]
-    fn test_with_defaults() {
+    fn test_validate_default_database() {
         let config = Config {
-            workgroup: "custom".to_string(),
-            output_location: None,
-            region: None,
-            query_timeout_seconds: None,
-            max_concurrent_queries: None,
-            databases: None,
+            default_database: Some("salesdb".to_string()),
+            ..Default::default()
         };
+        assert!(config.validate().is_ok());
 
-        let config_with_defaults = config.with_defaults();
-        assert_eq!(config_with_defaults.workgroup, "custom");
-        assert_eq!(config_with_defaults.query_timeout_seconds, Some(300));
-        assert_eq!(config_with_defaults.max_concurrent_queries, Some(5));
+        let config = Config {
+            default_database: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
     }
 
     #[test]
-    fn test_with_defaults_preserves_custom_values() {
+    fn test_validate_file_extension() {
+        for file_extension in ["sql", "ddl", "hql"] {
+            let config = Config {
+                file_extension: Some(file_extension.to_string()),
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+
+        for file_extension in ["", ".ddl", "d/dl"] {
+            let config = Config {
+                file_extension: Some(file_extension.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                config.validate().is_err(),
+                "{:?} should be rejected",
+                file_extension
+            );
+        }
+    }
+
+    #[test]
+    fn test_sql_layout() {
+        let layout = Config::default().sql_layout();
+        assert_eq!(layout, SqlLayout::default());
+
         let config = Config {
-            workgroup: "custom".to_string(),
-            output_location: Some("s3://bucket/path/".to_string()),
-            region: Some("us-east-1".to_string()),
-            query_timeout_seconds: Some(600),
-            max_concurrent_queries: Some(10),
-            databases: Some(vec!["db1".to_string(), "db2".to_string()]),
+            file_extension: Some("ddl".to_string()),
+            default_database: Some("salesdb".to_string()),
+            ..Default::default()
         };
+        let layout = config.sql_layout();
+        assert_eq!(layout.file_name("orders"), "orders.ddl");
+        assert!(layout.is_default_database("SalesDB"));
+    }
 
-        let config_with_defaults = config.with_defaults();
-        assert_eq!(config_with_defaults.workgroup, "custom");
+    #[test]
+    fn test_validate_export_header() {
+        for export_header in ["", DEFAULT_EXPORT_HEADER, "-- Generated\n\n  -- {table}\n"] {
+            let config = Config {
+                export_header: Some(export_header.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                config.validate().is_ok(),
+                "{:?} should be accepted",
+                export_header
+            );
+        }
+
+        let config = Config {
+            export_header: Some("-- Generated\nSource: {table}".to_string()),
+            ..Default::default()
+        };
         assert_eq!(
-            config_with_defaults.output_location,
-            Some("s3://bucket/path/".to_string())
+            config.validate().unwrap_err().to_string(),
+            "Invalid export_header line: 'Source: {table}'. Every line must be an SQL comment starting with '--'"
+        );
+    }
+
+    #[test]
+    fn test_validate_zero_max_concurrent() {
+        let config = Config {
+            max_concurrent_queries: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_result_reuse_max_age() {
+        for (max_age, valid) in [(0, false), (1, true), (10080, true), (10081, false)] {
+            let config = Config {
+                result_reuse_max_age_minutes: Some(max_age),
+                ..Default::default()
+            };
+            assert_eq!(config.validate().is_ok(), valid, "max age {}", max_age);
+        }
+    }
+
+    #[test]
+    fn test_with_workgroup_override() {
+        let config = Config::default().with_workgroup_override(Some("analytics"));
+        assert_eq!(config.workgroup, "analytics");
+
+        let config = Config::default().with_workgroup_override(Some(""));
+        assert_eq!(config.workgroup, "primary");
+
+        let config = Config::default().with_workgroup_override(None);
+        assert_eq!(config.workgroup, "primary");
+    }
+
+    fn env_lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: BTreeMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let lookup = env_lookup(&[("STAGE", "prod"), ("EMPTY", "")]);
+
+        assert_eq!(
+            interpolate_env("s3://results-${STAGE}/athena/", &lookup).unwrap(),
+            "s3://results-prod/athena/"
+        );
+        assert_eq!(
+            interpolate_env("${STAGE}-${STAGE}", &lookup).unwrap(),
+            "prod-prod"
+        );
+        assert_eq!(interpolate_env("primary", &lookup).unwrap(), "primary");
+        assert_eq!(interpolate_env("cost$5", &lookup).unwrap(), "cost$5");
+
+        // Defaults apply when the variable is unset or empty
+        assert_eq!(
+            interpolate_env("${REGION:-us-east-1}", &lookup).unwrap(),
+            "us-east-1"
+        );
+        assert_eq!(interpolate_env("${EMPTY:-dev}", &lookup).unwrap(), "dev");
+        assert_eq!(interpolate_env("${STAGE:-dev}", &lookup).unwrap(), "prod");
+        assert_eq!(interpolate_env("${EMPTY}", &lookup).unwrap(), "");
+        assert_eq!(interpolate_env("${MISSING:-}", &lookup).unwrap(), "");
+
+        let err = interpolate_env("wg-${MISSING}", &lookup).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("environment variable 'MISSING' is not set")
+        );
+        assert!(interpolate_env("${STAGE", &lookup).is_err());
+        assert!(interpolate_env("${1ST}", &lookup).is_err());
+        assert!(interpolate_env("${}", &lookup).is_err());
+    }
+
+    #[test]
+    fn test_with_env_interpolation() {
+        let config = Config {
+            workgroup: "${WORKGROUP}".to_string(),
+            output_location: Some("s3://${BUCKET:-athena-results}/${STAGE}/".to_string()),
+            region: Some("${AWS_TARGET_REGION:-us-east-1}".to_string()),
+            databases: Some(vec!["sales_${STAGE}".to_string(), "shared".to_string()]),
+            ..Config::default()
+        };
+        let lookup = env_lookup(&[("WORKGROUP", "analytics"), ("STAGE", "staging")]);
+
+        let config = config.with_env_interpolation(&lookup).unwrap();
+        assert_eq!(config.workgroup, "analytics");
+        assert_eq!(
+            config.output_location.as_deref(),
+            Some("s3://athena-results/staging/")
+        );
+        assert_eq!(config.region.as_deref(), Some("us-east-1"));
+        assert_eq!(
+            config.databases,
+            Some(vec!["sales_staging".to_string(), "shared".to_string()])
+        );
+
+        let config = Config {
+            variables: Some(BTreeMap::from([(
+                "bucket".to_string(),
+                "data-${STAGE}".to_string(),
+            )])),
+            ..Config::default()
+        };
+        let config = config.with_env_interpolation(&lookup).unwrap();
+        assert_eq!(config.variables.unwrap()["bucket"], "data-staging");
+
+        let config = Config {
+            region: Some("${AWS_TARGET_REGION}".to_string()),
+            ..Config::default()
+        };
+        let err = config.with_env_interpolation(&lookup).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "Invalid value for region: environment variable 'AWS_TARGET_REGION' is not set"
+        ));
+    }
+
+    #[test]
+    fn test_load_config_interpolates_env() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "workgroup: \"${{ATHENADEF_TEST_UNSET_WORKGROUP:-from-default}}\""
+        )
+        .unwrap();
+        let config = Config::load_from_path(temp_file.path().to_str().unwrap()).unwrap();
+        if std::env::var(WORKGROUP_ENV_VAR).is_err() {
+            assert_eq!(config.workgroup, "from-default");
+        }
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "workgroup: primary\noutput_location: \"s3://${{ATHENADEF_TEST_UNSET_BUCKET}}/\""
+        )
+        .unwrap();
+        let err = Config::load_from_path(temp_file.path().to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("ATHENADEF_TEST_UNSET_BUCKET"));
+    }
+
+    const WORKSPACES_YAML: &str = r#"
+workgroup: primary
+region: us-east-1
+databases:
+  - salesdb
+workspaces:
+  default:
+    output_location: "s3://dev-results/"
+  prod:
+    workgroup: production
+    region: eu-west-1
+    output_location: "s3://prod-results/"
+  staging:
+"#;
+
+    fn load_workspace(workspace: Option<&str>) -> anyhow::Result<Config> {
+        let value = serde_yaml::from_str(WORKSPACES_YAML).unwrap();
+        Ok(serde_yaml::from_value(select_workspace(value, workspace)?)?)
+    }
+
+    #[test]
+    fn test_select_workspace_merges_onto_shared_settings() {
+        let config = load_workspace(Some("prod")).unwrap();
+        assert_eq!(config.workgroup, "production");
+        assert_eq!(config.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(
+            config.output_location.as_deref(),
+            Some("s3://prod-results/")
+        );
+        // Keys the workspace does not set are shared
+        assert_eq!(config.databases, Some(vec!["salesdb".to_string()]));
+
+        // An empty workspace uses the shared settings as they are
+        let config = load_workspace(Some("staging")).unwrap();
+        assert_eq!(config.workgroup, "primary");
+        assert_eq!(config.output_location, None);
+    }
+
+    #[test]
+    fn test_select_workspace_defaults() {
+        // Without --workspace, the default workspace applies when defined
+        let config = load_workspace(None).unwrap();
+        assert_eq!(config.workgroup, "primary");
+        assert_eq!(config.output_location.as_deref(), Some("s3://dev-results/"));
+
+        // ... and files without workspaces load as before
+        let value = serde_yaml::from_str("workgroup: primary\n").unwrap();
+        let config: Config =
+            serde_yaml::from_value(select_workspace(value, None).unwrap()).unwrap();
+        assert_eq!(config.workgroup, "primary");
+    }
+
+    #[test]
+    fn test_select_workspace_unknown_name() {
+        let err = load_workspace(Some("qa")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Workspace 'qa' is not defined. Available workspaces: default, prod, staging"
+        );
+
+        let value = serde_yaml::from_str("workgroup: primary\n").unwrap();
+        let err = select_workspace(value, Some("prod")).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Workspace 'prod' is not defined")
+        );
+
+        let value = serde_yaml::from_str("workgroup: primary\nworkspaces: [prod]\n").unwrap();
+        assert!(select_workspace(value, Some("prod")).is_err());
+    }
+
+    #[test]
+    fn test_load_with_overrides_selects_workspace() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(WORKSPACES_YAML.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_from_path_with_workspace(path, Some("prod")).unwrap();
+        assert_eq!(config.region.as_deref(), Some("eu-west-1"));
+
+        let overrides = ConfigOverrides {
+            workspace: Some("prod".to_string()),
+            ..ConfigOverrides::default()
+        };
+        let config = Config::load_with_overrides(path, &overrides).unwrap();
+        assert_eq!(config.region.as_deref(), Some("eu-west-1"));
+
+        let config = Config::load_with_overrides(path, &ConfigOverrides::default()).unwrap();
+        assert_eq!(config.region.as_deref(), Some("us-east-1"));
+        assert_eq!(config.output_location.as_deref(), Some("s3://dev-results/"));
+    }
+
+    #[test]
+    fn test_concurrency_auto_scales_with_query_count() {
+        assert_eq!(Concurrency::Auto.resolve(0), 1);
+        assert_eq!(Concurrency::Auto.resolve(3), 3);
+        assert_eq!(Concurrency::Auto.resolve(25), 25);
+        assert_eq!(Concurrency::Auto.resolve(1000), AUTO_MAX_CONCURRENT_QUERIES);
+        assert_eq!(Concurrency::Fixed(5).resolve(1000), 5);
+    }
+
+    #[test]
+    fn test_concurrency_from_str_and_override() {
+        assert_eq!("auto".parse::<Concurrency>(), Ok(Concurrency::Auto));
+        assert_eq!("8".parse::<Concurrency>(), Ok(Concurrency::Fixed(8)));
+        assert!("0".parse::<Concurrency>().is_err());
+        assert!("fast".parse::<Concurrency>().is_err());
+
+        let config = Config {
+            max_concurrent_queries: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(config.concurrency(None), Concurrency::Fixed(10));
+        assert_eq!(
+            config.concurrency(Some(Concurrency::Auto)),
+            Concurrency::Auto
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_s3_path() {
+        let config = Config {
+            output_location: Some("invalid-s3-path".to_string()),
+            ..Default::default()
+        };
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("must start with 's3://'")
+        );
+    }
+
+    #[test]
+    fn test_validate_valid_s3_path() {
+        let config = Config {
+            output_location: Some("s3://my-bucket/path/".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_empty_s3_path() {
+        let config = Config {
+            output_location: Some("".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok()); // Empty string is allowed (treated as None)
+    }
+
+    #[test]
+    fn test_with_defaults() {
+        let config = Config {
+            workgroup: "custom".to_string(),
+            output_location: None,
+            region: None,
+            query_timeout_seconds: None,
+            max_concurrent_queries: None,
+            max_concurrent_glue_requests: None,
+            databases: None,
+            update_strategy: None,
+            drop_behavior: None,
+            auto_apply_when: None,
+            assume_role_arn: None,
+            assume_role: None,
+            profile: None,
+            region_overrides: None,
+            exclude_databases: None,
+            table_name_case: None,
+            comparison_mode: None,
+            ignore_type_synonyms: None,
+            normalize_whitespace: None,
+            result_reuse_max_age_minutes: None,
+            output_location_region_check: None,
+            max_retries: None,
+            retry_base_delay_ms: None,
+            price_per_tb: None,
+            endpoint_url: None,
+            s3_force_path_style: None,
+            catalog_id: None,
+            data_catalog: None,
+            cache_ttl_seconds: None,
+            export_header: None,
+            cleanup_query_results: None,
+            default_database: None,
+            file_extension: None,
+            templating: None,
+            variables: None,
+            path: None,
+            path_override: None,
+        };
+
+        let config_with_defaults = config.with_defaults();
+        assert_eq!(config_with_defaults.workgroup, "custom");
+        assert_eq!(config_with_defaults.query_timeout_seconds, Some(300));
+        assert_eq!(config_with_defaults.max_concurrent_queries, Some(5));
+        assert_eq!(
+            config_with_defaults.max_concurrent_glue_requests,
+            Some(DEFAULT_MAX_CONCURRENT_GLUE_REQUESTS)
+        );
+        assert_eq!(
+            config_with_defaults.update_strategy,
+            Some(UpdateStrategy::DropCreate)
+        );
+        assert_eq!(
+            config_with_defaults.drop_behavior,
+            Some(DropBehavior::KeepData)
+        );
+        assert_eq!(
+            config_with_defaults.table_name_case,
+            Some(TableNameCase::Insensitive)
+        );
+        assert_eq!(
+            config_with_defaults.comparison_mode,
+            Some(ComparisonMode::Text)
+        );
+        assert_eq!(
+            config_with_defaults.output_location_region_check,
+            Some(RegionCheck::Off)
+        );
+        assert_eq!(
+            config_with_defaults.exclude_databases,
+            Some(vec!["information_schema".to_string()])
+        );
+        assert_eq!(config_with_defaults.max_retries, Some(5));
+        assert_eq!(config_with_defaults.retry_base_delay_ms, Some(200));
+        assert_eq!(
+            config_with_defaults.export_header.as_deref(),
+            Some(DEFAULT_EXPORT_HEADER)
+        );
+        assert_eq!(config_with_defaults.file_extension.as_deref(), Some("sql"));
+    }
+
+    #[test]
+    fn test_with_defaults_preserves_custom_values() {
+        let config = Config {
+            workgroup: "custom".to_string(),
+            output_location: Some("s3://bucket/path/".to_string()),
+            region: Some("us-east-1".to_string()),
+            query_timeout_seconds: Some(600),
+            max_concurrent_queries: Some(10),
+            max_concurrent_glue_requests: None,
+            databases: Some(vec!["db1".to_string(), "db2".to_string()]),
+            update_strategy: Some(UpdateStrategy::AlterWhenPossible),
+            drop_behavior: Some(DropBehavior::Purge),
+            auto_apply_when: Some(vec![ChangeCategory::ColumnAdded]),
+            assume_role_arn: Some("arn:aws:iam::123456789012:role/athenadef".to_string()),
+            assume_role: None,
+            profile: Some("staging".to_string()),
+            region_overrides: Some(BTreeMap::from([(
+                "eudb".to_string(),
+                "eu-west-1".to_string(),
+            )])),
+            exclude_databases: Some(vec![]),
+            table_name_case: Some(TableNameCase::Sensitive),
+            comparison_mode: Some(ComparisonMode::Semantic),
+            ignore_type_synonyms: Some(true),
+            normalize_whitespace: Some(true),
+            result_reuse_max_age_minutes: Some(60),
+            output_location_region_check: Some(RegionCheck::Error),
+            max_retries: Some(0),
+            retry_base_delay_ms: Some(1000),
+            price_per_tb: Some(5.0),
+            endpoint_url: Some("http://localhost:4566".to_string()),
+            s3_force_path_style: Some(true),
+            catalog_id: Some("210987654321".to_string()),
+            data_catalog: None,
+            cache_ttl_seconds: Some(900),
+            export_header: Some(String::new()),
+            cleanup_query_results: Some(true),
+            default_database: Some("salesdb".to_string()),
+            file_extension: Some("ddl".to_string()),
+            templating: Some(true),
+            variables: Some(BTreeMap::from([(
+                "bucket".to_string(),
+                "data-prod".to_string(),
+            )])),
+            path: Some("sql".to_string()),
+            path_override: None,
+        };
+
+        let config_with_defaults = config.with_defaults();
+        assert_eq!(config_with_defaults.workgroup, "custom");
+        assert_eq!(
+            config_with_defaults.output_location,
+            Some("s3://bucket/path/".to_string())
         );
         assert_eq!(config_with_defaults.region, Some("us-east-1".to_string()));
         assert_eq!(config_with_defaults.query_timeout_seconds, Some(600));
@@ -218,6 +1485,60 @@ mod tests {
             config_with_defaults.databases,
             Some(vec!["db1".to_string(), "db2".to_string()])
         );
+        assert_eq!(
+            config_with_defaults.update_strategy,
+            Some(UpdateStrategy::AlterWhenPossible)
+        );
+        assert_eq!(
+            config_with_defaults.auto_apply_when,
+            Some(vec![ChangeCategory::ColumnAdded])
+        );
+        // An explicit empty list turns off the default exclusions
+        assert_eq!(config_with_defaults.exclude_databases, Some(vec![]));
+        assert_eq!(
+            config_with_defaults.drop_behavior,
+            Some(DropBehavior::Purge)
+        );
+        assert_eq!(
+            config_with_defaults.table_name_case,
+            Some(TableNameCase::Sensitive)
+        );
+        assert_eq!(
+            config_with_defaults.comparison_mode,
+            Some(ComparisonMode::Semantic)
+        );
+        assert_eq!(
+            config_with_defaults.output_location_region_check,
+            Some(RegionCheck::Error)
+        );
+        assert_eq!(config_with_defaults.max_retries, Some(0));
+        assert_eq!(config_with_defaults.retry_base_delay_ms, Some(1000));
+        assert_eq!(config_with_defaults.price_per_tb, Some(5.0));
+        assert_eq!(
+            config_with_defaults.endpoint_url.as_deref(),
+            Some("http://localhost:4566")
+        );
+        assert_eq!(config_with_defaults.s3_force_path_style, Some(true));
+        assert_eq!(
+            config_with_defaults.catalog_id.as_deref(),
+            Some("210987654321")
+        );
+        assert_eq!(config_with_defaults.cache_ttl_seconds, Some(900));
+        // An empty header turns off the default one
+        assert_eq!(config_with_defaults.export_header.as_deref(), Some(""));
+        assert_eq!(config_with_defaults.ignore_type_synonyms, Some(true));
+        assert_eq!(config_with_defaults.normalize_whitespace, Some(true));
+        assert_eq!(config_with_defaults.cleanup_query_results, Some(true));
+        assert_eq!(
+            config_with_defaults.default_database.as_deref(),
+            Some("salesdb")
+        );
+        assert_eq!(config_with_defaults.file_extension.as_deref(), Some("ddl"));
+        assert_eq!(config_with_defaults.templating, Some(true));
+        assert_eq!(
+            config_with_defaults.variables.unwrap()["bucket"],
+            "data-prod"
+        );
     }
 
     #[test]
@@ -236,6 +1557,51 @@ workgroup: "test-workgroup"
         assert_eq!(config.max_concurrent_queries, Some(5)); // Default applied
     }
 
+    #[test]
+    fn test_load_with_overrides_sets_base_path() {
+        let yaml = r#"
+workgroup: "test-workgroup"
+path: "sql"
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_with_overrides(path, &ConfigOverrides::default()).unwrap();
+        assert_eq!(config.path_override, None);
+        assert!(config.base_path(path).ends_with("sql"));
+
+        let overrides = ConfigOverrides {
+            path: Some(PathBuf::from("other")),
+            ..ConfigOverrides::default()
+        };
+        let config = Config::load_with_overrides(path, &overrides).unwrap();
+        assert_eq!(config.base_path(path), PathBuf::from("other"));
+    }
+
+    #[test]
+    fn test_load_with_overrides_replaces_catalog() {
+        let yaml = r#"
+workgroup: "test-workgroup"
+catalog_id: "210987654321"
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_with_overrides(path, &ConfigOverrides::default()).unwrap();
+        assert_eq!(config.data_catalog, None);
+        assert_eq!(config.catalog_id.as_deref(), Some("210987654321"));
+
+        let overrides = ConfigOverrides {
+            data_catalog: Some("dynamo_connector".to_string()),
+            ..ConfigOverrides::default()
+        };
+        let config = Config::load_with_overrides(path, &overrides).unwrap();
+        assert_eq!(config.data_catalog.as_deref(), Some("dynamo_connector"));
+        assert_eq!(config.catalog_id, None);
+    }
+
     #[test]
     fn test_load_from_path_full_config() {
         let yaml = r#"
@@ -260,6 +1626,370 @@ max_concurrent_queries: 10
         assert_eq!(config.max_concurrent_queries, Some(10));
     }
 
+    #[test]
+    fn test_load_from_path_update_strategy() {
+        for (value, expected) in [
+            ("drop_create", UpdateStrategy::DropCreate),
+            ("recreate", UpdateStrategy::Recreate),
+            ("alter_when_possible", UpdateStrategy::AlterWhenPossible),
+        ] {
+            let yaml = format!("workgroup: \"primary\"\nupdate_strategy: {}\n", value);
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(yaml.as_bytes()).unwrap();
+            let path = file.path().to_str().unwrap();
+
+            let config = Config::load_from_path(path).unwrap();
+            assert_eq!(config.update_strategy, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_load_from_path_drop_behavior() {
+        for (yaml, expected) in [
+            ("workgroup: \"primary\"\n", DropBehavior::KeepData),
+            (
+                "workgroup: \"primary\"\ndrop_behavior: keep_data\n",
+                DropBehavior::KeepData,
+            ),
+            (
+                "workgroup: \"primary\"\ndrop_behavior: purge\n",
+                DropBehavior::Purge,
+            ),
+        ] {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(yaml.as_bytes()).unwrap();
+            let path = file.path().to_str().unwrap();
+
+            let config = Config::load_from_path(path).unwrap();
+            assert_eq!(config.drop_behavior, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_load_from_path_output_location_region_check() {
+        let yaml = "workgroup: \"primary\"\noutput_location_region_check: warn\n";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_from_path(path).unwrap();
+        assert_eq!(config.output_location_region_check, Some(RegionCheck::Warn));
+    }
+
+    #[test]
+    fn test_load_from_path_invalid_update_strategy() {
+        let yaml = r#"
+workgroup: "primary"
+update_strategy: "truncate"
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let result = Config::load_from_path(path);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Failed to parse YAML")
+        );
+    }
+
+    #[test]
+    fn test_load_from_path_auto_apply_when() {
+        let yaml = r#"
+workgroup: "primary"
+auto_apply_when:
+  - column_added
+  - comment_changed
+  - "property: tblproperties"
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_from_path(path).unwrap();
+        assert_eq!(
+            config.auto_apply_when,
+            Some(vec![
+                ChangeCategory::ColumnAdded,
+                ChangeCategory::CommentChanged,
+                ChangeCategory::Property("tblproperties".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_load_from_path_invalid_auto_apply_when() {
+        let yaml = r#"
+workgroup: "primary"
+auto_apply_when:
+  - drop_everything
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let err = Config::load_from_path(path).unwrap_err().to_string();
+        assert!(err.contains("Unknown change category 'drop_everything'"));
+    }
+
+    #[test]
+    fn test_validate_invalid_assume_role_arn() {
+        let config = Config {
+            assume_role_arn: Some("athenadef-role".to_string()),
+            ..Config::default()
+        };
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid assume_role_arn")
+        );
+    }
+
+    #[test]
+    fn test_validate_assume_role() {
+        let assume_role = AssumeRoleConfig {
+            role_arn: "arn:aws:iam::123456789012:role/athenadef".to_string(),
+            external_id: Some("shared-secret".to_string()),
+            session_name: Some("ci-deploy".to_string()),
+        };
+        let config = Config {
+            assume_role: Some(assume_role.clone()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let invalid = [
+            AssumeRoleConfig {
+                role_arn: "arn:aws:iam::123456789012:user/alice".to_string(),
+                ..assume_role.clone()
+            },
+            AssumeRoleConfig {
+                role_arn: "athenadef".to_string(),
+                ..assume_role.clone()
+            },
+            AssumeRoleConfig {
+                external_id: Some("x".to_string()),
+                ..assume_role.clone()
+            },
+            AssumeRoleConfig {
+                session_name: Some("ci deploy".to_string()),
+                ..assume_role.clone()
+            },
+        ];
+        for assume_role in invalid {
+            let config = Config {
+                assume_role: Some(assume_role),
+                ..Config::default()
+            };
+            assert!(
+                config
+                    .validate()
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Invalid assume_role.")
+            );
+        }
+
+        // Only one way of configuring the role is accepted
+        let config = Config {
+            assume_role: Some(assume_role),
+            assume_role_arn: Some("arn:aws:iam::123456789012:role/athenadef".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_from_path_assume_role_section() {
+        let yaml = r#"
+workgroup: "primary"
+assume_role:
+  role_arn: "arn:aws:iam::210987654321:role/athena-tables"
+  external_id: "shared-secret"
+"#;
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(yaml.as_bytes()).unwrap();
+
+        let config = Config::load_from_path(temp_file.path().to_str().unwrap()).unwrap();
+        let assume_role = config.assume_role.unwrap();
+        assert_eq!(
+            assume_role.role_arn,
+            "arn:aws:iam::210987654321:role/athena-tables"
+        );
+        assert_eq!(assume_role.external_id.as_deref(), Some("shared-secret"));
+        assert_eq!(assume_role.session_name, None);
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        for profile in ["", "my profile"] {
+            let config = Config {
+                profile: Some(profile.to_string()),
+                ..Config::default()
+            };
+            let result = config.validate();
+            assert!(result.unwrap_err().to_string().contains("Invalid profile"));
+        }
+
+        let config = Config {
+            profile: Some("staging-admin".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_load_from_path_region_overrides() {
+        let yaml = r#"
+workgroup: "primary"
+region: "us-east-1"
+region_overrides:
+  eudb: "eu-west-1"
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = Config::load_from_path(path).unwrap();
+        assert_eq!(
+            config.region_overrides,
+            Some(BTreeMap::from([(
+                "eudb".to_string(),
+                "eu-west-1".to_string()
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_region_override() {
+        let config = Config {
+            region_overrides: Some(BTreeMap::from([("eudb".to_string(), "".to_string())])),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_resolve_base_path_precedence() {
+        let config_path = Path::new("project/athenadef.yaml");
+
+        assert_eq!(
+            resolve_base_path(Some(Path::new("other")), config_path, Some("sql")),
+            PathBuf::from("other")
+        );
+        assert_eq!(
+            resolve_base_path(None, config_path, Some("sql")),
+            PathBuf::from("project/sql")
+        );
+        assert_eq!(
+            resolve_base_path(None, config_path, None),
+            PathBuf::from("project")
+        );
+    }
+
+    #[test]
+    fn test_resolve_base_path_bare_config_file() {
+        assert_eq!(
+            resolve_base_path(None, Path::new("athenadef.yaml"), None),
+            PathBuf::from(".")
+        );
+        assert_eq!(
+            resolve_base_path(None, Path::new("athenadef.yaml"), Some("sql")),
+            PathBuf::from("./sql")
+        );
+        assert_eq!(
+            resolve_base_path(None, Path::new("athenadef.yaml"), Some("/srv/sql")),
+            PathBuf::from("/srv/sql")
+        );
+    }
+
+    #[test]
+    fn test_validate_zero_max_concurrent_glue_requests() {
+        let config = Config {
+            max_concurrent_glue_requests: Some(0),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = Config {
+            max_concurrent_glue_requests: Some(4),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_data_catalog() {
+        for data_catalog in ["dynamo_connector", "mysql-prod", "Catalog2"] {
+            let config = Config {
+                data_catalog: Some(data_catalog.to_string()),
+                ..Config::default()
+            };
+            assert!(config.validate().is_ok(), "{}", data_catalog);
+        }
+
+        for data_catalog in ["", "my catalog", "\"quoted\"", "catalog.db"] {
+            let config = Config {
+                data_catalog: Some(data_catalog.to_string()),
+                ..Config::default()
+            };
+            assert!(
+                config
+                    .validate()
+                    .unwrap_err()
+                    .to_string()
+                    .starts_with("Invalid data catalog name"),
+                "{}",
+                data_catalog
+            );
+        }
+
+        let config = Config {
+            data_catalog: Some("shared_lake".to_string()),
+            catalog_id: Some("210987654321".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_cleanup_with_result_reuse() {
+        let config = Config {
+            result_reuse_max_age_minutes: Some(15),
+            cleanup_query_results: Some(true),
+            ..Config::default()
+        };
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .starts_with("cleanup_query_results cannot be combined")
+        );
+
+        let config = Config {
+            result_reuse_max_age_minutes: Some(15),
+            cleanup_query_results: Some(false),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_empty_path() {
+        let config = Config {
+            path: Some(String::new()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_load_from_path_missing_file() {
         let result = Config::load_from_path("nonexistent.yaml");
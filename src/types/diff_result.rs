@@ -1,60 +1,157 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+use crate::types::table_definition::ObjectType;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DiffResult {
     pub no_change: bool,
     pub summary: DiffSummary,
     pub table_diffs: Vec<TableDiff>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, JsonSchema)]
 pub struct DiffSummary {
     pub to_add: usize,
     pub to_change: usize,
     pub to_destroy: usize,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct TableDiff {
     pub database_name: String,
     pub table_name: String,
     pub operation: DiffOperation,
     pub text_diff: Option<String>, // Unified diff text for updates
     pub change_details: Option<ChangeDetails>, // Detailed change information
+    /// S3 locations touched by this change, for data impact analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_impact: Option<LocationImpact>,
+    /// Kind of object after the change (the dropped object for deletes)
+    #[serde(default, skip_serializing_if = "ObjectType::is_table")]
+    pub object_type: ObjectType,
+}
+
+/// S3 locations affected by a table change
+///
+/// Creates only have a new location, deletes only an old one, and location
+/// changes have both.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LocationImpact {
+    pub old_location: Option<String>,
+    pub new_location: Option<String>,
+}
+
+impl std::fmt::Display for LocationImpact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.old_location, &self.new_location) {
+            (Some(old), Some(new)) => write!(f, "{} -> {}", old, new),
+            (Some(old), None) => write!(f, "{} (data is left in place)", old),
+            (None, Some(new)) => write!(f, "{}", new),
+            (None, None) => write!(f, "(none)"),
+        }
+    }
 }
 
 /// Detailed information about what changed in a table
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ChangeDetails {
     pub column_changes: Vec<ColumnChange>,
     pub property_changes: Vec<PropertyChange>,
+    /// Bucketing before and after the change, when it changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucketing_change: Option<BucketingChange>,
+    /// Whether the definitions also differ outside the detected changes, such as
+    /// in the table COMMENT
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unmodeled_changes: bool,
+}
+
+/// Bucketing of a table: `CLUSTERED BY (columns) INTO num_buckets BUCKETS`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Bucketing {
+    pub columns: Vec<String>,
+    pub num_buckets: u32,
+}
+
+impl std::fmt::Display for Bucketing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}) INTO {} BUCKETS",
+            self.columns.join(", "),
+            self.num_buckets
+        )
+    }
+}
+
+/// Change of a table's bucketing
+///
+/// A side without bucketing means the table is not bucketed there, so adding
+/// bucketing only has a new value and removing it only an old one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BucketingChange {
+    pub old_bucketing: Option<Bucketing>,
+    pub new_bucketing: Option<Bucketing>,
 }
 
 /// Column-level changes
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ColumnChange {
     pub change_type: ColumnChangeType,
     pub column_name: String,
     pub old_type: Option<String>,
     pub new_type: Option<String>,
+    /// Column constraints before the change, e.g. `NOT NULL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_constraints: Option<String>,
+    /// Column constraints after the change, e.g. `NOT NULL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_constraints: Option<String>,
+}
+
+impl ColumnChange {
+    /// Describe a constraint change, e.g. "NOT NULL added"
+    ///
+    /// # Returns
+    /// None unless this is a ConstraintChanged change
+    pub fn constraint_summary(&self) -> Option<String> {
+        if self.change_type != ColumnChangeType::ConstraintChanged {
+            return None;
+        }
+
+        Some(
+            match (
+                self.old_constraints.as_deref(),
+                self.new_constraints.as_deref(),
+            ) {
+                (None, Some(new)) => format!("{} added", new),
+                (Some(old), None) => format!("{} removed", old),
+                (Some(old), Some(new)) => format!("{} changed to {}", old, new),
+                (None, None) => "unchanged".to_string(),
+            },
+        )
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum ColumnChangeType {
     Added,
     Removed,
     TypeChanged,
+    /// Only the column constraints (such as `NOT NULL`) changed
+    ConstraintChanged,
 }
 
 /// Property-level changes (location, format, partitions, etc.)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PropertyChange {
     pub property_name: String,
     pub old_value: Option<String>,
     pub new_value: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum DiffOperation {
     Create,
     Update,
@@ -62,6 +159,102 @@ pub enum DiffOperation {
     NoChange,
 }
 
+/// Category of change used to decide what may be applied without review
+///
+/// Written in configuration as `column_added`, `column_removed`, `column_type_changed`,
+/// `column_constraint_changed`, `comment_changed`, `table_created`, `table_deleted`, or
+/// `property: <name>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ChangeCategory {
+    TableCreated,
+    TableDeleted,
+    ColumnAdded,
+    ColumnRemoved,
+    ColumnTypeChanged,
+    /// A column constraint such as `NOT NULL` was added or removed
+    ColumnConstraintChanged,
+    /// Only the COMMENT of a column changed
+    CommentChanged,
+    /// A table property such as `location`, `tblproperties.<key>`, or
+    /// `projection.<column>.<attribute>`
+    Property(String),
+}
+
+impl ChangeCategory {
+    /// Check whether this category is covered by an allowlist
+    ///
+    /// `property: <name>` also covers nested properties, so `property: tblproperties`
+    /// allows every `tblproperties.<key>` change.
+    pub fn is_allowed_by(&self, allowlist: &[ChangeCategory]) -> bool {
+        allowlist.iter().any(|allowed| match (allowed, self) {
+            (ChangeCategory::Property(allowed), ChangeCategory::Property(name)) => {
+                name == allowed || name.starts_with(&format!("{}.", allowed))
+            }
+            (allowed, category) => allowed == category,
+        })
+    }
+}
+
+impl std::str::FromStr for ChangeCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("property:") {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(
+                    "Property category requires a name, e.g. 'property: location'".to_string(),
+                );
+            }
+            return Ok(ChangeCategory::Property(name.to_string()));
+        }
+
+        match s.trim() {
+            "table_created" => Ok(ChangeCategory::TableCreated),
+            "table_deleted" => Ok(ChangeCategory::TableDeleted),
+            "column_added" => Ok(ChangeCategory::ColumnAdded),
+            "column_removed" => Ok(ChangeCategory::ColumnRemoved),
+            "column_type_changed" => Ok(ChangeCategory::ColumnTypeChanged),
+            "column_constraint_changed" => Ok(ChangeCategory::ColumnConstraintChanged),
+            "comment_changed" => Ok(ChangeCategory::CommentChanged),
+            other => Err(format!(
+                "Unknown change category '{}'. Expected one of: table_created, table_deleted, column_added, column_removed, column_type_changed, column_constraint_changed, comment_changed, property: <name>",
+                other
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for ChangeCategory {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ChangeCategory> for String {
+    fn from(category: ChangeCategory) -> Self {
+        category.to_string()
+    }
+}
+
+impl std::fmt::Display for ChangeCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeCategory::TableCreated => write!(f, "table_created"),
+            ChangeCategory::TableDeleted => write!(f, "table_deleted"),
+            ChangeCategory::ColumnAdded => write!(f, "column_added"),
+            ChangeCategory::ColumnRemoved => write!(f, "column_removed"),
+            ChangeCategory::ColumnTypeChanged => write!(f, "column_type_changed"),
+            ChangeCategory::ColumnConstraintChanged => write!(f, "column_constraint_changed"),
+            ChangeCategory::CommentChanged => write!(f, "comment_changed"),
+            ChangeCategory::Property(name) => write!(f, "property: {}", name),
+        }
+    }
+}
+
 impl DiffResult {
     /// Create a new empty diff result
     pub fn new() -> Self {
@@ -72,6 +265,16 @@ impl DiffResult {
         }
     }
 
+    /// Create a diff result from table diffs, computing the summary
+    pub fn from_table_diffs(table_diffs: Vec<TableDiff>) -> Self {
+        let summary = DiffSummary::from_table_diffs(&table_diffs);
+        Self {
+            no_change: summary.to_add == 0 && summary.to_change == 0 && summary.to_destroy == 0,
+            summary,
+            table_diffs,
+        }
+    }
+
     /// Check if there are any changes
     pub fn has_changes(&self) -> bool {
         !self.no_change
@@ -115,10 +318,88 @@ impl TableDiff {
         format!("{}.{}", self.database_name, self.table_name)
     }
 
+    /// Kind of object in the catalog before the change
+    ///
+    /// Same as `object_type`, unless an `object_type` property change records that a
+    /// table is replaced by a view or the other way around.
+    pub fn remote_object_type(&self) -> ObjectType {
+        let previous = self
+            .change_details
+            .iter()
+            .flat_map(|details| &details.property_changes)
+            .find(|change| change.property_name == "object_type")
+            .and_then(|change| change.old_value.as_deref());
+        match previous {
+            Some("view") => ObjectType::View,
+            Some("table") => ObjectType::Table,
+            _ => self.object_type,
+        }
+    }
+
     /// Check if this diff represents a change
     pub fn is_change(&self) -> bool {
         self.operation != DiffOperation::NoChange
     }
+
+    /// Get the categories of change in this diff
+    ///
+    /// Returns None for updates without detected details, or that also differ
+    /// outside them, since their risk is unknown.
+    pub fn change_categories(&self) -> Option<Vec<ChangeCategory>> {
+        match self.operation {
+            DiffOperation::Create => Some(vec![ChangeCategory::TableCreated]),
+            DiffOperation::Delete => Some(vec![ChangeCategory::TableDeleted]),
+            DiffOperation::NoChange => Some(Vec::new()),
+            DiffOperation::Update => {
+                let details = self.change_details.as_ref()?;
+                if details.unmodeled_changes
+                    || (details.column_changes.is_empty() && details.property_changes.is_empty())
+                {
+                    return None;
+                }
+
+                let mut categories = Vec::new();
+                for change in &details.column_changes {
+                    let category = match change.change_type {
+                        ColumnChangeType::Added => ChangeCategory::ColumnAdded,
+                        ColumnChangeType::Removed => ChangeCategory::ColumnRemoved,
+                        ColumnChangeType::TypeChanged => {
+                            let old_type = change.old_type.as_deref().map(strip_column_comment);
+                            let new_type = change.new_type.as_deref().map(strip_column_comment);
+                            if old_type == new_type {
+                                ChangeCategory::CommentChanged
+                            } else {
+                                ChangeCategory::ColumnTypeChanged
+                            }
+                        }
+                        ColumnChangeType::ConstraintChanged => {
+                            ChangeCategory::ColumnConstraintChanged
+                        }
+                    };
+                    if !categories.contains(&category) {
+                        categories.push(category);
+                    }
+                }
+                for change in &details.property_changes {
+                    let category = ChangeCategory::Property(change.property_name.clone());
+                    if !categories.contains(&category) {
+                        categories.push(category);
+                    }
+                }
+
+                Some(categories)
+            }
+        }
+    }
+}
+
+/// Remove a trailing `COMMENT '...'` clause from a column type
+fn strip_column_comment(column_type: &str) -> &str {
+    let lower = column_type.to_lowercase();
+    match lower.find(" comment ") {
+        Some(pos) => column_type[..pos].trim_end(),
+        None => column_type,
+    }
 }
 
 impl std::fmt::Display for DiffOperation {
@@ -178,6 +459,8 @@ mod tests {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db1".to_string(),
@@ -185,6 +468,8 @@ mod tests {
                 operation: DiffOperation::Update,
                 text_diff: Some("diff".to_string()),
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db1".to_string(),
@@ -192,6 +477,8 @@ mod tests {
                 operation: DiffOperation::Delete,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db1".to_string(),
@@ -199,6 +486,8 @@ mod tests {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
         ];
 
@@ -216,6 +505,8 @@ mod tests {
             operation: DiffOperation::Create,
             text_diff: None,
             change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
         };
         assert_eq!(diff.qualified_name(), "salesdb.customers");
     }
@@ -228,6 +519,8 @@ mod tests {
             operation: DiffOperation::Create,
             text_diff: None,
             change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
         };
         assert!(diff_create.is_change());
 
@@ -237,6 +530,8 @@ mod tests {
             operation: DiffOperation::NoChange,
             text_diff: None,
             change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
         };
         assert!(!diff_no_change.is_change());
     }
@@ -258,21 +553,29 @@ mod tests {
                     column_name: "new_column".to_string(),
                     old_type: None,
                     new_type: Some("string".to_string()),
+                    old_constraints: None,
+                    new_constraints: None,
                 },
                 ColumnChange {
                     change_type: ColumnChangeType::TypeChanged,
                     column_name: "id".to_string(),
                     old_type: Some("int".to_string()),
                     new_type: Some("bigint".to_string()),
+                    old_constraints: None,
+                    new_constraints: None,
                 },
                 ColumnChange {
                     change_type: ColumnChangeType::Removed,
                     column_name: "old_column".to_string(),
                     old_type: Some("string".to_string()),
                     new_type: None,
+                    old_constraints: None,
+                    new_constraints: None,
                 },
             ],
             property_changes: vec![],
+            bucketing_change: None,
+            unmodeled_changes: false,
         };
 
         assert_eq!(changes.column_changes.len(), 3);
@@ -290,6 +593,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bucketing_display_and_json() {
+        let bucketing = Bucketing {
+            columns: vec!["user_id".to_string(), "region".to_string()],
+            num_buckets: 16,
+        };
+        assert_eq!(bucketing.to_string(), "(user_id, region) INTO 16 BUCKETS");
+
+        let changes = ChangeDetails {
+            column_changes: vec![],
+            property_changes: vec![],
+            bucketing_change: Some(BucketingChange {
+                old_bucketing: None,
+                new_bucketing: Some(bucketing),
+            }),
+            unmodeled_changes: false,
+        };
+        let json = serde_json::to_value(&changes).unwrap();
+        assert_eq!(json["bucketing_change"]["new_bucketing"]["num_buckets"], 16);
+        assert!(json["bucketing_change"]["old_bucketing"].is_null());
+
+        // Omitted entirely when bucketing did not change
+        let json = serde_json::to_value(ChangeDetails {
+            bucketing_change: None,
+            ..changes
+        })
+        .unwrap();
+        assert!(json.get("bucketing_change").is_none());
+    }
+
     #[test]
     fn test_change_details_property_changes() {
         let changes = ChangeDetails {
@@ -306,10 +639,119 @@ mod tests {
                     new_value: Some("ORC".to_string()),
                 },
             ],
+            bucketing_change: None,
+            unmodeled_changes: false,
         };
 
         assert_eq!(changes.property_changes.len(), 2);
         assert_eq!(changes.property_changes[0].property_name, "location");
         assert_eq!(changes.property_changes[1].property_name, "format");
     }
+
+    fn update_diff(
+        column_changes: Vec<ColumnChange>,
+        property_changes: Vec<PropertyChange>,
+    ) -> TableDiff {
+        TableDiff {
+            database_name: "db".to_string(),
+            table_name: "t".to_string(),
+            operation: DiffOperation::Update,
+            text_diff: Some("diff".to_string()),
+            change_details: Some(ChangeDetails {
+                column_changes,
+                property_changes,
+                bucketing_change: None,
+                unmodeled_changes: false,
+            }),
+            location_impact: None,
+            object_type: ObjectType::Table,
+        }
+    }
+
+    fn type_change(old_type: &str, new_type: &str) -> ColumnChange {
+        ColumnChange {
+            change_type: ColumnChangeType::TypeChanged,
+            column_name: "c".to_string(),
+            old_type: Some(old_type.to_string()),
+            new_type: Some(new_type.to_string()),
+            old_constraints: None,
+            new_constraints: None,
+        }
+    }
+
+    #[test]
+    fn test_change_category_parse() {
+        assert_eq!(
+            "column_added".parse::<ChangeCategory>().unwrap(),
+            ChangeCategory::ColumnAdded
+        );
+        assert_eq!(
+            "property: location".parse::<ChangeCategory>().unwrap(),
+            ChangeCategory::Property("location".to_string())
+        );
+        assert_eq!(
+            "property:tblproperties".parse::<ChangeCategory>().unwrap(),
+            ChangeCategory::Property("tblproperties".to_string())
+        );
+        assert!("property:".parse::<ChangeCategory>().is_err());
+        assert!("drop_everything".parse::<ChangeCategory>().is_err());
+    }
+
+    #[test]
+    fn test_change_category_is_allowed_by() {
+        let allowlist = vec![
+            ChangeCategory::ColumnAdded,
+            ChangeCategory::Property("tblproperties".to_string()),
+        ];
+        assert!(ChangeCategory::ColumnAdded.is_allowed_by(&allowlist));
+        assert!(!ChangeCategory::ColumnRemoved.is_allowed_by(&allowlist));
+        assert!(
+            ChangeCategory::Property("tblproperties.projection.enabled".to_string())
+                .is_allowed_by(&allowlist)
+        );
+        assert!(!ChangeCategory::Property("location".to_string()).is_allowed_by(&allowlist));
+        assert!(!ChangeCategory::Property("tblpropertiesx".to_string()).is_allowed_by(&allowlist));
+    }
+
+    #[test]
+    fn test_change_categories_comment_vs_type_change() {
+        let diff = update_diff(
+            vec![type_change("string COMMENT 'old'", "string COMMENT 'new'")],
+            vec![],
+        );
+        assert_eq!(
+            diff.change_categories(),
+            Some(vec![ChangeCategory::CommentChanged])
+        );
+
+        let diff = update_diff(vec![type_change("int", "bigint")], vec![]);
+        assert_eq!(
+            diff.change_categories(),
+            Some(vec![ChangeCategory::ColumnTypeChanged])
+        );
+    }
+
+    #[test]
+    fn test_change_categories_without_details() {
+        let diff = update_diff(vec![], vec![]);
+        assert_eq!(diff.change_categories(), None);
+    }
+
+    #[test]
+    fn test_location_impact_display() {
+        let impact = |old: Option<&str>, new: Option<&str>| LocationImpact {
+            old_location: old.map(String::from),
+            new_location: new.map(String::from),
+        };
+
+        assert_eq!(
+            impact(Some("s3://a/"), Some("s3://b/")).to_string(),
+            "s3://a/ -> s3://b/"
+        );
+        assert_eq!(
+            impact(Some("s3://a/"), None).to_string(),
+            "s3://a/ (data is left in place)"
+        );
+        assert_eq!(impact(None, Some("s3://b/")).to_string(), "s3://b/");
+    }
 }
@@ -1,3 +1,4 @@
+pub mod apply_result;
 pub mod config;
 pub mod diff_result;
 pub mod query_execution;
@@ -17,6 +17,19 @@ pub struct QueryResult {
     pub status: QueryExecutionStatus,
     pub error_message: Option<String>,
     pub rows: Vec<QueryRow>,
+    /// Bytes Athena scanned, from the query execution statistics
+    pub data_scanned_bytes: Option<i64>,
+    /// Time the query engine spent running the query, in milliseconds
+    pub engine_execution_time_ms: Option<i64>,
+    /// S3 URL of the result file Athena wrote
+    pub output_location: Option<String>,
+}
+
+/// Data scanned and engine time added up over one or more queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct QueryStats {
+    pub data_scanned_bytes: i64,
+    pub engine_execution_time_ms: i64,
 }
 
 /// A single row in a query result
@@ -33,6 +46,9 @@ impl QueryResult {
             status,
             error_message: None,
             rows: Vec::new(),
+            data_scanned_bytes: None,
+            engine_execution_time_ms: None,
+            output_location: None,
         }
     }
 
@@ -60,6 +76,20 @@ impl QueryResult {
     }
 }
 
+impl QueryStats {
+    /// Add the statistics of a query; missing statistics count as zero
+    pub fn record(&mut self, result: &QueryResult) {
+        self.data_scanned_bytes += result.data_scanned_bytes.unwrap_or(0);
+        self.engine_execution_time_ms += result.engine_execution_time_ms.unwrap_or(0);
+    }
+
+    /// Add statistics gathered elsewhere
+    pub fn merge(&mut self, other: QueryStats) {
+        self.data_scanned_bytes += other.data_scanned_bytes;
+        self.engine_execution_time_ms += other.engine_execution_time_ms;
+    }
+}
+
 impl QueryRow {
     /// Create a new query row
     pub fn new(columns: Vec<String>) -> Self {
@@ -141,6 +171,32 @@ mod tests {
         assert_eq!(result.row_count(), 1);
     }
 
+    #[test]
+    fn test_query_stats_record_and_merge() {
+        let mut result = QueryResult::new("exec-123".to_string(), QueryExecutionStatus::Succeeded);
+        result.data_scanned_bytes = Some(1024);
+        result.engine_execution_time_ms = Some(150);
+
+        let mut stats = QueryStats::default();
+        stats.record(&result);
+        // Queries without statistics (e.g. failed to load them) add nothing
+        stats.record(&QueryResult::new(
+            "exec-456".to_string(),
+            QueryExecutionStatus::Succeeded,
+        ));
+        assert_eq!(
+            stats,
+            QueryStats {
+                data_scanned_bytes: 1024,
+                engine_execution_time_ms: 150,
+            }
+        );
+
+        stats.merge(stats);
+        assert_eq!(stats.data_scanned_bytes, 2048);
+        assert_eq!(stats.engine_execution_time_ms, 300);
+    }
+
     #[test]
     fn test_query_row_new() {
         let row = QueryRow::new(vec!["col1".to_string(), "col2".to_string()]);
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -35,6 +36,47 @@ pub struct StorageDescriptor {
     pub parameters: HashMap<String, String>,
 }
 
+/// Kind of catalog object a definition creates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
+pub enum ObjectType {
+    #[default]
+    Table,
+    /// A view, created by `CREATE VIEW` and read back with `SHOW CREATE VIEW`
+    View,
+}
+
+impl ObjectType {
+    /// Tell the object type from a DDL statement
+    ///
+    /// `CREATE [OR REPLACE] VIEW` (after any leading `--` comments) is a view;
+    /// anything else is treated as a table.
+    pub fn from_ddl(sql: &str) -> Self {
+        let view = regex::Regex::new(
+            r"(?is)^(?:\s*--[^\n]*(?:\n|$))*\s*CREATE\s+(?:OR\s+REPLACE\s+)?(?:PROTECTED\s+MULTI_DIALECT\s+)?VIEW\b",
+        )
+        .expect("valid CREATE VIEW regex");
+        if view.is_match(sql) {
+            ObjectType::View
+        } else {
+            ObjectType::Table
+        }
+    }
+
+    /// Check whether this is a table, the default object type
+    pub fn is_table(&self) -> bool {
+        *self == ObjectType::Table
+    }
+}
+
+impl std::fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectType::Table => write!(f, "table"),
+            ObjectType::View => write!(f, "view"),
+        }
+    }
+}
+
 impl TableDefinition {
     /// Create a new table definition
     pub fn new(database_name: String, table_name: String) -> Self {
@@ -68,6 +110,26 @@ mod tests {
         assert_eq!(table_def.partitions.len(), 0);
     }
 
+    #[test]
+    fn test_object_type_from_ddl() {
+        for sql in [
+            "CREATE VIEW v AS SELECT 1",
+            "create or replace view `db`.`v` as select 1",
+            "-- Daily totals\nCREATE VIEW v AS SELECT 1",
+            "CREATE PROTECTED MULTI_DIALECT VIEW v SECURITY DEFINER AS SELECT 1",
+        ] {
+            assert_eq!(ObjectType::from_ddl(sql), ObjectType::View, "{}", sql);
+        }
+        for sql in [
+            "CREATE EXTERNAL TABLE t (id int)",
+            "CREATE TABLE views (id int)",
+            "-- CREATE VIEW v AS SELECT 1\nCREATE TABLE t (id int)",
+        ] {
+            assert_eq!(ObjectType::from_ddl(sql), ObjectType::Table, "{}", sql);
+        }
+        assert_eq!(ObjectType::View.to_string(), "view");
+    }
+
     #[test]
     fn test_qualified_name() {
         let table_def = TableDefinition::new("salesdb".to_string(), "customers".to_string());
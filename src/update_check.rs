@@ -0,0 +1,171 @@
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Version of this build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// GitHub API endpoint of the latest athenadef release
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/riseshia/athenadef/releases/latest";
+
+/// Page releases are downloaded from
+pub const RELEASES_PAGE_URL: &str = "https://github.com/riseshia/athenadef/releases/latest";
+
+/// How long the release lookup may take before it is given up
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lookup of the latest release, abstracted so the version comparison can be
+/// tested without network access
+#[cfg_attr(test, mockall::automock)]
+pub trait ReleaseFetcher {
+    /// Tag of the latest release, such as "v0.2.0"
+    fn latest_tag(&self) -> impl std::future::Future<Output = Result<String>> + Send;
+}
+
+/// Fetches the latest release from the GitHub releases API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitHubReleases;
+
+/// The part of a GitHub release the check needs
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+impl ReleaseFetcher for GitHubReleases {
+    async fn latest_tag(&self) -> Result<String> {
+        // ureq blocks, so it runs off the async runtime's worker threads
+        tokio::task::spawn_blocking(fetch_latest_tag)
+            .await
+            .context("Release lookup was cancelled")?
+    }
+}
+
+/// Request the latest release from GitHub and return its tag
+fn fetch_latest_tag() -> Result<String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(FETCH_TIMEOUT))
+        .build()
+        .into();
+    let body = agent
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", &format!("athenadef/{}", CURRENT_VERSION))
+        .call()
+        .with_context(|| format!("Failed to fetch {}", LATEST_RELEASE_URL))?
+        .body_mut()
+        .read_to_string()
+        .context("Failed to read the latest release")?;
+
+    let release: Release =
+        serde_json::from_str(&body).context("Unexpected response for the latest release")?;
+    Ok(release.tag_name)
+}
+
+/// Whether a newer release than the running version exists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateStatus {
+    /// The running version is the latest release or newer
+    UpToDate,
+    /// A newer release exists; holds its tag
+    Available { latest: String },
+}
+
+/// Compare `current_version` with the latest release
+///
+/// # Arguments
+/// * `fetcher` - Where the latest release is looked up
+/// * `current_version` - Version to compare, normally `CURRENT_VERSION`
+pub async fn check_for_update<F: ReleaseFetcher>(
+    fetcher: &F,
+    current_version: &str,
+) -> Result<UpdateStatus> {
+    let latest_tag = fetcher.latest_tag().await?;
+    let latest = parse_version(&latest_tag)
+        .ok_or_else(|| anyhow!("Unexpected release tag '{}'", latest_tag))?;
+    let current = parse_version(current_version)
+        .ok_or_else(|| anyhow!("Unexpected version '{}'", current_version))?;
+
+    if latest > current {
+        Ok(UpdateStatus::Available { latest: latest_tag })
+    } else {
+        Ok(UpdateStatus::UpToDate)
+    }
+}
+
+/// Parse `v1.2.3` or `1.2.3` into its numbers
+///
+/// A pre-release or build suffix (`-rc.1`, `+build`) is ignored.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let core = version.split(['-', '+']).next()?;
+
+    let mut numbers = core.split('.').map(|part| part.parse::<u64>().ok());
+    let parsed = (numbers.next()??, numbers.next()??, numbers.next()??);
+    numbers.next().is_none().then_some(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetcher_returning(tag: &'static str) -> MockReleaseFetcher {
+        let mut fetcher = MockReleaseFetcher::new();
+        fetcher
+            .expect_latest_tag()
+            .times(1)
+            .returning(move || Box::pin(async move { Ok(tag.to_string()) }));
+        fetcher
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v0.1.1"), Some((0, 1, 1)));
+        assert_eq!(parse_version("1.12.0"), Some((1, 12, 0)));
+        assert_eq!(parse_version("v2.0.0-rc.1"), Some((2, 0, 0)));
+        assert_eq!(parse_version("1.0.0+build.5"), Some((1, 0, 0)));
+        assert_eq!(parse_version("1.0"), None);
+        assert_eq!(parse_version("1.0.0.0"), None);
+        assert_eq!(parse_version("nightly"), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_for_update_newer_release() {
+        let status = check_for_update(&fetcher_returning("v0.10.0"), "0.9.3")
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            UpdateStatus::Available {
+                latest: "v0.10.0".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_for_update_up_to_date() {
+        for tag in ["v0.1.1", "v0.1.0"] {
+            let status = check_for_update(&fetcher_returning(tag), "0.1.1")
+                .await
+                .unwrap();
+            assert_eq!(status, UpdateStatus::UpToDate, "latest {}", tag);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_for_update_errors() {
+        let mut fetcher = MockReleaseFetcher::new();
+        fetcher
+            .expect_latest_tag()
+            .returning(|| Box::pin(async { Err(anyhow!("connection refused")) }));
+        let err = check_for_update(&fetcher, "0.1.1").await.unwrap_err();
+        assert_eq!(err.to_string(), "connection refused");
+
+        let err = check_for_update(&fetcher_returning("latest"), "0.1.1")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected release tag 'latest'");
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+
+/// Warnings recorded while a command runs
+///
+/// Warnings are still printed as they happen; recording them lets the CLI report
+/// a summary and fail the run under `--fail-on-warnings`.
+#[derive(Debug, Default)]
+pub struct WarningCollector {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl WarningCollector {
+    /// Create an empty collector
+    pub const fn new() -> Self {
+        Self {
+            warnings: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a warning
+    pub fn record(&self, message: impl Into<String>) {
+        self.lock().push(message.into());
+    }
+
+    /// Get all recorded warnings in the order they were recorded
+    pub fn warnings(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        // A panic while holding the lock cannot leave the Vec inconsistent
+        self.warnings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Process-wide collector used by `warn`
+static WARNINGS: WarningCollector = WarningCollector::new();
+
+/// Print a warning to stderr and record it
+///
+/// # Arguments
+/// * `message` - Warning text, without a "Warning:" prefix
+pub fn warn(message: impl Into<String>) {
+    let message = message.into();
+    eprintln!("Warning: {}", message);
+    WARNINGS.record(message);
+}
+
+/// Get every warning recorded so far in this process
+pub fn recorded_warnings() -> Vec<String> {
+    WARNINGS.warnings()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_records_in_order() {
+        let collector = WarningCollector::new();
+        assert!(collector.warnings().is_empty());
+
+        collector.record("first");
+        collector.record(String::from("second"));
+
+        assert_eq!(collector.warnings(), vec!["first", "second"]);
+    }
+}
@@ -0,0 +1,44 @@
+//! Smoke tests against real AWS credentials
+//!
+//! Run inside an environment with web identity credentials (e.g. an EKS pod using
+//! IAM Roles for Service Accounts):
+//!
+//!   cargo test --features aws-smoke-tests --test aws_credentials_smoke_test
+#![cfg(feature = "aws-smoke-tests")]
+
+use athenadef::aws::sdk_config::{CredentialsSource, load_sdk_config};
+use athenadef::types::config::Config;
+use aws_sdk_athena::config::ProvideCredentials;
+use std::env;
+
+#[tokio::test]
+async fn test_web_identity_credentials_are_not_overridden() {
+    if env::var("AWS_WEB_IDENTITY_TOKEN_FILE").is_err() || env::var("AWS_ROLE_ARN").is_err() {
+        eprintln!("Skipping: AWS_WEB_IDENTITY_TOKEN_FILE and AWS_ROLE_ARN are not set");
+        return;
+    }
+
+    // No explicit profile or role: the default chain must resolve web identity credentials
+    let config = Config::default();
+    assert_eq!(
+        CredentialsSource::from_config(&config),
+        CredentialsSource::DefaultChain
+    );
+
+    let sdk_config = load_sdk_config(&config)
+        .await
+        .expect("SDK config should load");
+    let provider = sdk_config
+        .credentials_provider()
+        .expect("credentials provider should be configured");
+    let credentials = provider
+        .provide_credentials()
+        .await
+        .expect("web identity credentials should resolve");
+
+    assert!(!credentials.access_key_id().is_empty());
+    assert!(
+        credentials.session_token().is_some(),
+        "web identity credentials are always temporary"
+    );
+}
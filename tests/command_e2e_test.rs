@@ -58,7 +58,7 @@ fn test_plan_command_with_target_filter() {
     let _config_path = create_test_config(temp_dir.path(), "primary", None);
 
     // Test target filter parsing
-    let filter = athenadef::target_filter::parse_target_filter(&["db1.*".to_string()]);
+    let filter = athenadef::target_filter::parse_target_filter(&["db1.*".to_string()]).unwrap();
 
     let files = athenadef::file_utils::FileUtils::find_sql_files(temp_dir.path()).unwrap();
     let filtered: Vec<_> = files
@@ -180,7 +180,8 @@ fn test_apply_with_target_filter() {
     let _config_path = create_test_config(temp_dir.path(), "primary", None);
 
     // Test filtering for apply command
-    let filter = athenadef::target_filter::parse_target_filter(&["prod.users".to_string()]);
+    let filter =
+        athenadef::target_filter::parse_target_filter(&["prod.users".to_string()]).unwrap();
 
     let files = athenadef::file_utils::FileUtils::find_sql_files(temp_dir.path()).unwrap();
     let filtered: Vec<_> = files
@@ -299,7 +300,7 @@ fn test_plan_command_with_wildcard_targets() {
     let _config_path = create_test_config(temp_dir.path(), "primary", None);
 
     // Test wildcard database
-    let filter1 = athenadef::target_filter::parse_target_filter(&["*.table1".to_string()]);
+    let filter1 = athenadef::target_filter::parse_target_filter(&["*.table1".to_string()]).unwrap();
     let files = athenadef::file_utils::FileUtils::find_sql_files(temp_dir.path()).unwrap();
     let filtered1: Vec<_> = files
         .values()
@@ -308,7 +309,7 @@ fn test_plan_command_with_wildcard_targets() {
     assert_eq!(filtered1.len(), 2);
 
     // Test wildcard table
-    let filter2 = athenadef::target_filter::parse_target_filter(&["db1.*".to_string()]);
+    let filter2 = athenadef::target_filter::parse_target_filter(&["db1.*".to_string()]).unwrap();
     let filtered2: Vec<_> = files
         .values()
         .filter(|sql_file| filter2(&sql_file.database_name, &sql_file.table_name))
@@ -384,3 +385,176 @@ fn test_command_working_directory_handling() {
     assert!(sql_file.file_path.exists());
     assert!(sql_file.file_path.is_absolute());
 }
+
+#[test]
+fn test_fail_on_warnings_exit_code() {
+    let temp_dir = create_test_directory_with_files(vec![(
+        "testdb/table1.sql",
+        sample_create_table_sql("testdb", "table1").as_str(),
+    )]);
+    let config_path = create_test_config(temp_dir.path(), "primary", None);
+
+    // A target that matches no local table is reported as a warning
+    let run = |extra_args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+            .args([
+                "plan",
+                "--no-remote",
+                "--config",
+                &config_path,
+                "--target",
+                "nosuchdb.*",
+            ])
+            .args(extra_args)
+            .env_remove("ATHENADEF_TARGETS")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("matched no local tables"));
+
+    let output = run(&["--fail-on-warnings"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 warning(s) reported"));
+}
+
+#[test]
+fn test_log_file_is_written() {
+    let temp_dir = create_test_directory_with_files(vec![(
+        "testdb/table1.sql",
+        sample_create_table_sql("testdb", "table1").as_str(),
+    )]);
+    let config_path = create_test_config(temp_dir.path(), "primary", None);
+    let log_path = temp_dir.path().join("athenadef.log");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+        .args([
+            "plan",
+            "--no-remote",
+            "--config",
+            &config_path,
+            "--log-file",
+        ])
+        .arg(&log_path)
+        .env_remove("ATHENADEF_TARGETS")
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("Starting athenadef plan"));
+}
+
+#[test]
+fn test_no_color_disables_escape_codes() {
+    let temp_dir = create_test_directory_with_files(vec![(
+        "testdb/table1.sql",
+        sample_create_table_sql("testdb", "table1").as_str(),
+    )]);
+    let config_path = create_test_config(temp_dir.path(), "primary", None);
+
+    let run = |extra_args: &[&str], envs: &[(&str, &str)]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+            .args(["plan", "--no-remote", "--config", &config_path])
+            .args(extra_args)
+            .env_remove("ATHENADEF_TARGETS")
+            .env_remove("NO_COLOR")
+            .env("CLICOLOR_FORCE", "1")
+            .envs(envs.iter().copied())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // CLICOLOR_FORCE keeps colors in piped output...
+    assert!(run(&[], &[]).contains('\u{1b}'));
+    // ...unless --no-color or NO_COLOR turns them off
+    assert!(!run(&["--no-color"], &[]).contains('\u{1b}'));
+    assert!(!run(&[], &[("NO_COLOR", "1")]).contains('\u{1b}'));
+    // Machine-readable output never has escape codes, even with colors forced
+    let json = run(&["--format", "json"], &[("RUST_LOG", "warn")]);
+    assert!(!json.contains('\u{1b}'));
+    serde_json::from_str::<serde_json::Value>(&json).unwrap();
+}
+
+#[test]
+fn test_config_command_prints_env_override() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = create_test_config(temp_dir.path(), "from-file", None);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+        .args(["config", "--json", "--config", &config_path])
+        .env("ATHENADEF_WORKGROUP", "from-env")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let config: Config = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(config.workgroup, "from-env");
+    // Defaults are filled in
+    assert_eq!(config.max_concurrent_queries, Some(5));
+}
+
+#[test]
+fn test_config_command_fails_on_invalid_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("athenadef.yaml");
+    std::fs::write(
+        &config_path,
+        "workgroup: primary\nquery_timeout_seconds: 0\n",
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+        .args(["config", "--config"])
+        .arg(&config_path)
+        .env_remove("ATHENADEF_WORKGROUP")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_destroy_requires_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = create_test_config(temp_dir.path(), "primary", None);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+        .args(["destroy", "--auto-approve", "--config", &config_path])
+        .env_remove("ATHENADEF_TARGETS")
+        .env_remove("ATHENADEF_WORKGROUP")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("destroy requires at least one target")
+    );
+}
+
+#[test]
+fn test_validate_command_reports_errors_as_json() {
+    let temp_dir = create_test_directory_with_files(vec![
+        (
+            "testdb/table1.sql",
+            sample_create_table_sql("testdb", "table1").as_str(),
+        ),
+        ("testdb/empty.sql", ""),
+    ]);
+    let config_path = create_test_config(temp_dir.path(), "primary", None);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_athenadef"))
+        .args(["validate", "--json", "--config", &config_path])
+        .env_remove("ATHENADEF_WORKGROUP")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["databases"]["testdb"].as_array().unwrap().len(), 2);
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+}
@@ -1,5 +1,8 @@
 mod common;
 
+use athenadef::commands::diff::diff_directories;
+use athenadef::differ::DiffOptions;
+use athenadef::types::diff_result::DiffOperation;
 use athenadef::types::table_definition::{ColumnDefinition, StorageDescriptor, TableDefinition};
 use std::collections::HashMap;
 
@@ -238,3 +241,100 @@ fn test_complex_table_structure() {
     assert!(table_def.comment.is_some());
     assert!(table_def.storage_descriptor.location.is_some());
 }
+
+#[test]
+fn test_diff_directories() {
+    let old_dir = common::create_test_directory_with_files(vec![
+        (
+            "salesdb/customers.sql",
+            common::sample_create_table_sql("salesdb", "customers").as_str(),
+        ),
+        (
+            "salesdb/orders.sql",
+            common::sample_create_table_sql("salesdb", "orders").as_str(),
+        ),
+        (
+            "salesdb/returns.sql",
+            common::sample_create_table_sql("salesdb", "returns").as_str(),
+        ),
+    ]);
+    let new_dir = common::create_test_directory_with_files(vec![
+        (
+            "salesdb/customers.sql",
+            common::sample_create_table_sql("salesdb", "customers").as_str(),
+        ),
+        (
+            "salesdb/orders.sql",
+            &common::sample_create_table_sql("salesdb", "orders")
+                .replace("`name` string", "`name` varchar(64)"),
+        ),
+        (
+            "salesdb/events.sql",
+            common::sample_partitioned_table_sql("salesdb", "events").as_str(),
+        ),
+    ]);
+
+    let diff_result =
+        diff_directories(old_dir.path(), new_dir.path(), &DiffOptions::default()).unwrap();
+
+    let operations: Vec<(&str, DiffOperation)> = diff_result
+        .table_diffs
+        .iter()
+        .map(|table_diff| (table_diff.table_name.as_str(), table_diff.operation.clone()))
+        .collect();
+    assert_eq!(
+        operations,
+        vec![
+            ("events", DiffOperation::Create),
+            ("orders", DiffOperation::Update),
+            ("returns", DiffOperation::Delete),
+        ]
+    );
+    assert_eq!(diff_result.summary.to_add, 1);
+    assert_eq!(diff_result.summary.to_change, 1);
+    assert_eq!(diff_result.summary.to_destroy, 1);
+
+    let orders = &diff_result.table_diffs[1];
+    let text_diff = orders.text_diff.as_deref().unwrap();
+    assert!(text_diff.contains("-  `name` string,"));
+    assert!(text_diff.contains("+  `name` varchar(64),"));
+    let column_changes = &orders.change_details.as_ref().unwrap().column_changes;
+    assert_eq!(column_changes.len(), 1);
+    assert_eq!(column_changes[0].column_name, "`name`");
+}
+
+#[test]
+fn test_diff_directories_identical() {
+    let sql = common::sample_create_table_sql("salesdb", "customers");
+    let files = vec![("salesdb/customers.sql", sql.as_str())];
+    let old_dir = common::create_test_directory_with_files(files.clone());
+    let new_dir = common::create_test_directory_with_files(files);
+
+    let diff_result =
+        diff_directories(old_dir.path(), new_dir.path(), &DiffOptions::default()).unwrap();
+    assert!(diff_result.no_change);
+    assert!(diff_result.table_diffs.is_empty());
+
+    let options = DiffOptions {
+        include_unchanged: true,
+        ..DiffOptions::default()
+    };
+    let diff_result = diff_directories(old_dir.path(), new_dir.path(), &options).unwrap();
+    assert_eq!(diff_result.table_diffs.len(), 1);
+    assert_eq!(
+        diff_result.table_diffs[0].operation,
+        DiffOperation::NoChange
+    );
+}
+
+#[test]
+fn test_diff_directories_missing_directory() {
+    let dir = common::create_test_directory_with_files(vec![]);
+    let err = diff_directories(
+        dir.path(),
+        &dir.path().join("missing"),
+        &DiffOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().ends_with("is not a directory"));
+}
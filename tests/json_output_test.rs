@@ -4,6 +4,7 @@ use athenadef::types::diff_result::{
     ChangeDetails, ColumnChange, ColumnChangeType, DiffOperation, DiffResult, DiffSummary,
     PropertyChange, TableDiff,
 };
+use athenadef::types::table_definition::ObjectType;
 
 // Tests for JSON output format verification
 // Ensures JSON output is valid, complete, and parseable
@@ -24,6 +25,8 @@ fn test_json_serialization_basic_diff_result() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "testdb".to_string(),
@@ -31,6 +34,8 @@ fn test_json_serialization_basic_diff_result() {
                 operation: DiffOperation::Update,
                 text_diff: Some("--- remote\n+++ local\n-old line\n+new line".to_string()),
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "testdb".to_string(),
@@ -38,6 +43,8 @@ fn test_json_serialization_basic_diff_result() {
                 operation: DiffOperation::Delete,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
         ],
     };
@@ -71,6 +78,8 @@ fn test_json_contains_all_fields() {
             operation: DiffOperation::Create,
             text_diff: None,
             change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
         }],
     };
 
@@ -111,12 +120,16 @@ fn test_json_with_change_details() {
                         column_name: "score".to_string(),
                         old_type: Some("int".to_string()),
                         new_type: Some("double".to_string()),
+                        old_constraints: None,
+                        new_constraints: None,
                     },
                     ColumnChange {
                         change_type: ColumnChangeType::Added,
                         column_name: "created_at".to_string(),
                         old_type: None,
                         new_type: Some("timestamp".to_string()),
+                        old_constraints: None,
+                        new_constraints: None,
                     },
                 ],
                 property_changes: vec![PropertyChange {
@@ -124,7 +137,11 @@ fn test_json_with_change_details() {
                     old_value: Some("false".to_string()),
                     new_value: Some("true".to_string()),
                 }],
+                bucketing_change: None,
+                unmodeled_changes: false,
             }),
+            location_impact: None,
+            object_type: ObjectType::Table,
         }],
     };
 
@@ -186,6 +203,8 @@ fn test_json_multiple_operations() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db1".to_string(),
@@ -193,6 +212,8 @@ fn test_json_multiple_operations() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db2".to_string(),
@@ -200,6 +221,8 @@ fn test_json_multiple_operations() {
                 operation: DiffOperation::Update,
                 text_diff: Some("diff1".to_string()),
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db2".to_string(),
@@ -207,6 +230,8 @@ fn test_json_multiple_operations() {
                 operation: DiffOperation::Update,
                 text_diff: Some("diff2".to_string()),
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db3".to_string(),
@@ -214,6 +239,8 @@ fn test_json_multiple_operations() {
                 operation: DiffOperation::Delete,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
         ],
     };
@@ -276,6 +303,8 @@ fn test_json_text_diff_preservation() {
             operation: DiffOperation::Update,
             text_diff: Some(text_diff.to_string()),
             change_details: None,
+            location_impact: None,
+            object_type: ObjectType::Table,
         }],
     };
 
@@ -308,6 +337,8 @@ fn test_json_qualified_table_names() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "marketingdb".to_string(),
@@ -315,6 +346,8 @@ fn test_json_qualified_table_names() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "analyticsdb".to_string(),
@@ -322,6 +355,8 @@ fn test_json_qualified_table_names() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
         ],
     };
@@ -361,6 +396,8 @@ fn test_json_is_valid_for_programmatic_use() {
                 operation: DiffOperation::Create,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db2".to_string(),
@@ -368,6 +405,8 @@ fn test_json_is_valid_for_programmatic_use() {
                 operation: DiffOperation::Update,
                 text_diff: Some("diff content".to_string()),
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
             TableDiff {
                 database_name: "db3".to_string(),
@@ -375,6 +414,8 @@ fn test_json_is_valid_for_programmatic_use() {
                 operation: DiffOperation::Delete,
                 text_diff: None,
                 change_details: None,
+                location_impact: None,
+                object_type: ObjectType::Table,
             },
         ],
     };
@@ -436,22 +477,32 @@ fn test_json_column_change_types() {
                         column_name: "new_col".to_string(),
                         old_type: None,
                         new_type: Some("string".to_string()),
+                        old_constraints: None,
+                        new_constraints: None,
                     },
                     ColumnChange {
                         change_type: ColumnChangeType::Removed,
                         column_name: "old_col".to_string(),
                         old_type: Some("int".to_string()),
                         new_type: None,
+                        old_constraints: None,
+                        new_constraints: None,
                     },
                     ColumnChange {
                         change_type: ColumnChangeType::TypeChanged,
                         column_name: "id".to_string(),
                         old_type: Some("int".to_string()),
                         new_type: Some("bigint".to_string()),
+                        old_constraints: None,
+                        new_constraints: None,
                     },
                 ],
                 property_changes: vec![],
+                bucketing_change: None,
+                unmodeled_changes: false,
             }),
+            location_impact: None,
+            object_type: ObjectType::Table,
         }],
     };
 
@@ -57,7 +57,7 @@ fn test_filter_across_multiple_databases() {
     ]);
 
     let files = FileUtils::find_sql_files(temp_dir.path()).unwrap();
-    let filter = parse_target_filter(&["*.users".to_string()]);
+    let filter = parse_target_filter(&["*.users".to_string()]).unwrap();
 
     let filtered: Vec<_> = files
         .values()
@@ -93,7 +93,7 @@ fn test_filter_multiple_tables_in_single_database() {
     ]);
 
     let files = FileUtils::find_sql_files(temp_dir.path()).unwrap();
-    let filter = parse_target_filter(&["analytics.*".to_string()]);
+    let filter = parse_target_filter(&["analytics.*".to_string()]).unwrap();
 
     let filtered: Vec<_> = files
         .values()
@@ -1,8 +1,9 @@
-use athenadef::target_filter::parse_target_filter;
+use athenadef::target_filter::{parse_target_filter, parse_target_filter_with_case};
+use athenadef::types::config::TableNameCase;
 
 #[test]
 fn test_no_filters() {
-    let filter = parse_target_filter(&[]);
+    let filter = parse_target_filter(&[]).unwrap();
 
     // Should match all tables
     assert!(filter("db1", "table1"));
@@ -12,7 +13,7 @@ fn test_no_filters() {
 
 #[test]
 fn test_specific_database_and_table() {
-    let filter = parse_target_filter(&["db1.table1".to_string()]);
+    let filter = parse_target_filter(&["db1.table1".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(!filter("db1", "table2"));
@@ -22,7 +23,7 @@ fn test_specific_database_and_table() {
 
 #[test]
 fn test_wildcard_table() {
-    let filter = parse_target_filter(&["db1.*".to_string()]);
+    let filter = parse_target_filter(&["db1.*".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(filter("db1", "table2"));
@@ -32,7 +33,7 @@ fn test_wildcard_table() {
 
 #[test]
 fn test_wildcard_database() {
-    let filter = parse_target_filter(&["*.table1".to_string()]);
+    let filter = parse_target_filter(&["*.table1".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(filter("db2", "table1"));
@@ -42,7 +43,8 @@ fn test_wildcard_database() {
 
 #[test]
 fn test_multiple_filters() {
-    let filter = parse_target_filter(&["db1.table1".to_string(), "db2.table2".to_string()]);
+    let filter =
+        parse_target_filter(&["db1.table1".to_string(), "db2.table2".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(filter("db2", "table2"));
@@ -53,7 +55,7 @@ fn test_multiple_filters() {
 
 #[test]
 fn test_mixed_specific_and_wildcard() {
-    let filter = parse_target_filter(&["db1.table1".to_string(), "db2.*".to_string()]);
+    let filter = parse_target_filter(&["db1.table1".to_string(), "db2.*".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(!filter("db1", "table2"));
@@ -65,7 +67,7 @@ fn test_mixed_specific_and_wildcard() {
 
 #[test]
 fn test_multiple_wildcard_databases() {
-    let filter = parse_target_filter(&["*.users".to_string(), "*.orders".to_string()]);
+    let filter = parse_target_filter(&["*.users".to_string(), "*.orders".to_string()]).unwrap();
 
     assert!(filter("db1", "users"));
     assert!(filter("db2", "users"));
@@ -76,7 +78,7 @@ fn test_multiple_wildcard_databases() {
 
 #[test]
 fn test_multiple_wildcard_tables_same_database() {
-    let filter = parse_target_filter(&["analytics.*".to_string()]);
+    let filter = parse_target_filter(&["analytics.*".to_string()]).unwrap();
 
     assert!(filter("analytics", "events"));
     assert!(filter("analytics", "users"));
@@ -84,11 +86,24 @@ fn test_multiple_wildcard_tables_same_database() {
     assert!(!filter("warehouse", "events"));
 }
 
+#[test]
+fn test_case_insensitivity() {
+    let filter = parse_target_filter(&["DB1.TABLE1".to_string()]).unwrap();
+
+    // Athena treats identifiers case-insensitively
+    assert!(filter("DB1", "TABLE1"));
+    assert!(filter("db1", "table1"));
+    assert!(filter("DB1", "table1"));
+    assert!(filter("db1", "TABLE1"));
+    assert!(!filter("db1", "table2"));
+}
+
 #[test]
 fn test_case_sensitivity() {
-    let filter = parse_target_filter(&["DB1.TABLE1".to_string()]);
+    let filter =
+        parse_target_filter_with_case(&["DB1.TABLE1".to_string()], TableNameCase::Sensitive)
+            .unwrap();
 
-    // AWS is case-sensitive for identifiers
     assert!(filter("DB1", "TABLE1"));
     assert!(!filter("db1", "table1"));
     assert!(!filter("DB1", "table1"));
@@ -97,7 +112,7 @@ fn test_case_sensitivity() {
 
 #[test]
 fn test_underscores_in_names() {
-    let filter = parse_target_filter(&["my_database.my_table".to_string()]);
+    let filter = parse_target_filter(&["my_database.my_table".to_string()]).unwrap();
 
     assert!(filter("my_database", "my_table"));
     assert!(!filter("mydatabase", "mytable"));
@@ -111,7 +126,8 @@ fn test_complex_filter_combination() {
         "prod_db.orders".to_string(),
         "staging_db.*".to_string(),
         "*.events".to_string(),
-    ]);
+    ])
+    .unwrap();
 
     // Specific tables in prod_db
     assert!(filter("prod_db", "users"));
@@ -135,7 +151,7 @@ fn test_complex_filter_combination() {
 #[test]
 fn test_empty_string_filter() {
     // Edge case: empty strings in filter list
-    let filter = parse_target_filter(&["db1.table1".to_string(), "".to_string()]);
+    let filter = parse_target_filter(&["db1.table1".to_string(), "".to_string()]).unwrap();
 
     assert!(filter("db1", "table1"));
     assert!(!filter("db2", "table2"));
@@ -143,7 +159,7 @@ fn test_empty_string_filter() {
 
 #[test]
 fn test_only_wildcard_filters() {
-    let filter = parse_target_filter(&["*.*".to_string()]);
+    let filter = parse_target_filter(&["*.*".to_string()]).unwrap();
 
     // This should match everything (though it's redundant with no filter)
     assert!(filter("db1", "table1"));
@@ -157,7 +173,8 @@ fn test_duplicate_filters() {
         "db1.table1".to_string(),
         "db1.table1".to_string(),
         "db1.table1".to_string(),
-    ]);
+    ])
+    .unwrap();
 
     // Duplicates shouldn't affect the result
     assert!(filter("db1", "table1"));
@@ -166,7 +183,7 @@ fn test_duplicate_filters() {
 
 #[test]
 fn test_overlapping_filters() {
-    let filter = parse_target_filter(&["db1.*".to_string(), "db1.table1".to_string()]);
+    let filter = parse_target_filter(&["db1.*".to_string(), "db1.table1".to_string()]).unwrap();
 
     // More specific filter is redundant but shouldn't cause issues
     assert!(filter("db1", "table1"));
@@ -176,7 +193,7 @@ fn test_overlapping_filters() {
 
 #[test]
 fn test_numbers_in_names() {
-    let filter = parse_target_filter(&["db1.table2023".to_string(), "db2.*".to_string()]);
+    let filter = parse_target_filter(&["db1.table2023".to_string(), "db2.*".to_string()]).unwrap();
 
     assert!(filter("db1", "table2023"));
     assert!(!filter("db1", "table2024"));
@@ -186,7 +203,7 @@ fn test_numbers_in_names() {
 
 #[test]
 fn test_special_characters() {
-    let filter = parse_target_filter(&["my-database.my-table".to_string()]);
+    let filter = parse_target_filter(&["my-database.my-table".to_string()]).unwrap();
 
     assert!(filter("my-database", "my-table"));
     assert!(!filter("my_database", "my_table"));